@@ -0,0 +1,368 @@
+//! Access control lists, which writes ask for with the `x-amz-acl` and `x-amz-grant-*` headers
+//! and the `?acl` subresource reads and replaces as an `AccessControlPolicy`
+
+use crate::{auth::Identity, xml};
+use actix_web::{http::header::HeaderMap, HttpMessage, HttpRequest};
+use s3_entities::{
+    acl::{
+        AccessControlList, CannedAcl, Grant, Grantee, ObjectOwnership, Owner, Permission,
+        ALL_USERS, AUTHENTICATED_USERS, MAX_GRANTS,
+    },
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+/// Who buckets that were never given an ACL belong to
+const DEFAULT_OWNER_ID: &str = "AIDACKCEVSQ6C2EXAMPLE";
+const DEFAULT_OWNER_DISPLAY_NAME: &str = "Account+Name";
+
+const CANNED_ACL_HEADER: &str = "x-amz-acl";
+/// The headers granting a permission to the grantees they list
+const GRANT_HEADERS: [(&str, Permission); 5] = [
+    ("x-amz-grant-full-control", Permission::FullControl),
+    ("x-amz-grant-read", Permission::Read),
+    ("x-amz-grant-write", Permission::Write),
+    ("x-amz-grant-read-acp", Permission::ReadAcp),
+    ("x-amz-grant-write-acp", Permission::WriteAcp),
+];
+
+/// Why an ACL couldn't be read from a request or applied
+#[derive(Debug)]
+pub(crate) enum AclError {
+    /// The `AccessControlPolicy` isn't well-formed, or has a permission that doesn't exist
+    Malformed,
+    /// A canned ACL or grantee that doesn't exist, or more grants than an ACL can have
+    InvalidArgument,
+    /// A canned ACL along with grant headers
+    InvalidRequest,
+    /// A grant to an email address, which can't be resolved to a caller
+    UnresolvableGrantByEmailAddress,
+    /// The bucket has ACLs disabled by its ownership controls
+    NotSupported,
+    Storage(StorageErr),
+}
+
+impl From<StorageErr> for AclError {
+    fn from(err: StorageErr) -> Self {
+        AclError::Storage(err)
+    }
+}
+
+pub(crate) fn default_owner() -> Owner {
+    Owner {
+        id: DEFAULT_OWNER_ID.into(),
+        display_name: DEFAULT_OWNER_DISPLAY_NAME.into(),
+    }
+}
+
+/// The owner of what a request creates, `None` when it's anonymous
+pub(crate) fn requester(req: &HttpRequest) -> Option<Owner> {
+    req.extensions().get::<Identity>().map(Identity::owner)
+}
+
+/// Read the ACL a request asks for in its headers, `None` when it asks for none
+pub(crate) fn acl_from_headers(
+    headers: &HeaderMap,
+    owner: Owner,
+    bucket_owner: &Owner,
+) -> Result<Option<AccessControlList>, AclError> {
+    let header = |name| {
+        headers
+            .get(name)
+            .map(|value| value.to_str().unwrap_or_default())
+    };
+    let grants: Vec<_> = GRANT_HEADERS
+        .into_iter()
+        .filter_map(|(name, permission)| Some((header(name)?, permission)))
+        .collect();
+
+    if let Some(canned) = header(CANNED_ACL_HEADER) {
+        if !grants.is_empty() {
+            return Err(AclError::InvalidRequest);
+        }
+        let canned = CannedAcl::parse(canned).ok_or(AclError::InvalidArgument)?;
+
+        return Ok(Some(canned.acl(owner, bucket_owner)));
+    }
+    if grants.is_empty() {
+        return Ok(None);
+    }
+
+    let mut acl = AccessControlList {
+        owner,
+        grants: Vec::new(),
+    };
+    for (value, permission) in grants {
+        for grantee in value.split(',') {
+            acl.grants.push(Grant {
+                grantee: parse_grantee(grantee.trim())?,
+                permission,
+            });
+        }
+    }
+    if acl.grants.len() > MAX_GRANTS {
+        return Err(AclError::InvalidArgument);
+    }
+
+    Ok(Some(acl))
+}
+
+/// Parse a grantee of a grant header, such as `id="..."` or `uri="..."`
+fn parse_grantee(grantee: &str) -> Result<Grantee, AclError> {
+    let (kind, value) = grantee.split_once('=').ok_or(AclError::InvalidArgument)?;
+    let value = value.trim().trim_matches('"').to_string();
+
+    match kind.trim() {
+        "id" if !value.is_empty() => Ok(Grantee::CanonicalUser {
+            id: value,
+            display_name: None,
+        }),
+        "uri" => group(value),
+        "emailAddress" => Err(AclError::UnresolvableGrantByEmailAddress),
+        _ => Err(AclError::InvalidArgument),
+    }
+}
+
+/// The group a URI stands for, which has to be one of those that are predefined
+fn group(uri: String) -> Result<Grantee, AclError> {
+    match uri.as_str() {
+        ALL_USERS | AUTHENTICATED_USERS => Ok(Grantee::Group { uri }),
+        _ => Err(AclError::InvalidArgument),
+    }
+}
+
+/// Read the ACL of an `AccessControlPolicy`. The owner of a bucket or an object can't be changed,
+/// so the one the policy names is ignored.
+pub(crate) fn acl_from_xml(
+    policy: xml::AccessControlPolicy,
+    owner: Owner,
+) -> Result<AccessControlList, AclError> {
+    let grants = policy.access_control_list.grants;
+    if grants.len() > MAX_GRANTS {
+        return Err(AclError::Malformed);
+    }
+
+    let grants = grants
+        .into_iter()
+        .map(|grant| {
+            let permission = Permission::parse(&grant.permission).ok_or(AclError::Malformed)?;
+            let grantee = grant.grantee;
+            let grantee = match grantee.grantee_type.as_str() {
+                "CanonicalUser" => Grantee::CanonicalUser {
+                    id: grantee
+                        .id
+                        .filter(|id| !id.is_empty())
+                        .ok_or(AclError::Malformed)?,
+                    display_name: grantee.display_name,
+                },
+                "Group" => group(grantee.uri.ok_or(AclError::Malformed)?)?,
+                "AmazonCustomerByEmail" => return Err(AclError::UnresolvableGrantByEmailAddress),
+                _ => return Err(AclError::Malformed),
+            };
+
+            Ok(Grant {
+                grantee,
+                permission,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(AccessControlList { owner, grants })
+}
+
+pub(crate) fn acl_to_xml(acl: AccessControlList) -> xml::AccessControlPolicy {
+    let grants = acl
+        .grants
+        .into_iter()
+        .map(|grant| {
+            let (grantee_type, id, display_name, uri) = match grant.grantee {
+                Grantee::CanonicalUser { id, display_name } => {
+                    ("CanonicalUser", Some(id), display_name, None)
+                }
+                Grantee::Group { uri } => ("Group", None, None, Some(uri)),
+            };
+
+            xml::Grant {
+                grantee: xml::Grantee {
+                    xmlns_xsi: Some(xml::XSI_NAMESPACE.into()),
+                    grantee_type: grantee_type.into(),
+                    id,
+                    display_name,
+                    uri,
+                    email_address: None,
+                },
+                permission: grant.permission.as_str().into(),
+            }
+        })
+        .collect();
+
+    xml::AccessControlPolicy {
+        owner: Some(xml::AclOwner {
+            id: acl.owner.id,
+            display_name: Some(acl.owner.display_name),
+        }),
+        access_control_list: xml::AccessControlList { grants },
+    }
+}
+
+/// The ownership controls of a bucket, buckets without any behaving as `ObjectWriter`
+pub(crate) async fn object_ownership(
+    provider: &dyn StorageProvider,
+    bucket: &str,
+) -> Result<ObjectOwnership, StorageErr> {
+    match provider.get_bucket_ownership_controls(bucket).await {
+        Err(StorageErr::NoSuchOwnershipControls) => Ok(ObjectOwnership::default()),
+        ownership => ownership,
+    }
+}
+
+/// The ACL that decides on access to a bucket. Buckets that were never given one belong to the
+/// default owner, and only the owner has access when ACLs are disabled.
+pub(crate) async fn bucket_acl(
+    provider: &dyn StorageProvider,
+    bucket: &str,
+) -> Result<AccessControlList, StorageErr> {
+    let acl = provider.get_bucket_acl(bucket).await?;
+    let acl = match acl.is_empty() {
+        true => AccessControlList::private(default_owner()),
+        false => acl,
+    };
+
+    match object_ownership(provider, bucket).await?.acls_disabled() {
+        true => Ok(AccessControlList::private(acl.owner)),
+        false => Ok(acl),
+    }
+}
+
+/// The ACL that decides on access to an object. Objects that were never given one are private to
+/// the bucket owner, as are all objects when ACLs are disabled.
+pub(crate) async fn object_acl(
+    provider: &dyn StorageProvider,
+    bucket: &str,
+    key: &str,
+    version_id: Option<&str>,
+) -> Result<AccessControlList, StorageErr> {
+    let bucket_owner = bucket_acl(provider, bucket).await?.owner;
+    let acl = provider.get_object_acl(bucket, key, version_id).await?;
+
+    match acl.is_empty() || object_ownership(provider, bucket).await?.acls_disabled() {
+        true => Ok(AccessControlList::private(bucket_owner)),
+        false => Ok(acl),
+    }
+}
+
+/// The ACL a write asks for on the object it creates in a bucket, `None` when it asks for none.
+/// When ACLs are disabled, only `bucket-owner-full-control` is accepted, and nothing is stored.
+pub(crate) async fn object_write_acl(
+    provider: &dyn StorageProvider,
+    bucket: &str,
+    req: &HttpRequest,
+) -> Result<Option<AccessControlList>, AclError> {
+    let headers = req.headers();
+    let canned = headers.get(CANNED_ACL_HEADER).map(|value| value.as_bytes());
+    let bucket_owner_full_control = canned == Some(b"bucket-owner-full-control");
+    let ownership = object_ownership(provider, bucket).await?;
+
+    if ownership.acls_disabled() {
+        let has_grants = GRANT_HEADERS
+            .iter()
+            .any(|(name, _)| headers.contains_key(*name));
+
+        return match (canned.is_none() || bucket_owner_full_control) && !has_grants {
+            true => Ok(None),
+            false => Err(AclError::NotSupported),
+        };
+    }
+
+    let bucket_owner = bucket_acl(provider, bucket).await?.owner;
+    let owner = match ownership {
+        ObjectOwnership::BucketOwnerPreferred if bucket_owner_full_control => bucket_owner.clone(),
+        _ => requester(req).unwrap_or_else(|| bucket_owner.clone()),
+    };
+
+    acl_from_headers(headers, owner, &bucket_owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn owner(id: &str) -> Owner {
+        Owner {
+            id: id.into(),
+            display_name: id.into(),
+        }
+    }
+
+    fn headers(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut req = TestRequest::default();
+        for header in headers {
+            req = req.insert_header(*header);
+        }
+
+        req.to_http_request().headers().clone()
+    }
+
+    fn parse(
+        request_headers: &[(&'static str, &'static str)],
+    ) -> Result<Option<AccessControlList>, AclError> {
+        acl_from_headers(&headers(request_headers), owner("alice"), &owner("alice"))
+    }
+
+    #[test]
+    fn test_acl_from_headers() {
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(
+            parse(&[("x-amz-acl", "public-read")]).unwrap(),
+            Some(CannedAcl::PublicRead.acl(owner("alice"), &owner("alice")))
+        );
+
+        let acl = parse(&[
+            (
+                "x-amz-grant-read",
+                r#"uri="http://acs.amazonaws.com/groups/global/AllUsers", id="bob""#,
+            ),
+            ("x-amz-grant-full-control", r#"id="alice""#),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(acl.grants.len(), 3);
+        assert!(acl.allows(None, Permission::Read));
+        assert!(acl.allows(Some("bob"), Permission::Read));
+        assert!(!acl.allows(Some("bob"), Permission::Write));
+        assert!(acl.allows(Some("alice"), Permission::WriteAcp));
+    }
+
+    #[test]
+    fn test_invalid_acl_headers() {
+        assert!(matches!(
+            parse(&[("x-amz-acl", "public")]),
+            Err(AclError::InvalidArgument)
+        ));
+        assert!(matches!(
+            parse(&[
+                ("x-amz-acl", "private"),
+                ("x-amz-grant-read", r#"id="bob""#)
+            ]),
+            Err(AclError::InvalidRequest)
+        ));
+        assert!(matches!(
+            parse(&[("x-amz-grant-read", r#"emailAddress="bob@example.com""#)]),
+            Err(AclError::UnresolvableGrantByEmailAddress)
+        ));
+        assert!(matches!(
+            parse(&[("x-amz-grant-read", r#"uri="http://example.com/everyone""#)]),
+            Err(AclError::InvalidArgument)
+        ));
+    }
+
+    #[test]
+    fn test_xml_round_trip() {
+        let acl = CannedAcl::PublicRead.acl(owner("alice"), &owner("alice"));
+
+        assert_eq!(
+            acl_from_xml(acl_to_xml(acl.clone()), owner("alice")).unwrap(),
+            acl
+        );
+    }
+}
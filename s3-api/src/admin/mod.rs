@@ -0,0 +1,199 @@
+//! The administration API, served apart from S3 to the administrators' credentials only, which
+//! manages the users and groups of the identity store, their access keys and their policies
+
+extern crate self as s3_api;
+
+use crate::{auth::Identity, generate_request_id, xml, xml_date};
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, Ready};
+use rand::distributions::{Alphanumeric, DistString};
+use s3_derive::S3Error;
+use s3_entities::identity::{AccessKey, Group, User};
+
+mod access_keys;
+mod groups;
+mod policies;
+mod users;
+
+/// The longest name a user, group or policy can have, the same as IAM for users
+const MAX_NAME_LENGTH: usize = 64;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(users::create_user)
+        .service(users::list_users)
+        .service(users::delete_user)
+        .service(access_keys::create_access_key)
+        .service(access_keys::list_access_keys)
+        .service(access_keys::update_access_key)
+        .service(access_keys::rotate_access_key)
+        .service(access_keys::delete_access_key)
+        .service(groups::create_group)
+        .service(groups::list_groups)
+        .service(groups::delete_group)
+        .service(groups::add_user_to_group)
+        .service(groups::remove_user_from_group)
+        .service(groups::list_groups_for_user)
+        .service(policies::put_policy)
+        .service(policies::get_policy)
+        .service(policies::delete_policy)
+        .service(policies::list_policies);
+}
+
+#[derive(Debug, S3Error)]
+enum AdminError {
+    #[error(status_code = 403, message = "Access Denied")]
+    AccessDenied {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 409, message = "The user or group already exists.")]
+    EntityAlreadyExists {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 409,
+        message = "The user already has as many access keys as they can have."
+    )]
+    LimitExceeded {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "The policy document is not valid.")]
+    MalformedPolicyDocument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The user, group, access key or policy does not exist."
+    )]
+    NoSuchEntity {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Proof that a request is from an administrator with their own access key, which every handler
+/// of the API asks for
+struct Admin;
+
+impl FromRequest for Admin {
+    type Error = AdminError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let admin = req
+            .extensions()
+            .get::<Identity>()
+            .is_some_and(|identity| identity.is_admin() && !identity.is_session());
+
+        ready(match admin {
+            true => Ok(Admin),
+            false => Err(AdminError::AccessDenied {
+                request_id: generate_request_id(),
+                resource: req.path().into(),
+            }),
+        })
+    }
+}
+
+/// Letters, digits and `+=,.@_-`, which are all IAM allows in the name of a user, group or policy
+fn valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LENGTH).contains(&name.len())
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"+=,.@_-".contains(&byte))
+}
+
+fn random_id(prefix: &str, len: usize) -> String {
+    format!(
+        "{prefix}{}",
+        Alphanumeric
+            .sample_string(&mut rand::thread_rng(), len)
+            .to_uppercase()
+    )
+}
+
+/// A user id in the shape IAM gives them
+fn generate_user_id() -> String {
+    random_id("AIDA", 17)
+}
+
+/// A group id in the shape IAM gives them
+fn generate_group_id() -> String {
+    random_id("AGPA", 17)
+}
+
+/// A new access key of a user, in the shape of the ones IAM issues
+fn generate_access_key(user_name: &str) -> AccessKey {
+    AccessKey {
+        access_key_id: random_id("AKIA", 16),
+        secret_access_key: Alphanumeric.sample_string(&mut rand::thread_rng(), 40),
+        user_name: user_name.into(),
+        status: Default::default(),
+        creation_date: chrono::Utc::now(),
+    }
+}
+
+fn user_to_xml(user: User) -> xml::User {
+    xml::User {
+        user_name: user.name,
+        user_id: user.id,
+        create_date: xml_date(&user.creation_date),
+    }
+}
+
+fn group_to_xml(group: Group) -> xml::Group {
+    xml::Group {
+        group_name: group.name,
+        group_id: group.id,
+        create_date: xml_date(&group.creation_date),
+    }
+}
+
+/// An access key as it's shown, which its secret is only when it's just been created
+fn access_key_to_xml(access_key: AccessKey, with_secret: bool) -> xml::AccessKey {
+    xml::AccessKey {
+        user_name: access_key.user_name,
+        access_key_id: access_key.access_key_id,
+        secret_access_key: with_secret.then_some(access_key.secret_access_key),
+        status: access_key.status.as_str().into(),
+        create_date: xml_date(&access_key.creation_date),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_name() {
+        assert!(valid_name("alice"));
+        assert!(valid_name("ci+deploy@example.com"));
+        assert!(!valid_name(""));
+        assert!(!valid_name("alice/admin"));
+        assert!(!valid_name(&"a".repeat(MAX_NAME_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_generated_ids() {
+        let access_key = generate_access_key("alice");
+
+        assert_eq!(generate_user_id().len(), 21);
+        assert!(generate_group_id().starts_with("AGPA"));
+        assert!(access_key.access_key_id.starts_with("AKIA"));
+        assert_eq!(access_key.access_key_id.len(), 20);
+        assert_eq!(access_key.secret_access_key.len(), 40);
+    }
+}
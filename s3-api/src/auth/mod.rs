@@ -0,0 +1,725 @@
+extern crate self as s3_api;
+
+use actix_web::{
+    dev::ServiceRequest,
+    http::{
+        header::{self, HeaderMap},
+        Method,
+    },
+};
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use s3_derive::S3Error;
+use s3_entities::{
+    acl::Owner,
+    identity::{AccessKeyStatus, User},
+    policy::Policy,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use std::{collections::HashMap, env, time::Duration};
+
+mod middleware;
+mod presign;
+pub mod signature;
+
+pub use middleware::SigV4Auth;
+pub use presign::presign_url;
+use signature::{
+    CredentialScope, ALGORITHM, SERVICE, STREAMING_PAYLOAD, STREAMING_PAYLOAD_TRAILER,
+    STREAMING_UNSIGNED_PAYLOAD_TRAILER, STS_SERVICE, UNSIGNED_PAYLOAD,
+};
+
+/// Access keys the server accepts, mapped to their secret keys. They belong to the
+/// administrators rather than to a user of the identity store.
+#[derive(Clone, Debug, Default)]
+pub struct Credentials {
+    keys: HashMap<String, String>,
+}
+
+impl Credentials {
+    pub fn new() -> Self {
+        Credentials {
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Read `ACCESS_KEY_ID:SECRET_ACCESS_KEY` pairs from the comma separated `S3_CREDENTIALS`
+    /// environment variable
+    pub fn from_env() -> Self {
+        let mut credentials = Credentials::new();
+
+        for pair in env::var("S3_CREDENTIALS").unwrap_or_default().split(',') {
+            if let Some((access_key_id, secret_access_key)) = pair.trim().split_once(':') {
+                credentials.insert(access_key_id, secret_access_key);
+            }
+        }
+
+        credentials
+    }
+
+    pub fn insert(
+        &mut self,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) {
+        self.keys
+            .insert(access_key_id.into(), secret_access_key.into());
+    }
+
+    pub(crate) fn secret_access_key(&self, access_key_id: &str) -> Option<&str> {
+        self.keys.get(access_key_id).map(String::as_str)
+    }
+}
+
+/// The caller a request was authenticated as, available from the request extensions
+#[derive(Clone, Debug)]
+pub struct Identity {
+    /// The long-term access key of the caller, the one temporary credentials were issued to
+    pub access_key_id: String,
+    /// The user the access key belongs to, `None` for the administrators' [`Credentials`]
+    pub user: Option<User>,
+    /// The temporary credentials the request was signed with, `None` for a long-term access key
+    pub session: Option<SessionCredentials>,
+}
+
+/// Temporary credentials, which act for the access key they were issued to
+#[derive(Clone, Debug)]
+pub struct SessionCredentials {
+    pub access_key_id: String,
+    /// The policy the credentials are scoped by, which has to allow whatever they do
+    pub policy: Policy,
+}
+
+impl Identity {
+    /// Administrators can do anything the policies don't deny, and manage the identity store
+    /// unless they're using temporary credentials
+    pub fn is_admin(&self) -> bool {
+        self.user.is_none()
+    }
+
+    /// Whether the request was signed with temporary credentials
+    pub fn is_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Who owns what the caller creates, and is granted access to by the ACLs
+    pub fn owner(&self) -> Owner {
+        match &self.user {
+            Some(user) => user.owner(),
+            None => Owner {
+                id: self.access_key_id.clone(),
+                display_name: self.access_key_id.clone(),
+            },
+        }
+    }
+}
+
+/// Marks the requests let through unsigned, which are authorized as anonymous rather than left
+/// alone like those of an app without authentication
+#[derive(Clone, Copy, Debug)]
+pub struct Anonymous;
+
+/// How a signed payload fails once it's read through, when it doesn't hash to its
+/// `x-amz-content-sha256`
+#[derive(Debug, thiserror::Error)]
+#[error("the payload doesn't match x-amz-content-sha256")]
+pub(crate) struct PayloadSha256Mismatch;
+
+/// The region a signed request's credential scope names, which is where the client expects the
+/// bucket to be
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningRegion(pub String);
+
+#[derive(Debug, S3Error)]
+enum AuthError {
+    #[error(status_code = 403, message = "Access Denied")]
+    AccessDenied {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The authorization header that you provided is not valid."
+    )]
+    AuthorizationHeaderMalformed {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Query-string authentication requires the X-Amz-Algorithm, X-Amz-Credential, X-Amz-Signature, X-Amz-Date, X-Amz-SignedHeaders, and X-Amz-Expires parameters."
+    )]
+    AuthorizationQueryParametersError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "The provided token has expired.")]
+    ExpiredToken {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "x-amz-content-sha256 must be UNSIGNED-PAYLOAD, STREAMING-UNSIGNED-PAYLOAD-TRAILER, STREAMING-AWS4-HMAC-SHA256-PAYLOAD, STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER or a valid sha256 value."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Missing required header for this request: x-amz-content-sha256"
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "The AWS access key ID that you provided does not exist in our records."
+    )]
+    InvalidAccessKeyId {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The provided token is malformed or otherwise invalid."
+    )]
+    InvalidToken {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "AccessDenied",
+        status_code = 403,
+        message = "Request has expired"
+    )]
+    RequestExpired {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "AccessDenied",
+        status_code = 403,
+        message = "Request is not yet valid"
+    )]
+    RequestNotYetValid {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "The difference between the request time and the server's time is too large."
+    )]
+    RequestTimeTooSkewed {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "The request signature we calculated does not match the signature you provided. Check your key and signing method."
+    )]
+    SignatureDoesNotMatch {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Why a request was rejected, turned into an [`AuthError`] once it's answered
+#[derive(Debug, PartialEq, Eq)]
+enum Rejection {
+    AccessDenied,
+    /// The request isn't signed at all, which only anonymous access lets through
+    Anonymous,
+    AuthorizationHeaderMalformed,
+    AuthorizationQueryParametersError,
+    ExpiredToken,
+    InternalError,
+    /// The `x-amz-content-sha256` header is neither a digest nor one of the values standing in
+    /// for it
+    InvalidArgument,
+    InvalidRequest,
+    InvalidAccessKeyId,
+    /// The session token doesn't belong to the temporary credentials
+    InvalidToken,
+    RequestExpired,
+    /// A presigned URL dated later than the clock skew allows
+    RequestNotYetValid,
+    RequestTimeTooSkewed,
+    SignatureDoesNotMatch,
+}
+
+impl Rejection {
+    fn into_error(self, request_id: String, resource: String) -> AuthError {
+        match self {
+            Rejection::AccessDenied | Rejection::Anonymous => AuthError::AccessDenied {
+                request_id,
+                resource,
+            },
+            Rejection::AuthorizationHeaderMalformed => AuthError::AuthorizationHeaderMalformed {
+                request_id,
+                resource,
+            },
+            Rejection::AuthorizationQueryParametersError => {
+                AuthError::AuthorizationQueryParametersError {
+                    request_id,
+                    resource,
+                }
+            }
+            Rejection::ExpiredToken => AuthError::ExpiredToken {
+                request_id,
+                resource,
+            },
+            Rejection::InternalError => AuthError::InternalError {
+                request_id,
+                resource,
+            },
+            Rejection::InvalidArgument => AuthError::InvalidArgument {
+                request_id,
+                resource,
+            },
+            Rejection::InvalidRequest => AuthError::InvalidRequest {
+                request_id,
+                resource,
+            },
+            Rejection::InvalidAccessKeyId => AuthError::InvalidAccessKeyId {
+                request_id,
+                resource,
+            },
+            Rejection::InvalidToken => AuthError::InvalidToken {
+                request_id,
+                resource,
+            },
+            Rejection::RequestExpired => AuthError::RequestExpired {
+                request_id,
+                resource,
+            },
+            Rejection::RequestNotYetValid => AuthError::RequestNotYetValid {
+                request_id,
+                resource,
+            },
+            Rejection::RequestTimeTooSkewed => AuthError::RequestTimeTooSkewed {
+                request_id,
+                resource,
+            },
+            Rejection::SignatureDoesNotMatch => AuthError::SignatureDoesNotMatch {
+                request_id,
+                resource,
+            },
+        }
+    }
+}
+
+/// What a client signed, taken either from the `Authorization` header or from the
+/// `X-Amz-*` query parameters of a presigned URL
+struct SignedRequest {
+    access_key_id: String,
+    scope: CredentialScope,
+    signed_headers: Vec<String>,
+    signature: String,
+    timestamp: DateTime<Utc>,
+    payload_hash: String,
+}
+
+/// Checks the signatures of an `aws-chunked` body, each chained to the one before it starting
+/// from the signature of the request itself
+#[derive(Clone, Debug)]
+pub(crate) struct ChunkSigner {
+    signing_key: Vec<u8>,
+    timestamp: DateTime<Utc>,
+    scope: CredentialScope,
+    previous_signature: String,
+}
+
+impl ChunkSigner {
+    pub(crate) fn new(
+        signing_key: Vec<u8>,
+        timestamp: DateTime<Utc>,
+        scope: CredentialScope,
+        seed_signature: &str,
+    ) -> Self {
+        ChunkSigner {
+            signing_key,
+            timestamp,
+            scope,
+            previous_signature: seed_signature.to_lowercase(),
+        }
+    }
+
+    pub(crate) fn verify_chunk(&mut self, chunk: &[u8], signature: &str) -> bool {
+        let string_to_sign = signature::chunk_string_to_sign(
+            &self.timestamp,
+            &self.scope,
+            &self.previous_signature,
+            chunk,
+        );
+
+        self.verify(&string_to_sign, signature)
+    }
+
+    pub(crate) fn verify_trailer(&mut self, trailer: &[u8], signature: &str) -> bool {
+        let string_to_sign = signature::trailer_string_to_sign(
+            &self.timestamp,
+            &self.scope,
+            &self.previous_signature,
+            trailer,
+        );
+
+        self.verify(&string_to_sign, signature)
+    }
+
+    fn verify(&mut self, string_to_sign: &str, signature: &str) -> bool {
+        if !signature::verify(&self.signing_key, string_to_sign, signature) {
+            return false;
+        }
+
+        self.previous_signature = signature.to_lowercase();
+        true
+    }
+}
+
+/// The outcome of checking a request's signature
+struct Verified {
+    identity: Identity,
+    region: SigningRegion,
+    /// The hex SHA256 the body has to match, if the client signed it
+    payload_sha256: Option<String>,
+    /// Set when the body is sent as signed `aws-chunked` chunks
+    chunk_signer: Option<ChunkSigner>,
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn query_params(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+
+            (
+                percent_decode_str(key).decode_utf8_lossy().into_owned(),
+                percent_decode_str(value).decode_utf8_lossy().into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Split `<access key id>/<date>/<region>/<service>/aws4_request`
+fn parse_credential(credential: &str) -> Option<(String, CredentialScope)> {
+    let (access_key_id, scope) = credential.split_once('/')?;
+    let scope = CredentialScope::parse(scope)
+        .filter(|scope| [SERVICE, STS_SERVICE].contains(&scope.service.as_str()))?;
+
+    Some((access_key_id.into(), scope))
+}
+
+/// Whether a request is the `AssumeRole` call of STS, the only one that may be signed for the
+/// `sts` service
+fn is_sts_request(req: &ServiceRequest) -> bool {
+    req.method() == Method::POST && req.path() == "/" && crate::guard::assume_role(&req.guard_ctx())
+}
+
+fn parse_signed_headers(signed_headers: &str) -> Option<Vec<String>> {
+    let signed_headers: Vec<String> = signed_headers.split(';').map(String::from).collect();
+
+    signed_headers
+        .iter()
+        .any(|name| name == "host")
+        .then_some(signed_headers)
+}
+
+/// The time a request was signed at, taken from `x-amz-date` or, failing that, `Date`
+fn request_time(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    if let Some(timestamp) = header_str(headers, "x-amz-date") {
+        return signature::parse_timestamp(timestamp);
+    }
+
+    header_str(headers, header::DATE.as_str())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+        .map(|date| date.with_timezone(&Utc))
+}
+
+fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Read an `Authorization: AWS4-HMAC-SHA256 Credential=..., SignedHeaders=..., Signature=...`
+/// header
+fn header_signature(
+    headers: &HeaderMap,
+    authorization: &str,
+    now: DateTime<Utc>,
+    max_skew: Duration,
+) -> Result<SignedRequest, Rejection> {
+    let components = authorization
+        .strip_prefix(ALGORITHM)
+        .ok_or(Rejection::AuthorizationHeaderMalformed)?;
+
+    let mut credential = None;
+    let mut signed_headers = None;
+    let mut signature = None;
+
+    for component in components.trim().split(',') {
+        match component.trim().split_once('=') {
+            Some(("Credential", value)) => credential = parse_credential(value),
+            Some(("SignedHeaders", value)) => signed_headers = parse_signed_headers(value),
+            Some(("Signature", value)) => signature = Some(value.to_string()),
+            _ => return Err(Rejection::AuthorizationHeaderMalformed),
+        }
+    }
+
+    let (Some((access_key_id, scope)), Some(signed_headers), Some(signature)) =
+        (credential, signed_headers, signature)
+    else {
+        return Err(Rejection::AuthorizationHeaderMalformed);
+    };
+
+    let timestamp = request_time(headers).ok_or(Rejection::AccessDenied)?;
+    if (now - timestamp)
+        .abs()
+        .to_std()
+        .map_or(true, |skew| skew > max_skew)
+    {
+        return Err(Rejection::RequestTimeTooSkewed);
+    }
+
+    let payload_hash =
+        header_str(headers, "x-amz-content-sha256").ok_or(Rejection::InvalidRequest)?;
+    let placeholders = [
+        UNSIGNED_PAYLOAD,
+        STREAMING_PAYLOAD,
+        STREAMING_PAYLOAD_TRAILER,
+        STREAMING_UNSIGNED_PAYLOAD_TRAILER,
+    ];
+    if !is_sha256(payload_hash) && !placeholders.contains(&payload_hash) {
+        return Err(Rejection::InvalidArgument);
+    }
+
+    Ok(SignedRequest {
+        access_key_id,
+        scope,
+        signed_headers,
+        signature,
+        timestamp,
+        payload_hash: payload_hash.into(),
+    })
+}
+
+/// Read the `X-Amz-*` query parameters of a presigned URL
+fn query_signature(
+    params: &HashMap<String, String>,
+    now: DateTime<Utc>,
+    max_skew: Duration,
+) -> Result<SignedRequest, Rejection> {
+    let param = |name: &str| {
+        params
+            .get(name)
+            .ok_or(Rejection::AuthorizationQueryParametersError)
+    };
+
+    if param("X-Amz-Algorithm")? != ALGORITHM {
+        return Err(Rejection::AuthorizationQueryParametersError);
+    }
+
+    let (access_key_id, scope) = parse_credential(param("X-Amz-Credential")?)
+        .ok_or(Rejection::AuthorizationQueryParametersError)?;
+    let signed_headers = parse_signed_headers(param("X-Amz-SignedHeaders")?)
+        .ok_or(Rejection::AuthorizationQueryParametersError)?;
+    let timestamp = signature::parse_timestamp(param("X-Amz-Date")?)
+        .ok_or(Rejection::AuthorizationQueryParametersError)?;
+    let expires = param("X-Amz-Expires")?
+        .parse::<u64>()
+        .ok()
+        .filter(|expires| (1..=presign::MAX_EXPIRES.as_secs()).contains(expires))
+        .ok_or(Rejection::AuthorizationQueryParametersError)?;
+
+    if timestamp + Duration::from_secs(expires) < now {
+        return Err(Rejection::RequestExpired);
+    }
+    if timestamp > now + max_skew {
+        return Err(Rejection::RequestNotYetValid);
+    }
+
+    Ok(SignedRequest {
+        access_key_id,
+        scope,
+        signed_headers,
+        signature: param("X-Amz-Signature")?.clone(),
+        timestamp,
+        payload_hash: UNSIGNED_PAYLOAD.into(),
+    })
+}
+
+/// The secret of an access key and the caller it authenticates, looked up in the administrators'
+/// credentials and then in the identity store. Disabled keys are as good as unknown.
+async fn secret_access_key(
+    access_key_id: &str,
+    credentials: &Credentials,
+    storage_provider: Option<&dyn StorageProvider>,
+) -> Result<(String, Identity), Rejection> {
+    if let Some(secret_access_key) = credentials.secret_access_key(access_key_id) {
+        let identity = Identity {
+            access_key_id: access_key_id.into(),
+            user: None,
+            session: None,
+        };
+        return Ok((secret_access_key.into(), identity));
+    }
+
+    let storage_provider = storage_provider.ok_or(Rejection::InvalidAccessKeyId)?;
+    let error = |err| match err {
+        StorageErr::AccessKeyNotFound | StorageErr::UserNotFound => Rejection::InvalidAccessKeyId,
+        _ => Rejection::InternalError,
+    };
+    let access_key = storage_provider
+        .get_access_key(access_key_id)
+        .await
+        .map_err(error)?;
+    if access_key.status != AccessKeyStatus::Active {
+        return Err(Rejection::InvalidAccessKeyId);
+    }
+    let user = storage_provider
+        .get_user(&access_key.user_name)
+        .await
+        .map_err(error)?;
+    let identity = Identity {
+        access_key_id: access_key_id.into(),
+        user: Some(user),
+        session: None,
+    };
+
+    Ok((access_key.secret_access_key, identity))
+}
+
+/// The secret of temporary credentials and the caller they act for, scoped by their session
+/// policy. The session token has to be theirs, and they stop working once they expire or their
+/// parent access key does.
+async fn session_secret(
+    access_key_id: &str,
+    session_token: &str,
+    credentials: &Credentials,
+    storage_provider: Option<&dyn StorageProvider>,
+    now: DateTime<Utc>,
+) -> Result<(String, Identity), Rejection> {
+    let storage_provider = storage_provider.ok_or(Rejection::InvalidToken)?;
+    let session = storage_provider
+        .get_session(access_key_id)
+        .await
+        .map_err(|err| match err {
+            StorageErr::SessionNotFound => Rejection::InvalidToken,
+            _ => Rejection::InternalError,
+        })?;
+    if session.session_token != session_token {
+        return Err(Rejection::InvalidToken);
+    }
+    if session.expiration <= now {
+        return Err(Rejection::ExpiredToken);
+    }
+
+    let (_, identity) = secret_access_key(
+        &session.parent_access_key_id,
+        credentials,
+        Some(storage_provider),
+    )
+    .await?;
+    let policy = Policy::parse_identity(&session.policy).map_err(|_| Rejection::InternalError)?;
+    let identity = Identity {
+        session: Some(SessionCredentials {
+            access_key_id: session.access_key_id,
+            policy,
+        }),
+        ..identity
+    };
+
+    Ok((session.secret_access_key, identity))
+}
+
+/// Verify the SigV4 signature of a request, from either its `Authorization` header or its
+/// query string
+async fn authenticate(
+    req: &ServiceRequest,
+    credentials: &Credentials,
+    storage_provider: Option<&dyn StorageProvider>,
+    now: DateTime<Utc>,
+    max_skew: Duration,
+) -> Result<Verified, Rejection> {
+    let headers = req.headers();
+    let params = query_params(req.query_string());
+
+    let signed = if let Some(authorization) = header_str(headers, header::AUTHORIZATION.as_str()) {
+        header_signature(headers, authorization, now, max_skew)?
+    } else if params.contains_key("X-Amz-Algorithm") {
+        query_signature(&params, now, max_skew)?
+    } else {
+        return Err(Rejection::Anonymous);
+    };
+    if signed.scope.service == STS_SERVICE && !is_sts_request(req) {
+        return Err(Rejection::AuthorizationHeaderMalformed);
+    }
+
+    let session_token = header_str(headers, "x-amz-security-token")
+        .or(params.get("X-Amz-Security-Token").map(String::as_str));
+    let (secret_access_key, identity) = match session_token {
+        Some(session_token) => {
+            session_secret(
+                &signed.access_key_id,
+                session_token,
+                credentials,
+                storage_provider,
+                now,
+            )
+            .await?
+        }
+        None => secret_access_key(&signed.access_key_id, credentials, storage_provider).await?,
+    };
+
+    if signed.timestamp.format(signature::DATE_FORMAT).to_string() != signed.scope.date {
+        return Err(Rejection::AuthorizationHeaderMalformed);
+    }
+
+    let canonical_headers = signature::canonical_headers(headers, &signed.signed_headers)
+        .ok_or(Rejection::AuthorizationHeaderMalformed)?;
+    let canonical_request = signature::canonical_request(
+        req.method().as_str(),
+        &signature::canonical_uri(req.path()),
+        &signature::canonical_query_string(req.query_string()),
+        &canonical_headers,
+        &signed.signed_headers,
+        &signed.payload_hash,
+    );
+    let string_to_sign =
+        signature::string_to_sign(&signed.timestamp, &signed.scope, &canonical_request);
+    let signing_key = signature::signing_key(&secret_access_key, &signed.scope);
+
+    if !signature::verify(&signing_key, &string_to_sign, &signed.signature) {
+        return Err(Rejection::SignatureDoesNotMatch);
+    }
+
+    let region = SigningRegion(signed.scope.region.clone());
+    let chunk_signer = [STREAMING_PAYLOAD, STREAMING_PAYLOAD_TRAILER]
+        .contains(&signed.payload_hash.as_str())
+        .then(|| {
+            ChunkSigner::new(
+                signing_key,
+                signed.timestamp,
+                signed.scope,
+                &signed.signature,
+            )
+        });
+
+    Ok(Verified {
+        identity,
+        region,
+        payload_sha256: is_sha256(&signed.payload_hash).then(|| signed.payload_hash.to_lowercase()),
+        chunk_signer,
+    })
+}
@@ -0,0 +1,329 @@
+use actix_web::http::header::HeaderMap;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha2::{Digest, Sha256};
+
+pub const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+pub const STREAMING_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
+pub const STREAMING_PAYLOAD_TRAILER: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER";
+pub const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+pub const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+pub const SCOPE_TERMINATOR: &str = "aws4_request";
+pub const SERVICE: &str = "s3";
+/// The service STS clients sign for, which the S3 port also serves `AssumeRole` to. Other
+/// requests must be signed for `s3`.
+pub const STS_SERVICE: &str = "sts";
+
+pub const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+pub const DATE_FORMAT: &str = "%Y%m%d";
+
+/// Characters that SigV4 leaves unencoded, everything else is percent-encoded
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+const PATH_ENCODE_SET: &AsciiSet = &URI_ENCODE_SET.remove(b'/');
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The `<date>/<region>/<service>/aws4_request` scope a signature is valid for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CredentialScope {
+    pub date: String,
+    pub region: String,
+    pub service: String,
+}
+
+impl CredentialScope {
+    /// Parse the part of a credential that follows the access key id
+    pub(crate) fn parse(scope: &str) -> Option<Self> {
+        let mut parts = scope.split('/');
+        let date = parts.next()?;
+        let region = parts.next()?;
+        let service = parts.next()?;
+
+        if parts.next() != Some(SCOPE_TERMINATOR) || parts.next().is_some() {
+            return None;
+        }
+
+        Some(CredentialScope {
+            date: date.into(),
+            region: region.into(),
+            service: service.into(),
+        })
+    }
+}
+
+impl std::fmt::Display for CredentialScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.date, self.region, self.service, SCOPE_TERMINATOR
+        )
+    }
+}
+
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|timestamp| timestamp.and_utc())
+}
+
+pub fn uri_encode(input: &str) -> String {
+    utf8_percent_encode(input, URI_ENCODE_SET).to_string()
+}
+
+/// Normalize the request path so it's encoded exactly once, keeping the slashes
+pub fn canonical_uri(path: &str) -> String {
+    let decoded = percent_decode_str(path).decode_utf8_lossy();
+    let encoded = utf8_percent_encode(&decoded, PATH_ENCODE_SET).to_string();
+
+    if encoded.is_empty() {
+        "/".into()
+    } else {
+        encoded
+    }
+}
+
+/// Sort and re-encode the query parameters, leaving out `X-Amz-Signature` since a signature
+/// can't sign itself
+pub fn canonical_query_string(query: &str) -> String {
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+
+            (
+                uri_encode(&percent_decode_str(key).decode_utf8_lossy()),
+                uri_encode(&percent_decode_str(value).decode_utf8_lossy()),
+            )
+        })
+        .filter(|(key, _)| key != "X-Amz-Signature")
+        .collect();
+    params.sort();
+
+    params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Build the `name:value` lines of the signed headers, with their values trimmed and folded
+/// the way SigV4 expects. Returns `None` if a signed header is missing from the request.
+pub fn canonical_headers(headers: &HeaderMap, signed_headers: &[String]) -> Option<String> {
+    signed_headers
+        .iter()
+        .map(|name| {
+            let values: Vec<_> = headers
+                .get_all(name.as_str())
+                .map(|value| {
+                    String::from_utf8_lossy(value.as_bytes())
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+
+            if values.is_empty() {
+                None
+            } else {
+                Some(format!("{}:{}\n", name, values.join(",")))
+            }
+        })
+        .collect()
+}
+
+pub fn canonical_request(
+    method: &str,
+    canonical_uri: &str,
+    canonical_query_string: &str,
+    canonical_headers: &str,
+    signed_headers: &[String],
+    payload_hash: &str,
+) -> String {
+    [
+        method,
+        canonical_uri,
+        canonical_query_string,
+        canonical_headers,
+        &signed_headers.join(";"),
+        payload_hash,
+    ]
+    .join("\n")
+}
+
+pub fn string_to_sign(
+    timestamp: &DateTime<Utc>,
+    scope: &CredentialScope,
+    canonical_request: &str,
+) -> String {
+    [
+        ALGORITHM,
+        &timestamp.format(TIMESTAMP_FORMAT).to_string(),
+        &scope.to_string(),
+        &hex::encode(Sha256::digest(canonical_request.as_bytes())),
+    ]
+    .join("\n")
+}
+
+/// The string signed for each chunk of an `aws-chunked` body, chained to the signature of the
+/// chunk before it
+pub fn chunk_string_to_sign(
+    timestamp: &DateTime<Utc>,
+    scope: &CredentialScope,
+    previous_signature: &str,
+    chunk: &[u8],
+) -> String {
+    [
+        "AWS4-HMAC-SHA256-PAYLOAD",
+        &timestamp.format(TIMESTAMP_FORMAT).to_string(),
+        &scope.to_string(),
+        previous_signature,
+        EMPTY_SHA256,
+        &hex::encode(Sha256::digest(chunk)),
+    ]
+    .join("\n")
+}
+
+/// The string signed for the trailing headers of an `aws-chunked` body
+pub fn trailer_string_to_sign(
+    timestamp: &DateTime<Utc>,
+    scope: &CredentialScope,
+    previous_signature: &str,
+    trailer: &[u8],
+) -> String {
+    [
+        "AWS4-HMAC-SHA256-TRAILER",
+        &timestamp.format(TIMESTAMP_FORMAT).to_string(),
+        &scope.to_string(),
+        previous_signature,
+        &hex::encode(Sha256::digest(trailer)),
+    ]
+    .join("\n")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(data);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub fn signing_key(secret_access_key: &str, scope: &CredentialScope) -> Vec<u8> {
+    let key = hmac(
+        format!("AWS4{secret_access_key}").as_bytes(),
+        scope.date.as_bytes(),
+    );
+    let key = hmac(&key, scope.region.as_bytes());
+    let key = hmac(&key, scope.service.as_bytes());
+
+    hmac(&key, SCOPE_TERMINATOR.as_bytes())
+}
+
+pub fn sign(signing_key: &[u8], string_to_sign: &str) -> String {
+    hex::encode(hmac(signing_key, string_to_sign.as_bytes()))
+}
+
+/// Compare a signature without leaking how much of it matched through timing
+pub fn verify(signing_key: &[u8], string_to_sign: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(signing_key).expect("HMAC can take a key of any size");
+    mac.update(string_to_sign.as_bytes());
+
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    const SECRET_ACCESS_KEY: &str = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+
+    // The GET Object example from the Amazon S3 SigV4 documentation
+    #[test]
+    fn test_get_object_example() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("host", "examplebucket.s3.amazonaws.com"),
+            ("range", "bytes=0-9"),
+            ("x-amz-content-sha256", EMPTY_SHA256),
+            ("x-amz-date", "20130524T000000Z"),
+        ] {
+            headers.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        let signed_headers: Vec<String> = ["host", "range", "x-amz-content-sha256", "x-amz-date"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let scope = CredentialScope::parse("20130524/us-east-1/s3/aws4_request").unwrap();
+
+        let canonical_request = canonical_request(
+            "GET",
+            &canonical_uri("/test.txt"),
+            &canonical_query_string(""),
+            &canonical_headers(&headers, &signed_headers).unwrap(),
+            &signed_headers,
+            EMPTY_SHA256,
+        );
+        let string_to_sign = string_to_sign(
+            &parse_timestamp("20130524T000000Z").unwrap(),
+            &scope,
+            &canonical_request,
+        );
+        let key = signing_key(SECRET_ACCESS_KEY, &scope);
+
+        assert_eq!(
+            sign(&key, &string_to_sign),
+            "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+        );
+        assert!(verify(
+            &key,
+            &string_to_sign,
+            "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+        ));
+        assert!(!verify(&key, &string_to_sign, "f0e8bdb8"));
+    }
+
+    #[test]
+    fn test_canonical_query_string() {
+        assert_eq!(
+            canonical_query_string("prefix=a%20b&list-type=2&acl"),
+            "acl=&list-type=2&prefix=a%20b"
+        );
+        assert_eq!(
+            canonical_query_string("X-Amz-Signature=abc&X-Amz-Date=20130524T000000Z"),
+            "X-Amz-Date=20130524T000000Z"
+        );
+    }
+
+    #[test]
+    fn test_canonical_uri() {
+        assert_eq!(canonical_uri(""), "/");
+        assert_eq!(canonical_uri("/bucket/a b/c%2Bd"), "/bucket/a%20b/c%2Bd");
+    }
+
+    #[test]
+    fn test_credential_scope() {
+        assert_eq!(
+            CredentialScope::parse("20130524/us-east-1/s3/aws4_request"),
+            Some(CredentialScope {
+                date: "20130524".into(),
+                region: "us-east-1".into(),
+                service: "s3".into(),
+            })
+        );
+        assert_eq!(CredentialScope::parse("20130524/us-east-1/s3"), None);
+    }
+}
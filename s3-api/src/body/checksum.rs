@@ -0,0 +1,83 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crc::{Crc, Digest, CRC_32_ISCSI, CRC_32_ISO_HDLC};
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256};
+
+static CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+static CRC32C: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+/// One of the `x-amz-checksum-*` checksums a client can send along with a body
+pub(crate) enum Checksum {
+    Crc32(Digest<'static, u32>),
+    Crc32c(Digest<'static, u32>),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl Checksum {
+    /// Start the checksum named by a header such as `x-amz-checksum-crc32`
+    pub(crate) fn from_header(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "x-amz-checksum-crc32" => Some(Checksum::Crc32(CRC32.digest())),
+            "x-amz-checksum-crc32c" => Some(Checksum::Crc32c(CRC32C.digest())),
+            "x-amz-checksum-sha1" => Some(Checksum::Sha1(Sha1::new())),
+            "x-amz-checksum-sha256" => Some(Checksum::Sha256(Sha256::new())),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn header(&self) -> &'static str {
+        match self {
+            Checksum::Crc32(_) => "x-amz-checksum-crc32",
+            Checksum::Crc32c(_) => "x-amz-checksum-crc32c",
+            Checksum::Sha1(_) => "x-amz-checksum-sha1",
+            Checksum::Sha256(_) => "x-amz-checksum-sha256",
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Checksum::Crc32(digest) | Checksum::Crc32c(digest) => digest.update(data),
+            Checksum::Sha1(hasher) => hasher.update(data),
+            Checksum::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// The checksum base64 encoded, the way it's sent in headers
+    pub(crate) fn finalize(self) -> String {
+        match self {
+            Checksum::Crc32(digest) | Checksum::Crc32c(digest) => {
+                STANDARD.encode(digest.finalize().to_be_bytes())
+            }
+            Checksum::Sha1(hasher) => STANDARD.encode(hasher.finalize()),
+            Checksum::Sha256(hasher) => STANDARD.encode(hasher.finalize()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checksum(header: &str, data: &[u8]) -> String {
+        let mut checksum = Checksum::from_header(header).unwrap();
+        checksum.update(data);
+
+        checksum.finalize()
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(checksum("x-amz-checksum-crc32", b"123456789"), "y/Q5Jg==");
+        assert_eq!(checksum("X-Amz-Checksum-CRC32C", b"123456789"), "4waSgw==");
+        assert_eq!(
+            checksum("x-amz-checksum-sha1", b"meow"),
+            "fVwqLWE2+/FmIR1Rg79mIUokfzE="
+        );
+        assert_eq!(
+            checksum("x-amz-checksum-sha256", b"meow"),
+            "QEzde8EJxDL4zCRDtFvP6VmA9RByFcZFI25XeSmsPlI="
+        );
+        assert!(Checksum::from_header("x-amz-checksum-md5").is_none());
+    }
+}
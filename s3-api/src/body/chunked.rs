@@ -0,0 +1,382 @@
+//! Decoding of the `aws-chunked` bodies SDKs send for streaming uploads:
+//!
+//! ```text
+//! <hex size>[;chunk-signature=<signature>]\r\n<data>\r\n
+//! ...
+//! 0[;chunk-signature=<signature>]\r\n
+//! [<trailer>:<value>\r\n]
+//! [x-amz-trailer-signature:<signature>\r\n]
+//! \r\n
+//! ```
+
+use super::checksum::Checksum;
+use crate::auth::ChunkSigner;
+use bytes::{Bytes, BytesMut};
+use futures::{stream, StreamExt};
+use s3_entities::storage_provider::{ByteStream, StorageErr};
+
+/// Longest chunk header or trailer line that's accepted
+const MAX_LINE_LENGTH: usize = 4096;
+/// Chunks are verified before they're passed on, so each one has to fit in memory
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+const TRAILER_SIGNATURE: &str = "x-amz-trailer-signature";
+
+struct Decoder {
+    body: ByteStream,
+    buffer: BytesMut,
+    signer: Option<ChunkSigner>,
+    checksum: Option<Checksum>,
+    decoded_length: Option<u64>,
+    decoded: u64,
+}
+
+/// Decode an `aws-chunked` body into its data.
+///
+/// Every chunk is checked against `signer` when the body is signed, the declared trailing
+/// `checksum` is checked once the body ends and so is `decoded_length`, the
+/// `x-amz-decoded-content-length` of the body.
+pub(crate) fn decode(
+    body: ByteStream,
+    signer: Option<ChunkSigner>,
+    checksum: Option<Checksum>,
+    decoded_length: Option<u64>,
+) -> ByteStream {
+    let decoder = Decoder {
+        body,
+        buffer: BytesMut::new(),
+        signer,
+        checksum,
+        decoded_length,
+        decoded: 0,
+    };
+
+    Box::pin(stream::unfold(Some(decoder), |decoder| async move {
+        let mut decoder = decoder?;
+
+        match decoder.next_chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk), Some(decoder))),
+            Ok(None) => None,
+            Err(err) => Some((Err(err), None)),
+        }
+    }))
+}
+
+impl Decoder {
+    async fn fill(&mut self) -> Result<(), StorageErr> {
+        match self.body.next().await {
+            Some(Ok(chunk)) => {
+                self.buffer.extend_from_slice(&chunk);
+                Ok(())
+            }
+            Some(Err(err)) => Err(err),
+            None => Err(StorageErr::IncompleteBody),
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String, StorageErr> {
+        loop {
+            if let Some(end) = self.buffer.windows(2).position(|window| window == b"\r\n") {
+                let line = self.buffer.split_to(end + 2);
+
+                return String::from_utf8(line[..end].to_vec())
+                    .map_err(|_| StorageErr::IncompleteBody);
+            }
+            if self.buffer.len() > MAX_LINE_LENGTH {
+                return Err(StorageErr::IncompleteBody);
+            }
+
+            self.fill().await?;
+        }
+    }
+
+    async fn read_exact(&mut self, length: usize) -> Result<Bytes, StorageErr> {
+        while self.buffer.len() < length {
+            self.fill().await?;
+        }
+
+        Ok(self.buffer.split_to(length).freeze())
+    }
+
+    /// Read the next chunk of data, or `None` once the final chunk and trailer are read
+    async fn next_chunk(&mut self) -> Result<Option<Bytes>, StorageErr> {
+        let header = self.read_line().await?;
+        let (size, signature) = match header.split_once(';') {
+            Some((size, extension)) => (
+                size,
+                Some(
+                    extension
+                        .strip_prefix("chunk-signature=")
+                        .ok_or(StorageErr::IncompleteBody)?,
+                ),
+            ),
+            None => (header.as_str(), None),
+        };
+        let size = usize::from_str_radix(size.trim(), 16)
+            .ok()
+            .filter(|size| *size <= MAX_CHUNK_SIZE)
+            .ok_or(StorageErr::IncompleteBody)?;
+
+        let data = self.read_exact(size).await?;
+        if let Some(signer) = &mut self.signer {
+            let signature = signature.ok_or(StorageErr::SignatureDoesNotMatch)?;
+
+            if !signer.verify_chunk(&data, signature) {
+                return Err(StorageErr::SignatureDoesNotMatch);
+            }
+        }
+
+        if size == 0 {
+            self.read_trailer().await?;
+
+            return match self.decoded_length {
+                Some(decoded_length) if decoded_length != self.decoded => {
+                    Err(StorageErr::IncompleteBody)
+                }
+                _ => Ok(None),
+            };
+        }
+
+        if !self.read_line().await?.is_empty() {
+            return Err(StorageErr::IncompleteBody);
+        }
+
+        self.decoded += size as u64;
+        if matches!(self.decoded_length, Some(decoded_length) if self.decoded > decoded_length) {
+            return Err(StorageErr::IncompleteBody);
+        }
+        if let Some(checksum) = &mut self.checksum {
+            checksum.update(&data);
+        }
+
+        Ok(Some(data))
+    }
+
+    /// Read the trailing headers after the final chunk and check them against the body
+    async fn read_trailer(&mut self) -> Result<(), StorageErr> {
+        let mut trailers = Vec::new();
+        let mut trailer_signature = None;
+
+        loop {
+            let line = match self.read_line().await {
+                // Bodies without a trailer may end right after the final chunk
+                Err(StorageErr::IncompleteBody)
+                    if self.buffer.is_empty() && trailers.is_empty() && self.checksum.is_none() =>
+                {
+                    break
+                }
+                line => line?,
+            };
+            if line.is_empty() {
+                break;
+            }
+
+            let (name, value) = line.split_once(':').ok_or(StorageErr::IncompleteBody)?;
+            let name = name.trim().to_lowercase();
+            let value = value.trim().to_string();
+
+            if name == TRAILER_SIGNATURE {
+                trailer_signature = Some(value);
+            } else {
+                trailers.push((name, value));
+            }
+        }
+
+        if let (Some(signer), false) = (&mut self.signer, trailers.is_empty()) {
+            let canonical_trailer: String = trailers
+                .iter()
+                .map(|(name, value)| format!("{name}:{value}\n"))
+                .collect();
+            let signature = trailer_signature.ok_or(StorageErr::SignatureDoesNotMatch)?;
+
+            if !signer.verify_trailer(canonical_trailer.as_bytes(), &signature) {
+                return Err(StorageErr::SignatureDoesNotMatch);
+            }
+        }
+
+        if let Some(checksum) = self.checksum.take() {
+            let expected = trailers
+                .iter()
+                .find(|(name, _)| name == checksum.header())
+                .map(|(_, value)| value.clone())
+                .ok_or(StorageErr::IncompleteBody)?;
+
+            if checksum.finalize() != expected {
+                return Err(StorageErr::BadDigest);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::signature::{self, CredentialScope};
+    use futures::TryStreamExt;
+
+    const SECRET_ACCESS_KEY: &str = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+
+    /// Split a body into uneven pieces so chunk boundaries don't line up with the stream's
+    fn body(data: Vec<u8>) -> ByteStream {
+        let pieces: Vec<_> = data
+            .chunks(1000)
+            .map(|piece| Ok(Bytes::copy_from_slice(piece)))
+            .collect();
+
+        Box::pin(stream::iter(pieces))
+    }
+
+    async fn collect(body: ByteStream) -> Result<Vec<u8>, StorageErr> {
+        let chunks: Vec<Bytes> = body.try_collect().await?;
+
+        Ok(chunks.concat())
+    }
+
+    fn signer() -> ChunkSigner {
+        let scope = CredentialScope::parse("20130524/us-east-1/s3/aws4_request").unwrap();
+
+        ChunkSigner::new(
+            signature::signing_key(SECRET_ACCESS_KEY, &scope),
+            signature::parse_timestamp("20130524T000000Z").unwrap(),
+            scope,
+            "4f232c4386841ef735655705268965c44a0e4690baa4adea153f7db9fa80a0a9",
+        )
+    }
+
+    /// The chunked upload example from the Amazon S3 SigV4 documentation
+    fn signed_example() -> Vec<u8> {
+        let mut encoded = Vec::new();
+
+        encoded.extend_from_slice(b"10000;chunk-signature=ad80c730a21e5b8d04586a2213dd63b9a0e99e0e2307b0ade35a65485a288648\r\n");
+        encoded.extend_from_slice(&[b'a'; 65536]);
+        encoded.extend_from_slice(b"\r\n400;chunk-signature=0055627c9e194cb4542bae2aa5492e3c1575bbb81b612b7d234b86a503ef5497\r\n");
+        encoded.extend_from_slice(&[b'a'; 1024]);
+        encoded.extend_from_slice(b"\r\n0;chunk-signature=b6c6ea8a5354eaf15b3cb7646744f4275b71ea724fed81ceb9323e279d449df9\r\n\r\n");
+
+        encoded
+    }
+
+    #[actix_web::test]
+    async fn test_signed_chunks() {
+        let data = collect(decode(
+            body(signed_example()),
+            Some(signer()),
+            None,
+            Some(66560),
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(data, vec![b'a'; 66560]);
+    }
+
+    #[actix_web::test]
+    async fn test_tampered_chunk() {
+        let mut encoded = signed_example();
+        let position = encoded.len() - 200;
+        encoded[position] = b'b';
+
+        let result = collect(decode(body(encoded), Some(signer()), None, None)).await;
+
+        assert!(matches!(result, Err(StorageErr::SignatureDoesNotMatch)));
+    }
+
+    #[actix_web::test]
+    async fn test_signed_trailer() {
+        let scope = CredentialScope::parse("20130524/us-east-1/s3/aws4_request").unwrap();
+        let timestamp = signature::parse_timestamp("20130524T000000Z").unwrap();
+        let key = signature::signing_key(SECRET_ACCESS_KEY, &scope);
+        let seed = "4f232c4386841ef735655705268965c44a0e4690baa4adea153f7db9fa80a0a9";
+
+        let first = signature::sign(
+            &key,
+            &signature::chunk_string_to_sign(&timestamp, &scope, seed, b"meow"),
+        );
+        let last = signature::sign(
+            &key,
+            &signature::chunk_string_to_sign(&timestamp, &scope, &first, b""),
+        );
+        let trailer = signature::sign(
+            &key,
+            &signature::trailer_string_to_sign(
+                &timestamp,
+                &scope,
+                &last,
+                b"x-amz-checksum-crc32:ihBq/g==\n",
+            ),
+        );
+        let encoded = format!(
+            "4;chunk-signature={first}\r\nmeow\r\n0;chunk-signature={last}\r\nx-amz-checksum-crc32:ihBq/g==\r\nx-amz-trailer-signature:{trailer}\r\n\r\n"
+        );
+
+        let checksum = Checksum::from_header("x-amz-checksum-crc32");
+        let data = collect(decode(
+            body(encoded.clone().into()),
+            Some(signer()),
+            checksum,
+            None,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(data, b"meow");
+
+        let tampered = encoded.replace(&trailer, &last);
+        let checksum = Checksum::from_header("x-amz-checksum-crc32");
+        let result = collect(decode(
+            body(tampered.into()),
+            Some(signer()),
+            checksum,
+            None,
+        ))
+        .await;
+        assert!(matches!(result, Err(StorageErr::SignatureDoesNotMatch)));
+    }
+
+    #[actix_web::test]
+    async fn test_missing_chunk_signature() {
+        let encoded = b"4\r\nmeow\r\n0\r\n\r\n".to_vec();
+        let result = collect(decode(body(encoded), Some(signer()), None, None)).await;
+
+        assert!(matches!(result, Err(StorageErr::SignatureDoesNotMatch)));
+    }
+
+    #[actix_web::test]
+    async fn test_unsigned_trailer() {
+        let encoded = b"2\r\nme\r\n2\r\now\r\n0\r\nx-amz-checksum-crc32:ihBq/g==\r\n\r\n".to_vec();
+        let checksum = Checksum::from_header("x-amz-checksum-crc32");
+        let data = collect(decode(body(encoded), None, checksum, Some(4)))
+            .await
+            .unwrap();
+
+        assert_eq!(data, b"meow");
+    }
+
+    #[actix_web::test]
+    async fn test_bad_trailing_checksum() {
+        let encoded = b"4\r\nmeow\r\n0\r\nx-amz-checksum-crc32:AAAAAA==\r\n\r\n".to_vec();
+        let checksum = Checksum::from_header("x-amz-checksum-crc32");
+        let result = collect(decode(body(encoded), None, checksum, None)).await;
+
+        assert!(matches!(result, Err(StorageErr::BadDigest)));
+    }
+
+    #[actix_web::test]
+    async fn test_decoded_length_mismatch() {
+        let encoded = b"4\r\nmeow\r\n0\r\n\r\n".to_vec();
+        let result = collect(decode(body(encoded.clone()), None, None, Some(5))).await;
+        assert!(matches!(result, Err(StorageErr::IncompleteBody)));
+
+        let result = collect(decode(body(encoded), None, None, Some(3))).await;
+        assert!(matches!(result, Err(StorageErr::IncompleteBody)));
+    }
+
+    #[actix_web::test]
+    async fn test_truncated_body() {
+        let encoded = b"8\r\nmeow".to_vec();
+        let result = collect(decode(body(encoded), None, None, None)).await;
+
+        assert!(matches!(result, Err(StorageErr::IncompleteBody)));
+    }
+}
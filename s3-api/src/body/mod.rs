@@ -0,0 +1,87 @@
+use crate::auth::{
+    signature::{STREAMING_PAYLOAD, STREAMING_PAYLOAD_TRAILER, STREAMING_UNSIGNED_PAYLOAD_TRAILER},
+    ChunkSigner, PayloadSha256Mismatch,
+};
+use actix_web::{error::PayloadError, web, HttpMessage, HttpRequest};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::{channel::mpsc, future, stream, SinkExt, StreamExt};
+use md5::{Digest, Md5};
+use s3_entities::storage_provider::{ByteStream, StorageErr};
+
+mod checksum;
+mod chunked;
+
+use checksum::Checksum;
+
+const PAYLOAD_BUFFER_SIZE: usize = 16;
+
+/// Turn a request payload into a [`ByteStream`] that can be handed to a storage provider,
+/// decoding it first if it was sent `aws-chunked`.
+///
+/// Actix payloads aren't `Send`, so the chunks are forwarded through a bounded channel from a
+/// task running on the worker's local set. The channel keeps the upload back-pressured by the
+/// provider consuming it.
+pub(crate) fn payload_stream(req: &HttpRequest, mut payload: web::Payload) -> ByteStream {
+    let (mut tx, rx) = mpsc::channel(PAYLOAD_BUFFER_SIZE);
+
+    actix_web::rt::spawn(async move {
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.map_err(|err| match err {
+                PayloadError::Io(err)
+                    if err
+                        .get_ref()
+                        .is_some_and(|err| err.is::<PayloadSha256Mismatch>()) =>
+                {
+                    StorageErr::ContentSha256Mismatch
+                }
+                err => StorageErr::IOErr(err.to_string().into()),
+            });
+
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let body: ByteStream = Box::pin(rx);
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+
+    let signer = match header("x-amz-content-sha256") {
+        Some(STREAMING_PAYLOAD | STREAMING_PAYLOAD_TRAILER) => {
+            match req.extensions().get::<ChunkSigner>().cloned() {
+                Some(signer) => Some(signer),
+                None => {
+                    return Box::pin(stream::once(future::ready(Err(
+                        StorageErr::SignatureDoesNotMatch,
+                    ))))
+                }
+            }
+        }
+        Some(STREAMING_UNSIGNED_PAYLOAD_TRAILER) => None,
+        _ => return body,
+    };
+    let checksum = header("x-amz-trailer").and_then(Checksum::from_header);
+    let decoded_length =
+        header("x-amz-decoded-content-length").and_then(|value| value.parse().ok());
+
+    chunked::decode(body, signer, checksum, decoded_length)
+}
+
+/// Check a buffered body against the `Content-MD5` or `x-amz-checksum-*` header sent along with
+/// it, `None` meaning the request had neither
+pub(crate) fn verify_integrity(req: &HttpRequest, body: &[u8]) -> Option<bool> {
+    if let Some(content_md5) = req.headers().get("content-md5") {
+        return Some(content_md5.as_bytes() == STANDARD.encode(Md5::digest(body)).as_bytes());
+    }
+
+    req.headers().iter().find_map(|(name, value)| {
+        let mut checksum = Checksum::from_header(name.as_str())?;
+        checksum.update(body);
+
+        Some(value.as_bytes() == checksum.finalize().as_bytes())
+    })
+}
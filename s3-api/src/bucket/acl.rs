@@ -0,0 +1,332 @@
+extern crate self as s3_api;
+
+use crate::{
+    acl::{acl_from_headers, acl_from_xml, acl_to_xml, bucket_acl, object_ownership, AclError},
+    body::verify_integrity,
+    generate_request_id,
+    xml::AccessControlPolicy,
+};
+use actix_web::{get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    acl::AccessControlList,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum GetBucketAclError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::acl",
+    wrap = "crate::policy::Authorize::action(\"s3:GetBucketAcl\")"
+)]
+pub async fn get_bucket_acl(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketAclError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let acl = bucket_acl(storage_provider.into_inner().as_ref(), &bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketAclError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => GetBucketAclError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(quick_xml::se::to_string(&acl_to_xml(acl)).unwrap()))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketAclError {
+    #[error(status_code = 400, message = "The bucket does not allow ACLs.")]
+    AccessControlListNotSupported {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedACLError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Replace the ACL of a bucket with the one given in the headers, or else in the body. A bucket
+/// with ACLs disabled only accepts the ACL giving its owner full control.
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::acl",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketAcl\")"
+)]
+pub async fn put_bucket_acl(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketAclError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutBucketAclError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| {
+        let (request_id, resource) = (request_id.clone(), resource.clone());
+        match e {
+            AclError::Malformed => PutBucketAclError::MalformedACLError {
+                request_id,
+                resource,
+            },
+            AclError::InvalidArgument => PutBucketAclError::InvalidArgument {
+                request_id,
+                resource,
+            },
+            AclError::InvalidRequest => PutBucketAclError::InvalidRequest {
+                request_id,
+                resource,
+            },
+            AclError::UnresolvableGrantByEmailAddress => {
+                PutBucketAclError::UnresolvableGrantByEmailAddress {
+                    request_id,
+                    resource,
+                }
+            }
+            AclError::NotSupported => PutBucketAclError::AccessControlListNotSupported {
+                request_id,
+                resource,
+            },
+            AclError::Storage(StorageErr::BucketNotFound) => PutBucketAclError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            AclError::Storage(_) => PutBucketAclError::InternalError {
+                request_id,
+                resource,
+            },
+        }
+    };
+
+    let owner = bucket_acl(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(|e| error(e.into()))?
+        .owner;
+    let acl = match acl_from_headers(req.headers(), owner.clone(), &owner).map_err(error)? {
+        Some(acl) => acl,
+        None => {
+            let policy = quick_xml::de::from_reader::<_, AccessControlPolicy>(body.as_ref())
+                .map_err(|_| error(AclError::Malformed))?;
+            acl_from_xml(policy, owner.clone()).map_err(error)?
+        }
+    };
+
+    let ownership = object_ownership(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(|e| error(e.into()))?;
+    if ownership.acls_disabled() && acl != AccessControlList::private(owner) {
+        return Err(error(AclError::NotSupported));
+    }
+
+    storage_provider
+        .put_bucket_acl(&bucket, acl)
+        .await
+        .map_err(|e| error(e.into()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::{
+        acl::{ObjectOwnership, Permission},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    const ACL: &str = "<AccessControlPolicy>\
+            <Owner><ID>someone</ID></Owner>\
+            <AccessControlList>\
+                <Grant>\
+                    <Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"Group\">\
+                        <URI>http://acs.amazonaws.com/groups/global/AuthenticatedUsers</URI>\
+                    </Grantee>\
+                    <Permission>READ</Permission>\
+                </Grant>\
+            </AccessControlList>\
+        </AccessControlPolicy>";
+
+    #[actix_web::test]
+    async fn test_bucket_acl() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_acl)
+                .service(put_bucket_acl),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?acl")
+                .to_request()
+        };
+
+        // Buckets that were never given an ACL are private to the default owner
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("<ID>AIDACKCEVSQ6C2EXAMPLE</ID>"));
+        assert!(body.contains("<Permission>FULL_CONTROL</Permission>"));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?acl")
+            .insert_header(("x-amz-acl", "public-read"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let acl = provider.get_bucket_acl("bucket").await.unwrap();
+        assert!(acl.allows(None, Permission::Read));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?acl")
+            .set_payload(ACL)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let acl = provider.get_bucket_acl("bucket").await.unwrap();
+        assert_eq!(acl.owner.id, "AIDACKCEVSQ6C2EXAMPLE");
+        assert!(!acl.allows(None, Permission::Read));
+        assert!(acl.allows(Some("someone"), Permission::Read));
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains(
+            "<Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"Group\">\
+                <URI>http://acs.amazonaws.com/groups/global/AuthenticatedUsers</URI>\
+            </Grantee>"
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_bucket_acl() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_bucket_acl),
+        )
+        .await;
+        let put = |acl: &'static str| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket?acl")
+                .insert_header(("x-amz-acl", acl))
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, put("everyone")).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>InvalidArgument</Code>"));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?acl")
+            .set_payload(ACL.replace("READ", "LIST"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>MalformedACLError</Code>"));
+
+        provider
+            .put_bucket_ownership_controls("bucket", Some(ObjectOwnership::BucketOwnerEnforced))
+            .await
+            .unwrap();
+        let resp = actix_web::test::call_service(&app, put("public-read")).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(
+            String::from_utf8_lossy(&body).contains("<Code>AccessControlListNotSupported</Code>")
+        );
+        let resp = actix_web::test::call_service(&app, put("private")).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+}
@@ -0,0 +1,394 @@
+extern crate self as s3_api;
+
+use crate::{body::verify_integrity, generate_request_id, xml};
+use actix_web::{delete, get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    cors::{CorsConfiguration, CorsRule, CORS_METHODS, MAX_CORS_RULES, MAX_RULE_ID_LENGTH},
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+/// Why a CORS configuration was refused
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidConfiguration {
+    /// It doesn't have the shape S3 expects, such as a rule without any origin
+    Malformed,
+    /// It's well-formed but one of its values isn't allowed
+    Request,
+}
+
+fn parse_rule(rule: xml::CorsRule) -> Result<CorsRule, InvalidConfiguration> {
+    if rule.allowed_origins.is_empty() || rule.allowed_methods.is_empty() {
+        return Err(InvalidConfiguration::Malformed);
+    }
+
+    let many_wildcards = |value: &String| value.matches('*').count() > 1;
+    if rule
+        .id
+        .as_ref()
+        .is_some_and(|id| id.chars().count() > MAX_RULE_ID_LENGTH)
+        || rule
+            .allowed_methods
+            .iter()
+            .any(|method| !CORS_METHODS.contains(&method.as_str()))
+        || rule.allowed_origins.iter().any(many_wildcards)
+        || rule.allowed_headers.iter().any(many_wildcards)
+    {
+        return Err(InvalidConfiguration::Request);
+    }
+
+    Ok(CorsRule {
+        id: rule.id,
+        allowed_origins: rule.allowed_origins,
+        allowed_methods: rule.allowed_methods,
+        allowed_headers: rule.allowed_headers,
+        expose_headers: rule.expose_headers,
+        max_age_seconds: rule.max_age_seconds,
+    })
+}
+
+/// Check a CORS configuration the way S3 does, which takes between one and [`MAX_CORS_RULES`]
+/// rules
+fn parse_configuration(
+    configuration: xml::CorsConfiguration,
+) -> Result<CorsConfiguration, InvalidConfiguration> {
+    if configuration.rules.is_empty() {
+        return Err(InvalidConfiguration::Malformed);
+    }
+    if configuration.rules.len() > MAX_CORS_RULES {
+        return Err(InvalidConfiguration::Request);
+    }
+
+    Ok(CorsConfiguration {
+        rules: configuration
+            .rules
+            .into_iter()
+            .map(parse_rule)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+#[derive(Debug, S3Error)]
+enum GetBucketCorsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The CORS configuration does not exist.")]
+    NoSuchCORSConfiguration {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::cors",
+    wrap = "crate::policy::Authorize::action(\"s3:GetBucketCORS\")"
+)]
+pub async fn get_bucket_cors(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketCorsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let configuration = storage_provider
+        .into_inner()
+        .get_bucket_cors(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketCorsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::NoSuchCORSConfiguration => GetBucketCorsError::NoSuchCORSConfiguration {
+                request_id,
+                resource,
+            },
+            _ => GetBucketCorsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    let rules = configuration
+        .rules
+        .into_iter()
+        .map(|rule| xml::CorsRule {
+            id: rule.id,
+            allowed_headers: rule.allowed_headers,
+            allowed_methods: rule.allowed_methods,
+            allowed_origins: rule.allowed_origins,
+            expose_headers: rule.expose_headers,
+            max_age_seconds: rule.max_age_seconds,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(quick_xml::se::to_string(&xml::CorsConfiguration { rules }).unwrap()))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketCorsError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The CORS configuration has a rule that isn't valid."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::cors",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketCORS\")"
+)]
+pub async fn put_bucket_cors(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketCorsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutBucketCorsError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let configuration = quick_xml::de::from_reader(body.as_ref())
+        .map_err(|_| InvalidConfiguration::Malformed)
+        .and_then(parse_configuration);
+    let configuration = match configuration {
+        Ok(configuration) => configuration,
+        Err(InvalidConfiguration::Malformed) => {
+            return Err(PutBucketCorsError::MalformedXML {
+                request_id,
+                resource,
+            })
+        }
+        Err(InvalidConfiguration::Request) => {
+            return Err(PutBucketCorsError::InvalidRequest {
+                request_id,
+                resource,
+            })
+        }
+    };
+
+    storage_provider
+        .into_inner()
+        .put_bucket_cors(&bucket, configuration)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => PutBucketCorsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => PutBucketCorsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteBucketCorsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}",
+    guard = "crate::guard::cors",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketCORS\")"
+)]
+pub async fn delete_bucket_cors(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteBucketCorsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    storage_provider
+        .into_inner()
+        .put_bucket_cors(&bucket, CorsConfiguration::default())
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => DeleteBucketCorsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => DeleteBucketCorsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::test::storage_provider::get_mock_app_data;
+
+    fn rules(rules: &str) -> Result<CorsConfiguration, InvalidConfiguration> {
+        let configuration: xml::CorsConfiguration =
+            quick_xml::de::from_str(&format!("<CORSConfiguration>{rules}</CORSConfiguration>"))
+                .map_err(|_| InvalidConfiguration::Malformed)?;
+
+        parse_configuration(configuration)
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        for (rules_, invalid) in [
+            ("", InvalidConfiguration::Malformed),
+            (
+                "<CORSRule><AllowedMethod>GET</AllowedMethod></CORSRule>",
+                InvalidConfiguration::Malformed,
+            ),
+            (
+                "<CORSRule><AllowedOrigin>*</AllowedOrigin><AllowedMethod>PATCH</AllowedMethod></CORSRule>",
+                InvalidConfiguration::Request,
+            ),
+            (
+                "<CORSRule><AllowedOrigin>https://*.*.com</AllowedOrigin><AllowedMethod>GET</AllowedMethod></CORSRule>",
+                InvalidConfiguration::Request,
+            ),
+        ] {
+            assert_eq!(rules(rules_).unwrap_err(), invalid, "{rules_}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_bucket_cors() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_cors)
+                .service(put_bucket_cors)
+                .service(delete_bucket_cors),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?cors")
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let configuration = "<CORSConfiguration><CORSRule>\
+                <ID>uploads</ID>\
+                <AllowedHeader>*</AllowedHeader>\
+                <AllowedMethod>PUT</AllowedMethod>\
+                <AllowedOrigin>https://app.example.com</AllowedOrigin>\
+                <ExposeHeader>ETag</ExposeHeader>\
+                <MaxAgeSeconds>3000</MaxAgeSeconds>\
+            </CORSRule></CORSConfiguration>";
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?cors")
+            .set_payload(configuration)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(actix_web::test::read_body(resp).await, configuration);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket?cors")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert!(matches!(
+            provider.get_bucket_cors("bucket").await,
+            Err(StorageErr::NoSuchCORSConfiguration)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(put_bucket_cors),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?cors")
+            .set_payload(
+                "<CORSConfiguration><CORSRule>\
+                    <AllowedMethod>GET</AllowedMethod>\
+                    <AllowedOrigin>*</AllowedOrigin>\
+                </CORSRule></CORSConfiguration>",
+            )
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
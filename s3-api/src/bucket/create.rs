@@ -1,11 +1,18 @@
 extern crate self as s3_api;
 
-use crate::xml::{BucketInfo, LocationInfo};
-use actix_web::{http::StatusCode, put, web, HttpResponse};
+use crate::{
+    acl::{acl_from_headers, default_owner, requester, AclError},
+    xml::{BucketInfo, LocationInfo},
+};
+use actix_web::{http::StatusCode, put, web, HttpRequest, HttpResponse};
 use actix_xml::Xml;
 use rand::distributions::DistString;
 use s3_derive::S3Error;
-use s3_entities::storage_provider::{StorageErr, StorageProvider};
+use s3_entities::{
+    acl::{AccessControlList, ObjectOwnership},
+    bucket::CreateBucketOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +23,7 @@ pub struct CreateBucketConfiguration {
     #[serde(rename = "Location")]
     pub location: Option<LocationInfo>,
     #[serde(rename = "Bucket")]
+    #[allow(dead_code)]
     pub bucket: Option<BucketInfo>,
 }
 
@@ -38,15 +46,51 @@ enum CreateBucketError {
         status_code = 409,
         message = "The bucket that you tried to create already exists, and you own it. Amazon S3 returns this error in all AWS Regions except in the US East (N. Virginia) Region (us-east-1). For legacy compatibility, if you re-create an existing bucket that you already own in us-east-1, Amazon S3 returns 200 OK and resets the bucket access control lists (ACLs). For Amazon S3 on Outposts, the bucket that you tried to create already exists in your Outpost and you own it."
     )]
+    #[allow(dead_code)]
     BucketAlreadyOwnedByYou {
         request_id: String,
         resource: String,
     },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Bucket cannot have ACLs set with ObjectOwnership's BucketOwnerEnforced setting."
+    )]
+    InvalidBucketAclWithObjectOwnership {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
 }
 
-#[put("/{bucket}")]
+/// Create a bucket belonging to the caller, with the ACL and the ownership controls given in the
+/// headers
+#[put(
+    "/{bucket}",
+    wrap = "crate::policy::Authorize::action(\"s3:CreateBucket\")"
+)]
 pub async fn create(
     path: web::Path<String>,
+    req: HttpRequest,
     storage_provider: web::Data<dyn StorageProvider>,
     payload: Option<Xml<CreateBucketConfiguration>>,
 ) -> Result<HttpResponse, CreateBucketError> {
@@ -54,28 +98,81 @@ pub async fn create(
         .sample_string(&mut rand::thread_rng(), 16)
         .to_uppercase();
     let bucket = path.into_inner();
+    let resource = bucket.clone();
 
-    let location = payload
-        .and_then(|payload| payload.into_inner().location)
-        .and_then(|location| location.name);
-
-    storage_provider
-        .into_inner()
-        .create_bucket(&bucket, location)
-        .await
-        .map_err(|e| {
-            let bucket = String::clone(&bucket);
-            match e {
-                StorageErr::BucketAlreadyExists => CreateBucketError::BucketAlreadyExists {
+    let owner = requester(&req).unwrap_or_else(default_owner);
+    let acl = acl_from_headers(req.headers(), owner.clone(), &owner).map_err(|e| {
+        let (request_id, resource) = (request_id.clone(), resource.clone());
+        match e {
+            AclError::InvalidRequest => CreateBucketError::InvalidRequest {
+                request_id,
+                resource,
+            },
+            AclError::UnresolvableGrantByEmailAddress => {
+                CreateBucketError::UnresolvableGrantByEmailAddress {
                     request_id,
-                    resource: bucket,
-                },
-                _ => CreateBucketError::InternalError {
+                    resource,
+                }
+            }
+            _ => CreateBucketError::InvalidArgument {
+                request_id,
+                resource,
+            },
+        }
+    })?;
+    let acl = acl.unwrap_or_else(|| AccessControlList::private(owner.clone()));
+    let ownership = match req.headers().get("x-amz-object-ownership") {
+        Some(value) => match value.to_str().ok().and_then(ObjectOwnership::parse) {
+            Some(ownership) => Some(ownership),
+            None => {
+                return Err(CreateBucketError::InvalidArgument {
                     request_id,
-                    resource: bucket,
-                },
+                    resource,
+                })
             }
-        })?;
+        },
+        None => None,
+    };
+    if ownership.is_some_and(ObjectOwnership::acls_disabled)
+        && acl != AccessControlList::private(owner.clone())
+    {
+        return Err(CreateBucketError::InvalidBucketAclWithObjectOwnership {
+            request_id,
+            resource,
+        });
+    }
+
+    let location = payload.and_then(|payload| {
+        let payload = payload.into_inner();
+
+        payload
+            .location
+            .and_then(|location| location.name)
+            .or(payload.location_constraint)
+    });
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketAlreadyExists => CreateBucketError::BucketAlreadyExists {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => CreateBucketError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let options = CreateBucketOptions {
+        region: location,
+        owner_id: Some(owner.id),
+        acl: Some(acl),
+        object_ownership: ownership,
+    };
+    storage_provider
+        .create_bucket(&bucket, options)
+        .await
+        .map_err(error)?;
 
     Ok(HttpResponse::Ok()
         .status(StatusCode::OK)
@@ -174,6 +271,47 @@ mod tests {
         );
     }
 
+    #[actix_web::test]
+    async fn test_acl_and_ownership() {
+        let storage = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .service(create),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket")
+            .insert_header(("x-amz-acl", "public-read"))
+            .insert_header(("x-amz-object-ownership", "BucketOwnerPreferred"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let acl = storage.get_bucket_acl("bucket").await.unwrap();
+        assert_eq!(acl.owner, default_owner());
+        assert!(acl.allows(None, s3_entities::acl::Permission::Read));
+        assert_eq!(
+            storage
+                .get_bucket_ownership_controls("bucket")
+                .await
+                .unwrap(),
+            ObjectOwnership::BucketOwnerPreferred
+        );
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/other")
+            .insert_header(("x-amz-acl", "public-read"))
+            .insert_header(("x-amz-object-ownership", "BucketOwnerEnforced"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        assert!(matches!(
+            storage.head_bucket("other").await,
+            Err(StorageErr::BucketNotFound)
+        ));
+    }
+
     // Should ignore the bad payload and assume there's none provided
     #[actix_web::test]
     async fn test_invalid_payload() {
@@ -27,7 +27,10 @@ enum DeleteBucketError {
     },
 }
 
-#[delete("/{bucket}")]
+#[delete(
+    "/{bucket}",
+    wrap = "crate::policy::Authorize::action(\"s3:DeleteBucket\")"
+)]
 pub async fn delete_bucket(
     path: web::Path<String>,
     storage_provider: web::Data<dyn StorageProvider>,
@@ -71,7 +74,10 @@ mod tests {
         let bucket_name = "bucket";
 
         let provider = get_mock_app_data();
-        provider.create_bucket(bucket_name, None).await.unwrap();
+        provider
+            .create_bucket(bucket_name, Default::default())
+            .await
+            .unwrap();
 
         let app = actix_web::test::init_service(
             App::new()
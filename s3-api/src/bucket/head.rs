@@ -0,0 +1,184 @@
+extern crate self as s3_api;
+
+use crate::{auth::SigningRegion, generate_request_id};
+use actix_web::{head, web, HttpMessage, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::storage_provider::{StorageErr, StorageProvider};
+
+#[derive(Debug, S3Error)]
+#[error(empty_body)]
+enum HeadBucketError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 301,
+        message = "The bucket you are attempting to access must be addressed using the specified endpoint."
+    )]
+    PermanentRedirect {
+        request_id: String,
+        resource: String,
+        headers: Vec<(&'static str, String)>,
+    },
+}
+
+/// Report the region of a bucket, redirecting signed requests that expected it elsewhere so that
+/// SDKs can retry against the right region
+#[head(
+    "/{bucket}",
+    wrap = "crate::policy::Authorize::action(\"s3:ListBucket\")"
+)]
+pub async fn head_bucket(
+    req: HttpRequest,
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, HeadBucketError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let bucket = storage_provider
+        .into_inner()
+        .head_bucket(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => HeadBucketError::NoSuchBucket {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            },
+            _ => HeadBucketError::InternalError {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            },
+        })?;
+
+    let signing_region = req.extensions().get::<SigningRegion>().cloned();
+    if signing_region.is_some_and(|SigningRegion(region)| region != bucket.region) {
+        return Err(HeadBucketError::PermanentRedirect {
+            request_id,
+            resource,
+            headers: vec![("x-amz-bucket-region", bucket.region)],
+        });
+    }
+
+    Ok(HttpResponse::Ok()
+        .append_header(("x-amz-bucket-region", bucket.region))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{
+        body::{BodySize, MessageBody},
+        dev::Service as _,
+        http, App,
+    };
+    use s3_entities::{bucket::CreateBucketOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_existing_bucket() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket(
+                "bucket",
+                CreateBucketOptions {
+                    region: Some("eu-west-1".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_bucket),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("x-amz-bucket-region").unwrap(),
+            "eu-west-1"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_bucket),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert!(resp.headers().contains_key("x-amz-request-id"));
+        assert_eq!(resp.response().body().size(), BodySize::Sized(0));
+    }
+
+    #[actix_web::test]
+    async fn test_bucket_in_other_region() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket(
+                "bucket",
+                CreateBucketOptions {
+                    region: Some("eu-west-1".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .wrap_fn(|req, srv| {
+                    let region = req.headers().get("x-test-region").unwrap();
+                    let region = SigningRegion(region.to_str().unwrap().into());
+                    req.extensions_mut().insert(region);
+                    srv.call(req)
+                })
+                .service(head_bucket),
+        )
+        .await;
+        let req = |region: &str| {
+            actix_web::test::TestRequest::default()
+                .method(http::Method::HEAD)
+                .uri("/bucket")
+                .insert_header(("x-test-region", region))
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, req("us-east-1")).await;
+        assert_eq!(resp.status(), http::StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            resp.headers().get("x-amz-bucket-region").unwrap(),
+            "eu-west-1"
+        );
+        assert_eq!(resp.response().body().size(), BodySize::Sized(0));
+
+        let resp = actix_web::test::call_service(&app, req("eu-west-1")).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+}
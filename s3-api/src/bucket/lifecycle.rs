@@ -0,0 +1,671 @@
+extern crate self as s3_api;
+
+use crate::{body::verify_integrity, generate_request_id, xml, xml_date};
+use actix_web::{delete, get, http::header, put, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, NaiveTime, Utc};
+use s3_derive::S3Error;
+use s3_entities::{
+    lifecycle::{
+        Expiration, LifecycleConfiguration, LifecycleFilter, LifecycleRule, MAX_LIFECYCLE_RULES,
+        MAX_RULE_ID_LENGTH,
+    },
+    object::{valid_tags, Tags},
+    storage_provider::{StorageErr, StorageProvider},
+};
+use std::collections::HashSet;
+
+/// Why a lifecycle configuration was refused
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidConfiguration {
+    /// It doesn't have the shape S3 expects, such as a filter with several conditions outside
+    /// of `And`
+    Malformed,
+    /// It's well-formed but one of its values isn't allowed
+    Argument,
+}
+
+fn collect_tags(tags: Vec<xml::Tag>) -> Result<Tags, InvalidConfiguration> {
+    let mut collected = Tags::new();
+    for tag in tags {
+        if collected.insert(tag.key, tag.value).is_some() {
+            return Err(InvalidConfiguration::Argument);
+        }
+    }
+
+    match valid_tags(&collected) {
+        true => Ok(collected),
+        false => Err(InvalidConfiguration::Argument),
+    }
+}
+
+fn parse_filter(filter: xml::LifecycleFilter) -> Result<LifecycleFilter, InvalidConfiguration> {
+    let conditions = [
+        filter.prefix.is_some(),
+        filter.tag.is_some(),
+        filter.object_size_greater_than.is_some(),
+        filter.object_size_less_than.is_some(),
+        filter.and.is_some(),
+    ];
+    if conditions
+        .into_iter()
+        .filter(|&condition| condition)
+        .count()
+        > 1
+    {
+        return Err(InvalidConfiguration::Malformed);
+    }
+
+    let filter = match filter.and {
+        Some(and) => LifecycleFilter {
+            prefix: and.prefix.unwrap_or_default(),
+            tags: collect_tags(and.tags)?,
+            object_size_greater_than: and.object_size_greater_than,
+            object_size_less_than: and.object_size_less_than,
+        },
+        None => LifecycleFilter {
+            prefix: filter.prefix.unwrap_or_default(),
+            tags: collect_tags(filter.tag.into_iter().collect())?,
+            object_size_greater_than: filter.object_size_greater_than,
+            object_size_less_than: filter.object_size_less_than,
+        },
+    };
+
+    match (
+        filter.object_size_greater_than,
+        filter.object_size_less_than,
+    ) {
+        (Some(greater_than), Some(less_than)) if greater_than >= less_than => {
+            Err(InvalidConfiguration::Argument)
+        }
+        _ => Ok(filter),
+    }
+}
+
+fn parse_expiration(
+    expiration: xml::LifecycleExpiration,
+) -> Result<Option<Expiration>, InvalidConfiguration> {
+    let delete_marker = expiration.expired_object_delete_marker == Some(true);
+
+    match (expiration.days, expiration.date, delete_marker) {
+        (Some(0), None, false) => Err(InvalidConfiguration::Argument),
+        (Some(days), None, false) => Ok(Some(Expiration::Days(days))),
+        // Dates have to be at midnight UTC, just like the expiry dates worked out from days
+        (None, Some(date), false) => DateTime::parse_from_rfc3339(&date)
+            .ok()
+            .map(|date| date.with_timezone(&Utc))
+            .filter(|date| date.time() == NaiveTime::MIN)
+            .map(|date| Some(Expiration::Date(date)))
+            .ok_or(InvalidConfiguration::Argument),
+        (None, None, true) => Ok(Some(Expiration::ExpiredObjectDeleteMarker)),
+        (None, None, false) if expiration.expired_object_delete_marker.is_some() => Ok(None),
+        _ => Err(InvalidConfiguration::Malformed),
+    }
+}
+
+fn parse_rule(rule: xml::LifecycleRule) -> Result<LifecycleRule, InvalidConfiguration> {
+    let enabled = match rule.status.as_str() {
+        "Enabled" => true,
+        "Disabled" => false,
+        _ => return Err(InvalidConfiguration::Malformed),
+    };
+    let filter = match (rule.filter, rule.prefix) {
+        (Some(filter), None) => parse_filter(filter)?,
+        (None, Some(prefix)) => LifecycleFilter {
+            prefix,
+            ..Default::default()
+        },
+        _ => return Err(InvalidConfiguration::Malformed),
+    };
+    let expiration = match rule.expiration {
+        Some(expiration) => parse_expiration(expiration)?,
+        None => None,
+    };
+    let noncurrent_days = rule
+        .noncurrent_version_expiration
+        .map(|expiration| expiration.noncurrent_days);
+    let abort_incomplete_upload_days = rule
+        .abort_incomplete_multipart_upload
+        .map(|abort| abort.days_after_initiation);
+
+    if rule
+        .id
+        .as_ref()
+        .is_some_and(|id| id.chars().count() > MAX_RULE_ID_LENGTH)
+        || noncurrent_days == Some(0)
+        || abort_incomplete_upload_days == Some(0)
+    {
+        return Err(InvalidConfiguration::Argument);
+    }
+    if expiration.is_none() && noncurrent_days.is_none() && abort_incomplete_upload_days.is_none() {
+        return Err(InvalidConfiguration::Argument);
+    }
+    // Delete markers and uploads carry no tags nor size for the filter to check
+    let by_object = !filter.tags.is_empty()
+        || filter.object_size_greater_than.is_some()
+        || filter.object_size_less_than.is_some();
+    if by_object
+        && (expiration == Some(Expiration::ExpiredObjectDeleteMarker)
+            || abort_incomplete_upload_days.is_some())
+    {
+        return Err(InvalidConfiguration::Argument);
+    }
+
+    Ok(LifecycleRule {
+        id: rule.id,
+        enabled,
+        filter,
+        expiration,
+        noncurrent_days,
+        abort_incomplete_upload_days,
+    })
+}
+
+/// Check a lifecycle configuration the way S3 does, which takes between one and
+/// [`MAX_LIFECYCLE_RULES`] rules with unique IDs
+fn parse_configuration(
+    configuration: xml::LifecycleConfiguration,
+) -> Result<LifecycleConfiguration, InvalidConfiguration> {
+    if configuration.rules.is_empty() {
+        return Err(InvalidConfiguration::Malformed);
+    }
+    if configuration.rules.len() > MAX_LIFECYCLE_RULES {
+        return Err(InvalidConfiguration::Argument);
+    }
+
+    let mut ids = HashSet::new();
+    let rules = configuration
+        .rules
+        .into_iter()
+        .map(parse_rule)
+        .collect::<Result<Vec<_>, _>>()?;
+    if !rules
+        .iter()
+        .filter_map(|rule| rule.id.as_deref())
+        .all(|id| ids.insert(id))
+    {
+        return Err(InvalidConfiguration::Argument);
+    }
+
+    Ok(LifecycleConfiguration { rules })
+}
+
+/// Write a filter back the way it's sent, wrapping its conditions in `And` when there are
+/// several of them
+fn filter_xml(filter: LifecycleFilter) -> xml::LifecycleFilter {
+    let conditions = usize::from(!filter.prefix.is_empty())
+        + filter.tags.len()
+        + usize::from(filter.object_size_greater_than.is_some())
+        + usize::from(filter.object_size_less_than.is_some());
+    let mut tags = filter
+        .tags
+        .into_iter()
+        .map(|(key, value)| xml::Tag { key, value });
+    let prefix = Some(filter.prefix).filter(|prefix| !prefix.is_empty());
+
+    if conditions > 1 {
+        return xml::LifecycleFilter {
+            and: Some(xml::LifecycleAnd {
+                prefix,
+                tags: tags.collect(),
+                object_size_greater_than: filter.object_size_greater_than,
+                object_size_less_than: filter.object_size_less_than,
+            }),
+            ..Default::default()
+        };
+    }
+
+    xml::LifecycleFilter {
+        prefix,
+        tag: tags.next(),
+        object_size_greater_than: filter.object_size_greater_than,
+        object_size_less_than: filter.object_size_less_than,
+        and: None,
+    }
+}
+
+fn rule_xml(rule: LifecycleRule) -> xml::LifecycleRule {
+    let expiration = rule.expiration.map(|expiration| match expiration {
+        Expiration::Days(days) => xml::LifecycleExpiration {
+            days: Some(days),
+            ..Default::default()
+        },
+        Expiration::Date(date) => xml::LifecycleExpiration {
+            date: Some(xml_date(&date)),
+            ..Default::default()
+        },
+        Expiration::ExpiredObjectDeleteMarker => xml::LifecycleExpiration {
+            expired_object_delete_marker: Some(true),
+            ..Default::default()
+        },
+    });
+
+    xml::LifecycleRule {
+        id: rule.id,
+        filter: Some(filter_xml(rule.filter)),
+        prefix: None,
+        status: match rule.enabled {
+            true => "Enabled".into(),
+            false => "Disabled".into(),
+        },
+        expiration,
+        noncurrent_version_expiration: rule
+            .noncurrent_days
+            .map(|noncurrent_days| xml::NoncurrentVersionExpiration { noncurrent_days }),
+        abort_incomplete_multipart_upload: rule.abort_incomplete_upload_days.map(
+            |days_after_initiation| xml::AbortIncompleteMultipartUpload {
+                days_after_initiation,
+            },
+        ),
+    }
+}
+
+#[derive(Debug, S3Error)]
+enum GetBucketLifecycleConfigurationError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The lifecycle configuration does not exist."
+    )]
+    NoSuchLifecycleConfiguration {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::lifecycle",
+    wrap = "crate::policy::Authorize::action(\"s3:GetLifecycleConfiguration\")"
+)]
+pub async fn get_bucket_lifecycle_configuration(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketLifecycleConfigurationError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let configuration = storage_provider
+        .into_inner()
+        .get_bucket_lifecycle(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketLifecycleConfigurationError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::NoSuchLifecycleConfiguration => {
+                GetBucketLifecycleConfigurationError::NoSuchLifecycleConfiguration {
+                    request_id,
+                    resource,
+                }
+            }
+            _ => GetBucketLifecycleConfigurationError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&xml::LifecycleConfiguration {
+                rules: configuration.rules.into_iter().map(rule_xml).collect(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketLifecycleConfigurationError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The lifecycle configuration has a rule that isn't valid."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::lifecycle",
+    wrap = "crate::policy::Authorize::action(\"s3:PutLifecycleConfiguration\")"
+)]
+pub async fn put_bucket_lifecycle_configuration(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketLifecycleConfigurationError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutBucketLifecycleConfigurationError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let configuration = quick_xml::de::from_reader(body.as_ref())
+        .map_err(|_| InvalidConfiguration::Malformed)
+        .and_then(parse_configuration);
+    let configuration = match configuration {
+        Ok(configuration) => configuration,
+        Err(InvalidConfiguration::Malformed) => {
+            return Err(PutBucketLifecycleConfigurationError::MalformedXML {
+                request_id,
+                resource,
+            })
+        }
+        Err(InvalidConfiguration::Argument) => {
+            return Err(PutBucketLifecycleConfigurationError::InvalidArgument {
+                request_id,
+                resource,
+            })
+        }
+    };
+
+    storage_provider
+        .into_inner()
+        .put_bucket_lifecycle(&bucket, configuration)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => PutBucketLifecycleConfigurationError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => PutBucketLifecycleConfigurationError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteBucketLifecycleError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}",
+    guard = "crate::guard::lifecycle",
+    wrap = "crate::policy::Authorize::action(\"s3:PutLifecycleConfiguration\")"
+)]
+pub async fn delete_bucket_lifecycle(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteBucketLifecycleError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    storage_provider
+        .into_inner()
+        .put_bucket_lifecycle(&bucket, LifecycleConfiguration::default())
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => DeleteBucketLifecycleError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => DeleteBucketLifecycleError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::test::storage_provider::get_mock_app_data;
+
+    fn rule(rule: &str) -> Result<LifecycleConfiguration, InvalidConfiguration> {
+        let configuration: xml::LifecycleConfiguration = quick_xml::de::from_str(&format!(
+            "<LifecycleConfiguration><Rule>{rule}</Rule></LifecycleConfiguration>"
+        ))
+        .map_err(|_| InvalidConfiguration::Malformed)?;
+
+        parse_configuration(configuration)
+    }
+
+    #[test]
+    fn test_parse_configuration() {
+        let configuration = rule(
+            "<ID>logs</ID>\
+            <Filter><And>\
+                <Prefix>logs/</Prefix>\
+                <Tag><Key>project</Key><Value>cat</Value></Tag>\
+                <ObjectSizeLessThan>100</ObjectSizeLessThan>\
+            </And></Filter>\
+            <Status>Enabled</Status>\
+            <Expiration><Date>2026-06-01T00:00:00.000Z</Date></Expiration>",
+        )
+        .unwrap();
+        let rule_ = &configuration.rules[0];
+        assert_eq!(rule_.id.as_deref(), Some("logs"));
+        assert_eq!(rule_.filter.prefix, "logs/");
+        assert_eq!(rule_.filter.tags["project"], "cat");
+        assert_eq!(rule_.filter.object_size_less_than, Some(100));
+        assert!(matches!(rule_.expiration, Some(Expiration::Date(_))));
+
+        let configuration = rule(
+            "<Prefix>logs/</Prefix><Status>Disabled</Status>\
+            <NoncurrentVersionExpiration><NoncurrentDays>7</NoncurrentDays></NoncurrentVersionExpiration>",
+        )
+        .unwrap();
+        assert!(!configuration.rules[0].enabled);
+        assert_eq!(configuration.rules[0].filter.prefix, "logs/");
+        assert_eq!(configuration.rules[0].noncurrent_days, Some(7));
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        for (rule_, invalid) in [
+            // A filter's conditions have to be wrapped in And
+            (
+                "<Filter><Prefix>a</Prefix><ObjectSizeLessThan>1</ObjectSizeLessThan></Filter>\
+                <Status>Enabled</Status><Expiration><Days>1</Days></Expiration>",
+                InvalidConfiguration::Malformed,
+            ),
+            (
+                "<Filter/><Status>On</Status><Expiration><Days>1</Days></Expiration>",
+                InvalidConfiguration::Malformed,
+            ),
+            (
+                "<Status>Enabled</Status><Expiration><Days>1</Days></Expiration>",
+                InvalidConfiguration::Malformed,
+            ),
+            (
+                "<Filter/><Status>Enabled</Status>\
+                <Expiration><Days>1</Days><ExpiredObjectDeleteMarker>true</ExpiredObjectDeleteMarker></Expiration>",
+                InvalidConfiguration::Malformed,
+            ),
+            (
+                "<Filter/><Status>Enabled</Status><Expiration><Days>0</Days></Expiration>",
+                InvalidConfiguration::Argument,
+            ),
+            (
+                "<Filter/><Status>Enabled</Status>\
+                <Expiration><Date>2026-06-01T12:00:00.000Z</Date></Expiration>",
+                InvalidConfiguration::Argument,
+            ),
+            (
+                "<Filter/><Status>Enabled</Status>",
+                InvalidConfiguration::Argument,
+            ),
+            (
+                "<Filter><Tag><Key>a</Key><Value>b</Value></Tag></Filter><Status>Enabled</Status>\
+                <AbortIncompleteMultipartUpload><DaysAfterInitiation>1</DaysAfterInitiation></AbortIncompleteMultipartUpload>",
+                InvalidConfiguration::Argument,
+            ),
+            (
+                "<Filter><And>\
+                    <ObjectSizeGreaterThan>10</ObjectSizeGreaterThan>\
+                    <ObjectSizeLessThan>10</ObjectSizeLessThan>\
+                </And></Filter>\
+                <Status>Enabled</Status><Expiration><Days>1</Days></Expiration>",
+                InvalidConfiguration::Argument,
+            ),
+        ] {
+            assert_eq!(rule(rule_).unwrap_err(), invalid, "{rule_}");
+        }
+
+        let duplicate = "<Rule><ID>a</ID><Filter/><Status>Enabled</Status>\
+            <Expiration><Days>1</Days></Expiration></Rule>";
+        let configuration = quick_xml::de::from_str(&format!(
+            "<LifecycleConfiguration>{duplicate}{duplicate}</LifecycleConfiguration>"
+        ))
+        .unwrap();
+        assert_eq!(
+            parse_configuration(configuration).unwrap_err(),
+            InvalidConfiguration::Argument
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_bucket_lifecycle_configuration() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_lifecycle_configuration)
+                .service(put_bucket_lifecycle_configuration)
+                .service(delete_bucket_lifecycle),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?lifecycle")
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?lifecycle")
+            .set_payload(
+                "<LifecycleConfiguration><Rule>\
+                    <ID>logs</ID>\
+                    <Filter><And>\
+                        <Prefix>logs/</Prefix>\
+                        <ObjectSizeGreaterThan>10</ObjectSizeGreaterThan>\
+                    </And></Filter>\
+                    <Status>Enabled</Status>\
+                    <Expiration><Days>30</Days></Expiration>\
+                </Rule></LifecycleConfiguration>",
+            )
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        assert_eq!(
+            body,
+            "<LifecycleConfiguration><Rule>\
+                <ID>logs</ID>\
+                <Filter><And>\
+                    <Prefix>logs/</Prefix>\
+                    <ObjectSizeGreaterThan>10</ObjectSizeGreaterThan>\
+                </And></Filter>\
+                <Status>Enabled</Status>\
+                <Expiration><Days>30</Days></Expiration>\
+            </Rule></LifecycleConfiguration>"
+        );
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?lifecycle")
+            .set_payload("<LifecycleConfiguration/>")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket?lifecycle")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert!(matches!(
+            provider.get_bucket_lifecycle("bucket").await,
+            Err(StorageErr::NoSuchLifecycleConfiguration)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_bucket_lifecycle_configuration),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?lifecycle")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{error::BaseError, generate_request_id};
-use actix_web::{get, http::StatusCode, web, HttpResponse};
+use crate::{
+    acl::{default_owner, requester},
+    auth::Identity,
+    error::BaseError,
+    generate_request_id,
+};
+use actix_web::{get, http::StatusCode, web, HttpMessage, HttpRequest, HttpResponse};
 use s3_entities::storage_provider::StorageProvider;
 use serde::{Deserialize, Serialize};
 
@@ -48,22 +53,37 @@ struct DisplayName {
     content: String,
 }
 
-#[get("/")]
+/// List the buckets the caller owns, which is every authenticated caller but the administrators.
+/// They're exempt on purpose and list every bucket, since they're the ones managing them all, and
+/// so is an app without authentication, whose callers all act as the default owner. Anonymous
+/// callers are denied before getting here.
+#[get(
+    "/",
+    wrap = "crate::policy::Authorize::action(\"s3:ListAllMyBuckets\")"
+)]
 pub async fn list(
+    req: HttpRequest,
     storage_provider: web::Data<dyn StorageProvider>,
 ) -> Result<HttpResponse, BaseError> {
     let request_id = generate_request_id();
-    let buckets: Vec<Bucket> = storage_provider
-        .into_inner()
-        .list_buckets()
+    let error = |_| BaseError::InternalError {
+        request_id: request_id.clone(),
+        resource: "/".into(),
+    };
+    let owner = requester(&req).unwrap_or_else(default_owner);
+    let filtered = req
+        .extensions()
+        .get::<Identity>()
+        .is_some_and(|identity| !identity.is_admin());
+
+    let owner_id = filtered.then_some(owner.id.as_str());
+    let buckets = storage_provider
+        .list_buckets(owner_id)
         .await
-        .map_err(|_| BaseError::InternalError {
-            request_id,
-            resource: "/".into(),
-        })?
-        .iter()
+        .map_err(error)?
+        .into_iter()
         .map(|bucket| Bucket {
-            name: bucket.name.clone(),
+            name: bucket.name,
             creation_date: bucket.creation_date.to_rfc3339(),
         })
         .collect();
@@ -72,11 +92,9 @@ pub async fn list(
         quick_xml::se::to_string(&ListAllMyBucketsResult {
             buckets: Buckets { buckets },
             owner: Owner {
-                id: ID {
-                    content: "AIDACKCEVSQ6C2EXAMPLE".into(),
-                },
+                id: ID { content: owner.id },
                 display_name: DisplayName {
-                    content: "Account+Name".into(),
+                    content: owner.display_name,
                 },
             },
         })
@@ -86,21 +104,28 @@ pub async fn list(
 
 #[cfg(test)]
 mod tests {
-    use actix_web::{http, App};
+    use actix_web::{dev::Service as _, http, App};
 
-    use s3_entities::test::storage_provider::get_mock_app_data;
+    use s3_entities::{
+        acl::AccessControlList, bucket::CreateBucketOptions, identity::User,
+        test::storage_provider::get_mock_app_data,
+    };
 
     use super::*;
 
     #[actix_web::test]
     async fn no_payload() {
         let provider = get_mock_app_data();
+        let region = CreateBucketOptions {
+            region: Some("Europe".into()),
+            ..Default::default()
+        };
         provider
-            .create_bucket("DOC-EXAMPLE-BUCKET", Some("Europe".into()))
+            .create_bucket("DOC-EXAMPLE-BUCKET", region.clone())
             .await
             .unwrap();
         provider
-            .create_bucket("DOC-EXAMPLE-BUCKET2", Some("Europe".into()))
+            .create_bucket("DOC-EXAMPLE-BUCKET2", region)
             .await
             .unwrap();
 
@@ -110,15 +135,108 @@ mod tests {
                 .service(list),
         )
         .await;
-        let req = actix_web::test::TestRequest::get()
-            .uri(&format!("/"))
-            .to_request();
+        let req = actix_web::test::TestRequest::get().uri("/").to_request();
         let resp = actix_web::test::call_service(&app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::OK);
         println!("{:?}", resp.response().body())
     }
 
+    #[actix_web::test]
+    async fn test_only_owned_buckets() {
+        let provider = get_mock_app_data();
+        let alice = User {
+            id: "AIDAALICE".into(),
+            name: "alice".into(),
+            creation_date: chrono::Utc::now(),
+        };
+        for (bucket, owner_id) in [("mine", "AIDAALICE"), ("theirs", "AIDABOB")] {
+            let options = CreateBucketOptions {
+                owner_id: Some(owner_id.into()),
+                acl: Some(AccessControlList::private(alice.owner())),
+                ..Default::default()
+            };
+            provider.create_bucket(bucket, options).await.unwrap();
+        }
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(actix_web::web::Data::from(provider))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(Identity {
+                        access_key_id: "AKIAALICE".into(),
+                        user: Some(alice.clone()),
+                        session: None,
+                    });
+                    srv.call(req)
+                })
+                .service(list),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get().uri("/").to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("<String>mine</String>"));
+        assert!(!body.contains("<String>theirs</String>"));
+        assert!(body.contains("<Owner><ID>AIDAALICE</ID><DisplayName>alice</DisplayName></Owner>"));
+    }
+
+    #[actix_web::test]
+    async fn test_admins_and_anonymous_callers() {
+        use crate::auth::Anonymous;
+
+        let provider = get_mock_app_data();
+        let options = CreateBucketOptions {
+            owner_id: Some("AIDAALICE".into()),
+            ..Default::default()
+        };
+        provider.create_bucket("alices", options).await.unwrap();
+
+        for (identity, listed) in [
+            (
+                Some(Identity {
+                    access_key_id: "AKIAADMIN".into(),
+                    user: None,
+                    session: None,
+                }),
+                Some(true),
+            ),
+            (None, None),
+        ] {
+            let app = actix_web::test::init_service(
+                App::new()
+                    .app_data(actix_web::web::Data::from(provider.clone()))
+                    .wrap_fn(move |req, srv| {
+                        match identity.clone() {
+                            Some(identity) => {
+                                req.extensions_mut().insert(identity);
+                            }
+                            None => {
+                                req.extensions_mut().insert(Anonymous);
+                            }
+                        }
+                        srv.call(req)
+                    })
+                    .service(list),
+            )
+            .await;
+            let req = actix_web::test::TestRequest::get().uri("/").to_request();
+            let resp = actix_web::test::call_service(&app, req).await;
+            let Some(listed) = listed else {
+                assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+                continue;
+            };
+            assert_eq!(resp.status(), http::StatusCode::OK);
+
+            let body = actix_web::test::read_body(resp).await;
+            let body = String::from_utf8_lossy(&body);
+            assert_eq!(body.contains("<String>alices</String>"), listed);
+        }
+    }
+
     #[test]
     fn test_serialization() {
         let res = quick_xml::se::to_string(&ListAllMyBucketsResult {
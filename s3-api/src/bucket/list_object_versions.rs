@@ -0,0 +1,293 @@
+extern crate self as s3_api;
+
+use super::{key_encoder, xml_owner};
+use crate::{
+    acl::bucket_acl,
+    generate_request_id,
+    xml::{CommonPrefix, DeleteMarkerEntry, ListVersionsResult, ListedVersion, Version},
+    xml_date,
+};
+use actix_web::{get, http::header, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    list::MAX_KEYS,
+    object::{ListVersionsOptions, NULL_VERSION_ID},
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum ListObjectVersionsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The max-keys or encoding-type provided is not valid."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ListVersionsQuery {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    #[serde(rename = "key-marker")]
+    key_marker: Option<String>,
+    #[serde(rename = "version-id-marker")]
+    version_id_marker: Option<String>,
+    #[serde(rename = "max-keys")]
+    max_keys: Option<String>,
+    #[serde(rename = "encoding-type")]
+    encoding_type: Option<String>,
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::versions",
+    wrap = "crate::policy::Authorize::action(\"s3:ListBucketVersions\")"
+)]
+pub async fn list_object_versions(
+    path: web::Path<String>,
+    query: web::Query<ListVersionsQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, ListObjectVersionsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+    let query = query.into_inner();
+
+    let max_keys = match &query.max_keys {
+        Some(max_keys) => max_keys.parse().ok(),
+        None => Some(MAX_KEYS),
+    };
+    let encode = key_encoder(query.encoding_type.as_deref());
+    let (Some(max_keys), Some(encode)) = (max_keys, encode) else {
+        return Err(ListObjectVersionsError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    let max_keys = max_keys.min(MAX_KEYS);
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => ListObjectVersionsError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => ListObjectVersionsError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let page = storage_provider
+        .list_object_versions(
+            &bucket,
+            ListVersionsOptions {
+                prefix: query.prefix.clone(),
+                delimiter: query.delimiter.clone(),
+                key_marker: query.key_marker.clone(),
+                version_id_marker: query.version_id_marker.clone(),
+                max_keys,
+            },
+        )
+        .await
+        .map_err(error)?;
+    let owner = bucket_acl(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(error)?
+        .owner;
+
+    // The version id marker is only needed when the page ended on a version rather than on a
+    // common prefix, since a key can have several versions
+    let next_version_id_marker = page
+        .items
+        .last()
+        .filter(|version| page.next_marker.as_ref() == Some(&version.object.key))
+        .map(|version| version.object.version_id.clone());
+    let (next_key_marker, next_version_id_marker) = match page.is_truncated {
+        true => (
+            page.next_marker.map(encode),
+            next_version_id_marker.map(Option::unwrap_or_default),
+        ),
+        false => (None, None),
+    };
+
+    // Objects written before versioning was ever enabled are listed as the null version
+    let versions = page
+        .items
+        .into_iter()
+        .map(|version| {
+            let object = version.object;
+            let key = encode(object.key);
+            let version_id = object.version_id.unwrap_or(NULL_VERSION_ID.into());
+            let last_modified = xml_date(&object.last_modified);
+
+            match object.delete_marker {
+                true => ListedVersion::DeleteMarker(DeleteMarkerEntry {
+                    key,
+                    version_id,
+                    is_latest: version.is_latest,
+                    last_modified,
+                    owner: xml_owner(&owner),
+                }),
+                false => ListedVersion::Version(Version {
+                    key,
+                    version_id,
+                    is_latest: version.is_latest,
+                    last_modified,
+                    etag: format!("\"{}\"", object.etag),
+                    size: object.size,
+                    storage_class: "STANDARD".into(),
+                    owner: xml_owner(&owner),
+                }),
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&ListVersionsResult {
+                name: bucket,
+                prefix: encode(query.prefix.unwrap_or_default()),
+                key_marker: encode(query.key_marker.unwrap_or_default()),
+                version_id_marker: query.version_id_marker.unwrap_or_default(),
+                next_key_marker,
+                next_version_id_marker,
+                delimiter: query.delimiter.map(encode),
+                max_keys,
+                encoding_type: query.encoding_type,
+                is_truncated: page.is_truncated,
+                versions,
+                common_prefixes: page
+                    .common_prefixes
+                    .into_iter()
+                    .map(|prefix| CommonPrefix {
+                        prefix: encode(prefix),
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        bucket::VersioningStatus,
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_list_object_versions() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let put = |key: &'static str| {
+            provider.put_object(
+                "bucket",
+                key,
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+        };
+        put("cat.jpg").await.unwrap();
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        let latest = put("cat.jpg").await.unwrap().version_id.unwrap();
+        put("photos/cat.jpg").await.unwrap();
+        put("z.jpg").await.unwrap();
+        let marker = provider
+            .delete_object("bucket", "z.jpg", None)
+            .await
+            .unwrap()
+            .version_id
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_object_versions),
+        )
+        .await;
+        let list = |uri: String| {
+            let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+            let app = &app;
+            async move {
+                let resp = actix_web::test::call_service(&app, req).await;
+                assert_eq!(resp.status(), http::StatusCode::OK);
+
+                let body = actix_web::test::read_body(resp).await;
+                String::from_utf8(body.to_vec()).unwrap()
+            }
+        };
+
+        let body = list("/bucket?versions&delimiter=/".into()).await;
+        assert!(body.contains(&format!(
+            "<Version><Key>cat.jpg</Key><VersionId>{latest}</VersionId><IsLatest>true</IsLatest>"
+        )));
+        assert!(body.contains(
+            "<Version><Key>cat.jpg</Key><VersionId>null</VersionId><IsLatest>false</IsLatest>"
+        ));
+        assert!(body.contains(&format!(
+            "<DeleteMarker><Key>z.jpg</Key><VersionId>{marker}</VersionId><IsLatest>true</IsLatest>"
+        )));
+        assert!(body.contains("<CommonPrefixes><Prefix>photos/</Prefix></CommonPrefixes>"));
+        // Entries of a key are listed newest first, delete markers included
+        assert_eq!(body.matches("<VersionId>").count(), 4);
+        assert!(body.find("<Key>z.jpg</Key>").unwrap() > body.find("null").unwrap());
+
+        let body = list("/bucket?versions&max-keys=1".into()).await;
+        assert!(body.contains("<IsTruncated>true</IsTruncated>"));
+        assert!(body.contains("<NextKeyMarker>cat.jpg</NextKeyMarker>"));
+        assert!(body.contains(&format!(
+            "<NextVersionIdMarker>{latest}</NextVersionIdMarker>"
+        )));
+
+        let body = list(format!(
+            "/bucket?versions&max-keys=1&key-marker=cat.jpg&version-id-marker={latest}"
+        ))
+        .await;
+        assert!(body.contains("<VersionId>null</VersionId>"));
+        assert!(body.contains("<NextVersionIdMarker>null</NextVersionIdMarker>"));
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_object_versions),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?versions")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
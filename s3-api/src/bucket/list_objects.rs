@@ -0,0 +1,268 @@
+extern crate self as s3_api;
+
+use super::{contents, key_encoder};
+use crate::{
+    acl::bucket_acl,
+    generate_request_id,
+    xml::{CommonPrefix, ListBucketResult},
+};
+use actix_web::{get, http::header, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    list::MAX_KEYS,
+    object::ListObjectsOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum ListObjectsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The max-keys or encoding-type provided is not valid."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ListObjectsQuery {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    marker: Option<String>,
+    #[serde(rename = "max-keys")]
+    max_keys: Option<String>,
+    #[serde(rename = "encoding-type")]
+    encoding_type: Option<String>,
+}
+
+/// The original ListObjects, which pages by the last key itself rather than by an opaque token
+#[get(
+    "/{bucket}",
+    wrap = "crate::policy::Authorize::action(\"s3:ListBucket\")"
+)]
+pub async fn list_objects(
+    path: web::Path<String>,
+    query: web::Query<ListObjectsQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, ListObjectsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+    let query = query.into_inner();
+
+    let max_keys = match &query.max_keys {
+        Some(max_keys) => max_keys.parse().ok(),
+        None => Some(MAX_KEYS),
+    };
+    let encode = key_encoder(query.encoding_type.as_deref());
+    let (Some(max_keys), Some(encode)) = (max_keys, encode) else {
+        return Err(ListObjectsError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    let max_keys = max_keys.min(MAX_KEYS);
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => ListObjectsError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => ListObjectsError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let page = storage_provider
+        .list_objects(
+            &bucket,
+            ListObjectsOptions {
+                prefix: query.prefix.clone(),
+                delimiter: query.delimiter.clone(),
+                marker: query.marker.clone(),
+                max_keys,
+            },
+        )
+        .await
+        .map_err(error)?;
+    let owner = bucket_acl(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(error)?
+        .owner;
+
+    // Like S3, NextMarker is only returned along with a delimiter. Without one, clients continue
+    // from the last key in Contents
+    let next_marker = page
+        .next_marker
+        .filter(|_| page.is_truncated && query.delimiter.is_some())
+        .map(encode);
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&ListBucketResult {
+                name: bucket,
+                prefix: encode(query.prefix.unwrap_or_default()),
+                marker: Some(encode(query.marker.unwrap_or_default())),
+                next_marker,
+                delimiter: query.delimiter.map(encode),
+                max_keys,
+                encoding_type: query.encoding_type,
+                key_count: None,
+                is_truncated: page.is_truncated,
+                continuation_token: None,
+                next_continuation_token: None,
+                start_after: None,
+                contents: page
+                    .items
+                    .into_iter()
+                    .map(|object| contents(object, Some(&owner), encode))
+                    .collect(),
+                common_prefixes: page
+                    .common_prefixes
+                    .into_iter()
+                    .map(|prefix| CommonPrefix {
+                        prefix: encode(prefix),
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    async fn read_result(resp: actix_web::dev::ServiceResponse) -> ListBucketResult {
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        quick_xml::de::from_reader(body.as_ref()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_list_objects() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        for key in ["a.jpg", "photos/a.jpg", "photos/b.jpg", "z.jpg"] {
+            provider
+                .put_object(
+                    "bucket",
+                    key,
+                    PutObjectOptions::default(),
+                    Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                    WriteCondition::Overwrite,
+                )
+                .await
+                .unwrap();
+        }
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_objects),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?delimiter=/&max-keys=2")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.contents[0].key, "a.jpg");
+        assert!(result.contents[0].owner.is_some());
+        assert_eq!(result.common_prefixes[0].prefix, "photos/");
+        assert_eq!(result.marker.as_deref(), Some(""));
+        assert_eq!(result.next_marker.as_deref(), Some("photos/"));
+        assert_eq!(result.key_count, None);
+        assert!(result.is_truncated);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?delimiter=/&marker=photos/")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.contents.len(), 1);
+        assert_eq!(result.contents[0].key, "z.jpg");
+        assert_eq!(result.next_marker, None);
+        assert!(!result.is_truncated);
+
+        // Without a delimiter the last key is the marker, and NextMarker is left out
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?max-keys=1")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.contents[0].key, "a.jpg");
+        assert_eq!(result.next_marker, None);
+        assert!(result.is_truncated);
+    }
+
+    #[actix_web::test]
+    async fn test_routes_by_list_type() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .configure(crate::bucket::config),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?list-type=2")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.key_count, Some(0));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.key_count, None);
+        assert_eq!(result.marker.as_deref(), Some(""));
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_objects),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,283 @@
+extern crate self as s3_api;
+
+use super::{contents, key_encoder};
+use crate::{
+    acl::bucket_acl,
+    generate_request_id,
+    xml::{CommonPrefix, ListBucketResult},
+};
+use actix_web::{get, http::header, web, HttpResponse};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use s3_derive::S3Error;
+use s3_entities::{
+    list::MAX_KEYS,
+    object::ListObjectsOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum ListObjectsV2Error {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The continuation token, max-keys or encoding-type provided is not valid."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ListObjectsV2Query {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    #[serde(rename = "max-keys")]
+    max_keys: Option<String>,
+    #[serde(rename = "continuation-token")]
+    continuation_token: Option<String>,
+    #[serde(rename = "start-after")]
+    start_after: Option<String>,
+    #[serde(rename = "fetch-owner")]
+    fetch_owner: Option<String>,
+    #[serde(rename = "encoding-type")]
+    encoding_type: Option<String>,
+}
+
+/// Continuation tokens are the key or common prefix to continue after, encoded so that clients
+/// don't come to rely on what's inside them
+fn encode_token(marker: &str) -> String {
+    URL_SAFE_NO_PAD.encode(marker)
+}
+
+fn decode_token(token: &str) -> Option<String> {
+    String::from_utf8(URL_SAFE_NO_PAD.decode(token).ok()?).ok()
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::list_objects_v2",
+    wrap = "crate::policy::Authorize::action(\"s3:ListBucket\")"
+)]
+pub async fn list_objects_v2(
+    path: web::Path<String>,
+    query: web::Query<ListObjectsV2Query>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, ListObjectsV2Error> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+    let query = query.into_inner();
+
+    let max_keys = match &query.max_keys {
+        Some(max_keys) => max_keys.parse().ok(),
+        None => Some(MAX_KEYS),
+    };
+    let marker = match &query.continuation_token {
+        Some(token) => decode_token(token).map(Some),
+        None => Some(query.start_after.clone()),
+    };
+    let encode = key_encoder(query.encoding_type.as_deref());
+    let (Some(max_keys), Some(marker), Some(encode)) = (max_keys, marker, encode) else {
+        return Err(ListObjectsV2Error::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    let max_keys = max_keys.min(MAX_KEYS);
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => ListObjectsV2Error::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => ListObjectsV2Error::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let page = storage_provider
+        .list_objects(
+            &bucket,
+            ListObjectsOptions {
+                prefix: query.prefix.clone(),
+                delimiter: query.delimiter.clone(),
+                marker,
+                max_keys,
+            },
+        )
+        .await
+        .map_err(error)?;
+
+    let owner = match query.fetch_owner.as_deref() {
+        Some("true") => Some(
+            bucket_acl(storage_provider.as_ref(), &bucket)
+                .await
+                .map_err(error)?
+                .owner,
+        ),
+        _ => None,
+    };
+    let next_continuation_token = page
+        .next_marker
+        .filter(|_| page.is_truncated)
+        .map(|marker| encode_token(&marker));
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&ListBucketResult {
+                name: bucket,
+                prefix: encode(query.prefix.unwrap_or_default()),
+                marker: None,
+                next_marker: None,
+                delimiter: query.delimiter.map(encode),
+                max_keys,
+                encoding_type: query.encoding_type,
+                key_count: Some(page.items.len() + page.common_prefixes.len()),
+                is_truncated: page.is_truncated,
+                continuation_token: query.continuation_token,
+                next_continuation_token,
+                start_after: query.start_after.map(encode),
+                contents: page
+                    .items
+                    .into_iter()
+                    .map(|object| contents(object, owner.as_ref(), encode))
+                    .collect(),
+                common_prefixes: page
+                    .common_prefixes
+                    .into_iter()
+                    .map(|prefix| CommonPrefix {
+                        prefix: encode(prefix),
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    async fn read_result(resp: actix_web::dev::ServiceResponse) -> ListBucketResult {
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        quick_xml::de::from_reader(body.as_ref()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_list_objects_v2() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        for key in ["a.jpg", "photos/a.jpg", "photos/b.jpg", "z one.jpg"] {
+            provider
+                .put_object(
+                    "bucket",
+                    key,
+                    PutObjectOptions::default(),
+                    Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                    WriteCondition::Overwrite,
+                )
+                .await
+                .unwrap();
+        }
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_objects_v2),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?list-type=2&delimiter=/&max-keys=2")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.key_count.unwrap(), 2);
+        assert_eq!(result.contents[0].key, "a.jpg");
+        assert!(result.contents[0].owner.is_none());
+        assert_eq!(result.common_prefixes[0].prefix, "photos/");
+        assert!(result.is_truncated);
+
+        let token = result.next_continuation_token.unwrap();
+        assert_ne!(token, "photos/");
+
+        let req = actix_web::test::TestRequest::get().uri(&format!("/bucket?list-type=2&delimiter=/&continuation-token={token}&fetch-owner=true&encoding-type=url")).to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.key_count.unwrap(), 1);
+        assert_eq!(result.contents[0].key, "z%20one.jpg");
+        assert!(result.contents[0].owner.is_some());
+        assert_eq!(result.continuation_token.as_deref(), Some(token.as_str()));
+        assert_eq!(result.next_continuation_token, None);
+        assert!(!result.is_truncated);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?list-type=2&start-after=photos/a.jpg")
+            .to_request();
+        let result = read_result(actix_web::test::call_service(&app, req).await).await;
+        assert_eq!(result.key_count.unwrap(), 2);
+        assert_eq!(result.contents[0].key, "photos/b.jpg");
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_continuation_token() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_objects_v2),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?list-type=2&continuation-token=%21%21")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_objects_v2),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?list-type=2")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
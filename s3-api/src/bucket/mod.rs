@@ -1,14 +1,80 @@
+use crate::{
+    auth::signature::uri_encode,
+    xml::{Contents, Owner},
+    xml_date,
+};
 use actix_web::web;
+use s3_entities::object::Object;
 
+mod acl;
+mod cors;
 mod create;
 mod delete;
+mod head;
+mod lifecycle;
 mod list;
+mod list_object_versions;
+mod list_objects;
+mod list_objects_v2;
+mod ownership;
+mod policy;
+mod versioning;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("")
-            .service(list::list)
-            .service(create::create)
-            .service(delete::delete_bucket),
-    );
+    cfg.service(versioning::get_bucket_versioning)
+        .service(versioning::put_bucket_versioning)
+        .service(lifecycle::get_bucket_lifecycle_configuration)
+        .service(lifecycle::put_bucket_lifecycle_configuration)
+        .service(lifecycle::delete_bucket_lifecycle)
+        .service(cors::get_bucket_cors)
+        .service(cors::put_bucket_cors)
+        .service(cors::delete_bucket_cors)
+        .service(policy::get_bucket_policy)
+        .service(policy::put_bucket_policy)
+        .service(policy::delete_bucket_policy)
+        .service(acl::get_bucket_acl)
+        .service(acl::put_bucket_acl)
+        .service(ownership::get_bucket_ownership_controls)
+        .service(ownership::put_bucket_ownership_controls)
+        .service(ownership::delete_bucket_ownership_controls)
+        .service(list_object_versions::list_object_versions)
+        .service(list::list)
+        .service(list_objects_v2::list_objects_v2)
+        .service(list_objects::list_objects)
+        .service(head::head_bucket)
+        .service(create::create)
+        .service(delete::delete_bucket);
+}
+
+/// How keys and prefixes are written in a listing, `None` when the `encoding-type` isn't
+/// supported
+fn key_encoder(encoding_type: Option<&str>) -> Option<fn(String) -> String> {
+    match encoding_type {
+        Some("url") => Some(|value| uri_encode(&value)),
+        Some(_) => None,
+        None => Some(|value| value),
+    }
+}
+
+fn contents(
+    object: Object,
+    owner: Option<&s3_entities::acl::Owner>,
+    encode: fn(String) -> String,
+) -> Contents {
+    Contents {
+        key: encode(object.key),
+        last_modified: xml_date(&object.last_modified),
+        etag: format!("\"{}\"", object.etag),
+        size: object.size,
+        storage_class: "STANDARD".into(),
+        owner: owner.map(xml_owner),
+    }
+}
+
+/// An owner as listings show it, which is the bucket owner for every object
+fn xml_owner(owner: &s3_entities::acl::Owner) -> Owner {
+    Owner {
+        id: owner.id.clone(),
+        display_name: owner.display_name.clone(),
+    }
 }
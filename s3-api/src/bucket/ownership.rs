@@ -0,0 +1,342 @@
+extern crate self as s3_api;
+
+use crate::{
+    acl::bucket_acl,
+    body::verify_integrity,
+    generate_request_id,
+    xml::{OwnershipControls, OwnershipControlsRule},
+};
+use actix_web::{delete, get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    acl::{AccessControlList, ObjectOwnership},
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum GetBucketOwnershipControlsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The bucket ownership controls were not found."
+    )]
+    OwnershipControlsNotFoundError {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::ownership_controls",
+    wrap = "crate::policy::Authorize::action(\"s3:GetBucketOwnershipControls\")"
+)]
+pub async fn get_bucket_ownership_controls(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketOwnershipControlsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let ownership = storage_provider
+        .into_inner()
+        .get_bucket_ownership_controls(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketOwnershipControlsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::NoSuchOwnershipControls => {
+                GetBucketOwnershipControlsError::OwnershipControlsNotFoundError {
+                    request_id,
+                    resource,
+                }
+            }
+            _ => GetBucketOwnershipControlsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&OwnershipControls {
+                rules: vec![OwnershipControlsRule {
+                    object_ownership: ownership.as_str().into(),
+                }],
+            })
+            .unwrap(),
+        ))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketOwnershipControlsError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Bucket cannot have ACLs set with ObjectOwnership's BucketOwnerEnforced setting."
+    )]
+    InvalidBucketAclWithObjectOwnership {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Set who owns the objects written to a bucket. ACLs can only be disabled on a bucket whose ACL
+/// gives nobody but its owner access.
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::ownership_controls",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketOwnershipControls\")"
+)]
+pub async fn put_bucket_ownership_controls(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketOwnershipControlsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutBucketOwnershipControlsError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    // S3 takes exactly one rule
+    let ownership = quick_xml::de::from_reader::<_, OwnershipControls>(body.as_ref())
+        .ok()
+        .and_then(|controls| match controls.rules.as_slice() {
+            [rule] => ObjectOwnership::parse(&rule.object_ownership),
+            _ => None,
+        });
+    let Some(ownership) = ownership else {
+        return Err(PutBucketOwnershipControlsError::MalformedXML {
+            request_id,
+            resource,
+        });
+    };
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => PutBucketOwnershipControlsError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => PutBucketOwnershipControlsError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    if ownership.acls_disabled() {
+        let acl = bucket_acl(storage_provider.as_ref(), &bucket)
+            .await
+            .map_err(error)?;
+        if acl != AccessControlList::private(acl.owner.clone()) {
+            return Err(
+                PutBucketOwnershipControlsError::InvalidBucketAclWithObjectOwnership {
+                    request_id,
+                    resource,
+                },
+            );
+        }
+    }
+
+    storage_provider
+        .put_bucket_ownership_controls(&bucket, Some(ownership))
+        .await
+        .map_err(error)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteBucketOwnershipControlsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}",
+    guard = "crate::guard::ownership_controls",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketOwnershipControls\")"
+)]
+pub async fn delete_bucket_ownership_controls(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteBucketOwnershipControlsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    storage_provider
+        .into_inner()
+        .put_bucket_ownership_controls(&bucket, None)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => DeleteBucketOwnershipControlsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => DeleteBucketOwnershipControlsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::{acl::CannedAcl, test::storage_provider::get_mock_app_data};
+
+    const ENFORCED: &str = "<OwnershipControls>\
+            <Rule><ObjectOwnership>BucketOwnerEnforced</ObjectOwnership></Rule>\
+        </OwnershipControls>";
+
+    #[actix_web::test]
+    async fn test_ownership_controls() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_ownership_controls)
+                .service(put_bucket_ownership_controls)
+                .service(delete_bucket_ownership_controls),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?ownershipControls")
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(
+            String::from_utf8_lossy(&body).contains("<Code>OwnershipControlsNotFoundError</Code>")
+        );
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?ownershipControls")
+            .set_payload(ENFORCED)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(actix_web::test::read_body(resp).await, ENFORCED);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket?ownershipControls")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert!(matches!(
+            provider.get_bucket_ownership_controls("bucket").await,
+            Err(StorageErr::NoSuchOwnershipControls)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_ownership_controls() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_bucket_ownership_controls),
+        )
+        .await;
+        let put = |body: String| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket?ownershipControls")
+                .set_payload(body)
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(
+            &app,
+            put(ENFORCED.replace("BucketOwnerEnforced", "Everyone")),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>MalformedXML</Code>"));
+
+        // ACLs can't be disabled while the bucket's ACL gives others access
+        let owner = provider.get_bucket_acl("bucket").await.unwrap().owner;
+        provider
+            .put_bucket_acl("bucket", CannedAcl::PublicRead.acl(owner.clone(), &owner))
+            .await
+            .unwrap();
+        let resp = actix_web::test::call_service(&app, put(ENFORCED.into())).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body)
+            .contains("<Code>InvalidBucketAclWithObjectOwnership</Code>"));
+    }
+}
@@ -0,0 +1,289 @@
+extern crate self as s3_api;
+
+use crate::{body::verify_integrity, generate_request_id};
+use actix_web::{delete, get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    policy::Policy,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum GetBucketPolicyError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The bucket policy does not exist.")]
+    NoSuchBucketPolicy {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::policy",
+    wrap = "crate::policy::Authorize::action(\"s3:GetBucketPolicy\")"
+)]
+pub async fn get_bucket_policy(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketPolicyError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let policy = storage_provider
+        .into_inner()
+        .get_bucket_policy(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketPolicyError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::NoSuchBucketPolicy => GetBucketPolicyError::NoSuchBucketPolicy {
+                request_id,
+                resource,
+            },
+            _ => GetBucketPolicyError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::json())
+        .body(policy))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketPolicyError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The policy is not valid JSON, or has an element that isn't supported."
+    )]
+    MalformedPolicy {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Store a bucket policy. The document is kept as it was sent once it parses, so that reading
+/// it back gives the same JSON.
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::policy",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketPolicy\")"
+)]
+pub async fn put_bucket_policy(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketPolicyError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutBucketPolicyError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let document = match std::str::from_utf8(&body) {
+        Ok(document) if Policy::parse(document, &bucket).is_ok() => document.to_string(),
+        _ => {
+            return Err(PutBucketPolicyError::MalformedPolicy {
+                request_id,
+                resource,
+            })
+        }
+    };
+
+    storage_provider
+        .into_inner()
+        .put_bucket_policy(&bucket, Some(document))
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => PutBucketPolicyError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => PutBucketPolicyError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteBucketPolicyError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}",
+    guard = "crate::guard::policy",
+    wrap = "crate::policy::Authorize::action(\"s3:DeleteBucketPolicy\")"
+)]
+pub async fn delete_bucket_policy(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteBucketPolicyError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    storage_provider
+        .into_inner()
+        .put_bucket_policy(&bucket, None)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => DeleteBucketPolicyError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => DeleteBucketPolicyError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::test::storage_provider::get_mock_app_data;
+
+    const POLICY: &str = r#"{
+        "Version": "2012-10-17",
+        "Statement": {
+            "Effect": "Deny",
+            "Principal": "*",
+            "Action": "s3:DeleteObject",
+            "Resource": "arn:aws:s3:::bucket/archive/*"
+        }
+    }"#;
+
+    #[actix_web::test]
+    async fn test_bucket_policy() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_policy)
+                .service(put_bucket_policy)
+                .service(delete_bucket_policy),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?policy")
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?policy")
+            .set_payload(POLICY)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(actix_web::test::read_body(resp).await, POLICY);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket?policy")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert!(matches!(
+            provider.get_bucket_policy("bucket").await,
+            Err(StorageErr::NoSuchBucketPolicy)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_policy() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_bucket_policy),
+        )
+        .await;
+
+        // A policy can't reach past its own bucket
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket?policy")
+            .set_payload(POLICY.replace("bucket/", "other/"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>MalformedPolicy</Code>"));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/nonexisting?policy")
+            .set_payload(POLICY.replace("bucket/", "nonexisting/"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
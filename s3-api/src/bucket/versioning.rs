@@ -0,0 +1,216 @@
+extern crate self as s3_api;
+
+use crate::{generate_request_id, xml::VersioningConfiguration};
+use actix_web::{get, http::header, put, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    bucket::VersioningStatus,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum GetBucketVersioningError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::versioning",
+    wrap = "crate::policy::Authorize::action(\"s3:GetBucketVersioning\")"
+)]
+pub async fn get_bucket_versioning(
+    path: web::Path<String>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetBucketVersioningError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    let bucket = storage_provider
+        .into_inner()
+        .head_bucket(&bucket)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetBucketVersioningError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => GetBucketVersioningError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    // A bucket that was never versioned has no status at all
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&VersioningConfiguration {
+                status: bucket.versioning.as_str().map(Into::into),
+            })
+            .unwrap(),
+        ))
+}
+
+#[derive(Debug, S3Error)]
+enum PutBucketVersioningError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}",
+    guard = "crate::guard::versioning",
+    wrap = "crate::policy::Authorize::action(\"s3:PutBucketVersioning\")"
+)]
+pub async fn put_bucket_versioning(
+    path: web::Path<String>,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutBucketVersioningError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+
+    // Versioning can be enabled or suspended, but never turned off again
+    let status = quick_xml::de::from_reader::<_, VersioningConfiguration>(body.as_ref())
+        .ok()
+        .and_then(|configuration| VersioningStatus::parse(Some(configuration.status?.as_str())));
+    let Some(status) = status else {
+        return Err(PutBucketVersioningError::MalformedXML {
+            request_id,
+            resource,
+        });
+    };
+
+    storage_provider
+        .into_inner()
+        .put_bucket_versioning(&bucket, status)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => PutBucketVersioningError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => PutBucketVersioningError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::test::storage_provider::get_mock_app_data;
+
+    #[actix_web::test]
+    async fn test_bucket_versioning() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_bucket_versioning)
+                .service(put_bucket_versioning),
+        )
+        .await;
+        let get = || {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket?versioning")
+                .to_request()
+        };
+        let put = |body: &'static str| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket?versioning")
+                .set_payload(body)
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        assert_eq!(body, "<VersioningConfiguration/>");
+
+        let resp = actix_web::test::call_service(
+            &app,
+            put("<VersioningConfiguration><Status>Enabled</Status></VersioningConfiguration>"),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            provider.head_bucket("bucket").await.unwrap().versioning,
+            VersioningStatus::Enabled
+        );
+
+        let resp = actix_web::test::call_service(
+            &app,
+            put("<VersioningConfiguration><Status>Suspended</Status></VersioningConfiguration>"),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let resp = actix_web::test::call_service(&app, get()).await;
+        let body = actix_web::test::read_body(resp).await;
+        let configuration: VersioningConfiguration =
+            quick_xml::de::from_reader(body.as_ref()).unwrap();
+        assert_eq!(configuration.status.as_deref(), Some("Suspended"));
+
+        for body in [
+            "<VersioningConfiguration><Status>Disabled</Status></VersioningConfiguration>",
+            "<VersioningConfiguration/>",
+            "meow",
+        ] {
+            let resp = actix_web::test::call_service(&app, put(body)).await;
+            assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_bucket_versioning),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?versioning")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
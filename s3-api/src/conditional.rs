@@ -0,0 +1,121 @@
+//! Conditional requests, which only go ahead when an object's ETag or modification date matches
+//! the `If-*` headers sent along with them
+
+use actix_web::http::header::{self, HeaderMap};
+use chrono::{DateTime, Utc};
+use s3_entities::object::{Preconditions, WriteCondition};
+
+/// Read the conditions from headers such as `if-match`, or `x-amz-copy-source-if-match` with the
+/// prefix `x-amz-copy-source-` for the source of a copy. Dates that can't be parsed are ignored,
+/// as HTTP requires.
+pub(crate) fn preconditions_from_headers(headers: &HeaderMap, prefix: &str) -> Preconditions {
+    let header = |name: &str| {
+        headers
+            .get(format!("{prefix}{name}"))
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let date = |name: &str| {
+        header(name)
+            .and_then(|value| DateTime::parse_from_rfc2822(&value).ok())
+            .map(|date| date.with_timezone(&Utc))
+    };
+
+    Preconditions {
+        if_match: header("if-match"),
+        if_none_match: header("if-none-match"),
+        if_modified_since: date("if-modified-since"),
+        if_unmodified_since: date("if-unmodified-since"),
+    }
+}
+
+/// The condition a write's `If-None-Match` header puts on the object it would replace. Writes
+/// only support `*`, so `None` means the header asks for something that isn't implemented.
+pub(crate) fn write_condition(headers: &HeaderMap) -> Option<WriteCondition> {
+    match headers
+        .get(header::IF_NONE_MATCH)
+        .map(|value| value.as_bytes())
+    {
+        None => Some(WriteCondition::Overwrite),
+        Some(b"*") => Some(WriteCondition::IfAbsent),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::header::HeaderValue, test::TestRequest, HttpRequest};
+    use s3_entities::object::Precondition;
+
+    fn request(headers: &[(&'static str, &'static str)]) -> HttpRequest {
+        let mut req = TestRequest::default();
+        for (name, value) in headers {
+            req = req.insert_header((*name, HeaderValue::from_static(value)));
+        }
+
+        req.to_http_request()
+    }
+
+    fn preconditions(headers: &[(&'static str, &'static str)]) -> Preconditions {
+        preconditions_from_headers(request(headers).headers(), "")
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let last_modified = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        let evaluate = |headers| preconditions(headers).evaluate("abc", &last_modified);
+
+        assert_eq!(evaluate(&[]), Precondition::Passed);
+        assert_eq!(evaluate(&[("if-match", "\"abc\"")]), Precondition::Passed);
+        assert_eq!(evaluate(&[("if-match", "\"xyz\"")]), Precondition::Failed);
+        assert_eq!(evaluate(&[("if-match", "*")]), Precondition::Passed);
+        assert_eq!(
+            evaluate(&[("if-none-match", "\"xyz\", \"abc\"")]),
+            Precondition::NotModified
+        );
+        assert_eq!(
+            evaluate(&[("if-modified-since", "Wed, 21 Oct 2015 07:28:00 GMT")]),
+            Precondition::NotModified
+        );
+        assert_eq!(
+            evaluate(&[("if-unmodified-since", "Tue, 20 Oct 2015 07:28:00 GMT")]),
+            Precondition::Failed
+        );
+
+        // A matching If-Match wins over a failing If-Unmodified-Since
+        assert_eq!(
+            evaluate(&[
+                ("if-match", "\"abc\""),
+                ("if-unmodified-since", "Tue, 20 Oct 2015 07:28:00 GMT")
+            ]),
+            Precondition::Passed
+        );
+        // and a failing If-None-Match wins over a passing If-Modified-Since
+        assert_eq!(
+            evaluate(&[
+                ("if-none-match", "\"abc\""),
+                ("if-modified-since", "Tue, 20 Oct 2015 07:28:00 GMT")
+            ]),
+            Precondition::NotModified
+        );
+        assert_eq!(
+            evaluate(&[("if-modified-since", "yesterday")]),
+            Precondition::Passed
+        );
+    }
+
+    #[test]
+    fn test_write_condition() {
+        let condition = |headers| write_condition(request(headers).headers());
+
+        assert_eq!(condition(&[]), Some(WriteCondition::Overwrite));
+        assert_eq!(
+            condition(&[("if-none-match", "*")]),
+            Some(WriteCondition::IfAbsent)
+        );
+        assert_eq!(condition(&[("if-none-match", "\"abc\"")]), None);
+    }
+}
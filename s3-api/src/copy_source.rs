@@ -0,0 +1,70 @@
+//! Parsing of the `x-amz-copy-source` headers that server-side copies read their source from
+
+use percent_encoding::percent_decode_str;
+use s3_entities::object::CopySource;
+use std::ops::Range;
+
+/// Parse a copy source such as `/bucket/photos%2Fcat.jpg`, which is URL encoded and may or may
+/// not start with a slash. A `?versionId=` selects a version of the source object.
+pub(crate) fn parse_copy_source(value: &str) -> Option<CopySource> {
+    let (path, query) = value.split_once('?').unwrap_or((value, ""));
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    let (bucket, key) = path.trim_start_matches('/').split_once('/')?;
+
+    if bucket.is_empty() || key.is_empty() {
+        return None;
+    }
+
+    Some(CopySource {
+        bucket: bucket.into(),
+        key: key.into(),
+        version_id: query
+            .split('&')
+            .find_map(|param| param.strip_prefix("versionId="))
+            .map(Into::into),
+    })
+}
+
+/// Parse a copy source range such as `bytes=0-499`, which unlike `Range` has to give both the
+/// first and last byte
+pub(crate) fn parse_copy_source_range(value: &str) -> Option<Range<u64>> {
+    let (first, last) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (first, last): (u64, u64) = (first.parse().ok()?, last.parse().ok()?);
+
+    if first > last {
+        return None;
+    }
+
+    Some(first..last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_copy_source() {
+        let source = parse_copy_source("/bucket/photos%2Fcat%20one.jpg").unwrap();
+        assert_eq!(source.bucket, "bucket");
+        assert_eq!(source.key, "photos/cat one.jpg");
+        assert_eq!(source.version_id, None);
+
+        let source = parse_copy_source("bucket/key?versionId=abc").unwrap();
+        assert_eq!(source.bucket, "bucket");
+        assert_eq!(source.key, "key");
+        assert_eq!(source.version_id.as_deref(), Some("abc"));
+
+        assert!(parse_copy_source("/bucket").is_none());
+        assert!(parse_copy_source("/bucket/").is_none());
+    }
+
+    #[test]
+    fn test_parse_copy_source_range() {
+        assert_eq!(parse_copy_source_range("bytes=0-499"), Some(0..500));
+        assert_eq!(parse_copy_source_range("bytes=5-5"), Some(5..6));
+        assert_eq!(parse_copy_source_range("bytes=500-"), None);
+        assert_eq!(parse_copy_source_range("bytes=-500"), None);
+        assert_eq!(parse_copy_source_range("bytes=9-5"), None);
+        assert_eq!(parse_copy_source_range("0-5"), None);
+    }
+}
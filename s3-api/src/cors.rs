@@ -0,0 +1,421 @@
+//! Middleware answering the CORS preflight requests browsers send before reaching a bucket from
+//! another origin, and adding `Access-Control-*` headers to the requests that follow them.
+
+extern crate self as s3_api;
+
+use crate::generate_request_id;
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{self, HeaderMap, HeaderName, HeaderValue},
+        Method,
+    },
+    web, Error, HttpResponse, ResponseError,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use s3_derive::S3Error;
+use s3_entities::{cors::CorsRule, storage_provider::StorageProvider};
+use std::rc::Rc;
+
+#[derive(Debug, S3Error)]
+enum CorsError {
+    #[error(
+        code = "BadRequest",
+        status_code = 400,
+        message = "Insufficient information. Origin request header needed."
+    )]
+    MissingOrigin {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "BadRequest",
+        status_code = 400,
+        message = "Invalid Access-Control-Request-Method."
+    )]
+    InvalidRequestMethod {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "CORSResponse",
+        status_code = 403,
+        message = "CORS is not enabled for this bucket."
+    )]
+    CorsNotEnabled {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "This CORS request is not allowed. This is usually because the evalution of Origin, request method / Access-Control-Request-Method or Access-Control-Request-Headers are not whitelisted by the resource's CORS spec."
+    )]
+    CORSResponse {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Middleware applying the CORS configuration of the bucket a request is for. It has to wrap
+/// authentication, since browsers never sign preflight requests.
+pub struct Cors;
+
+impl<S, B> Transform<S, ServiceRequest> for Cors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = CorsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CorsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct CorsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for CorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let bucket = req
+                .path()
+                .trim_start_matches('/')
+                .split('/')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let origin = header_str(req.headers(), &header::ORIGIN);
+            let provider = req.app_data::<web::Data<dyn StorageProvider>>().cloned();
+
+            if req.method() == Method::OPTIONS {
+                let response = match preflight(&req, &bucket, provider).await {
+                    Ok(response) => response,
+                    Err(err) => err.error_response(),
+                };
+
+                return Ok(req.into_response(response).map_into_right_body());
+            }
+
+            let method = req.method().to_string();
+            let mut response = service.call(req).await?;
+
+            // Requests without an origin aren't cross-origin, and get no CORS headers
+            let (Some(origin), Some(provider)) = (origin, provider) else {
+                return Ok(response.map_into_left_body());
+            };
+            if let Ok(configuration) = provider.get_bucket_cors(&bucket).await {
+                if let Some(rule) = configuration.find_rule(&origin, &method, &[]) {
+                    cors_headers(response.headers_mut(), rule, &origin);
+                }
+            }
+
+            Ok(response.map_into_left_body())
+        })
+    }
+}
+
+/// Answer a preflight request with the first rule of the bucket that allows it
+async fn preflight(
+    req: &ServiceRequest,
+    bucket: &str,
+    provider: Option<web::Data<dyn StorageProvider>>,
+) -> Result<HttpResponse, CorsError> {
+    let request_id = generate_request_id();
+    let resource = req.path().to_string();
+
+    let Some(origin) = header_str(req.headers(), &header::ORIGIN) else {
+        return Err(CorsError::MissingOrigin {
+            request_id,
+            resource,
+        });
+    };
+    let Some(method) = header_str(req.headers(), &header::ACCESS_CONTROL_REQUEST_METHOD) else {
+        return Err(CorsError::InvalidRequestMethod {
+            request_id,
+            resource,
+        });
+    };
+    let requested_headers: Vec<String> =
+        header_str(req.headers(), &header::ACCESS_CONTROL_REQUEST_HEADERS)
+            .into_iter()
+            .flat_map(|headers| {
+                headers
+                    .split(',')
+                    .map(|header| header.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|header| !header.is_empty())
+            .collect();
+
+    let configuration = match provider {
+        Some(provider) if !bucket.is_empty() => provider.get_bucket_cors(bucket).await.ok(),
+        _ => None,
+    };
+    let Some(configuration) = configuration else {
+        return Err(CorsError::CorsNotEnabled {
+            request_id,
+            resource,
+        });
+    };
+    let Some(rule) = configuration.find_rule(&origin, &method, &requested_headers) else {
+        return Err(CorsError::CORSResponse {
+            request_id,
+            resource,
+        });
+    };
+
+    let mut response = HttpResponse::Ok().finish();
+    let headers = response.headers_mut();
+    cors_headers(headers, rule, &origin);
+    if !requested_headers.is_empty() {
+        insert(
+            headers,
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            &requested_headers.join(", "),
+        );
+    }
+    if let Some(max_age_seconds) = rule.max_age_seconds {
+        insert(
+            headers,
+            header::ACCESS_CONTROL_MAX_AGE,
+            &max_age_seconds.to_string(),
+        );
+    }
+
+    Ok(response)
+}
+
+/// The headers S3 adds to both preflight and actual requests that a rule allows
+fn cors_headers(headers: &mut HeaderMap, rule: &CorsRule, origin: &str) {
+    // A rule open to any origin answers with the wildcard, and without credentials
+    match rule.allowed_origin(origin) {
+        Some("*") => insert(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+        _ => {
+            insert(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            insert(headers, header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
+        }
+    }
+    insert(
+        headers,
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        &rule.allowed_methods.join(", "),
+    );
+    if !rule.expose_headers.is_empty() {
+        insert(
+            headers,
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            &rule.expose_headers.join(", "),
+        );
+    }
+    insert(
+        headers,
+        header::VARY,
+        "Origin, Access-Control-Request-Headers, Access-Control-Request-Method",
+    );
+}
+
+fn insert(headers: &mut HeaderMap, name: HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, test::TestRequest, App};
+    use s3_entities::{cors::CorsConfiguration, test::storage_provider::get_mock_app_data};
+
+    async fn call(req: TestRequest) -> ServiceResponse<impl actix_web::body::MessageBody> {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_bucket_cors(
+                "bucket",
+                CorsConfiguration {
+                    rules: vec![
+                        CorsRule {
+                            allowed_origins: vec!["https://*.example.com".into()],
+                            allowed_methods: vec!["PUT".into(), "POST".into()],
+                            allowed_headers: vec!["content-*".into(), "x-amz-*".into()],
+                            expose_headers: vec!["ETag".into()],
+                            max_age_seconds: Some(3000),
+                            ..Default::default()
+                        },
+                        CorsRule {
+                            allowed_origins: vec!["*".into()],
+                            allowed_methods: vec!["GET".into()],
+                            ..Default::default()
+                        },
+                    ],
+                },
+            )
+            .await
+            .unwrap();
+        provider
+            .create_bucket("private", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .wrap(Cors)
+                .default_service(web::to(HttpResponse::Ok)),
+        )
+        .await;
+
+        actix_web::test::call_service(&app, req.to_request()).await
+    }
+
+    fn preflight(uri: &str, origin: &str, method: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri(uri)
+            .insert_header((header::ORIGIN, origin))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, method))
+    }
+
+    fn header<B>(resp: &ServiceResponse<B>, name: HeaderName) -> Option<&str> {
+        resp.headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_preflight() {
+        let resp = call(
+            preflight("/bucket/cat.jpg", "https://app.example.com", "PUT").insert_header((
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "Content-Type, X-Amz-Date",
+            )),
+        )
+        .await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some("PUT, POST")
+        );
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("Content-Type, X-Amz-Date")
+        );
+        assert_eq!(header(&resp, header::ACCESS_CONTROL_MAX_AGE), Some("3000"));
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+            Some("ETag")
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_wildcard_origin() {
+        let resp = call(preflight("/bucket", "https://example.org", "GET")).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("*")
+        );
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            None
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_preflight_not_allowed() {
+        for req in [
+            preflight("/bucket/cat.jpg", "https://example.org", "PUT"),
+            preflight("/bucket/cat.jpg", "https://app.example.com", "DELETE"),
+            preflight("/bucket/cat.jpg", "https://app.example.com", "PUT")
+                .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "Authorization")),
+        ] {
+            let resp = call(req).await;
+            assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+            let body = actix_web::test::read_body(resp).await;
+            assert!(String::from_utf8_lossy(&body).contains("<Code>CORSResponse</Code>"));
+        }
+
+        let resp = call(preflight("/private", "https://example.org", "GET")).await;
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("CORS is not enabled for this bucket."));
+
+        let resp = call(
+            TestRequest::default()
+                .method(Method::OPTIONS)
+                .uri("/bucket")
+                .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET")),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_actual_request() {
+        let resp = call(
+            TestRequest::get()
+                .uri("/bucket/cat.jpg")
+                .insert_header((header::ORIGIN, "https://example.org")),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            header(&resp, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("*")
+        );
+
+        // Requests a rule doesn't allow still go through, only without CORS headers
+        let resp = call(
+            TestRequest::delete()
+                .uri("/bucket/cat.jpg")
+                .insert_header((header::ORIGIN, "https://example.org")),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(header(&resp, header::ACCESS_CONTROL_ALLOW_ORIGIN), None);
+
+        let resp = call(TestRequest::get().uri("/bucket/cat.jpg")).await;
+        assert_eq!(header(&resp, header::ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Guards that route requests sharing a path by the subresource in their query string, such as
+//! `?uploads`
+
+use actix_web::{guard::GuardContext, http::header};
+
+/// Whether the query string has the parameter, with or without a value
+pub(crate) fn has_query_param(ctx: &GuardContext, name: &str) -> bool {
+    ctx.head()
+        .uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|param| param.split_once('=').map_or(param, |(key, _)| key) == name)
+}
+
+pub(crate) fn uploads(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "uploads")
+}
+
+pub(crate) fn upload_id(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "uploadId")
+}
+
+pub(crate) fn delete(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "delete")
+}
+
+pub(crate) fn tagging(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "tagging")
+}
+
+pub(crate) fn versioning(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "versioning")
+}
+
+pub(crate) fn versions(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "versions")
+}
+
+pub(crate) fn lifecycle(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "lifecycle")
+}
+
+pub(crate) fn cors(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "cors")
+}
+
+pub(crate) fn policy(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "policy")
+}
+
+pub(crate) fn acl(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "acl")
+}
+
+pub(crate) fn ownership_controls(ctx: &GuardContext) -> bool {
+    has_query_param(ctx, "ownershipControls")
+}
+
+pub(crate) fn list_objects_v2(ctx: &GuardContext) -> bool {
+    ctx.head()
+        .uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|param| param == "list-type=2")
+}
+
+pub(crate) fn copy_source(ctx: &GuardContext) -> bool {
+    ctx.head().headers().contains_key("x-amz-copy-source")
+}
+
+/// An UploadPart request that copies its part from an existing object instead of sending a body
+pub(crate) fn upload_part_copy(ctx: &GuardContext) -> bool {
+    upload_id(ctx) && copy_source(ctx)
+}
+
+/// An STS request, which names its action either in the query string or, as SDKs do, in a form
+/// body that only the handler can read
+pub(crate) fn assume_role(ctx: &GuardContext) -> bool {
+    let form = ctx
+        .head()
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+
+    form || ctx
+        .head()
+        .uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .any(|param| param == "Action=AssumeRole")
+}
@@ -1,10 +1,28 @@
 use actix_web::{web, App, HttpServer};
+use auth::{Credentials, SigV4Auth};
+use chrono::{DateTime, SecondsFormat, Utc};
+use cors::Cors;
+use lifecycle::LifecycleWorker;
 use rand::distributions::DistString;
 use s3_entities::storage_provider::StorageProvider;
 use std::sync::Arc;
 
+mod acl;
+pub mod admin;
+pub mod auth;
+mod body;
 pub mod bucket;
+mod conditional;
+mod copy_source;
+pub mod cors;
 mod error;
+mod guard;
+pub mod lifecycle;
+mod metadata;
+pub mod multipart;
+pub mod object;
+pub mod policy;
+pub mod sts;
 mod xml;
 
 fn generate_request_id() -> String {
@@ -13,25 +31,58 @@ fn generate_request_id() -> String {
         .to_uppercase()
 }
 
+/// Format a date the way HTTP headers such as `Last-Modified` expect it.
+fn http_date(date: &DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Format a date the way XML bodies such as `ListPartsResult` expect it.
+fn xml_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
 #[actix_web::main]
 async fn start(
-    storage_provider: impl StorageProvider + Send + Sync + 'static,
+    storage_provider: impl StorageProvider + 'static,
+    credentials: Credentials,
 ) -> std::io::Result<()> {
     let arc_provider: Arc<dyn StorageProvider> = Arc::new(storage_provider);
+    actix_web::rt::spawn(LifecycleWorker::from_env(arc_provider.clone()).run());
     let provider: web::Data<dyn StorageProvider> = web::Data::from(arc_provider);
 
-    HttpServer::new(move || {
+    let s3 = {
+        let (provider, credentials) = (provider.clone(), credentials.clone());
+
+        HttpServer::new(move || {
+            App::new()
+                .wrap(SigV4Auth::new(credentials.clone()).allow_anonymous(true))
+                .wrap(Cors)
+                .app_data(provider.clone())
+                .configure(sts::config)
+                .configure(multipart::config)
+                .configure(bucket::config)
+                .configure(object::config)
+        })
+        .bind(("0.0.0.0", 8080))?
+        .run()
+    };
+    // Kept off the S3 port, so that it can be firewalled apart
+    let admin = HttpServer::new(move || {
         App::new()
+            .wrap(SigV4Auth::new(credentials.clone()))
             .app_data(provider.clone())
-            .configure(bucket::config)
+            .configure(admin::config)
     })
-    .bind(("0.0.0.0", 8080))?
-    .run()
-    .await
+    .bind(("0.0.0.0", 8081))?
+    .run();
+
+    futures::future::try_join(s3, admin).await.map(|_| ())
 }
 
 pub fn main(
-    storage_provider: impl StorageProvider + Send + Sync + 'static,
+    storage_provider: impl StorageProvider + 'static,
+    credentials: Credentials,
 ) -> Result<(), std::io::Error> {
-    start(storage_provider)
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    start(storage_provider, credentials)
 }
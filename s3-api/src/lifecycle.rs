@@ -0,0 +1,495 @@
+//! The background task applying the lifecycle rules of buckets, which expires objects and
+//! their versions and aborts the uploads that were never completed.
+
+use chrono::{DateTime, Utc};
+use s3_entities::{
+    lifecycle::LifecycleRule,
+    list::MAX_KEYS,
+    multipart::ListUploadsOptions,
+    object::{ListVersionsOptions, ObjectVersion, Tags},
+    storage_provider::{StorageErr, StorageProvider},
+};
+use std::{env, sync::Arc, time::Duration};
+
+/// How often the rules are applied unless `LIFECYCLE_INTERVAL_SECONDS` says otherwise
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Where the worker takes the current time from, so that tests can move it forward
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Something the rules of a bucket called for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifecycleAction {
+    /// Delete an object, or one of its versions, the way `DeleteObject` does
+    Delete {
+        bucket: String,
+        key: String,
+        version_id: Option<String>,
+    },
+    AbortUpload {
+        bucket: String,
+        key: String,
+        upload_id: String,
+    },
+}
+
+pub struct LifecycleWorker {
+    provider: Arc<dyn StorageProvider>,
+    clock: Arc<dyn Clock>,
+    /// Only report what would be done, leaving the objects alone
+    dry_run: bool,
+    interval: Duration,
+}
+
+impl LifecycleWorker {
+    pub fn new(provider: Arc<dyn StorageProvider>) -> Self {
+        LifecycleWorker {
+            provider,
+            clock: Arc::new(SystemClock),
+            dry_run: false,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// A worker set up by `LIFECYCLE_INTERVAL_SECONDS` and `LIFECYCLE_DRY_RUN`
+    pub fn from_env(provider: Arc<dyn StorageProvider>) -> Self {
+        let interval = env::var("LIFECYCLE_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.parse().ok())
+            .filter(|&seconds| seconds > 0)
+            .map_or(DEFAULT_INTERVAL, Duration::from_secs);
+        let dry_run = env::var("LIFECYCLE_DRY_RUN")
+            .is_ok_and(|dry_run| matches!(dry_run.as_str(), "1" | "true"));
+
+        LifecycleWorker::new(provider)
+            .interval(interval)
+            .dry_run(dry_run)
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Apply the rules every interval, for as long as the server runs
+    pub async fn run(self) {
+        let mut interval = actix_web::rt::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run_once().await {
+                log::error!("lifecycle: failed to list buckets: {err}");
+            }
+        }
+    }
+
+    /// Apply the rules of every bucket once, returning what they called for. A bucket that
+    /// fails is skipped until the next run.
+    pub async fn run_once(&self) -> Result<Vec<LifecycleAction>, StorageErr> {
+        let now = self.clock.now();
+        let mut actions = Vec::new();
+
+        for bucket in self.provider.list_buckets(None).await? {
+            let rules = match self.provider.get_bucket_lifecycle(&bucket.name).await {
+                Ok(configuration) => configuration.rules,
+                Err(StorageErr::NoSuchLifecycleConfiguration | StorageErr::BucketNotFound) => {
+                    continue
+                }
+                Err(err) => {
+                    log::error!(
+                        "lifecycle: failed to read the rules of {}: {err}",
+                        bucket.name
+                    );
+                    continue;
+                }
+            };
+            let rules: Vec<_> = rules.into_iter().filter(|rule| rule.enabled).collect();
+
+            match self.bucket_actions(&bucket.name, &rules, now).await {
+                Ok(due) => actions.extend(due),
+                Err(err) => {
+                    log::error!("lifecycle: failed to evaluate {}: {err}", bucket.name);
+                    continue;
+                }
+            }
+        }
+
+        if !self.dry_run {
+            let mut applied = Vec::with_capacity(actions.len());
+            for action in actions {
+                match self.apply(&action).await {
+                    Ok(()) => applied.push(action),
+                    Err(err) => log::warn!("lifecycle: failed to apply {action:?}: {err}"),
+                }
+            }
+            actions = applied;
+        }
+
+        for action in &actions {
+            match self.dry_run {
+                true => log::info!("lifecycle (dry run): {action:?}"),
+                false => log::info!("lifecycle: {action:?}"),
+            }
+        }
+
+        Ok(actions)
+    }
+
+    async fn apply(&self, action: &LifecycleAction) -> Result<(), StorageErr> {
+        match action {
+            LifecycleAction::Delete {
+                bucket,
+                key,
+                version_id,
+            } => self
+                .provider
+                .delete_object(bucket, key, version_id.as_deref())
+                .await
+                .map(|_| ()),
+            LifecycleAction::AbortUpload {
+                bucket,
+                key,
+                upload_id,
+            } => {
+                self.provider
+                    .abort_multipart_upload(bucket, key, upload_id)
+                    .await
+            }
+        }
+    }
+
+    async fn bucket_actions(
+        &self,
+        bucket: &str,
+        rules: &[LifecycleRule],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<LifecycleAction>, StorageErr> {
+        let mut actions = Vec::new();
+        let versions = self.versions(bucket).await?;
+
+        // Versions come newest first within each key
+        for versions in versions.chunk_by(|a, b| a.object.key == b.object.key) {
+            actions.extend(self.object_actions(bucket, rules, versions, now).await?);
+        }
+
+        if rules
+            .iter()
+            .any(|rule| rule.abort_incomplete_upload_days.is_some())
+        {
+            let mut options = ListUploadsOptions {
+                max_uploads: MAX_KEYS,
+                ..Default::default()
+            };
+            loop {
+                let page = self
+                    .provider
+                    .list_multipart_uploads(bucket, options.clone())
+                    .await?;
+                options.key_marker = page.next_marker;
+                options.upload_id_marker = page.items.last().map(|upload| upload.upload_id.clone());
+
+                actions.extend(
+                    page.items
+                        .into_iter()
+                        .filter(|upload| rules.iter().any(|rule| rule.aborts_upload(upload, now)))
+                        .map(|upload| LifecycleAction::AbortUpload {
+                            bucket: bucket.into(),
+                            key: upload.key,
+                            upload_id: upload.upload_id,
+                        }),
+                );
+                if !page.is_truncated {
+                    break;
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// Every version of every object in a bucket
+    async fn versions(&self, bucket: &str) -> Result<Vec<ObjectVersion>, StorageErr> {
+        let mut versions = Vec::new();
+        let mut options = ListVersionsOptions {
+            max_keys: MAX_KEYS,
+            ..Default::default()
+        };
+
+        loop {
+            let page = self
+                .provider
+                .list_object_versions(bucket, options.clone())
+                .await?;
+            options.key_marker = page.next_marker;
+            options.version_id_marker = page
+                .items
+                .last()
+                .and_then(|version| version.object.version_id.clone());
+            versions.extend(page.items);
+
+            if !page.is_truncated {
+                return Ok(versions);
+            }
+        }
+    }
+
+    /// What the rules call for on the versions of one object, given newest first
+    async fn object_actions(
+        &self,
+        bucket: &str,
+        rules: &[LifecycleRule],
+        versions: &[ObjectVersion],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<LifecycleAction>, StorageErr> {
+        let mut actions = Vec::new();
+        let delete =
+            |version: &ObjectVersion, version_id: Option<String>| LifecycleAction::Delete {
+                bucket: bucket.into(),
+                key: version.object.key.clone(),
+                version_id,
+            };
+        let Some((current, noncurrent)) = versions.split_first() else {
+            return Ok(actions);
+        };
+
+        // A version stops being the current one as soon as a newer one is written
+        let mut remaining = 1;
+        for (newer, version) in versions.iter().zip(noncurrent) {
+            let tags = self.tags(bucket, rules, version).await?;
+            if rules.iter().any(|rule| {
+                rule.expires_noncurrent(&version.object, &tags, newer.object.last_modified, now)
+            }) {
+                actions.push(delete(version, version.object.version_id.clone()));
+            } else {
+                remaining += 1;
+            }
+        }
+
+        if current.object.delete_marker {
+            if remaining == 1
+                && rules
+                    .iter()
+                    .any(|rule| rule.removes_delete_marker(&current.object))
+            {
+                actions.push(delete(current, current.object.version_id.clone()));
+            }
+        } else {
+            let tags = self.tags(bucket, rules, current).await?;
+            if rules
+                .iter()
+                .any(|rule| rule.expires_current(&current.object, &tags, now))
+            {
+                actions.push(delete(current, None));
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// The tags of a version, only read when a rule filters on them
+    async fn tags(
+        &self,
+        bucket: &str,
+        rules: &[LifecycleRule],
+        version: &ObjectVersion,
+    ) -> Result<Tags, StorageErr> {
+        if version.object.delete_marker || rules.iter().all(|rule| rule.filter.tags.is_empty()) {
+            return Ok(Tags::new());
+        }
+
+        self.provider
+            .get_object_tagging(
+                bucket,
+                &version.object.key,
+                version.object.version_id.as_deref(),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web;
+    use chrono::Days;
+    use futures::stream;
+    use s3_entities::{
+        bucket::VersioningStatus,
+        lifecycle::{Expiration, LifecycleConfiguration, LifecycleFilter},
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::MockStorageProvider,
+    };
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    fn in_days(days: u64) -> FixedClock {
+        FixedClock(Utc::now() + Days::new(days))
+    }
+
+    async fn put(provider: &Arc<dyn StorageProvider>, key: &str) -> Option<String> {
+        provider
+            .put_object(
+                "bucket",
+                key,
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap()
+            .version_id
+    }
+
+    async fn provider(rules: Vec<LifecycleRule>) -> Arc<dyn StorageProvider> {
+        let provider: Arc<dyn StorageProvider> = Arc::new(MockStorageProvider::new());
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_bucket_lifecycle("bucket", LifecycleConfiguration { rules })
+            .await
+            .unwrap();
+
+        provider
+    }
+
+    #[actix_web::test]
+    async fn test_expiration() {
+        let provider = provider(vec![LifecycleRule {
+            enabled: true,
+            filter: LifecycleFilter {
+                prefix: "logs/".into(),
+                ..Default::default()
+            },
+            expiration: Some(Expiration::Days(30)),
+            ..Default::default()
+        }])
+        .await;
+        put(&provider, "logs/a.log").await;
+        put(&provider, "cat.jpg").await;
+
+        let worker = LifecycleWorker::new(provider.clone()).clock(in_days(2));
+        assert_eq!(worker.run_once().await.unwrap(), vec![]);
+
+        let expired = vec![LifecycleAction::Delete {
+            bucket: "bucket".into(),
+            key: "logs/a.log".into(),
+            version_id: None,
+        }];
+        let worker = LifecycleWorker::new(provider.clone())
+            .clock(in_days(32))
+            .dry_run(true);
+        assert_eq!(worker.run_once().await.unwrap(), expired);
+        assert!(provider
+            .head_object("bucket", "logs/a.log", None)
+            .await
+            .is_ok());
+
+        let worker = worker.dry_run(false);
+        assert_eq!(worker.run_once().await.unwrap(), expired);
+        assert!(matches!(
+            provider.head_object("bucket", "logs/a.log", None).await,
+            Err(StorageErr::ObjectNotFound)
+        ));
+        assert!(provider
+            .head_object("bucket", "cat.jpg", None)
+            .await
+            .is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_noncurrent_versions() {
+        let provider = provider(vec![LifecycleRule {
+            enabled: true,
+            expiration: Some(Expiration::ExpiredObjectDeleteMarker),
+            noncurrent_days: Some(1),
+            abort_incomplete_upload_days: Some(7),
+            ..Default::default()
+        }])
+        .await;
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        let old = put(&provider, "cat.jpg").await;
+        let current = put(&provider, "cat.jpg").await;
+        let deleted = put(&provider, "dog.jpg").await;
+        let marker = provider
+            .delete_object("bucket", "dog.jpg", None)
+            .await
+            .unwrap()
+            .version_id;
+        let upload = provider
+            .create_multipart_upload("bucket", "big.bin", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        // The delete marker is only removed once it's all that's left of its object
+        let worker = LifecycleWorker::new(provider.clone()).clock(in_days(0));
+        assert_eq!(worker.run_once().await.unwrap(), vec![]);
+
+        let worker = worker.clock(in_days(8));
+        let actions = worker.run_once().await.unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                LifecycleAction::Delete {
+                    bucket: "bucket".into(),
+                    key: "cat.jpg".into(),
+                    version_id: old.clone(),
+                },
+                LifecycleAction::Delete {
+                    bucket: "bucket".into(),
+                    key: "dog.jpg".into(),
+                    version_id: deleted,
+                },
+                LifecycleAction::Delete {
+                    bucket: "bucket".into(),
+                    key: "dog.jpg".into(),
+                    version_id: marker,
+                },
+                LifecycleAction::AbortUpload {
+                    bucket: "bucket".into(),
+                    key: "big.bin".into(),
+                    upload_id: upload.upload_id,
+                },
+            ]
+        );
+        assert!(provider
+            .head_object("bucket", "cat.jpg", current.as_deref())
+            .await
+            .is_ok());
+        assert!(provider
+            .head_object("bucket", "cat.jpg", old.as_deref())
+            .await
+            .is_err());
+        assert_eq!(worker.run_once().await.unwrap(), vec![]);
+    }
+}
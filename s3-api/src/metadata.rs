@@ -0,0 +1,118 @@
+//! Object metadata, which is sent along with writes as headers and returned the same way
+
+use actix_web::{http::header, HttpResponseBuilder};
+use s3_entities::object::{ObjectMetadata, MAX_USER_METADATA_SIZE};
+
+const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+/// The content coding of streaming uploads, which describes the request rather than the object
+const AWS_CHUNKED: &str = "aws-chunked";
+
+/// Read the metadata of an object from the headers of the request writing it, `None` meaning
+/// its user-defined metadata is bigger than S3 allows
+pub(crate) fn metadata_from_headers(headers: &header::HeaderMap) -> Option<ObjectMetadata> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+
+    let content_encoding = header(header::CONTENT_ENCODING).and_then(|value| {
+        let codings: Vec<_> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|coding| !coding.eq_ignore_ascii_case(AWS_CHUNKED))
+            .collect();
+
+        (!codings.is_empty()).then(|| codings.join(", "))
+    });
+    let user = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = name.as_str().strip_prefix(USER_METADATA_PREFIX)?;
+
+            Some((name.to_owned(), value.to_str().ok()?.to_owned()))
+        })
+        .collect();
+
+    let metadata = ObjectMetadata {
+        content_type: header(header::CONTENT_TYPE),
+        content_encoding,
+        content_disposition: header(header::CONTENT_DISPOSITION),
+        content_language: header(header::CONTENT_LANGUAGE),
+        cache_control: header(header::CACHE_CONTROL),
+        expires: header(header::EXPIRES),
+        user,
+    };
+
+    (metadata.user_size() <= MAX_USER_METADATA_SIZE).then_some(metadata)
+}
+
+/// Append the headers an object's metadata is returned as, other than its Content-Type which
+/// has a default
+pub(crate) fn append_metadata_headers(
+    builder: &mut HttpResponseBuilder,
+    metadata: &ObjectMetadata,
+) {
+    for (name, value) in [
+        (header::CONTENT_ENCODING, &metadata.content_encoding),
+        (header::CONTENT_DISPOSITION, &metadata.content_disposition),
+        (header::CONTENT_LANGUAGE, &metadata.content_language),
+        (header::CACHE_CONTROL, &metadata.cache_control),
+        (header::EXPIRES, &metadata.expires),
+    ] {
+        if let Some(value) = value {
+            builder.append_header((name, value.as_str()));
+        }
+    }
+
+    for (name, value) in &metadata.user {
+        builder.append_header((format!("{USER_METADATA_PREFIX}{name}"), value.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_metadata_from_headers() {
+        let req = TestRequest::default()
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .insert_header((header::CONTENT_ENCODING, "gzip, aws-chunked"))
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            .insert_header(("X-Amz-Meta-Color", "orange"))
+            .to_http_request();
+        let metadata = metadata_from_headers(req.headers()).unwrap();
+
+        assert_eq!(metadata.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(metadata.content_encoding.as_deref(), Some("gzip"));
+        assert_eq!(metadata.cache_control.as_deref(), Some("no-cache"));
+        assert_eq!(metadata.content_language, None);
+        assert_eq!(metadata.user["color"], "orange");
+
+        let req = TestRequest::default()
+            .insert_header((header::CONTENT_ENCODING, "aws-chunked"))
+            .to_http_request();
+        assert_eq!(
+            metadata_from_headers(req.headers())
+                .unwrap()
+                .content_encoding,
+            None
+        );
+    }
+
+    #[test]
+    fn test_metadata_too_large() {
+        let req = TestRequest::default()
+            .insert_header(("x-amz-meta-a", "a".repeat(MAX_USER_METADATA_SIZE - 1)))
+            .to_http_request();
+        assert!(metadata_from_headers(req.headers()).is_some());
+
+        let req = TestRequest::default()
+            .insert_header(("x-amz-meta-ab", "a".repeat(MAX_USER_METADATA_SIZE - 1)))
+            .to_http_request();
+        assert!(metadata_from_headers(req.headers()).is_none());
+    }
+}
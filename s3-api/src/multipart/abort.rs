@@ -0,0 +1,105 @@
+extern crate self as s3_api;
+
+use super::UploadIdQuery;
+use crate::generate_request_id;
+use actix_web::{delete, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::storage_provider::{StorageErr, StorageProvider};
+
+#[derive(Debug, S3Error)]
+enum AbortMultipartUploadError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The specified multipart upload does not exist. The upload ID might not be valid, or the multipart upload might have been aborted or completed."
+    )]
+    NoSuchUpload {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::upload_id",
+    wrap = "crate::policy::Authorize::action(\"s3:AbortMultipartUpload\")"
+)]
+pub async fn abort_multipart_upload(
+    path: web::Path<(String, String)>,
+    query: web::Query<UploadIdQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, AbortMultipartUploadError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    storage_provider
+        .into_inner()
+        .abort_multipart_upload(&bucket, &key, &query.upload_id)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => AbortMultipartUploadError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::UploadNotFound => AbortMultipartUploadError::NoSuchUpload {
+                request_id,
+                resource,
+            },
+            _ => AbortMultipartUploadError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::{object::PutObjectOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_abort_multipart_upload() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(abort_multipart_upload),
+        )
+        .await;
+        let uri = format!("/bucket/key?uploadId={}", upload.upload_id);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&uri)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&uri)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,371 @@
+extern crate self as s3_api;
+
+use super::UploadIdQuery;
+use crate::{
+    conditional::write_condition,
+    generate_request_id,
+    xml::{CompleteMultipartUpload, CompleteMultipartUploadResult},
+};
+use actix_web::{http::header, post, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    multipart::CompletedPart,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum CompleteMultipartUploadError {
+    #[error(
+        status_code = 400,
+        message = "Your proposed upload is smaller than the minimum allowed object size."
+    )]
+    EntityTooSmall {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "One or more of the specified parts could not be found. The part might not have been uploaded, or the specified entity tag might not have matched the part's entity tag."
+    )]
+    InvalidPart {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The list of parts was not in ascending order. The parts list must be specified in order by part number."
+    )]
+    InvalidPartOrder {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The specified multipart upload does not exist. The upload ID might not be valid, or the multipart upload might have been aborted or completed."
+    )]
+    NoSuchUpload {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 501,
+        message = "A header you provided implies functionality that is not implemented."
+    )]
+    NotImplemented {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 412,
+        message = "At least one of the preconditions you specified did not hold."
+    )]
+    PreconditionFailed {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[post(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::upload_id",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn complete_multipart_upload(
+    path: web::Path<(String, String)>,
+    query: web::Query<UploadIdQuery>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, CompleteMultipartUploadError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let Some(condition) = write_condition(req.headers()) else {
+        return Err(CompleteMultipartUploadError::NotImplemented {
+            request_id,
+            resource,
+        });
+    };
+    let parts: Vec<CompletedPart> =
+        match quick_xml::de::from_reader::<_, CompleteMultipartUpload>(body.as_ref()) {
+            Ok(request) if !request.parts.is_empty() => request
+                .parts
+                .into_iter()
+                .map(|part| CompletedPart {
+                    part_number: part.part_number,
+                    etag: part.etag,
+                })
+                .collect(),
+            _ => {
+                return Err(CompleteMultipartUploadError::MalformedXML {
+                    request_id,
+                    resource,
+                })
+            }
+        };
+
+    let object = storage_provider
+        .into_inner()
+        .complete_multipart_upload(&bucket, &key, &query.upload_id, parts, condition)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => CompleteMultipartUploadError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::UploadNotFound => CompleteMultipartUploadError::NoSuchUpload {
+                request_id,
+                resource,
+            },
+            StorageErr::InvalidPart => CompleteMultipartUploadError::InvalidPart {
+                request_id,
+                resource,
+            },
+            StorageErr::InvalidPartOrder => CompleteMultipartUploadError::InvalidPartOrder {
+                request_id,
+                resource,
+            },
+            StorageErr::EntityTooSmall => CompleteMultipartUploadError::EntityTooSmall {
+                request_id,
+                resource,
+            },
+            StorageErr::PreconditionFailed => CompleteMultipartUploadError::PreconditionFailed {
+                request_id,
+                resource,
+            },
+            _ => CompleteMultipartUploadError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    let connection_info = req.connection_info();
+    let location = format!(
+        "{}://{}/{bucket}/{key}",
+        connection_info.scheme(),
+        connection_info.host()
+    );
+
+    let mut response = HttpResponse::Ok();
+    if let Some(version_id) = &object.version_id {
+        response.insert_header(("x-amz-version-id", version_id.as_str()));
+    }
+
+    Ok(response.insert_header(header::ContentType::xml()).body(
+        quick_xml::se::to_string(&CompleteMultipartUploadResult {
+            location,
+            bucket,
+            key,
+            etag: format!("\"{}\"", object.etag),
+        })
+        .unwrap(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::{future, stream, TryStreamExt};
+    use s3_entities::{
+        multipart::{MultipartUpload, MIN_PART_SIZE},
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+    use std::sync::Arc;
+
+    /// Start an upload of a part of the minimum size followed by a 4 byte part
+    async fn upload(provider: &Arc<dyn StorageProvider>) -> (MultipartUpload, Vec<String>) {
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let mut etags = Vec::new();
+        for (part_number, data) in [(1, vec![b'a'; MIN_PART_SIZE as usize]), (2, b"meow".into())] {
+            let part = provider
+                .upload_part(
+                    "bucket",
+                    "key",
+                    &upload.upload_id,
+                    part_number,
+                    Box::pin(stream::once(future::ready(Ok(data.into())))),
+                )
+                .await
+                .unwrap();
+
+            etags.push(part.etag);
+        }
+
+        (upload, etags)
+    }
+
+    fn request(upload: &MultipartUpload, parts: &[(u16, &str)]) -> actix_web::test::TestRequest {
+        let parts: String = parts
+            .iter()
+            .map(|(part_number, etag)| {
+                format!(
+                    "<Part><PartNumber>{part_number}</PartNumber><ETag>\"{etag}\"</ETag></Part>"
+                )
+            })
+            .collect();
+
+        actix_web::test::TestRequest::post()
+            .uri(&format!("/bucket/key?uploadId={}", upload.upload_id))
+            .set_payload(format!(
+                "<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>"
+            ))
+    }
+
+    #[actix_web::test]
+    async fn test_complete_multipart_upload() {
+        let provider = get_mock_app_data();
+        let (upload, etags) = upload(&provider).await;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(complete_multipart_upload),
+        )
+        .await;
+        let req = request(&upload, &[(1, &etags[0]), (2, &etags[1])]).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: CompleteMultipartUploadResult =
+            quick_xml::de::from_reader(body.as_ref()).unwrap();
+        let (object, data) = provider.get_object("bucket", "key", None).await.unwrap();
+        let data: Vec<_> = data.try_collect().await.unwrap();
+
+        assert!(result.etag.ends_with("-2\""));
+        assert_eq!(result.etag, format!("\"{}\"", object.etag));
+        assert_eq!(object.size, MIN_PART_SIZE + 4);
+        assert!(data.concat().ends_with(b"ameow"));
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_parts() {
+        let provider = get_mock_app_data();
+        let (upload, etags) = upload(&provider).await;
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(complete_multipart_upload),
+        )
+        .await;
+
+        for (parts, code) in [
+            (
+                vec![(2, etags[1].as_str()), (1, etags[0].as_str())],
+                "InvalidPartOrder",
+            ),
+            (vec![(1, etags[1].as_str())], "InvalidPart"),
+            (vec![(3, etags[1].as_str())], "InvalidPart"),
+            (vec![], "MalformedXML"),
+        ] {
+            let resp =
+                actix_web::test::call_service(&app, request(&upload, &parts).to_request()).await;
+            assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+            let body = actix_web::test::read_body(resp).await;
+            assert!(String::from_utf8_lossy(&body).contains(&format!("<Code>{code}</Code>")));
+        }
+
+        // The upload can still be completed after it was rejected
+        let resp = actix_web::test::call_service(
+            &app,
+            request(&upload, &[(1, &etags[0]), (2, &etags[1])]).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_complete_if_absent() {
+        let provider = get_mock_app_data();
+        let (upload, etags) = upload(&provider).await;
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(future::ready(Ok("purr".into())))),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(complete_multipart_upload),
+        )
+        .await;
+        let req = request(&upload, &[(1, &etags[0]), (2, &etags[1])])
+            .insert_header((header::IF_NONE_MATCH, "*"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+
+        // The upload is kept, and completes once the object is gone
+        provider.delete_object("bucket", "key", None).await.unwrap();
+        let req = request(&upload, &[(1, &etags[0]), (2, &etags[1])])
+            .insert_header((header::IF_NONE_MATCH, "*"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_upload() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(complete_multipart_upload),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/bucket/key?uploadId=missing")
+            .set_payload(
+                "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>\"a\"</ETag></Part></CompleteMultipartUpload>",
+            )
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
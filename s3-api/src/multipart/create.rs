@@ -0,0 +1,287 @@
+extern crate self as s3_api;
+
+use crate::{
+    acl::{object_write_acl, AclError},
+    generate_request_id,
+    metadata::metadata_from_headers,
+    object::tagging::tags_from_headers,
+    xml::InitiateMultipartUploadResult,
+};
+use actix_web::{http::header, post, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    object::PutObjectOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum CreateMultipartUploadError {
+    #[error(status_code = 400, message = "The bucket does not allow ACLs.")]
+    AccessControlListNotSupported {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The tag provided was not a valid tag. Objects can have up to 10 tags with unique keys of up to 128 characters and values of up to 256 characters."
+    )]
+    InvalidTag {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Your metadata headers exceed the maximum allowed metadata size."
+    )]
+    MetadataTooLarge {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[post(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::uploads",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn create_multipart_upload(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, CreateMultipartUploadError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let Some(metadata) = metadata_from_headers(req.headers()) else {
+        return Err(CreateMultipartUploadError::MetadataTooLarge {
+            request_id,
+            resource,
+        });
+    };
+    let Some(tags) = tags_from_headers(req.headers()) else {
+        return Err(CreateMultipartUploadError::InvalidTag {
+            request_id,
+            resource,
+        });
+    };
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => CreateMultipartUploadError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => CreateMultipartUploadError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    // Checked now rather than when the upload is completed, as S3 does
+    let acl = object_write_acl(storage_provider.as_ref(), &bucket, &req)
+        .await
+        .map_err(|e| {
+            let (request_id, resource) = (request_id.clone(), resource.clone());
+            match e {
+                AclError::NotSupported => {
+                    CreateMultipartUploadError::AccessControlListNotSupported {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::InvalidRequest => CreateMultipartUploadError::InvalidRequest {
+                    request_id,
+                    resource,
+                },
+                AclError::UnresolvableGrantByEmailAddress => {
+                    CreateMultipartUploadError::UnresolvableGrantByEmailAddress {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::Malformed | AclError::InvalidArgument => {
+                    CreateMultipartUploadError::InvalidArgument {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::Storage(e) => error(e),
+            }
+        })?;
+
+    let upload = storage_provider
+        .create_multipart_upload(
+            &bucket,
+            &key,
+            PutObjectOptions {
+                metadata,
+                tags,
+                acl,
+            },
+        )
+        .await
+        .map_err(error)?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&InitiateMultipartUploadResult {
+                bucket,
+                key,
+                upload_id: upload.upload_id,
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use bytes::Bytes;
+    use futures::{future, stream};
+    use s3_entities::{
+        multipart::CompletedPart, object::WriteCondition, test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_create_multipart_upload() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(create_multipart_upload),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/bucket/photos/cat.jpg?uploads")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: InitiateMultipartUploadResult =
+            quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        assert_eq!(result.bucket, "bucket");
+        assert_eq!(result.key, "photos/cat.jpg");
+        assert!(!result.upload_id.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_create_multipart_upload_tagging() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(create_multipart_upload),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/bucket/key?uploads")
+            .insert_header(("x-amz-tagging", "project=cat"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        let result: InitiateMultipartUploadResult =
+            quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        // The tags are only the object's once the upload is completed
+        let part = provider
+            .upload_part(
+                "bucket",
+                "key",
+                &result.upload_id,
+                1,
+                Box::pin(stream::once(future::ok(Bytes::from_static(b"meow")))),
+            )
+            .await
+            .unwrap();
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "key",
+                &result.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let tags = provider
+            .get_object_tagging("bucket", "key", None)
+            .await
+            .unwrap();
+        assert_eq!(tags["project"], "cat");
+
+        let req = actix_web::test::TestRequest::post()
+            .uri("/bucket/key?uploads")
+            .insert_header(("x-amz-tagging", "project=cat&project=dog"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(create_multipart_upload),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/bucket/key?uploads")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,213 @@
+extern crate self as s3_api;
+
+use super::parse_param;
+use crate::{
+    generate_request_id,
+    xml::{ListPartsResult, ListedPart},
+    xml_date,
+};
+use actix_web::{get, http::header, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    list::MAX_KEYS,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum ListPartsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Arguments part-number-marker and max-parts must be integers."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The specified multipart upload does not exist. The upload ID might not be valid, or the multipart upload might have been aborted or completed."
+    )]
+    NoSuchUpload {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ListPartsQuery {
+    #[serde(rename = "uploadId")]
+    upload_id: String,
+    #[serde(rename = "part-number-marker")]
+    part_number_marker: Option<String>,
+    #[serde(rename = "max-parts")]
+    max_parts: Option<String>,
+}
+
+#[get(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::upload_id",
+    wrap = "crate::policy::Authorize::action(\"s3:ListMultipartUploadParts\")"
+)]
+pub async fn list_parts(
+    path: web::Path<(String, String)>,
+    query: web::Query<ListPartsQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, ListPartsError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let (Some(part_number_marker), Some(max_parts)) = (
+        parse_param(&query.part_number_marker, 0),
+        parse_param(&query.max_parts, MAX_KEYS),
+    ) else {
+        return Err(ListPartsError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    let max_parts = max_parts.min(MAX_KEYS);
+
+    let page = storage_provider
+        .into_inner()
+        .list_parts(
+            &bucket,
+            &key,
+            &query.upload_id,
+            Some(part_number_marker).filter(|marker| *marker > 0),
+            max_parts,
+        )
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => ListPartsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::UploadNotFound => ListPartsError::NoSuchUpload {
+                request_id,
+                resource,
+            },
+            _ => ListPartsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&ListPartsResult {
+                bucket,
+                key,
+                upload_id: query.into_inner().upload_id,
+                part_number_marker,
+                next_part_number_marker: page.next_part_number_marker,
+                max_parts,
+                is_truncated: page.is_truncated,
+                parts: page
+                    .parts
+                    .into_iter()
+                    .map(|part| ListedPart {
+                        part_number: part.part_number,
+                        last_modified: xml_date(&part.last_modified),
+                        etag: format!("\"{}\"", part.etag),
+                        size: part.size,
+                    })
+                    .collect(),
+                storage_class: "STANDARD".into(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{object::PutObjectOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_list_parts() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        for part_number in 1..=3 {
+            provider
+                .upload_part(
+                    "bucket",
+                    "key",
+                    &upload.upload_id,
+                    part_number,
+                    Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                )
+                .await
+                .unwrap();
+        }
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_parts),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/bucket/key?uploadId={}&part-number-marker=1&max-parts=1",
+                upload.upload_id
+            ))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: ListPartsResult = quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        assert_eq!(result.parts.len(), 1);
+        assert_eq!(result.parts[0].part_number, 2);
+        assert_eq!(result.parts[0].etag, "\"4a4be40c96ac6314e91d93f38043a634\"");
+        assert_eq!(result.parts[0].size, 4);
+        assert_eq!(result.next_part_number_marker, Some(2));
+        assert!(result.is_truncated);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_upload() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_parts),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?uploadId=abc")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,234 @@
+extern crate self as s3_api;
+
+use super::parse_param;
+use crate::{
+    generate_request_id,
+    xml::{CommonPrefix, ListMultipartUploadsResult, Upload},
+    xml_date,
+};
+use actix_web::{get, http::header, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    list::MAX_KEYS,
+    multipart::ListUploadsOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum ListMultipartUploadsError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Argument max-uploads must be an integer."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ListUploadsQuery {
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    #[serde(rename = "key-marker")]
+    key_marker: Option<String>,
+    #[serde(rename = "upload-id-marker")]
+    upload_id_marker: Option<String>,
+    #[serde(rename = "max-uploads")]
+    max_uploads: Option<String>,
+}
+
+#[get(
+    "/{bucket}",
+    guard = "crate::guard::uploads",
+    wrap = "crate::policy::Authorize::action(\"s3:ListBucketMultipartUploads\")"
+)]
+pub async fn list_multipart_uploads(
+    path: web::Path<String>,
+    query: web::Query<ListUploadsQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, ListMultipartUploadsError> {
+    let request_id = generate_request_id();
+    let bucket = path.into_inner();
+    let resource = format!("/{bucket}");
+    let query = query.into_inner();
+
+    let Some(max_uploads) = parse_param(&query.max_uploads, MAX_KEYS) else {
+        return Err(ListMultipartUploadsError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    let max_uploads = max_uploads.min(MAX_KEYS);
+
+    let page = storage_provider
+        .into_inner()
+        .list_multipart_uploads(
+            &bucket,
+            ListUploadsOptions {
+                prefix: query.prefix.clone(),
+                delimiter: query.delimiter.clone(),
+                key_marker: query.key_marker.clone(),
+                upload_id_marker: query.upload_id_marker.clone(),
+                max_uploads,
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => ListMultipartUploadsError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            _ => ListMultipartUploadsError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    // The upload id marker is only needed when the page ended on an upload rather than on a
+    // common prefix, since a key can have several uploads in progress
+    let next_upload_id_marker = page
+        .items
+        .last()
+        .filter(|upload| page.next_marker.as_ref() == Some(&upload.key))
+        .map(|upload| upload.upload_id.clone());
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&ListMultipartUploadsResult {
+                bucket,
+                key_marker: query.key_marker.unwrap_or_default(),
+                upload_id_marker: query.upload_id_marker.unwrap_or_default(),
+                next_key_marker: page.next_marker,
+                next_upload_id_marker,
+                delimiter: query.delimiter,
+                prefix: query.prefix.unwrap_or_default(),
+                max_uploads,
+                is_truncated: page.is_truncated,
+                uploads: page
+                    .items
+                    .into_iter()
+                    .map(|upload| Upload {
+                        key: upload.key,
+                        upload_id: upload.upload_id,
+                        storage_class: "STANDARD".into(),
+                        initiated: xml_date(&upload.initiated),
+                    })
+                    .collect(),
+                common_prefixes: page
+                    .common_prefixes
+                    .into_iter()
+                    .map(|prefix| CommonPrefix { prefix })
+                    .collect(),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::{object::PutObjectOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_list_multipart_uploads() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        for key in ["a.jpg", "photos/a.jpg", "photos/b.jpg", "z.jpg"] {
+            provider
+                .create_multipart_upload("bucket", key, PutObjectOptions::default())
+                .await
+                .unwrap();
+        }
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_multipart_uploads),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?uploads&delimiter=/&max-uploads=2")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: ListMultipartUploadsResult = quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        assert_eq!(result.uploads.len(), 1);
+        assert_eq!(result.uploads[0].key, "a.jpg");
+        assert_eq!(result.common_prefixes[0].prefix, "photos/");
+        assert_eq!(result.next_key_marker.as_deref(), Some("photos/"));
+        assert_eq!(result.next_upload_id_marker, None);
+        assert!(result.is_truncated);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?uploads&delimiter=/&key-marker=photos/")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let body = actix_web::test::read_body(resp).await;
+        let result: ListMultipartUploadsResult = quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        assert_eq!(result.uploads.len(), 1);
+        assert_eq!(result.uploads[0].key, "z.jpg");
+        assert!(result.common_prefixes.is_empty());
+        assert!(!result.is_truncated);
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_max_uploads() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_multipart_uploads),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?uploads&max-uploads=many")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(list_multipart_uploads),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket?uploads")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,83 @@
+use actix_web::web;
+use serde::Deserialize;
+
+mod abort;
+mod complete;
+mod create;
+mod list_parts;
+mod list_uploads;
+mod upload_part;
+mod upload_part_copy;
+
+/// Has to be configured before the bucket and object routes, which share the same paths and are
+/// only told apart by the query string
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_uploads::list_multipart_uploads)
+        .service(list_parts::list_parts)
+        .service(create::create_multipart_upload)
+        .service(upload_part_copy::upload_part_copy)
+        .service(upload_part::upload_part)
+        .service(complete::complete_multipart_upload)
+        .service(abort::abort_multipart_upload);
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadIdQuery {
+    #[serde(rename = "uploadId")]
+    upload_id: String,
+}
+
+/// Parse an optional numeric query parameter such as `max-uploads`, `None` meaning it's invalid
+fn parse_param<T: std::str::FromStr>(value: &Option<String>, default: T) -> Option<T> {
+    match value {
+        Some(value) => value.parse().ok(),
+        None => Some(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, test, web, App};
+    use s3_entities::{object::PutObjectOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_routes_by_query() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .configure(super::config)
+                .configure(crate::object::config),
+        )
+        .await;
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/bucket/key?partNumber=1&uploadId={}",
+                upload.upload_id
+            ))
+            .set_payload("meow")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(provider.head_object("bucket", "key", None).await.is_err());
+
+        let req = test::TestRequest::put()
+            .uri("/bucket/key")
+            .set_payload("meow")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(provider.head_object("bucket", "key", None).await.is_ok());
+    }
+}
@@ -0,0 +1,251 @@
+extern crate self as s3_api;
+
+use crate::{body::payload_stream, generate_request_id};
+use actix_web::{http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    multipart::MAX_PART_NUMBER,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct UploadPartQuery {
+    #[serde(rename = "partNumber")]
+    part_number: Option<String>,
+    #[serde(rename = "uploadId")]
+    upload_id: String,
+}
+
+#[derive(Debug, S3Error)]
+enum UploadPartError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "You did not provide the number of bytes specified by the Content-Length HTTP header."
+    )]
+    IncompleteBody {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Part number must be an integer between 1 and 10000, inclusive."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The specified multipart upload does not exist. The upload ID might not be valid, or the multipart upload might have been aborted or completed."
+    )]
+    NoSuchUpload {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "The request signature we calculated does not match the signature you provided. Check your key and signing method."
+    )]
+    SignatureDoesNotMatch {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The provided 'x-amz-content-sha256' header does not match what was computed."
+    )]
+    XAmzContentSHA256Mismatch {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::upload_id",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn upload_part(
+    path: web::Path<(String, String)>,
+    query: web::Query<UploadPartQuery>,
+    req: HttpRequest,
+    payload: web::Payload,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, UploadPartError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+    let query = query.into_inner();
+
+    let Some(part_number) = query
+        .part_number
+        .and_then(|part_number| part_number.parse::<u16>().ok())
+        .filter(|part_number| (1..=MAX_PART_NUMBER).contains(part_number))
+    else {
+        return Err(UploadPartError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+
+    let part = storage_provider
+        .into_inner()
+        .upload_part(
+            &bucket,
+            &key,
+            &query.upload_id,
+            part_number,
+            payload_stream(&req, payload),
+        )
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => UploadPartError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::UploadNotFound => UploadPartError::NoSuchUpload {
+                request_id,
+                resource,
+            },
+            StorageErr::BadDigest => UploadPartError::BadDigest {
+                request_id,
+                resource,
+            },
+            StorageErr::IncompleteBody => UploadPartError::IncompleteBody {
+                request_id,
+                resource,
+            },
+            StorageErr::SignatureDoesNotMatch => UploadPartError::SignatureDoesNotMatch {
+                request_id,
+                resource,
+            },
+            StorageErr::ContentSha256Mismatch => UploadPartError::XAmzContentSHA256Mismatch {
+                request_id,
+                resource,
+            },
+            _ => UploadPartError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .append_header((header::ETAG, format!("\"{}\"", part.etag)))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use s3_entities::{object::PutObjectOptions, test::storage_provider::get_mock_app_data};
+
+    #[actix_web::test]
+    async fn test_upload_part() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(upload_part),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri(&format!(
+                "/bucket/key?partNumber=1&uploadId={}",
+                upload.upload_id
+            ))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::ETAG).unwrap(),
+            "\"4a4be40c96ac6314e91d93f38043a634\""
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_part_number() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(upload_part),
+        )
+        .await;
+
+        for part_number in ["0", "10001", "one"] {
+            let req = actix_web::test::TestRequest::put()
+                .uri(&format!(
+                    "/bucket/key?partNumber={part_number}&uploadId={}",
+                    upload.upload_id
+                ))
+                .set_payload("meow")
+                .to_request();
+            let resp = actix_web::test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_upload() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(upload_part),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key?partNumber=1&uploadId=missing")
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,288 @@
+extern crate self as s3_api;
+
+use crate::{
+    copy_source::{parse_copy_source, parse_copy_source_range},
+    generate_request_id,
+    xml::CopyPartResult,
+    xml_date,
+};
+use actix_web::{http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    multipart::MAX_PART_NUMBER,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct UploadPartCopyQuery {
+    #[serde(rename = "partNumber")]
+    part_number: Option<String>,
+    #[serde(rename = "uploadId")]
+    upload_id: String,
+}
+
+#[derive(Debug, S3Error)]
+enum UploadPartCopyError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Part number must be an integer between 1 and 10000, inclusive, and x-amz-copy-source and x-amz-copy-source-range must be of the form bucket/key and bytes=first-last."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 416, message = "The requested range is not satisfiable.")]
+    InvalidRange {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 404,
+        message = "The specified multipart upload does not exist. The upload ID might not be valid, or the multipart upload might have been aborted or completed."
+    )]
+    NoSuchUpload {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::upload_part_copy",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn upload_part_copy(
+    path: web::Path<(String, String)>,
+    query: web::Query<UploadPartCopyQuery>,
+    req: HttpRequest,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, UploadPartCopyError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+    let query = query.into_inner();
+
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let part_number = query
+        .part_number
+        .and_then(|part_number| part_number.parse::<u16>().ok())
+        .filter(|part_number| (1..=MAX_PART_NUMBER).contains(part_number));
+    let source = header("x-amz-copy-source").and_then(parse_copy_source);
+    let range = header("x-amz-copy-source-range").map(parse_copy_source_range);
+
+    let (Some(part_number), Some(source), None | Some(Some(_))) = (part_number, source, &range)
+    else {
+        return Err(UploadPartCopyError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+
+    let part = storage_provider
+        .into_inner()
+        .upload_part_copy(
+            &bucket,
+            &key,
+            &query.upload_id,
+            part_number,
+            &source,
+            range.flatten(),
+        )
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => UploadPartCopyError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::ObjectNotFound | StorageErr::DeleteMarker { .. } => {
+                UploadPartCopyError::NoSuchKey {
+                    request_id,
+                    resource,
+                }
+            }
+            StorageErr::VersionNotFound => UploadPartCopyError::NoSuchVersion {
+                request_id,
+                resource,
+            },
+            StorageErr::UploadNotFound => UploadPartCopyError::NoSuchUpload {
+                request_id,
+                resource,
+            },
+            StorageErr::InvalidRange => UploadPartCopyError::InvalidRange {
+                request_id,
+                resource,
+            },
+            _ => UploadPartCopyError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&CopyPartResult {
+                etag: format!("\"{}\"", part.etag),
+                last_modified: xml_date(&part.last_modified),
+            })
+            .unwrap(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        multipart::CompletedPart,
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_upload_part_copy() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "photos/cat one.jpg",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "copy", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(upload_part_copy),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri(&format!(
+                "/bucket/copy?partNumber=1&uploadId={}",
+                upload.upload_id
+            ))
+            .insert_header(("x-amz-copy-source", "/bucket/photos%2Fcat%20one.jpg"))
+            .insert_header(("x-amz-copy-source-range", "bytes=1-2"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: CopyPartResult = quick_xml::de::from_reader(body.as_ref()).unwrap();
+
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "copy",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: result.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            provider
+                .head_object("bucket", "copy", None)
+                .await
+                .unwrap()
+                .size,
+            2
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_range() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "source",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let upload = provider
+            .create_multipart_upload("bucket", "copy", PutObjectOptions::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(upload_part_copy),
+        )
+        .await;
+        let uri = format!("/bucket/copy?partNumber=1&uploadId={}", upload.upload_id);
+
+        let req = actix_web::test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("x-amz-copy-source", "bucket/source"))
+            .insert_header(("x-amz-copy-source-range", "bytes=2-4"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::RANGE_NOT_SATISFIABLE);
+
+        let req = actix_web::test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("x-amz-copy-source", "bucket/source"))
+            .insert_header(("x-amz-copy-source-range", "bytes=2-"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let req = actix_web::test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("x-amz-copy-source", "bucket/missing"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,338 @@
+extern crate self as s3_api;
+
+use crate::{
+    acl::{
+        acl_from_headers, acl_from_xml, acl_to_xml, bucket_acl, object_acl, object_ownership,
+        AclError,
+    },
+    body::verify_integrity,
+    generate_request_id,
+    xml::AccessControlPolicy,
+};
+use actix_web::{get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    acl::AccessControlList,
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, S3Error)]
+enum GetObjectAclError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct GetObjectAclQuery {
+    #[serde(rename = "versionId")]
+    version_id: Option<String>,
+}
+
+#[get(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::acl",
+    wrap = "crate::policy::Authorize::action(\"s3:GetObjectAcl\").versioned()"
+)]
+pub async fn get_object_acl(
+    path: web::Path<(String, String)>,
+    query: web::Query<GetObjectAclQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetObjectAclError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let acl = object_acl(
+        storage_provider.into_inner().as_ref(),
+        &bucket,
+        &key,
+        query.version_id.as_deref(),
+    )
+    .await
+    .map_err(|e| match e {
+        StorageErr::BucketNotFound => GetObjectAclError::NoSuchBucket {
+            request_id,
+            resource,
+        },
+        StorageErr::ObjectNotFound | StorageErr::DeleteMarker { .. } => {
+            GetObjectAclError::NoSuchKey {
+                request_id,
+                resource,
+            }
+        }
+        StorageErr::VersionNotFound => GetObjectAclError::NoSuchVersion {
+            request_id,
+            resource,
+        },
+        _ => GetObjectAclError::InternalError {
+            request_id,
+            resource,
+        },
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(quick_xml::se::to_string(&acl_to_xml(acl)).unwrap()))
+}
+
+#[derive(Debug, S3Error)]
+enum PutObjectAclError {
+    #[error(status_code = 400, message = "The bucket does not allow ACLs.")]
+    AccessControlListNotSupported {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedACLError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Replace the ACL of the current version of an object with the one given in the headers, or
+/// else in the body. The object keeps its owner.
+#[put(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::acl",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObjectAcl\").versioned()"
+)]
+pub async fn put_object_acl(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutObjectAclError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutObjectAclError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| {
+        let (request_id, resource) = (request_id.clone(), resource.clone());
+        match e {
+            AclError::Malformed => PutObjectAclError::MalformedACLError {
+                request_id,
+                resource,
+            },
+            AclError::InvalidArgument => PutObjectAclError::InvalidArgument {
+                request_id,
+                resource,
+            },
+            AclError::InvalidRequest => PutObjectAclError::InvalidRequest {
+                request_id,
+                resource,
+            },
+            AclError::UnresolvableGrantByEmailAddress => {
+                PutObjectAclError::UnresolvableGrantByEmailAddress {
+                    request_id,
+                    resource,
+                }
+            }
+            AclError::NotSupported => PutObjectAclError::AccessControlListNotSupported {
+                request_id,
+                resource,
+            },
+            AclError::Storage(StorageErr::BucketNotFound) => PutObjectAclError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            AclError::Storage(StorageErr::ObjectNotFound | StorageErr::DeleteMarker { .. }) => {
+                PutObjectAclError::NoSuchKey {
+                    request_id,
+                    resource,
+                }
+            }
+            AclError::Storage(_) => PutObjectAclError::InternalError {
+                request_id,
+                resource,
+            },
+        }
+    };
+
+    let bucket_owner = bucket_acl(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(|e| error(e.into()))?
+        .owner;
+    let owner = object_acl(storage_provider.as_ref(), &bucket, &key, None)
+        .await
+        .map_err(|e| error(e.into()))?
+        .owner;
+    let acl = match acl_from_headers(req.headers(), owner.clone(), &bucket_owner).map_err(error)? {
+        Some(acl) => acl,
+        None => {
+            let policy = quick_xml::de::from_reader::<_, AccessControlPolicy>(body.as_ref())
+                .map_err(|_| error(AclError::Malformed))?;
+            acl_from_xml(policy, owner.clone()).map_err(error)?
+        }
+    };
+
+    // With ACLs disabled every object belongs to the bucket owner, and only the ACL giving them
+    // full control is accepted
+    let ownership = object_ownership(storage_provider.as_ref(), &bucket)
+        .await
+        .map_err(|e| error(e.into()))?;
+    if ownership.acls_disabled() && acl != AccessControlList::private(owner) {
+        return Err(error(AclError::NotSupported));
+    }
+
+    storage_provider
+        .put_object_acl(&bucket, &key, acl)
+        .await
+        .map_err(|e| error(e.into()))?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        acl::{ObjectOwnership, Permission},
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_object_acl() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::empty()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_object_acl)
+                .service(put_object_acl),
+        )
+        .await;
+        let put = |uri: &str| {
+            actix_web::test::TestRequest::put()
+                .uri(uri)
+                .insert_header(("x-amz-grant-read", r#"id="bob""#))
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, put("/bucket/key?acl")).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let acl = provider
+            .get_object_acl("bucket", "key", None)
+            .await
+            .unwrap();
+        assert!(acl.allows(Some("bob"), Permission::Read));
+        assert!(!acl.allows(Some("AIDACKCEVSQ6C2EXAMPLE"), Permission::Read));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?acl")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("<Owner><ID>AIDACKCEVSQ6C2EXAMPLE</ID>"));
+        assert!(body.contains("<ID>bob</ID>"));
+
+        let resp = actix_web::test::call_service(&app, put("/bucket/missing?acl")).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        // ACLs are still kept once disabled, but no longer apply
+        provider
+            .put_bucket_ownership_controls("bucket", Some(ObjectOwnership::BucketOwnerEnforced))
+            .await
+            .unwrap();
+        let resp = actix_web::test::call_service(&app, put("/bucket/key?acl")).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?acl")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let body = actix_web::test::read_body(resp).await;
+        assert!(!String::from_utf8_lossy(&body).contains("<ID>bob</ID>"));
+    }
+}
@@ -0,0 +1,559 @@
+extern crate self as s3_api;
+
+use super::tagging::tags_from_headers;
+use crate::{
+    acl::{object_write_acl, AclError},
+    conditional::preconditions_from_headers,
+    copy_source::parse_copy_source,
+    generate_request_id,
+    metadata::metadata_from_headers,
+    xml::CopyObjectResult,
+    xml_date,
+};
+use actix_web::{http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    object::{CopyObjectOptions, MetadataDirective},
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum CopyObjectError {
+    #[error(status_code = 400, message = "The bucket does not allow ACLs.")]
+    AccessControlListNotSupported {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "x-amz-copy-source must be of the form bucket/key, and the metadata and tagging directives either COPY or REPLACE."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The tag provided was not a valid tag. Objects can have up to 10 tags with unique keys of up to 128 characters and values of up to 256 characters."
+    )]
+    InvalidTag {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "InvalidArgument",
+        status_code = 400,
+        message = "The canned ACL or the grantees given in the x-amz-grant-* headers are not valid."
+    )]
+    InvalidAcl {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "InvalidRequest",
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidAclHeaders {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "This copy request is illegal because it is trying to copy an object to itself without changing the object's metadata, storage class, website redirect location or encryption attributes."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        code = "InvalidRequest",
+        status_code = 400,
+        message = "The source of a copy request may not specifically refer to a delete marker by version id."
+    )]
+    InvalidCopySource {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Your metadata headers exceed the maximum allowed metadata size."
+    )]
+    MetadataTooLarge {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 412,
+        message = "At least one of the preconditions you specified did not hold."
+    )]
+    PreconditionFailed {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Directive {
+    Copy,
+    Replace,
+}
+
+/// Parse an `x-amz-*-directive` header, which defaults to `COPY`
+fn parse_directive(value: Option<&str>) -> Option<Directive> {
+    match value {
+        None | Some("COPY") => Some(Directive::Copy),
+        Some("REPLACE") => Some(Directive::Replace),
+        Some(_) => None,
+    }
+}
+
+#[put(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::copy_source",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn copy_object(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, CopyObjectError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let source = header("x-amz-copy-source").and_then(parse_copy_source);
+    let metadata_directive = parse_directive(header("x-amz-metadata-directive"));
+    let tagging_directive = parse_directive(header("x-amz-tagging-directive"));
+
+    let (Some(source), Some(metadata_directive), Some(tagging_directive)) =
+        (source, metadata_directive, tagging_directive)
+    else {
+        return Err(CopyObjectError::InvalidArgument {
+            request_id,
+            resource,
+        });
+    };
+    // Copying an earlier version over the current one is how a version is restored
+    if source.bucket == bucket
+        && source.key == key
+        && source.version_id.is_none()
+        && metadata_directive == Directive::Copy
+    {
+        return Err(CopyObjectError::InvalidRequest {
+            request_id,
+            resource,
+        });
+    }
+    // The tags of the source are copied along unless they're replaced by the header's
+    let tags = match tagging_directive {
+        Directive::Copy => None,
+        Directive::Replace => match tags_from_headers(req.headers()) {
+            Some(tags) => Some(tags),
+            None => {
+                return Err(CopyObjectError::InvalidTag {
+                    request_id,
+                    resource,
+                })
+            }
+        },
+    };
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => CopyObjectError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::ObjectNotFound => CopyObjectError::NoSuchKey {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::VersionNotFound => CopyObjectError::NoSuchVersion {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::DeleteMarker { .. } if source.version_id.is_some() => {
+            CopyObjectError::InvalidCopySource {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            }
+        }
+        StorageErr::DeleteMarker { .. } => CopyObjectError::NoSuchKey {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        // Either failing condition of a copy is a failed precondition, there's no 304 for it
+        StorageErr::PreconditionFailed => CopyObjectError::PreconditionFailed {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => CopyObjectError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let metadata = match metadata_directive {
+        Directive::Copy => MetadataDirective::Copy,
+        Directive::Replace => match metadata_from_headers(req.headers()) {
+            Some(metadata) => MetadataDirective::Replace(metadata),
+            None => {
+                return Err(CopyObjectError::MetadataTooLarge {
+                    request_id,
+                    resource,
+                })
+            }
+        },
+    };
+    // Like S3, the copy doesn't take the ACL of its source along
+    let acl = object_write_acl(storage_provider.as_ref(), &bucket, &req)
+        .await
+        .map_err(|e| {
+            let (request_id, resource) = (request_id.clone(), resource.clone());
+            match e {
+                AclError::NotSupported => CopyObjectError::AccessControlListNotSupported {
+                    request_id,
+                    resource,
+                },
+                AclError::InvalidRequest => CopyObjectError::InvalidAclHeaders {
+                    request_id,
+                    resource,
+                },
+                AclError::UnresolvableGrantByEmailAddress => {
+                    CopyObjectError::UnresolvableGrantByEmailAddress {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::Malformed | AclError::InvalidArgument => CopyObjectError::InvalidAcl {
+                    request_id,
+                    resource,
+                },
+                AclError::Storage(e) => error(e),
+            }
+        })?;
+
+    let copied = storage_provider
+        .copy_object(
+            &bucket,
+            &key,
+            &source,
+            CopyObjectOptions {
+                metadata,
+                tags,
+                acl,
+                source_preconditions: preconditions_from_headers(
+                    req.headers(),
+                    "x-amz-copy-source-",
+                ),
+            },
+        )
+        .await
+        .map_err(error)?;
+    let object = copied.object;
+
+    let mut response = HttpResponse::Ok();
+    if let Some(version_id) = &object.version_id {
+        response.insert_header(("x-amz-version-id", version_id.as_str()));
+    }
+    if let Some(version_id) = &copied.source_version_id {
+        response.insert_header(("x-amz-copy-source-version-id", version_id.as_str()));
+    }
+
+    Ok(response.insert_header(header::ContentType::xml()).body(
+        quick_xml::se::to_string(&CopyObjectResult {
+            etag: format!("\"{}\"", object.etag),
+            last_modified: xml_date(&object.last_modified),
+        })
+        .unwrap(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        object::{ObjectMetadata, PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_copy_object() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                ObjectMetadata {
+                    content_type: Some("image/jpeg".into()),
+                    ..Default::default()
+                }
+                .into(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(copy_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let result: CopyObjectResult = quick_xml::de::from_reader(body.as_ref()).unwrap();
+        let object = provider
+            .head_object("bucket", "copy.jpg", None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.etag, "\"4a4be40c96ac6314e91d93f38043a634\"");
+        assert_eq!(object.metadata.content_type.as_deref(), Some("image/jpeg"));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+            .insert_header(("x-amz-metadata-directive", "REPLACE"))
+            .insert_header((header::CONTENT_TYPE, "image/png"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let object = provider
+            .head_object("bucket", "copy.jpg", None)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(object.metadata.content_type.as_deref(), Some("image/png"));
+    }
+
+    #[actix_web::test]
+    async fn test_copy_object_tagging() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        provider
+            .put_object_tagging(
+                "bucket",
+                "cat.jpg",
+                [("project".to_owned(), "cat".to_owned())].into(),
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(copy_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+            .insert_header(("x-amz-tagging", "ignored=true"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let tags = provider
+            .get_object_tagging("bucket", "copy.jpg", None)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(tags, [("project".to_owned(), "cat".to_owned())].into());
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+            .insert_header(("x-amz-tagging-directive", "REPLACE"))
+            .insert_header(("x-amz-tagging", "project=dog"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let tags = provider
+            .get_object_tagging("bucket", "copy.jpg", None)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(tags, [("project".to_owned(), "dog".to_owned())].into());
+    }
+
+    #[actix_web::test]
+    async fn test_copy_preconditions() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(copy_object),
+        )
+        .await;
+        let copy = |name, value| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket/copy.jpg")
+                .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+                .insert_header((name, value))
+                .to_request()
+        };
+
+        let req = copy(
+            "x-amz-copy-source-if-match",
+            "\"4a4be40c96ac6314e91d93f38043a634\"",
+        );
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let req = copy(
+            "x-amz-copy-source-if-none-match",
+            "\"4a4be40c96ac6314e91d93f38043a634\"",
+        );
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+
+        // A failed copy writes neither the object nor the tags it asked for
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "/bucket/cat.jpg"))
+            .insert_header(("x-amz-copy-source-if-match", "\"0000\""))
+            .insert_header(("x-amz-tagging-directive", "REPLACE"))
+            .insert_header(("x-amz-tagging", "project=dog"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+        assert!(provider
+            .get_object_tagging("bucket", "copy.jpg", None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let req = copy(
+            "x-amz-copy-source-if-unmodified-since",
+            "Wed, 21 Oct 2015 07:28:00 GMT",
+        );
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+
+        let req = copy("x-amz-metadata-directive", "MOVE");
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_copy_onto_itself() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(copy_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/cat.jpg")
+            .insert_header(("x-amz-copy-source", "bucket/cat.jpg"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_source() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(copy_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/copy.jpg")
+            .insert_header(("x-amz-copy-source", "bucket/cat.jpg"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
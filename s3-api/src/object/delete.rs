@@ -0,0 +1,199 @@
+extern crate self as s3_api;
+
+use crate::generate_request_id;
+use actix_web::{delete, web, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::storage_provider::{StorageErr, StorageProvider};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct DeleteObjectQuery {
+    #[serde(rename = "versionId")]
+    version_id: Option<String>,
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteObjectError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}/{key:.*}",
+    wrap = "crate::policy::Authorize::action(\"s3:DeleteObject\").versioned()"
+)]
+pub async fn delete_object(
+    path: web::Path<(String, String)>,
+    query: web::Query<DeleteObjectQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteObjectError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    // Deleting a key or version that doesn't exist succeeds, as it does on S3
+    match storage_provider
+        .into_inner()
+        .delete_object(&bucket, &key, query.version_id.as_deref())
+        .await
+    {
+        Ok(deleted) => {
+            let mut response = HttpResponse::NoContent();
+            if let Some(version_id) = deleted.version_id {
+                response.insert_header(("x-amz-version-id", version_id));
+            }
+            if deleted.delete_marker {
+                response.insert_header(("x-amz-delete-marker", "true"));
+            }
+
+            Ok(response.finish())
+        }
+        Err(StorageErr::ObjectNotFound | StorageErr::VersionNotFound) => {
+            Ok(HttpResponse::NoContent().finish())
+        }
+        Err(StorageErr::BucketNotFound) => Err(DeleteObjectError::NoSuchBucket {
+            request_id,
+            resource,
+        }),
+        Err(_) => Err(DeleteObjectError::InternalError {
+            request_id,
+            resource,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        bucket::VersioningStatus,
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_delete_object() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "photos/cat.jpg",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(delete_object),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let req = actix_web::test::TestRequest::delete()
+                .uri("/bucket/photos/cat.jpg")
+                .to_request();
+            let resp = actix_web::test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        }
+        assert!(provider
+            .head_object("bucket", "photos/cat.jpg", None)
+            .await
+            .is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_delete_object_version() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        let version_id = provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap()
+            .version_id
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(delete_object),
+        )
+        .await;
+
+        // Deleting without a version only adds a delete marker
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket/key")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(resp.headers().get("x-amz-delete-marker").unwrap(), "true");
+        let marker = resp.headers().get("x-amz-version-id").unwrap();
+        assert_ne!(marker, &version_id);
+        assert!(provider
+            .head_object("bucket", "key", Some(&version_id))
+            .await
+            .is_ok());
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&format!("/bucket/key?versionId={version_id}"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(resp.headers().get("x-amz-version-id").unwrap(), &version_id);
+        assert!(!resp.headers().contains_key("x-amz-delete-marker"));
+        assert!(matches!(
+            provider
+                .head_object("bucket", "key", Some(&version_id))
+                .await,
+            Err(StorageErr::VersionNotFound)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(delete_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket/key")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
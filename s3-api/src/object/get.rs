@@ -0,0 +1,690 @@
+extern crate self as s3_api;
+
+use super::{append_object_headers, delete_marker_headers, not_modified};
+use crate::{conditional::preconditions_from_headers, generate_request_id};
+use actix_web::{
+    body::SizedStream, get, http::header, web, HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use s3_derive::S3Error;
+use s3_entities::{
+    multipart::MAX_PART_NUMBER,
+    object::{Object, Precondition, ReadRange},
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct GetObjectQuery {
+    #[serde(rename = "partNumber")]
+    part_number: Option<String>,
+    #[serde(rename = "versionId")]
+    version_id: Option<String>,
+}
+
+#[derive(Debug, S3Error)]
+enum GetObjectError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Part number must be an integer between 1 and 10000, inclusive."
+    )]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 416,
+        message = "The requested partnumber is not satisfiable."
+    )]
+    InvalidPartNumber {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 416, message = "The requested range is not satisfiable.")]
+    InvalidRange {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Cannot specify both Range header and partNumber query parameter."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 405,
+        message = "The specified method is not allowed against this resource."
+    )]
+    MethodNotAllowed {
+        request_id: String,
+        resource: String,
+        headers: Vec<(&'static str, String)>,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+        headers: Vec<(&'static str, String)>,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 412,
+        message = "At least one of the preconditions you specified did not hold."
+    )]
+    PreconditionFailed {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Parse a single `Range` such as `bytes=0-499`, `bytes=500-` or `bytes=-500`. Anything else,
+/// including several ranges at once, is ignored and the whole object returned.
+fn parse_range(value: &str) -> Option<ReadRange> {
+    let (first, last) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+
+    match (first.trim(), last.trim()) {
+        ("", length) => length.parse().ok().map(ReadRange::Suffix),
+        (first, "") => first
+            .parse()
+            .ok()
+            .map(|first| ReadRange::Bytes { first, last: None }),
+        (first, last) => {
+            let (first, last): (u64, u64) = (first.parse().ok()?, last.parse().ok()?);
+
+            (first <= last).then_some(ReadRange::Bytes {
+                first,
+                last: Some(last),
+            })
+        }
+    }
+}
+
+#[get(
+    "/{bucket}/{key:.*}",
+    wrap = "crate::policy::Authorize::action(\"s3:GetObject\").versioned()"
+)]
+pub async fn get_object(
+    path: web::Path<(String, String)>,
+    query: web::Query<GetObjectQuery>,
+    req: HttpRequest,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetObjectError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_range);
+    let query = query.into_inner();
+    let version_id = query.version_id.as_deref();
+    let part_number = match query.part_number {
+        Some(part_number) => match part_number
+            .parse::<u16>()
+            .ok()
+            .filter(|part_number| (1..=MAX_PART_NUMBER).contains(part_number))
+        {
+            Some(part_number) => Some(ReadRange::Part(part_number)),
+            None => {
+                return Err(GetObjectError::InvalidArgument {
+                    request_id,
+                    resource,
+                })
+            }
+        },
+        None => None,
+    };
+    let range = match (range, part_number) {
+        (Some(_), Some(_)) => {
+            return Err(GetObjectError::InvalidRequest {
+                request_id,
+                resource,
+            })
+        }
+        (range, part_number) => range.or(part_number),
+    };
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => GetObjectError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::ObjectNotFound => GetObjectError::NoSuchKey {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+            headers: Vec::new(),
+        },
+        StorageErr::VersionNotFound => GetObjectError::NoSuchVersion {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        // Asking for a delete marker by its version isn't the same as the object being gone
+        StorageErr::DeleteMarker {
+            version_id: marker_version_id,
+        } if version_id.is_some() => GetObjectError::MethodNotAllowed {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+            headers: delete_marker_headers(marker_version_id),
+        },
+        StorageErr::DeleteMarker {
+            version_id: marker_version_id,
+        } => GetObjectError::NoSuchKey {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+            headers: delete_marker_headers(marker_version_id),
+        },
+        StorageErr::InvalidRange => GetObjectError::InvalidRange {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::InvalidPartNumber => GetObjectError::InvalidPartNumber {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => GetObjectError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let preconditions = preconditions_from_headers(req.headers(), "");
+    let check = |object: &Object| match preconditions.evaluate(&object.etag, &object.last_modified)
+    {
+        Precondition::Passed => Ok(None),
+        Precondition::NotModified => Ok(Some(not_modified(object))),
+        Precondition::Failed => Err(GetObjectError::PreconditionFailed {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        }),
+    };
+
+    // Like S3, the conditions are evaluated before the range, and without opening the body of an
+    // object that isn't going to be sent
+    let head = storage_provider
+        .head_object(&bucket, &key, version_id)
+        .await
+        .map_err(error)?;
+    if let Some(response) = check(&head)? {
+        return Ok(response);
+    }
+
+    // Read the version the conditions held for, where the bucket has versions to tell apart
+    let read_version_id = version_id.or(head.version_id.as_deref());
+    let result = match &range {
+        Some(range) => storage_provider
+            .get_object_range(&bucket, &key, read_version_id, range)
+            .await
+            .map(|(object, range, body)| (object, Some(range), body)),
+        None => storage_provider
+            .get_object(&bucket, &key, read_version_id)
+            .await
+            .map(|(object, body)| (object, None, body)),
+    };
+    let (object, range, body) = result
+        .map_err(|e| match e {
+            // The version was deleted since, which is the object being gone to the client
+            StorageErr::VersionNotFound if version_id.is_none() => StorageErr::ObjectNotFound,
+            e => e,
+        })
+        .map_err(error)?;
+    // Without versions, the object can be overwritten between the lookup and the read, in which
+    // case the conditions have to hold for the object that was read instead
+    if (&object.etag, &object.last_modified) != (&head.etag, &head.last_modified) {
+        if let Some(response) = check(&object)? {
+            return Ok(response);
+        }
+    }
+
+    // The body is already open, so an object whose tags can't be read is still returned, only
+    // without their count
+    let tag_count = storage_provider
+        .get_object_tagging(&bucket, &key, read_version_id)
+        .await
+        .map(|tags| tags.len())
+        .unwrap_or_default();
+    let append_headers = |response: &mut HttpResponseBuilder| {
+        append_object_headers(response, &object);
+        if tag_count > 0 {
+            response.append_header(("x-amz-tagging-count", tag_count));
+        }
+    };
+
+    let Some(range) = range else {
+        let mut response = HttpResponse::Ok();
+        append_headers(&mut response);
+
+        return Ok(response.body(SizedStream::new(object.size, body)));
+    };
+
+    let mut response = HttpResponse::PartialContent();
+    append_headers(&mut response);
+    response.append_header((
+        header::CONTENT_RANGE,
+        format!("bytes {}-{}/{}", range.start, range.end - 1, object.size),
+    ));
+
+    Ok(response.body(SizedStream::new(range.end - range.start, body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{
+        body::{BodySize, MessageBody},
+        http, App,
+    };
+    use futures::stream;
+    use s3_entities::{
+        bucket::VersioningStatus,
+        object::{ObjectMetadata, PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_get_object() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "photos/cat.jpg",
+                ObjectMetadata {
+                    content_type: Some("image/jpeg".into()),
+                    ..Default::default()
+                }
+                .into(),
+                Box::pin(stream::iter(vec![Ok("me".into()), Ok("ow".into())])),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/photos/cat.jpg")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::ETAG).unwrap(),
+            "\"4a4be40c96ac6314e91d93f38043a634\""
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/jpeg"
+        );
+        assert_eq!(resp.response().body().size(), BodySize::Sized(4));
+        assert!(resp.headers().contains_key(header::LAST_MODIFIED));
+        assert!(!resp.headers().contains_key("x-amz-tagging-count"));
+        assert_eq!(actix_web::test::read_body(resp).await, "meow");
+    }
+
+    #[actix_web::test]
+    async fn test_get_object_tagging_count() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        provider
+            .put_object_tagging(
+                "bucket",
+                "key",
+                [("project", "cat"), ("color", "orange")]
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(resp.headers().get("x-amz-tagging-count").unwrap(), "2");
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_key() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_get_object_version() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        let put = |data: &'static str| {
+            provider.put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async move { Ok(web::Bytes::from(data)) })),
+                WriteCondition::Overwrite,
+            )
+        };
+        let first = put("meow").await.unwrap().version_id.unwrap();
+        put("purr").await.unwrap();
+        let marker = provider
+            .delete_object("bucket", "key", None)
+            .await
+            .unwrap()
+            .version_id
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_object),
+        )
+        .await;
+        let get = |uri: String| actix_web::test::TestRequest::get().uri(&uri).to_request();
+
+        let resp =
+            actix_web::test::call_service(&app, get(format!("/bucket/key?versionId={first}")))
+                .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(resp.headers().get("x-amz-version-id").unwrap(), &first);
+        assert_eq!(actix_web::test::read_body(resp).await, "meow");
+
+        // The current version is a delete marker, which shows in the error's headers
+        let resp = actix_web::test::call_service(&app, get("/bucket/key".into())).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.headers().get("x-amz-delete-marker").unwrap(), "true");
+        assert_eq!(resp.headers().get("x-amz-version-id").unwrap(), &marker);
+
+        let resp =
+            actix_web::test::call_service(&app, get(format!("/bucket/key?versionId={marker}")))
+                .await;
+        assert_eq!(resp.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get("x-amz-delete-marker").unwrap(), "true");
+
+        let resp =
+            actix_web::test::call_service(&app, get("/bucket/key?versionId=missing".into())).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(std::str::from_utf8(&body)
+            .unwrap()
+            .contains("<Code>NoSuchVersion</Code>"));
+    }
+
+    #[actix_web::test]
+    async fn test_get_object_range() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+        let get = |range| {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket/key")
+                .insert_header((header::RANGE, range))
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, get("bytes=1-2")).await;
+        assert_eq!(resp.status(), http::StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 1-2/4"
+        );
+        assert_eq!(resp.response().body().size(), BodySize::Sized(2));
+        assert_eq!(actix_web::test::read_body(resp).await, "eo");
+
+        let resp = actix_web::test::call_service(&app, get("bytes=-3")).await;
+        assert_eq!(resp.status(), http::StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 1-3/4"
+        );
+        assert_eq!(actix_web::test::read_body(resp).await, "eow");
+
+        let resp = actix_web::test::call_service(&app, get("bytes=2-")).await;
+        assert_eq!(actix_web::test::read_body(resp).await, "ow");
+
+        let resp = actix_web::test::call_service(&app, get("bytes=4-")).await;
+        assert_eq!(resp.status(), http::StatusCode::RANGE_NOT_SATISFIABLE);
+
+        let resp = actix_web::test::call_service(&app, get("bytes=0-1,3-3")).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(actix_web::test::read_body(resp).await, "meow");
+    }
+
+    #[actix_web::test]
+    async fn test_get_object_part() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?partNumber=1")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 0-3/4"
+        );
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?partNumber=2")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::RANGE_NOT_SATISFIABLE);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?partNumber=1")
+            .insert_header((header::RANGE, "bytes=0-1"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_conditional_get() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(get_object),
+        )
+        .await;
+        let get = |name, value| {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket/key")
+                .insert_header((name, value))
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(
+            &app,
+            get(
+                header::IF_NONE_MATCH,
+                "\"4a4be40c96ac6314e91d93f38043a634\"",
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_MODIFIED);
+        assert!(resp.headers().contains_key(header::ETAG));
+
+        let resp = actix_web::test::call_service(&app, get(header::IF_MATCH, "\"abc\"")).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+
+        let resp = actix_web::test::call_service(
+            &app,
+            get(header::IF_MODIFIED_SINCE, "Wed, 21 Oct 2015 07:28:00 GMT"),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(actix_web::test::read_body(resp).await, "meow");
+
+        // The conditions are evaluated before a range that can't be satisfied
+        let get_range = |name, value| {
+            actix_web::test::TestRequest::get()
+                .uri("/bucket/key")
+                .insert_header((header::RANGE, "bytes=10-20"))
+                .insert_header((name, value))
+                .to_request()
+        };
+        let resp =
+            actix_web::test::call_service(&app, get_range(header::IF_MATCH, "\"abc\"")).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+        let resp = actix_web::test::call_service(
+            &app,
+            get_range(
+                header::IF_NONE_MATCH,
+                "\"4a4be40c96ac6314e91d93f38043a634\"",
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_MODIFIED);
+        let resp = actix_web::test::call_service(
+            &app,
+            get_range(header::IF_MATCH, "\"4a4be40c96ac6314e91d93f38043a634\""),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::RANGE_NOT_SATISFIABLE);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("bytes=0-499"),
+            Some(ReadRange::Bytes {
+                first: 0,
+                last: Some(499)
+            })
+        );
+        assert_eq!(
+            parse_range("bytes=500-"),
+            Some(ReadRange::Bytes {
+                first: 500,
+                last: None
+            })
+        );
+        assert_eq!(parse_range("bytes=-500"), Some(ReadRange::Suffix(500)));
+        assert_eq!(parse_range("bytes=9-5"), None);
+        assert_eq!(parse_range("bytes=0-1,3-4"), None);
+        assert_eq!(parse_range("items=0-1"), None);
+    }
+}
@@ -0,0 +1,323 @@
+extern crate self as s3_api;
+
+use super::{append_object_headers, delete_marker_headers, not_modified};
+use crate::{conditional::preconditions_from_headers, generate_request_id};
+use actix_web::{body::SizedStream, head, web, HttpRequest, HttpResponse};
+use futures::stream;
+use s3_derive::S3Error;
+use s3_entities::{
+    object::Precondition,
+    storage_provider::{ByteStream, StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct HeadObjectQuery {
+    #[serde(rename = "versionId")]
+    version_id: Option<String>,
+}
+
+#[derive(Debug, S3Error)]
+#[error(empty_body)]
+enum HeadObjectError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 405,
+        message = "The specified method is not allowed against this resource."
+    )]
+    MethodNotAllowed {
+        request_id: String,
+        resource: String,
+        headers: Vec<(&'static str, String)>,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+        headers: Vec<(&'static str, String)>,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 412,
+        message = "At least one of the preconditions you specified did not hold."
+    )]
+    PreconditionFailed {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[head(
+    "/{bucket}/{key:.*}",
+    wrap = "crate::policy::Authorize::action(\"s3:GetObject\").versioned()"
+)]
+pub async fn head_object(
+    path: web::Path<(String, String)>,
+    query: web::Query<HeadObjectQuery>,
+    req: HttpRequest,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, HeadObjectError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let version_id = query.into_inner().version_id;
+    let object = storage_provider
+        .into_inner()
+        .head_object(&bucket, &key, version_id.as_deref())
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => HeadObjectError::NoSuchBucket {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            },
+            StorageErr::ObjectNotFound => HeadObjectError::NoSuchKey {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+                headers: Vec::new(),
+            },
+            StorageErr::VersionNotFound => HeadObjectError::NoSuchVersion {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            },
+            StorageErr::DeleteMarker {
+                version_id: marker_version_id,
+            } if version_id.is_some() => HeadObjectError::MethodNotAllowed {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+                headers: delete_marker_headers(marker_version_id),
+            },
+            StorageErr::DeleteMarker {
+                version_id: marker_version_id,
+            } => HeadObjectError::NoSuchKey {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+                headers: delete_marker_headers(marker_version_id),
+            },
+            _ => HeadObjectError::InternalError {
+                request_id: request_id.clone(),
+                resource: resource.clone(),
+            },
+        })?;
+
+    match preconditions_from_headers(req.headers(), "")
+        .evaluate(&object.etag, &object.last_modified)
+    {
+        Precondition::Passed => {}
+        Precondition::NotModified => return Ok(not_modified(&object)),
+        Precondition::Failed => {
+            return Err(HeadObjectError::PreconditionFailed {
+                request_id,
+                resource,
+            })
+        }
+    }
+
+    // The body is never written for HEAD responses, but its size still becomes Content-Length
+    let body: ByteStream = Box::pin(stream::empty());
+    let mut response = HttpResponse::Ok();
+    append_object_headers(&mut response, &object);
+
+    Ok(response.body(SizedStream::new(object.size, body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{
+        body::{BodySize, MessageBody},
+        http::{self, header},
+        App,
+    };
+    use s3_entities::{
+        object::{ObjectMetadata, PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[actix_web::test]
+    async fn test_head_object() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                ObjectMetadata {
+                    content_type: Some("image/jpeg".into()),
+                    ..Default::default()
+                }
+                .into(),
+                Box::pin(stream::iter(vec![Ok("meow".into())])),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket/cat.jpg")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::ETAG).unwrap(),
+            "\"4a4be40c96ac6314e91d93f38043a634\""
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/jpeg"
+        );
+        assert_eq!(resp.response().body().size(), BodySize::Sized(4));
+    }
+
+    #[actix_web::test]
+    async fn test_head_object_metadata() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                ObjectMetadata {
+                    content_language: Some("en".into()),
+                    expires: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+                    user: [("color".to_owned(), "orange".to_owned())].into(),
+                    ..Default::default()
+                }
+                .into(),
+                Box::pin(stream::iter(vec![Ok("meow".into())])),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket/cat.jpg")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "binary/octet-stream"
+        );
+        assert_eq!(resp.headers().get(header::CONTENT_LANGUAGE).unwrap(), "en");
+        assert_eq!(
+            resp.headers().get(header::EXPIRES).unwrap(),
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+        assert_eq!(resp.headers().get("x-amz-meta-color").unwrap(), "orange");
+        assert!(!resp.headers().contains_key(header::CACHE_CONTROL));
+    }
+
+    #[actix_web::test]
+    async fn test_conditional_head() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                PutObjectOptions::default(),
+                Box::pin(stream::iter(vec![Ok("meow".into())])),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_object),
+        )
+        .await;
+        let head = |name, value| {
+            actix_web::test::TestRequest::default()
+                .method(http::Method::HEAD)
+                .uri("/bucket/cat.jpg")
+                .insert_header((name, value))
+                .to_request()
+        };
+
+        // A matching If-Match takes precedence over a failing If-Unmodified-Since
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket/cat.jpg")
+            .insert_header((header::IF_MATCH, "\"4a4be40c96ac6314e91d93f38043a634\""))
+            .insert_header((header::IF_UNMODIFIED_SINCE, "Wed, 21 Oct 2015 07:28:00 GMT"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let resp = actix_web::test::call_service(
+            &app,
+            head(header::IF_UNMODIFIED_SINCE, "Wed, 21 Oct 2015 07:28:00 GMT"),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+        assert_eq!(resp.response().body().size(), BodySize::Sized(0));
+
+        let resp = actix_web::test::call_service(&app, head(header::IF_NONE_MATCH, "*")).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_MODIFIED);
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_key() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(head_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/bucket/cat.jpg")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.response().body().size(), BodySize::Sized(0));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{http_date, metadata::append_metadata_headers};
+use actix_web::{http::header, web, HttpResponse, HttpResponseBuilder};
+use s3_entities::object::Object;
+
+mod acl;
+mod copy;
+mod delete;
+mod delete_objects;
+mod get;
+mod head;
+mod put;
+pub(crate) mod tagging;
+
+const DEFAULT_CONTENT_TYPE: &str = "binary/octet-stream";
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(tagging::get_object_tagging)
+        .service(tagging::put_object_tagging)
+        .service(tagging::delete_object_tagging)
+        .service(acl::get_object_acl)
+        .service(acl::put_object_acl)
+        .service(copy::copy_object)
+        .service(get::get_object)
+        .service(head::head_object)
+        .service(put::put_object)
+        .service(delete::delete_object)
+        .service(delete_objects::delete_objects);
+}
+
+/// Append the headers describing a stored object, shared by GetObject and HeadObject
+fn append_object_headers(builder: &mut HttpResponseBuilder, object: &Object) {
+    builder
+        .append_header((header::ACCEPT_RANGES, "bytes"))
+        .append_header((header::ETAG, format!("\"{}\"", object.etag)))
+        .append_header((header::LAST_MODIFIED, http_date(&object.last_modified)))
+        .append_header((
+            header::CONTENT_TYPE,
+            object
+                .metadata
+                .content_type
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.into()),
+        ));
+    if let Some(version_id) = &object.version_id {
+        builder.append_header(("x-amz-version-id", version_id.as_str()));
+    }
+    append_metadata_headers(builder, &object.metadata);
+}
+
+/// The headers of an error reading a delete marker, which tell it from a missing object
+fn delete_marker_headers(version_id: String) -> Vec<(&'static str, String)> {
+    vec![
+        ("x-amz-delete-marker", "true".into()),
+        ("x-amz-version-id", version_id),
+    ]
+}
+
+/// Answer a conditional read of an object that didn't change, which still identifies it
+fn not_modified(object: &Object) -> HttpResponse {
+    HttpResponse::NotModified()
+        .append_header((header::ETAG, format!("\"{}\"", object.etag)))
+        .append_header((header::LAST_MODIFIED, http_date(&object.last_modified)))
+        .finish()
+}
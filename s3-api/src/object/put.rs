@@ -0,0 +1,504 @@
+extern crate self as s3_api;
+
+use super::tagging::tags_from_headers;
+use crate::{
+    acl::{object_write_acl, AclError},
+    body::payload_stream,
+    conditional::write_condition,
+    generate_request_id,
+    metadata::metadata_from_headers,
+};
+use actix_web::{http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    object::PutObjectOptions,
+    storage_provider::{StorageErr, StorageProvider},
+};
+
+#[derive(Debug, S3Error)]
+enum PutObjectError {
+    #[error(status_code = 400, message = "The bucket does not allow ACLs.")]
+    AccessControlListNotSupported {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "You did not provide the number of bytes specified by the Content-Length HTTP header."
+    )]
+    IncompleteBody {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "Invalid Argument")]
+    InvalidArgument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Specifying both Canned ACLs and Header Grants is not allowed."
+    )]
+    InvalidRequest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The tag provided was not a valid tag. Objects can have up to 10 tags with unique keys of up to 128 characters and values of up to 256 characters."
+    )]
+    InvalidTag {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "Your metadata headers exceed the maximum allowed metadata size."
+    )]
+    MetadataTooLarge {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 501,
+        message = "A header you provided implies functionality that is not implemented."
+    )]
+    NotImplemented {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 412,
+        message = "At least one of the preconditions you specified did not hold."
+    )]
+    PreconditionFailed {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 403,
+        message = "The request signature we calculated does not match the signature you provided. Check your key and signing method."
+    )]
+    SignatureDoesNotMatch {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The email address you provided does not match any account on record."
+    )]
+    UnresolvableGrantByEmailAddress {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The provided 'x-amz-content-sha256' header does not match what was computed."
+    )]
+    XAmzContentSHA256Mismatch {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}/{key:.*}",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObject\")"
+)]
+pub async fn put_object(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+    payload: web::Payload,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutObjectError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let Some(metadata) = metadata_from_headers(req.headers()) else {
+        return Err(PutObjectError::MetadataTooLarge {
+            request_id,
+            resource,
+        });
+    };
+    let Some(tags) = tags_from_headers(req.headers()) else {
+        return Err(PutObjectError::InvalidTag {
+            request_id,
+            resource,
+        });
+    };
+    let Some(condition) = write_condition(req.headers()) else {
+        return Err(PutObjectError::NotImplemented {
+            request_id,
+            resource,
+        });
+    };
+
+    let storage_provider = storage_provider.into_inner();
+    let error = |e| match e {
+        StorageErr::BucketNotFound => PutObjectError::NoSuchBucket {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::BadDigest => PutObjectError::BadDigest {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::IncompleteBody => PutObjectError::IncompleteBody {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::SignatureDoesNotMatch => PutObjectError::SignatureDoesNotMatch {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::PreconditionFailed => PutObjectError::PreconditionFailed {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        StorageErr::ContentSha256Mismatch => PutObjectError::XAmzContentSHA256Mismatch {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+        _ => PutObjectError::InternalError {
+            request_id: request_id.clone(),
+            resource: resource.clone(),
+        },
+    };
+
+    let acl = object_write_acl(storage_provider.as_ref(), &bucket, &req)
+        .await
+        .map_err(|e| {
+            let (request_id, resource) = (request_id.clone(), resource.clone());
+            match e {
+                AclError::NotSupported => PutObjectError::AccessControlListNotSupported {
+                    request_id,
+                    resource,
+                },
+                AclError::InvalidRequest => PutObjectError::InvalidRequest {
+                    request_id,
+                    resource,
+                },
+                AclError::UnresolvableGrantByEmailAddress => {
+                    PutObjectError::UnresolvableGrantByEmailAddress {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::Malformed | AclError::InvalidArgument => {
+                    PutObjectError::InvalidArgument {
+                        request_id,
+                        resource,
+                    }
+                }
+                AclError::Storage(e) => error(e),
+            }
+        })?;
+
+    let object = storage_provider
+        .put_object(
+            &bucket,
+            &key,
+            PutObjectOptions {
+                metadata,
+                tags,
+                acl,
+            },
+            payload_stream(&req, payload),
+            condition,
+        )
+        .await
+        .map_err(error)?;
+
+    let mut response = HttpResponse::Ok();
+    if let Some(version_id) = &object.version_id {
+        response.insert_header(("x-amz-version-id", version_id.as_str()));
+    }
+
+    Ok(response
+        .append_header((header::ETAG, format!("\"{}\"", object.etag)))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::TryStreamExt;
+    use s3_entities::test::storage_provider::get_mock_app_data;
+
+    #[actix_web::test]
+    async fn test_put_object() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/photos/cat.jpg")
+            .insert_header((header::CONTENT_TYPE, "image/jpeg"))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::ETAG).unwrap(),
+            "\"4a4be40c96ac6314e91d93f38043a634\""
+        );
+
+        let (object, body) = provider
+            .get_object("bucket", "photos/cat.jpg", None)
+            .await
+            .unwrap();
+        let data: Vec<_> = body.try_collect().await.unwrap();
+
+        assert_eq!(object.size, 4);
+        assert_eq!(object.metadata.content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(data.concat(), b"meow");
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_aws_chunked() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header((header::CONTENT_ENCODING, "aws-chunked"))
+            .insert_header(("x-amz-content-sha256", "STREAMING-UNSIGNED-PAYLOAD-TRAILER"))
+            .insert_header(("x-amz-decoded-content-length", "4"))
+            .insert_header(("x-amz-trailer", "x-amz-checksum-crc32"))
+            .set_payload("4\r\nmeow\r\n0\r\nx-amz-checksum-crc32:ihBq/g==\r\n\r\n")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let (object, body) = provider.get_object("bucket", "key", None).await.unwrap();
+        let data: Vec<_> = body.try_collect().await.unwrap();
+
+        assert_eq!(object.size, 4);
+        assert_eq!(data.concat(), b"meow");
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_bad_checksum() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header(("x-amz-content-sha256", "STREAMING-UNSIGNED-PAYLOAD-TRAILER"))
+            .insert_header(("x-amz-trailer", "x-amz-checksum-crc32"))
+            .set_payload("4\r\nmeow\r\n0\r\nx-amz-checksum-crc32:AAAAAA==\r\n\r\n")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        assert!(provider.head_object("bucket", "key", None).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_unverifiable_chunks() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(put_object),
+        )
+        .await;
+        // A signed streaming body can't be accepted without the signature it's chained to
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header(("x-amz-content-sha256", "STREAMING-AWS4-HMAC-SHA256-PAYLOAD"))
+            .set_payload("4;chunk-signature=00\r\nmeow\r\n0;chunk-signature=00\r\n\r\n")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_metadata() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header((header::CONTENT_DISPOSITION, "attachment"))
+            .insert_header(("x-amz-meta-color", "orange"))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let object = provider.head_object("bucket", "key", None).await.unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            object.metadata.content_disposition.as_deref(),
+            Some("attachment")
+        );
+        assert_eq!(object.metadata.user["color"], "orange");
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header(("x-amz-meta-color", "orange".repeat(400)))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>MetadataTooLarge</Code>"));
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_tagging() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .insert_header(("x-amz-tagging", "project=cat&color=orange"))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        let tags = provider
+            .get_object_tagging("bucket", "key", None)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["project"], "cat");
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/other")
+            .insert_header(("x-amz-tagging", "project=cat&project=dog"))
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        assert!(provider.head_object("bucket", "other", None).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_if_absent() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(put_object),
+        )
+        .await;
+        let put = |if_none_match, data| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket/lock")
+                .insert_header((header::IF_NONE_MATCH, if_none_match))
+                .set_payload(data)
+                .to_request()
+        };
+
+        let resp = actix_web::test::call_service(&app, put("*", "meow")).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let resp = actix_web::test::call_service(&app, put("*", "purr")).await;
+        assert_eq!(resp.status(), http::StatusCode::PRECONDITION_FAILED);
+
+        let resp = actix_web::test::call_service(&app, put("\"abc\"", "purr")).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_IMPLEMENTED);
+
+        let object = provider.head_object("bucket", "lock", None).await.unwrap();
+        assert_eq!(object.etag, "4a4be40c96ac6314e91d93f38043a634");
+    }
+
+    #[actix_web::test]
+    async fn test_nonexisting_bucket() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(put_object),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key")
+            .set_payload("meow")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
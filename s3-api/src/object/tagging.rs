@@ -0,0 +1,453 @@
+extern crate self as s3_api;
+
+use crate::{
+    body::verify_integrity,
+    generate_request_id,
+    xml::{Tag, TagSet, Tagging},
+};
+use actix_web::{delete, get, http::header, put, web, HttpRequest, HttpResponse};
+use s3_derive::S3Error;
+use s3_entities::{
+    object::{valid_tags, Tags},
+    storage_provider::{StorageErr, StorageProvider},
+};
+use serde::Deserialize;
+
+/// Collect tags given as key and value pairs, `None` meaning a key was repeated or the tags are
+/// beyond the limits S3 puts on them
+fn collect_tags(pairs: impl IntoIterator<Item = (String, String)>) -> Option<Tags> {
+    let mut tags = Tags::new();
+    for (key, value) in pairs {
+        if tags.insert(key, value).is_some() {
+            return None;
+        }
+    }
+
+    valid_tags(&tags).then_some(tags)
+}
+
+/// Read the tags sent along with a write in the URL encoded `x-amz-tagging` header, `None`
+/// meaning they aren't valid
+pub(crate) fn tags_from_headers(headers: &header::HeaderMap) -> Option<Tags> {
+    let Some(value) = headers.get("x-amz-tagging") else {
+        return Some(Tags::new());
+    };
+    let pairs = web::Query::<Vec<(String, String)>>::from_query(value.to_str().ok()?).ok()?;
+
+    collect_tags(pairs.into_inner())
+}
+
+#[derive(Debug, S3Error)]
+enum GetObjectTaggingError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified version does not exist.")]
+    NoSuchVersion {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct GetObjectTaggingQuery {
+    #[serde(rename = "versionId")]
+    version_id: Option<String>,
+}
+
+#[get(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::tagging",
+    wrap = "crate::policy::Authorize::action(\"s3:GetObjectTagging\").versioned()"
+)]
+pub async fn get_object_tagging(
+    path: web::Path<(String, String)>,
+    query: web::Query<GetObjectTaggingQuery>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, GetObjectTaggingError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    let tags = storage_provider
+        .into_inner()
+        .get_object_tagging(&bucket, &key, query.version_id.as_deref())
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => GetObjectTaggingError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::ObjectNotFound | StorageErr::DeleteMarker { .. } => {
+                GetObjectTaggingError::NoSuchKey {
+                    request_id,
+                    resource,
+                }
+            }
+            StorageErr::VersionNotFound => GetObjectTaggingError::NoSuchVersion {
+                request_id,
+                resource,
+            },
+            _ => GetObjectTaggingError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentType::xml())
+        .body(
+            quick_xml::se::to_string(&Tagging {
+                tag_set: TagSet {
+                    tags: tags
+                        .into_iter()
+                        .map(|(key, value)| Tag { key, value })
+                        .collect(),
+                },
+            })
+            .unwrap(),
+        ))
+}
+
+#[derive(Debug, S3Error)]
+enum PutObjectTaggingError {
+    #[error(
+        status_code = 400,
+        message = "The Content-MD5 or checksum value that you specified did not match what the server received."
+    )]
+    BadDigest {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The tag provided was not a valid tag. Objects can have up to 10 tags with unique keys of up to 128 characters and values of up to 256 characters."
+    )]
+    InvalidTag {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The XML you provided was not well-formed or did not validate against our published schema."
+    )]
+    MalformedXML {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[put(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::tagging",
+    wrap = "crate::policy::Authorize::action(\"s3:PutObjectTagging\").versioned()"
+)]
+pub async fn put_object_tagging(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, PutObjectTaggingError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    if verify_integrity(&req, &body) == Some(false) {
+        return Err(PutObjectTaggingError::BadDigest {
+            request_id,
+            resource,
+        });
+    }
+
+    let Ok(tagging) = quick_xml::de::from_reader::<_, Tagging>(body.as_ref()) else {
+        return Err(PutObjectTaggingError::MalformedXML {
+            request_id,
+            resource,
+        });
+    };
+    let Some(tags) = collect_tags(
+        tagging
+            .tag_set
+            .tags
+            .into_iter()
+            .map(|tag| (tag.key, tag.value)),
+    ) else {
+        return Err(PutObjectTaggingError::InvalidTag {
+            request_id,
+            resource,
+        });
+    };
+
+    storage_provider
+        .into_inner()
+        .put_object_tagging(&bucket, &key, tags)
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => PutObjectTaggingError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::ObjectNotFound => PutObjectTaggingError::NoSuchKey {
+                request_id,
+                resource,
+            },
+            _ => PutObjectTaggingError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, S3Error)]
+enum DeleteObjectTaggingError {
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified bucket does not exist.")]
+    NoSuchBucket {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 404, message = "The specified key does not exist.")]
+    NoSuchKey {
+        request_id: String,
+        resource: String,
+    },
+}
+
+#[delete(
+    "/{bucket}/{key:.*}",
+    guard = "crate::guard::tagging",
+    wrap = "crate::policy::Authorize::action(\"s3:DeleteObjectTagging\").versioned()"
+)]
+pub async fn delete_object_tagging(
+    path: web::Path<(String, String)>,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, DeleteObjectTaggingError> {
+    let request_id = generate_request_id();
+    let (bucket, key) = path.into_inner();
+    let resource = format!("/{bucket}/{key}");
+
+    storage_provider
+        .into_inner()
+        .put_object_tagging(&bucket, &key, Tags::new())
+        .await
+        .map_err(|e| match e {
+            StorageErr::BucketNotFound => DeleteObjectTaggingError::NoSuchBucket {
+                request_id,
+                resource,
+            },
+            StorageErr::ObjectNotFound => DeleteObjectTaggingError::NoSuchKey {
+                request_id,
+                resource,
+            },
+            _ => DeleteObjectTaggingError::InternalError {
+                request_id,
+                resource,
+            },
+        })?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http, App};
+    use futures::stream;
+    use s3_entities::{
+        object::{PutObjectOptions, WriteCondition},
+        test::storage_provider::get_mock_app_data,
+    };
+
+    #[test]
+    fn test_tags_from_headers() {
+        let headers = |value: &str| {
+            let mut headers = header::HeaderMap::new();
+            headers.insert(
+                header::HeaderName::from_static("x-amz-tagging"),
+                header::HeaderValue::from_str(value).unwrap(),
+            );
+            headers
+        };
+
+        assert_eq!(
+            tags_from_headers(&header::HeaderMap::new()),
+            Some(Tags::new())
+        );
+        assert_eq!(
+            tags_from_headers(&headers("project=cat&color=light%20orange&empty")),
+            Some(Tags::from([
+                ("project".to_owned(), "cat".to_owned()),
+                ("color".to_owned(), "light orange".to_owned()),
+                ("empty".to_owned(), "".to_owned()),
+            ]))
+        );
+        assert_eq!(tags_from_headers(&headers("project=cat&project=dog")), None);
+        assert_eq!(
+            tags_from_headers(&headers(&format!("project={}", "a".repeat(257)))),
+            None
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_object_tagging() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .service(get_object_tagging)
+                .service(put_object_tagging)
+                .service(delete_object_tagging),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/key?tagging")
+            .set_payload(
+                "<Tagging><TagSet>\
+                    <Tag><Key>project</Key><Value>cat</Value></Tag>\
+                    <Tag><Key>color</Key><Value>orange</Value></Tag>\
+                </TagSet></Tagging>",
+            )
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/bucket/key?tagging")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let body = actix_web::test::read_body(resp).await;
+        let tagging: Tagging = quick_xml::de::from_reader(body.as_ref()).unwrap();
+        let tags: Vec<_> = tagging
+            .tag_set
+            .tags
+            .iter()
+            .map(|tag| (tag.key.as_str(), tag.value.as_str()))
+            .collect();
+        assert_eq!(tags, [("color", "orange"), ("project", "cat")]);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri("/bucket/key?tagging")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+        assert!(provider
+            .get_object_tagging("bucket", "key", None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_put_object_tagging_invalid() {
+        let provider = get_mock_app_data();
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                Box::pin(stream::once(async { Ok(web::Bytes::from("meow")) })),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider))
+                .service(put_object_tagging),
+        )
+        .await;
+        let put = |body: String| {
+            actix_web::test::TestRequest::put()
+                .uri("/bucket/key?tagging")
+                .set_payload(body)
+                .to_request()
+        };
+
+        let too_many: String = (0..11)
+            .map(|i| format!("<Tag><Key>{i}</Key><Value></Value></Tag>"))
+            .collect();
+        let resp = actix_web::test::call_service(
+            &app,
+            put(format!("<Tagging><TagSet>{too_many}</TagSet></Tagging>")),
+        )
+        .await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>InvalidTag</Code>"));
+
+        let long_key = format!(
+            "<Tagging><TagSet><Tag><Key>{}</Key><Value></Value></Tag></TagSet></Tagging>",
+            "a".repeat(129)
+        );
+        let resp = actix_web::test::call_service(&app, put(long_key)).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let resp = actix_web::test::call_service(&app, put("<Tagging>".into())).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        let body = actix_web::test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("<Code>MalformedXML</Code>"));
+
+        let req = actix_web::test::TestRequest::put()
+            .uri("/bucket/missing?tagging")
+            .set_payload("<Tagging><TagSet></TagSet></Tagging>")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+    }
+}
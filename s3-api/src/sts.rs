@@ -0,0 +1,436 @@
+//! A minimal STS served on the S3 port, through which callers trade their long-term access key
+//! for temporary credentials scoped by a session policy
+
+extern crate self as s3_api;
+
+use crate::{
+    auth::Identity,
+    generate_request_id,
+    xml::{
+        AssumeRoleResponse, AssumeRoleResult, AssumedRoleUser, ResponseMetadata,
+        TemporaryCredentials, STS_NAMESPACE,
+    },
+    xml_date,
+};
+use actix_web::{http::header, post, web, HttpMessage, HttpRequest, HttpResponse};
+use chrono::Utc;
+use percent_encoding::percent_decode_str;
+use rand::distributions::{Alphanumeric, DistString};
+use s3_derive::S3Error;
+use s3_entities::{identity::Session, policy::Policy, storage_provider::StorageProvider};
+use std::{collections::HashMap, time::Duration};
+
+/// How long temporary credentials last unless `DurationSeconds` says otherwise, an hour like STS
+const DEFAULT_DURATION_SECONDS: u64 = 3600;
+/// The shortest and longest `DurationSeconds` STS allows
+const MIN_DURATION_SECONDS: u64 = 900;
+const MAX_DURATION_SECONDS: u64 = 43200;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(assume_role);
+}
+
+#[derive(Debug, S3Error)]
+enum AssumeRoleError {
+    #[error(status_code = 403, message = "Access Denied")]
+    AccessDenied {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 500, message = "An internal error occurred. Try again.")]
+    InternalError {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "The action or operation requested is invalid."
+    )]
+    InvalidAction {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "An invalid or out-of-range value was supplied for the input parameter."
+    )]
+    InvalidParameterValue {
+        request_id: String,
+        resource: String,
+    },
+    #[error(status_code = 400, message = "The policy document is not valid.")]
+    MalformedPolicyDocument {
+        request_id: String,
+        resource: String,
+    },
+    #[error(
+        status_code = 400,
+        message = "A required parameter for the specified action is not supplied."
+    )]
+    MissingParameter {
+        request_id: String,
+        resource: String,
+    },
+}
+
+/// Issue temporary credentials acting for the caller's own access key, which can do no more than
+/// both the caller and the inline `Policy` allow. There are no roles to assume, so `RoleArn` is
+/// ignored; temporary credentials can't assume again nor manage the identity store.
+#[post("/", guard = "crate::guard::assume_role")]
+pub async fn assume_role(
+    req: HttpRequest,
+    body: web::Bytes,
+    storage_provider: web::Data<dyn StorageProvider>,
+) -> Result<HttpResponse, AssumeRoleError> {
+    let request_id = generate_request_id();
+    let resource = String::from("/");
+
+    let identity = req.extensions().get::<Identity>().cloned();
+    let identity = match identity {
+        Some(identity) if !identity.is_session() => identity,
+        _ => {
+            return Err(AssumeRoleError::AccessDenied {
+                request_id,
+                resource,
+            })
+        }
+    };
+
+    // SDKs send the parameters as a form, `Action` and all, but they may be in the query too
+    let mut params = form_params(req.query_string());
+    params.extend(form_params(&String::from_utf8_lossy(&body)));
+    if params.get("Action").map(String::as_str) != Some("AssumeRole") {
+        return Err(AssumeRoleError::InvalidAction {
+            request_id,
+            resource,
+        });
+    }
+
+    let (Some(session_name), Some(policy)) = (params.get("RoleSessionName"), params.get("Policy"))
+    else {
+        return Err(AssumeRoleError::MissingParameter {
+            request_id,
+            resource,
+        });
+    };
+    let duration_seconds = match params.get("DurationSeconds") {
+        Some(duration_seconds) => duration_seconds.parse().ok(),
+        None => Some(DEFAULT_DURATION_SECONDS),
+    };
+    let duration_seconds = match duration_seconds {
+        Some(duration_seconds)
+            if (MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&duration_seconds)
+                && valid_session_name(session_name) =>
+        {
+            duration_seconds
+        }
+        _ => {
+            return Err(AssumeRoleError::InvalidParameterValue {
+                request_id,
+                resource,
+            })
+        }
+    };
+    if Policy::parse_identity(policy).is_err() {
+        return Err(AssumeRoleError::MalformedPolicyDocument {
+            request_id,
+            resource,
+        });
+    }
+
+    let now = Utc::now();
+    let session = Session {
+        access_key_id: random_string("ASIA", 16).to_uppercase(),
+        secret_access_key: random_string("", 40),
+        session_token: random_string("", 256),
+        parent_access_key_id: identity.access_key_id.clone(),
+        user_name: identity.user.as_ref().map(|user| user.name.clone()),
+        session_name: session_name.clone(),
+        policy: policy.clone(),
+        expiration: now + Duration::from_secs(duration_seconds),
+    };
+
+    // Sessions are looked up by access key, so those that expired are only swept from here
+    let storage_provider = storage_provider.into_inner();
+    let stored = match storage_provider.delete_expired_sessions(now).await {
+        Ok(_) => storage_provider.create_session(session.clone()).await,
+        Err(e) => Err(e),
+    };
+    if stored.is_err() {
+        return Err(AssumeRoleError::InternalError {
+            request_id,
+            resource,
+        });
+    }
+
+    let owner = identity.owner();
+    let role_name = identity.user.map_or(owner.display_name, |user| user.name);
+    let body = quick_xml::se::to_string(&AssumeRoleResponse {
+        xmlns: STS_NAMESPACE.into(),
+        result: AssumeRoleResult {
+            credentials: TemporaryCredentials {
+                access_key_id: session.access_key_id,
+                secret_access_key: session.secret_access_key,
+                session_token: session.session_token,
+                expiration: xml_date(&session.expiration),
+            },
+            assumed_role_user: AssumedRoleUser {
+                assumed_role_id: format!("{}:{}", owner.id, session.session_name),
+                arn: format!(
+                    "arn:aws:sts:::assumed-role/{role_name}/{}",
+                    session.session_name
+                ),
+            },
+        },
+        response_metadata: ResponseMetadata {
+            request_id: request_id.clone(),
+        },
+    })
+    .map_err(|_| AssumeRoleError::InternalError {
+        request_id,
+        resource,
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/xml"))
+        .body(body))
+}
+
+/// Decode `application/x-www-form-urlencoded` parameters, where `+` stands for a space
+fn form_params(form: &str) -> HashMap<String, String> {
+    let decode = |s: &str| {
+        percent_decode_str(&s.replace('+', " "))
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+
+    form.split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+/// Letters, digits and `+=,.@_-`, 2 to 64 of them, as STS allows in a `RoleSessionName`
+fn valid_session_name(name: &str) -> bool {
+    (2..=64).contains(&name.len())
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"+=,.@_-".contains(&byte))
+}
+
+fn random_string(prefix: &str, len: usize) -> String {
+    format!(
+        "{prefix}{}",
+        Alphanumeric.sample_string(&mut rand::thread_rng(), len)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::SessionCredentials;
+    use actix_web::{dev::Service as _, http, App};
+    use s3_entities::{identity::User, test::storage_provider::get_mock_app_data};
+
+    const POLICY: &str =
+        r#"{"Statement": {"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}}"#;
+
+    fn form(params: &[(&str, &str)]) -> String {
+        params
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{key}={}",
+                    percent_encoding::utf8_percent_encode(
+                        value,
+                        percent_encoding::NON_ALPHANUMERIC
+                    )
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    fn user() -> User {
+        User {
+            name: "ci".into(),
+            id: "AIDACI".into(),
+            creation_date: Utc::now(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_assume_role() {
+        let provider = get_mock_app_data();
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(provider.clone()))
+                .wrap_fn(|req, srv| {
+                    req.extensions_mut().insert(Identity {
+                        access_key_id: "AKIACI".into(),
+                        user: Some(user()),
+                        session: None,
+                    });
+                    srv.call(req)
+                })
+                .configure(config),
+        )
+        .await;
+
+        let body = form(&[
+            ("Action", "AssumeRole"),
+            ("Version", "2011-06-15"),
+            ("RoleArn", "arn:aws:iam:::role/ci"),
+            ("RoleSessionName", "build-42"),
+            ("Policy", POLICY),
+            ("DurationSeconds", "900"),
+        ]);
+        let req = actix_web::test::TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = actix_web::test::read_body(resp).await;
+        let response: AssumeRoleResponse = quick_xml::de::from_reader(body.as_ref()).unwrap();
+        let credentials = response.result.credentials;
+        assert!(credentials.access_key_id.starts_with("ASIA"));
+        assert_eq!(credentials.secret_access_key.len(), 40);
+        assert_eq!(
+            response.result.assumed_role_user.arn,
+            "arn:aws:sts:::assumed-role/ci/build-42"
+        );
+
+        let session = provider
+            .get_session(&credentials.access_key_id)
+            .await
+            .unwrap();
+        assert_eq!(session.secret_access_key, credentials.secret_access_key);
+        assert_eq!(session.session_token, credentials.session_token);
+        assert_eq!(session.parent_access_key_id, "AKIACI");
+        assert_eq!(session.user_name.as_deref(), Some("ci"));
+        assert_eq!(session.policy, POLICY);
+        assert_eq!(credentials.expiration, xml_date(&session.expiration));
+        assert!(session.expiration <= Utc::now() + Duration::from_secs(900));
+
+        // Without `Action=AssumeRole` it isn't an STS request at all
+        let req = actix_web::test::TestRequest::post().uri("/").to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let req = actix_web::test::TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .set_payload(form(&[("Action", "GetCallerIdentity")]))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_parameters() {
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(get_mock_app_data()))
+                .wrap_fn(|req, srv| {
+                    req.extensions_mut().insert(Identity {
+                        access_key_id: "AKIACI".into(),
+                        user: Some(user()),
+                        session: None,
+                    });
+                    srv.call(req)
+                })
+                .configure(config),
+        )
+        .await;
+
+        let cases = [
+            (form(&[("RoleSessionName", "build")]), "MissingParameter"),
+            (form(&[("Policy", POLICY)]), "MissingParameter"),
+            (
+                form(&[("RoleSessionName", "b"), ("Policy", POLICY)]),
+                "InvalidParameterValue",
+            ),
+            (
+                form(&[
+                    ("RoleSessionName", "build"),
+                    ("Policy", POLICY),
+                    ("DurationSeconds", "60"),
+                ]),
+                "InvalidParameterValue",
+            ),
+            (
+                form(&[("RoleSessionName", "build"), ("Policy", "{")]),
+                "MalformedPolicyDocument",
+            ),
+        ];
+        for (body, code) in cases {
+            let req = actix_web::test::TestRequest::post()
+                .uri("/?Action=AssumeRole")
+                .set_payload(body)
+                .to_request();
+            let resp = actix_web::test::call_service(&app, req).await;
+            assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+            let body = actix_web::test::read_body(resp).await;
+            assert!(String::from_utf8_lossy(&body).contains(&format!("<Code>{code}</Code>")));
+        }
+
+        // The parameters may be in the query string too
+        let req = actix_web::test::TestRequest::post()
+            .uri(&format!(
+                "/?Action=AssumeRole&{}",
+                form(&[("RoleSessionName", "build"), ("Policy", POLICY)])
+            ))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_access_denied() {
+        let body = form(&[("RoleSessionName", "build"), ("Policy", POLICY)]);
+
+        // Anonymous callers have no access key to act for
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(get_mock_app_data()))
+                .configure(config),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/?Action=AssumeRole")
+            .set_payload(body.clone())
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+
+        // Temporary credentials can't be traded for more of them
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::from(get_mock_app_data()))
+                .wrap_fn(|req, srv| {
+                    req.extensions_mut().insert(Identity {
+                        access_key_id: "AKIACI".into(),
+                        user: Some(user()),
+                        session: Some(SessionCredentials {
+                            access_key_id: "ASIACI".into(),
+                            policy: Policy::parse_identity(POLICY).unwrap(),
+                        }),
+                    });
+                    srv.call(req)
+                })
+                .configure(config),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/?Action=AssumeRole")
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// The namespace the `xsi:type` of grantees comes from
+pub const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AccessControlPolicy")]
+pub struct AccessControlPolicy {
+    #[serde(rename = "Owner", skip_serializing_if = "Option::is_none")]
+    pub owner: Option<AclOwner>,
+    #[serde(rename = "AccessControlList")]
+    pub access_control_list: AccessControlList,
+}
+
+/// The owner of an ACL, which clients may send without its display name
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Owner")]
+pub struct AclOwner {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "DisplayName", skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "AccessControlList")]
+pub struct AccessControlList {
+    #[serde(rename = "Grant", default)]
+    pub grants: Vec<Grant>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Grant")]
+pub struct Grant {
+    #[serde(rename = "Grantee")]
+    pub grantee: Grantee,
+    #[serde(rename = "Permission")]
+    pub permission: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Grantee")]
+pub struct Grantee {
+    #[serde(rename = "@xmlns:xsi", skip_deserializing)]
+    pub xmlns_xsi: Option<String>,
+    /// `CanonicalUser`, `Group` or `AmazonCustomerByEmail`, which reads back without its prefix
+    #[serde(rename = "@xsi:type", alias = "@type")]
+    pub grantee_type: String,
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "DisplayName", skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(rename = "URI", skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(rename = "EmailAddress", skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "OwnershipControls")]
+pub struct OwnershipControls {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<OwnershipControlsRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Rule")]
+pub struct OwnershipControlsRule {
+    #[serde(rename = "ObjectOwnership")]
+    pub object_ownership: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_control_policy_deserializes_correctly() {
+        let data: AccessControlPolicy = quick_xml::de::from_str(
+            "<AccessControlPolicy xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <Owner><ID>owner</ID></Owner>\
+                <AccessControlList>\
+                    <Grant>\
+                        <Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"CanonicalUser\">\
+                            <ID>owner</ID><DisplayName>name</DisplayName>\
+                        </Grantee>\
+                        <Permission>FULL_CONTROL</Permission>\
+                    </Grant>\
+                    <Grant>\
+                        <Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"Group\">\
+                            <URI>http://acs.amazonaws.com/groups/global/AllUsers</URI>\
+                        </Grantee>\
+                        <Permission>READ</Permission>\
+                    </Grant>\
+                </AccessControlList>\
+            </AccessControlPolicy>",
+        )
+        .unwrap();
+
+        assert_eq!(data.owner.unwrap().id, "owner");
+        let grants = data.access_control_list.grants;
+        assert_eq!(grants.len(), 2);
+        assert_eq!(grants[0].grantee.grantee_type, "CanonicalUser");
+        assert_eq!(grants[0].grantee.display_name.as_deref(), Some("name"));
+        assert_eq!(grants[1].grantee.grantee_type, "Group");
+        assert_eq!(grants[1].permission, "READ");
+    }
+
+    #[test]
+    fn access_control_policy_serializes_correctly() {
+        let res = quick_xml::se::to_string(&AccessControlPolicy {
+            owner: Some(AclOwner {
+                id: "owner".into(),
+                display_name: Some("name".into()),
+            }),
+            access_control_list: AccessControlList {
+                grants: vec![Grant {
+                    grantee: Grantee {
+                        xmlns_xsi: Some(XSI_NAMESPACE.into()),
+                        grantee_type: "Group".into(),
+                        id: None,
+                        display_name: None,
+                        uri: Some("http://acs.amazonaws.com/groups/global/AllUsers".into()),
+                        email_address: None,
+                    },
+                    permission: "READ".into(),
+                }],
+            },
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<AccessControlPolicy>\
+                <Owner><ID>owner</ID><DisplayName>name</DisplayName></Owner>\
+                <AccessControlList>\
+                    <Grant>\
+                        <Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"Group\">\
+                            <URI>http://acs.amazonaws.com/groups/global/AllUsers</URI>\
+                        </Grantee>\
+                        <Permission>READ</Permission>\
+                    </Grant>\
+                </AccessControlList>\
+            </AccessControlPolicy>"
+        );
+    }
+}
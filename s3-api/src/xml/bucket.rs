@@ -1,3 +1,4 @@
+use super::Tag;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +19,134 @@ pub struct BucketInfo {
     pub bucket_type: Option<String>,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "VersioningConfiguration")]
+pub struct VersioningConfiguration {
+    #[serde(rename = "Status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "LifecycleConfiguration")]
+pub struct LifecycleConfiguration {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<LifecycleRule>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Rule")]
+pub struct LifecycleRule {
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "Filter", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<LifecycleFilter>,
+    /// Where rules put their prefix before filters were introduced
+    #[serde(rename = "Prefix", skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "Expiration", skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<LifecycleExpiration>,
+    #[serde(
+        rename = "NoncurrentVersionExpiration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub noncurrent_version_expiration: Option<NoncurrentVersionExpiration>,
+    #[serde(
+        rename = "AbortIncompleteMultipartUpload",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub abort_incomplete_multipart_upload: Option<AbortIncompleteMultipartUpload>,
+}
+
+/// Holds a single condition, several of them having to be wrapped in `And`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Filter")]
+pub struct LifecycleFilter {
+    #[serde(rename = "Prefix", skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(rename = "Tag", skip_serializing_if = "Option::is_none")]
+    pub tag: Option<Tag>,
+    #[serde(
+        rename = "ObjectSizeGreaterThan",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub object_size_greater_than: Option<u64>,
+    #[serde(rename = "ObjectSizeLessThan", skip_serializing_if = "Option::is_none")]
+    pub object_size_less_than: Option<u64>,
+    #[serde(rename = "And", skip_serializing_if = "Option::is_none")]
+    pub and: Option<LifecycleAnd>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "And")]
+pub struct LifecycleAnd {
+    #[serde(rename = "Prefix", skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(rename = "Tag", default)]
+    pub tags: Vec<Tag>,
+    #[serde(
+        rename = "ObjectSizeGreaterThan",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub object_size_greater_than: Option<u64>,
+    #[serde(rename = "ObjectSizeLessThan", skip_serializing_if = "Option::is_none")]
+    pub object_size_less_than: Option<u64>,
+}
+
+/// Holds one of `Days`, `Date` or `ExpiredObjectDeleteMarker`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Expiration")]
+pub struct LifecycleExpiration {
+    #[serde(rename = "Days", skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(rename = "Date", skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(
+        rename = "ExpiredObjectDeleteMarker",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expired_object_delete_marker: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "NoncurrentVersionExpiration")]
+pub struct NoncurrentVersionExpiration {
+    #[serde(rename = "NoncurrentDays")]
+    pub noncurrent_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AbortIncompleteMultipartUpload")]
+pub struct AbortIncompleteMultipartUpload {
+    #[serde(rename = "DaysAfterInitiation")]
+    pub days_after_initiation: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "CORSConfiguration")]
+pub struct CorsConfiguration {
+    #[serde(rename = "CORSRule", default)]
+    pub rules: Vec<CorsRule>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "CORSRule")]
+pub struct CorsRule {
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "AllowedHeader", default)]
+    pub allowed_headers: Vec<String>,
+    #[serde(rename = "AllowedMethod", default)]
+    pub allowed_methods: Vec<String>,
+    #[serde(rename = "AllowedOrigin", default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(rename = "ExposeHeader", default)]
+    pub expose_headers: Vec<String>,
+    #[serde(rename = "MaxAgeSeconds", skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<u32>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,4 +178,170 @@ mod tests {
             "<BucketInfo><DataRedundancy>hello</DataRedundancy><Type>Directory</Type></BucketInfo>"
         )
     }
+
+    #[test]
+    fn versioning_configuration_serializes_correctly() {
+        let data = VersioningConfiguration {
+            status: Some("Enabled".into()),
+        };
+        let res = quick_xml::se::to_string(&data).unwrap();
+
+        assert_eq!(
+            res,
+            "<VersioningConfiguration><Status>Enabled</Status></VersioningConfiguration>"
+        );
+        assert_eq!(
+            quick_xml::se::to_string(&VersioningConfiguration::default()).unwrap(),
+            "<VersioningConfiguration/>"
+        );
+    }
+
+    #[test]
+    fn versioning_configuration_deserializes_correctly() {
+        let data: VersioningConfiguration = quick_xml::de::from_str(
+            "<VersioningConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <Status>Suspended</Status>\
+            </VersioningConfiguration>",
+        )
+        .unwrap();
+
+        assert_eq!(data.status.as_deref(), Some("Suspended"));
+    }
+
+    #[test]
+    fn lifecycle_configuration_deserializes_correctly() {
+        let data: LifecycleConfiguration = quick_xml::de::from_str(
+            "<LifecycleConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <Rule>\
+                    <ID>logs</ID>\
+                    <Filter>\
+                        <And>\
+                            <Prefix>logs/</Prefix>\
+                            <Tag><Key>project</Key><Value>cat</Value></Tag>\
+                            <ObjectSizeGreaterThan>10</ObjectSizeGreaterThan>\
+                        </And>\
+                    </Filter>\
+                    <Status>Enabled</Status>\
+                    <Expiration><Days>30</Days></Expiration>\
+                    <NoncurrentVersionExpiration>\
+                        <NoncurrentDays>7</NoncurrentDays>\
+                    </NoncurrentVersionExpiration>\
+                </Rule>\
+                <Rule>\
+                    <Prefix></Prefix>\
+                    <Status>Disabled</Status>\
+                    <AbortIncompleteMultipartUpload>\
+                        <DaysAfterInitiation>1</DaysAfterInitiation>\
+                    </AbortIncompleteMultipartUpload>\
+                </Rule>\
+            </LifecycleConfiguration>",
+        )
+        .unwrap();
+
+        assert_eq!(data.rules.len(), 2);
+        let and = data.rules[0].filter.as_ref().unwrap().and.as_ref().unwrap();
+        assert_eq!(and.prefix.as_deref(), Some("logs/"));
+        assert_eq!(and.tags[0].key, "project");
+        assert_eq!(and.object_size_greater_than, Some(10));
+        assert_eq!(data.rules[0].expiration.as_ref().unwrap().days, Some(30));
+        assert_eq!(
+            data.rules[0]
+                .noncurrent_version_expiration
+                .as_ref()
+                .unwrap()
+                .noncurrent_days,
+            7
+        );
+        assert_eq!(data.rules[1].prefix.as_deref(), Some(""));
+        assert_eq!(data.rules[1].status, "Disabled");
+        assert_eq!(
+            data.rules[1]
+                .abort_incomplete_multipart_upload
+                .as_ref()
+                .unwrap()
+                .days_after_initiation,
+            1
+        );
+    }
+
+    #[test]
+    fn lifecycle_configuration_serializes_correctly() {
+        let data = LifecycleConfiguration {
+            rules: vec![LifecycleRule {
+                id: Some("logs".into()),
+                filter: Some(LifecycleFilter {
+                    prefix: Some("logs/".into()),
+                    ..Default::default()
+                }),
+                status: "Enabled".into(),
+                expiration: Some(LifecycleExpiration {
+                    expired_object_delete_marker: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        };
+        let res = quick_xml::se::to_string(&data).unwrap();
+
+        assert_eq!(
+            res,
+            "<LifecycleConfiguration><Rule>\
+                <ID>logs</ID>\
+                <Filter><Prefix>logs/</Prefix></Filter>\
+                <Status>Enabled</Status>\
+                <Expiration><ExpiredObjectDeleteMarker>true</ExpiredObjectDeleteMarker></Expiration>\
+            </Rule></LifecycleConfiguration>"
+        );
+    }
+
+    #[test]
+    fn cors_configuration_deserializes_correctly() {
+        let data: CorsConfiguration = quick_xml::de::from_str(
+            "<CORSConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <CORSRule>\
+                    <ID>uploads</ID>\
+                    <AllowedOrigin>https://*.example.com</AllowedOrigin>\
+                    <AllowedMethod>PUT</AllowedMethod>\
+                    <AllowedMethod>POST</AllowedMethod>\
+                    <AllowedHeader>*</AllowedHeader>\
+                    <ExposeHeader>ETag</ExposeHeader>\
+                    <MaxAgeSeconds>3000</MaxAgeSeconds>\
+                </CORSRule>\
+                <CORSRule>\
+                    <AllowedOrigin>*</AllowedOrigin>\
+                    <AllowedMethod>GET</AllowedMethod>\
+                </CORSRule>\
+            </CORSConfiguration>",
+        )
+        .unwrap();
+
+        assert_eq!(data.rules.len(), 2);
+        assert_eq!(data.rules[0].id.as_deref(), Some("uploads"));
+        assert_eq!(data.rules[0].allowed_methods, ["PUT", "POST"]);
+        assert_eq!(data.rules[0].allowed_headers, ["*"]);
+        assert_eq!(data.rules[0].max_age_seconds, Some(3000));
+        assert_eq!(data.rules[1].allowed_origins, ["*"]);
+        assert!(data.rules[1].expose_headers.is_empty());
+    }
+
+    #[test]
+    fn cors_configuration_serializes_correctly() {
+        let data = CorsConfiguration {
+            rules: vec![CorsRule {
+                allowed_methods: vec!["GET".into(), "HEAD".into()],
+                allowed_origins: vec!["*".into()],
+                ..Default::default()
+            }],
+        };
+        let res = quick_xml::se::to_string(&data).unwrap();
+
+        assert_eq!(
+            res,
+            "<CORSConfiguration><CORSRule>\
+                <AllowedMethod>GET</AllowedMethod>\
+                <AllowedMethod>HEAD</AllowedMethod>\
+                <AllowedOrigin>*</AllowedOrigin>\
+            </CORSRule></CORSConfiguration>"
+        )
+    }
 }
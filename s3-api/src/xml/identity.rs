@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "User")]
+pub struct User {
+    #[serde(rename = "UserName")]
+    pub user_name: String,
+    #[serde(rename = "UserId")]
+    pub user_id: String,
+    #[serde(rename = "CreateDate")]
+    pub create_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListUsersResult")]
+pub struct ListUsersResult {
+    #[serde(rename = "User", default)]
+    pub users: Vec<User>,
+}
+
+/// An access key, with its secret only when it's just been created
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AccessKey")]
+pub struct AccessKey {
+    #[serde(rename = "UserName")]
+    pub user_name: String,
+    #[serde(rename = "AccessKeyId")]
+    pub access_key_id: String,
+    #[serde(rename = "SecretAccessKey", skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<String>,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "CreateDate")]
+    pub create_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListAccessKeysResult")]
+pub struct ListAccessKeysResult {
+    #[serde(rename = "AccessKey", default)]
+    pub access_keys: Vec<AccessKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Group")]
+pub struct Group {
+    #[serde(rename = "GroupName")]
+    pub group_name: String,
+    #[serde(rename = "GroupId")]
+    pub group_id: String,
+    #[serde(rename = "CreateDate")]
+    pub create_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListGroupsResult")]
+pub struct ListGroupsResult {
+    #[serde(rename = "Group", default)]
+    pub groups: Vec<Group>,
+}
+
+/// The names of the policies attached to a user or group
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListPoliciesResult")]
+pub struct ListPoliciesResult {
+    #[serde(rename = "PolicyName", default)]
+    pub policy_names: Vec<String>,
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "CommonPrefixes")]
+pub struct CommonPrefix {
+    #[serde(rename = "Prefix")]
+    pub prefix: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListBucketResult")]
+pub struct ListBucketResult {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Prefix")]
+    pub prefix: String,
+    /// Only in V1 listings, like `next_marker`
+    #[serde(rename = "Marker", skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    #[serde(rename = "NextMarker", skip_serializing_if = "Option::is_none")]
+    pub next_marker: Option<String>,
+    #[serde(rename = "Delimiter", skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    #[serde(rename = "MaxKeys")]
+    pub max_keys: usize,
+    #[serde(rename = "EncodingType", skip_serializing_if = "Option::is_none")]
+    pub encoding_type: Option<String>,
+    /// Only in V2 listings, like the continuation tokens and `start_after`
+    #[serde(rename = "KeyCount", skip_serializing_if = "Option::is_none")]
+    pub key_count: Option<usize>,
+    #[serde(rename = "IsTruncated")]
+    pub is_truncated: bool,
+    #[serde(rename = "ContinuationToken", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    #[serde(
+        rename = "NextContinuationToken",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_continuation_token: Option<String>,
+    #[serde(rename = "StartAfter", skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<String>,
+    #[serde(rename = "Contents", default)]
+    pub contents: Vec<Contents>,
+    #[serde(rename = "CommonPrefixes", default)]
+    pub common_prefixes: Vec<CommonPrefix>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Contents")]
+pub struct Contents {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    #[serde(rename = "Size")]
+    pub size: u64,
+    #[serde(rename = "StorageClass")]
+    pub storage_class: String,
+    #[serde(rename = "Owner", skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListVersionsResult")]
+pub struct ListVersionsResult {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Prefix")]
+    pub prefix: String,
+    #[serde(rename = "KeyMarker")]
+    pub key_marker: String,
+    #[serde(rename = "VersionIdMarker")]
+    pub version_id_marker: String,
+    #[serde(rename = "NextKeyMarker", skip_serializing_if = "Option::is_none")]
+    pub next_key_marker: Option<String>,
+    #[serde(
+        rename = "NextVersionIdMarker",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_version_id_marker: Option<String>,
+    #[serde(rename = "Delimiter", skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    #[serde(rename = "MaxKeys")]
+    pub max_keys: usize,
+    #[serde(rename = "EncodingType", skip_serializing_if = "Option::is_none")]
+    pub encoding_type: Option<String>,
+    #[serde(rename = "IsTruncated")]
+    pub is_truncated: bool,
+    /// Versions and delete markers interleaved, in the order they're listed in
+    #[serde(rename = "$value", default)]
+    pub versions: Vec<ListedVersion>,
+    #[serde(rename = "CommonPrefixes", default)]
+    pub common_prefixes: Vec<CommonPrefix>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ListedVersion {
+    Version(Version),
+    DeleteMarker(DeleteMarkerEntry),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Version")]
+pub struct Version {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    pub version_id: String,
+    #[serde(rename = "IsLatest")]
+    pub is_latest: bool,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    #[serde(rename = "Size")]
+    pub size: u64,
+    #[serde(rename = "StorageClass")]
+    pub storage_class: String,
+    #[serde(rename = "Owner")]
+    pub owner: Owner,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "DeleteMarker")]
+pub struct DeleteMarkerEntry {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    pub version_id: String,
+    #[serde(rename = "IsLatest")]
+    pub is_latest: bool,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+    #[serde(rename = "Owner")]
+    pub owner: Owner,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Owner")]
+pub struct Owner {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "DisplayName")]
+    pub display_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_bucket_result_serializes_correctly() {
+        let res = quick_xml::se::to_string(&ListBucketResult {
+            name: "bucket".into(),
+            prefix: "photos/".into(),
+            marker: None,
+            next_marker: None,
+            delimiter: Some("/".into()),
+            max_keys: 1000,
+            encoding_type: None,
+            key_count: Some(2),
+            is_truncated: false,
+            continuation_token: None,
+            next_continuation_token: None,
+            start_after: None,
+            contents: vec![Contents {
+                key: "photos/cat.jpg".into(),
+                last_modified: "2009-10-12T17:50:30.000Z".into(),
+                etag: "\"fba9dede5f27731c9771645a39863328\"".into(),
+                size: 434234,
+                storage_class: "STANDARD".into(),
+                owner: None,
+            }],
+            common_prefixes: vec![CommonPrefix {
+                prefix: "photos/2023/".into(),
+            }],
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<ListBucketResult>\
+                <Name>bucket</Name>\
+                <Prefix>photos/</Prefix>\
+                <Delimiter>/</Delimiter>\
+                <MaxKeys>1000</MaxKeys>\
+                <KeyCount>2</KeyCount>\
+                <IsTruncated>false</IsTruncated>\
+                <Contents>\
+                    <Key>photos/cat.jpg</Key>\
+                    <LastModified>2009-10-12T17:50:30.000Z</LastModified>\
+                    <ETag>\"fba9dede5f27731c9771645a39863328\"</ETag>\
+                    <Size>434234</Size>\
+                    <StorageClass>STANDARD</StorageClass>\
+                </Contents>\
+                <CommonPrefixes>\
+                    <Prefix>photos/2023/</Prefix>\
+                </CommonPrefixes>\
+            </ListBucketResult>"
+        )
+    }
+
+    #[test]
+    fn list_versions_result_serializes_correctly() {
+        let owner = || Owner {
+            id: "id".into(),
+            display_name: "name".into(),
+        };
+        let res = quick_xml::se::to_string(&ListVersionsResult {
+            name: "bucket".into(),
+            prefix: "".into(),
+            key_marker: "".into(),
+            version_id_marker: "".into(),
+            next_key_marker: Some("cat.jpg".into()),
+            next_version_id_marker: Some("null".into()),
+            delimiter: None,
+            max_keys: 2,
+            encoding_type: None,
+            is_truncated: true,
+            versions: vec![
+                ListedVersion::DeleteMarker(DeleteMarkerEntry {
+                    key: "cat.jpg".into(),
+                    version_id: "abc".into(),
+                    is_latest: true,
+                    last_modified: "2009-10-12T17:50:30.000Z".into(),
+                    owner: owner(),
+                }),
+                ListedVersion::Version(Version {
+                    key: "cat.jpg".into(),
+                    version_id: "null".into(),
+                    is_latest: false,
+                    last_modified: "2009-10-12T17:50:30.000Z".into(),
+                    etag: "\"fba9dede5f27731c9771645a39863328\"".into(),
+                    size: 434234,
+                    storage_class: "STANDARD".into(),
+                    owner: owner(),
+                }),
+            ],
+            common_prefixes: Vec::new(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<ListVersionsResult>\
+                <Name>bucket</Name>\
+                <Prefix/>\
+                <KeyMarker/>\
+                <VersionIdMarker/>\
+                <NextKeyMarker>cat.jpg</NextKeyMarker>\
+                <NextVersionIdMarker>null</NextVersionIdMarker>\
+                <MaxKeys>2</MaxKeys>\
+                <IsTruncated>true</IsTruncated>\
+                <DeleteMarker>\
+                    <Key>cat.jpg</Key>\
+                    <VersionId>abc</VersionId>\
+                    <IsLatest>true</IsLatest>\
+                    <LastModified>2009-10-12T17:50:30.000Z</LastModified>\
+                    <Owner><ID>id</ID><DisplayName>name</DisplayName></Owner>\
+                </DeleteMarker>\
+                <Version>\
+                    <Key>cat.jpg</Key>\
+                    <VersionId>null</VersionId>\
+                    <IsLatest>false</IsLatest>\
+                    <LastModified>2009-10-12T17:50:30.000Z</LastModified>\
+                    <ETag>\"fba9dede5f27731c9771645a39863328\"</ETag>\
+                    <Size>434234</Size>\
+                    <StorageClass>STANDARD</StorageClass>\
+                    <Owner><ID>id</ID><DisplayName>name</DisplayName></Owner>\
+                </Version>\
+            </ListVersionsResult>"
+        )
+    }
+}
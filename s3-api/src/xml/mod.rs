@@ -1,3 +1,15 @@
+mod acl;
 mod bucket;
+mod identity;
+mod list;
+mod multipart;
+mod object;
+mod sts;
 
+pub use acl::*;
 pub use bucket::*;
+pub use identity::*;
+pub use list::*;
+pub use multipart::*;
+pub use object::*;
+pub use sts::*;
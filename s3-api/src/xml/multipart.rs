@@ -0,0 +1,210 @@
+use super::CommonPrefix;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "InitiateMultipartUploadResult")]
+pub struct InitiateMultipartUploadResult {
+    #[serde(rename = "Bucket")]
+    pub bucket: String,
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "UploadId")]
+    pub upload_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "CompleteMultipartUpload")]
+pub struct CompleteMultipartUpload {
+    #[serde(rename = "Part", default)]
+    pub parts: Vec<CompletedPart>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Part")]
+pub struct CompletedPart {
+    #[serde(rename = "PartNumber")]
+    pub part_number: u16,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "CompleteMultipartUploadResult")]
+pub struct CompleteMultipartUploadResult {
+    #[serde(rename = "Location")]
+    pub location: String,
+    #[serde(rename = "Bucket")]
+    pub bucket: String,
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "CopyPartResult")]
+pub struct CopyPartResult {
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListMultipartUploadsResult")]
+pub struct ListMultipartUploadsResult {
+    #[serde(rename = "Bucket")]
+    pub bucket: String,
+    #[serde(rename = "KeyMarker")]
+    pub key_marker: String,
+    #[serde(rename = "UploadIdMarker")]
+    pub upload_id_marker: String,
+    #[serde(rename = "NextKeyMarker", skip_serializing_if = "Option::is_none")]
+    pub next_key_marker: Option<String>,
+    #[serde(rename = "NextUploadIdMarker", skip_serializing_if = "Option::is_none")]
+    pub next_upload_id_marker: Option<String>,
+    #[serde(rename = "Delimiter", skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    #[serde(rename = "Prefix")]
+    pub prefix: String,
+    #[serde(rename = "MaxUploads")]
+    pub max_uploads: usize,
+    #[serde(rename = "IsTruncated")]
+    pub is_truncated: bool,
+    #[serde(rename = "Upload", default)]
+    pub uploads: Vec<Upload>,
+    #[serde(rename = "CommonPrefixes", default)]
+    pub common_prefixes: Vec<CommonPrefix>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Upload")]
+pub struct Upload {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "UploadId")]
+    pub upload_id: String,
+    #[serde(rename = "StorageClass")]
+    pub storage_class: String,
+    #[serde(rename = "Initiated")]
+    pub initiated: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ListPartsResult")]
+pub struct ListPartsResult {
+    #[serde(rename = "Bucket")]
+    pub bucket: String,
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "UploadId")]
+    pub upload_id: String,
+    #[serde(rename = "PartNumberMarker")]
+    pub part_number_marker: u16,
+    #[serde(
+        rename = "NextPartNumberMarker",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_part_number_marker: Option<u16>,
+    #[serde(rename = "MaxParts")]
+    pub max_parts: usize,
+    #[serde(rename = "IsTruncated")]
+    pub is_truncated: bool,
+    #[serde(rename = "Part", default)]
+    pub parts: Vec<ListedPart>,
+    #[serde(rename = "StorageClass")]
+    pub storage_class: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Part")]
+pub struct ListedPart {
+    #[serde(rename = "PartNumber")]
+    pub part_number: u16,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    #[serde(rename = "Size")]
+    pub size: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_multipart_upload_deserializes_correctly() {
+        let data: CompleteMultipartUpload = quick_xml::de::from_str(
+            "<CompleteMultipartUpload xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <Part><ETag>\"a54357aff0632cce46d942af68356b38\"</ETag><PartNumber>1</PartNumber></Part>\
+                <Part><PartNumber>2</PartNumber><ETag>\"0c78aef83f66abc1fa1e8477f296d394\"</ETag><ChecksumCRC32>AAAAAA==</ChecksumCRC32></Part>\
+            </CompleteMultipartUpload>",
+        )
+        .unwrap();
+
+        assert_eq!(data.parts.len(), 2);
+        assert_eq!(data.parts[0].part_number, 1);
+        assert_eq!(data.parts[1].etag, "\"0c78aef83f66abc1fa1e8477f296d394\"");
+    }
+
+    #[test]
+    fn initiate_multipart_upload_result_serializes_correctly() {
+        let res = quick_xml::se::to_string(&InitiateMultipartUploadResult {
+            bucket: "bucket".into(),
+            key: "key".into(),
+            upload_id: "abc".into(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<InitiateMultipartUploadResult>\
+                <Bucket>bucket</Bucket>\
+                <Key>key</Key>\
+                <UploadId>abc</UploadId>\
+            </InitiateMultipartUploadResult>"
+        )
+    }
+
+    #[test]
+    fn list_parts_result_serializes_correctly() {
+        let res = quick_xml::se::to_string(&ListPartsResult {
+            bucket: "bucket".into(),
+            key: "key".into(),
+            upload_id: "abc".into(),
+            part_number_marker: 0,
+            next_part_number_marker: Some(1),
+            max_parts: 1,
+            is_truncated: true,
+            parts: vec![ListedPart {
+                part_number: 1,
+                last_modified: "2010-11-10T20:48:34.000Z".into(),
+                etag: "\"7778aef83f66abc1fa1e8477f296d394\"".into(),
+                size: 10485760,
+            }],
+            storage_class: "STANDARD".into(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<ListPartsResult>\
+                <Bucket>bucket</Bucket>\
+                <Key>key</Key>\
+                <UploadId>abc</UploadId>\
+                <PartNumberMarker>0</PartNumberMarker>\
+                <NextPartNumberMarker>1</NextPartNumberMarker>\
+                <MaxParts>1</MaxParts>\
+                <IsTruncated>true</IsTruncated>\
+                <Part>\
+                    <PartNumber>1</PartNumber>\
+                    <LastModified>2010-11-10T20:48:34.000Z</LastModified>\
+                    <ETag>\"7778aef83f66abc1fa1e8477f296d394\"</ETag>\
+                    <Size>10485760</Size>\
+                </Part>\
+                <StorageClass>STANDARD</StorageClass>\
+            </ListPartsResult>"
+        )
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "CopyObjectResult")]
+pub struct CopyObjectResult {
+    #[serde(rename = "ETag")]
+    pub etag: String,
+    #[serde(rename = "LastModified")]
+    pub last_modified: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Delete")]
+pub struct Delete {
+    #[serde(rename = "Object", default)]
+    pub objects: Vec<ObjectIdentifier>,
+    #[serde(rename = "Quiet", default)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Object")]
+pub struct ObjectIdentifier {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    pub version_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "DeleteResult")]
+pub struct DeleteResult {
+    #[serde(rename = "Deleted", default)]
+    pub deleted: Vec<Deleted>,
+    #[serde(rename = "Error", default)]
+    pub errors: Vec<DeleteError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Deleted")]
+pub struct Deleted {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId", skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    #[serde(rename = "DeleteMarker", skip_serializing_if = "Option::is_none")]
+    pub delete_marker: Option<bool>,
+    #[serde(
+        rename = "DeleteMarkerVersionId",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub delete_marker_version_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Error")]
+pub struct DeleteError {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId", skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    #[serde(rename = "Code")]
+    pub code: String,
+    #[serde(rename = "Message")]
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Tagging")]
+pub struct Tagging {
+    #[serde(rename = "TagSet")]
+    pub tag_set: TagSet,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "TagSet")]
+pub struct TagSet {
+    #[serde(rename = "Tag", default)]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Tag")]
+pub struct Tag {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_deserializes_correctly() {
+        let data: Delete = quick_xml::de::from_str(
+            "<Delete xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <Object><Key>sample1.txt</Key></Object>\
+                <Object><Key>sample2.txt</Key><VersionId>abc</VersionId></Object>\
+                <Quiet>true</Quiet>\
+            </Delete>",
+        )
+        .unwrap();
+
+        assert_eq!(data.objects.len(), 2);
+        assert_eq!(data.objects[1].key, "sample2.txt");
+        assert_eq!(data.objects[0].version_id, None);
+        assert_eq!(data.objects[1].version_id.as_deref(), Some("abc"));
+        assert!(data.quiet);
+    }
+
+    #[test]
+    fn delete_result_serializes_correctly() {
+        let res = quick_xml::se::to_string(&DeleteResult {
+            deleted: vec![
+                Deleted {
+                    key: "sample1.txt".into(),
+                    version_id: None,
+                    delete_marker: None,
+                    delete_marker_version_id: None,
+                },
+                Deleted {
+                    key: "sample3.txt".into(),
+                    version_id: None,
+                    delete_marker: Some(true),
+                    delete_marker_version_id: Some("abc".into()),
+                },
+            ],
+            errors: vec![DeleteError {
+                key: "sample2.txt".into(),
+                version_id: Some("def".into()),
+                code: "InternalError".into(),
+                message: "An internal error occurred. Try again.".into(),
+            }],
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<DeleteResult>\
+                <Deleted><Key>sample1.txt</Key></Deleted>\
+                <Deleted>\
+                    <Key>sample3.txt</Key>\
+                    <DeleteMarker>true</DeleteMarker>\
+                    <DeleteMarkerVersionId>abc</DeleteMarkerVersionId>\
+                </Deleted>\
+                <Error>\
+                    <Key>sample2.txt</Key>\
+                    <VersionId>def</VersionId>\
+                    <Code>InternalError</Code>\
+                    <Message>An internal error occurred. Try again.</Message>\
+                </Error>\
+            </DeleteResult>"
+        )
+    }
+
+    #[test]
+    fn tagging_deserializes_correctly() {
+        let data: Tagging = quick_xml::de::from_str(
+            "<Tagging xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+                <TagSet>\
+                    <Tag><Key>project</Key><Value>cat</Value></Tag>\
+                    <Tag><Key>color</Key><Value></Value></Tag>\
+                </TagSet>\
+            </Tagging>",
+        )
+        .unwrap();
+
+        assert_eq!(data.tag_set.tags.len(), 2);
+        assert_eq!(data.tag_set.tags[0].key, "project");
+        assert_eq!(data.tag_set.tags[1].value, "");
+    }
+
+    #[test]
+    fn tagging_serializes_correctly() {
+        let res = quick_xml::se::to_string(&Tagging {
+            tag_set: TagSet {
+                tags: vec![Tag {
+                    key: "project".into(),
+                    value: "cat".into(),
+                }],
+            },
+        })
+        .unwrap();
+
+        assert_eq!(
+            res,
+            "<Tagging>\
+                <TagSet><Tag><Key>project</Key><Value>cat</Value></Tag></TagSet>\
+            </Tagging>"
+        );
+        assert_eq!(
+            quick_xml::se::to_string(&Tagging::default()).unwrap(),
+            "<Tagging><TagSet/></Tagging>"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// The namespace STS responses are in, which SDKs expect unlike that of S3
+pub const STS_NAMESPACE: &str = "https://sts.amazonaws.com/doc/2011-06-15/";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AssumeRoleResponse")]
+pub struct AssumeRoleResponse {
+    #[serde(rename = "@xmlns", skip_deserializing)]
+    pub xmlns: String,
+    #[serde(rename = "AssumeRoleResult")]
+    pub result: AssumeRoleResult,
+    #[serde(rename = "ResponseMetadata")]
+    pub response_metadata: ResponseMetadata,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AssumeRoleResult")]
+pub struct AssumeRoleResult {
+    #[serde(rename = "Credentials")]
+    pub credentials: TemporaryCredentials,
+    #[serde(rename = "AssumedRoleUser")]
+    pub assumed_role_user: AssumedRoleUser,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Credentials")]
+pub struct TemporaryCredentials {
+    #[serde(rename = "AccessKeyId")]
+    pub access_key_id: String,
+    #[serde(rename = "SecretAccessKey")]
+    pub secret_access_key: String,
+    #[serde(rename = "SessionToken")]
+    pub session_token: String,
+    #[serde(rename = "Expiration")]
+    pub expiration: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AssumedRoleUser")]
+pub struct AssumedRoleUser {
+    #[serde(rename = "AssumedRoleId")]
+    pub assumed_role_id: String,
+    #[serde(rename = "Arn")]
+    pub arn: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ResponseMetadata")]
+pub struct ResponseMetadata {
+    #[serde(rename = "RequestId")]
+    pub request_id: String,
+}
@@ -1,13 +1,16 @@
-use darling::{FromDeriveInput, FromVariant};
+use darling::{FromDeriveInput, FromField, FromVariant};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
 #[derive(Debug, FromDeriveInput)]
-#[darling(supports(enum_any))]
+#[darling(attributes(error), supports(enum_any))]
 pub struct ErrorOpts {
     ident: syn::Ident,
     generics: syn::Generics,
     data: darling::ast::Data<VariantReceiver, ()>,
+
+    /// Respond with the status code only, for requests such as `HEAD` that can't carry a body
+    empty_body: darling::util::Flag,
 }
 
 #[derive(Debug, FromVariant)]
@@ -17,104 +20,152 @@ pub struct VariantReceiver {
 
     status_code: u16,
     message: String,
+    /// The S3 error code, when it differs from the variant name
+    code: Option<String>,
+    fields: darling::ast::Fields<FieldReceiver>,
+}
+
+#[derive(Debug, FromField)]
+pub struct FieldReceiver {
+    ident: Option<syn::Ident>,
+}
+
+impl VariantReceiver {
+    /// Whether the variant has a `headers` field of extra headers to respond with, as a
+    /// `Vec<(&'static str, String)>`
+    fn has_headers(&self) -> bool {
+        self.fields.iter().any(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == "headers")
+        })
+    }
 }
 
 impl ToTokens for ErrorOpts {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ErrorOpts {
             ref ident,
-            ref generics, 
+            ref generics,
             ref data,
+            ref empty_body,
         } = *self;
-        
+
         let variants = data.as_ref().take_enum().expect("Should never be struct");
 
-        let generic_lifetimes: Vec<_> = generics.lifetimes().map(|l| {
-            let syn::LifetimeParam {
-                attrs,
-                lifetime,
-                ..
-            } = l;
+        let generic_lifetimes: Vec<_> = generics
+            .lifetimes()
+            .map(|l| {
+                let syn::LifetimeParam {
+                    attrs, lifetime, ..
+                } = l;
 
-            quote! {
-                #(#attrs)*
-                #lifetime
-            }
-        }).collect();
-        let generic_types: Vec<_> = generics.type_params().map(|t| {
-            let syn::TypeParam {
-                attrs,
-                ident,
-                colon_token,
-                bounds,
-                ..
-            } = t;
+                quote! {
+                    #(#attrs)*
+                    #lifetime
+                }
+            })
+            .collect();
+        let generic_types: Vec<_> = generics
+            .type_params()
+            .map(|t| {
+                let syn::TypeParam {
+                    attrs,
+                    ident,
+                    colon_token,
+                    bounds,
+                    ..
+                } = t;
 
-            quote! {
-                #(#attrs)*
-                #ident
-                #colon_token
-                #bounds
-            }
-        }).collect();
-        let generic_consts: Vec<_> = generics.const_params().map(|c| {
-            let syn::ConstParam {
-                attrs,
-                const_token,
-                ident,
-                colon_token,
-                ty,
-                ..
-            } = c;
+                quote! {
+                    #(#attrs)*
+                    #ident
+                    #colon_token
+                    #bounds
+                }
+            })
+            .collect();
+        let generic_consts: Vec<_> = generics
+            .const_params()
+            .map(|c| {
+                let syn::ConstParam {
+                    attrs,
+                    const_token,
+                    ident,
+                    colon_token,
+                    ty,
+                    ..
+                } = c;
 
-            quote! {
-                #(#attrs)*
-                #const_token
-                #ident
-                #colon_token
-                #ty
-            }
-        }).collect();
-        let quoted_generics: Vec<_> = generic_lifetimes.into_iter().chain(generic_types).chain(generic_consts).collect();
+                quote! {
+                    #(#attrs)*
+                    #const_token
+                    #ident
+                    #colon_token
+                    #ty
+                }
+            })
+            .collect();
+        let quoted_generics: Vec<_> = generic_lifetimes
+            .into_iter()
+            .chain(generic_types)
+            .chain(generic_consts)
+            .collect();
 
-        let generic_names: Vec<_> = generics.params.iter().map(|p| match p {
-            syn::GenericParam::Type(syn::TypeParam { ident, .. }) => quote! { #ident },
-            syn::GenericParam::Lifetime(syn::LifetimeParam { lifetime, .. }) => quote! { #lifetime },
-            syn::GenericParam::Const(syn::ConstParam { ident, .. }) => quote! { #ident },
-        }).collect();
+        let generic_names: Vec<_> = generics
+            .params
+            .iter()
+            .map(|p| match p {
+                syn::GenericParam::Type(syn::TypeParam { ident, .. }) => quote! { #ident },
+                syn::GenericParam::Lifetime(syn::LifetimeParam { lifetime, .. }) => {
+                    quote! { #lifetime }
+                }
+                syn::GenericParam::Const(syn::ConstParam { ident, .. }) => quote! { #ident },
+            })
+            .collect();
 
-        let where_clauses: Vec<_> = generics.where_clause.iter().flat_map(|c| c.predicates.iter().map(|p| quote! { #p })).collect();
-        
-        let message_arms: Vec<_> = variants.iter().map(|v| {
-            let enum_name = &ident;
-            let variant_name = &v.ident;
-            let format = &v.message;
-            
-            quote! {
-                #enum_name::#variant_name { .. } => {
-                    write!(__formatter, #format)
+        let where_clauses: Vec<_> = generics
+            .where_clause
+            .iter()
+            .flat_map(|c| c.predicates.iter().map(|p| quote! { #p }))
+            .collect();
+
+        let message_arms: Vec<_> = variants
+            .iter()
+            .map(|v| {
+                let enum_name = &ident;
+                let variant_name = &v.ident;
+                let format = &v.message;
+
+                quote! {
+                    #enum_name::#variant_name { .. } => {
+                        write!(__formatter, #format)
+                    }
                 }
-            }
-        })
-        .collect();
-        let description_arms: Vec<_> = variants.iter().map(|v| {
-            let enum_name = &ident;
-            let variant_name = &v.ident;
-            
-            quote! {
-                #enum_name::#variant_name { .. } => stringify!(#enum_name::#variant_name),
-            }
-        }).collect();
-        
+            })
+            .collect();
+        let description_arms: Vec<_> = variants
+            .iter()
+            .map(|v| {
+                let enum_name = &ident;
+                let variant_name = &v.ident;
+
+                quote! {
+                    #enum_name::#variant_name { .. } => stringify!(#enum_name::#variant_name),
+                }
+            })
+            .collect();
+
         let error_response_arms:Vec<_> = variants.iter().map(|v| {
             let enum_name = &ident;
             let variant_name = &v.ident;
 
-            let error_code = format!("{}", variant_name);
-            let message = format!("{}", v.message);
+            let error_code = v.code.clone().unwrap_or_else(|| variant_name.to_string());
+            let message = v.message.to_string();
             
             quote! {
-                #enum_name::#variant_name { ref resource, ref request_id } => ::quick_xml::se::to_string(&s3_api::error::XmlError {
+                #enum_name::#variant_name { ref resource, ref request_id, .. } => ::quick_xml::se::to_string(&s3_api::error::XmlError {
                     code: #error_code.into(),
                     message: #message.into(),
                     resource: String::clone(&resource),
@@ -122,6 +173,38 @@ impl ToTokens for ErrorOpts {
                 }).expect("error should be serializable into xml"),
             }
         }).collect();
+        let request_id_arms: Vec<_> = variants
+            .iter()
+            .map(|v| {
+                let enum_name = &ident;
+                let variant_name = &v.ident;
+
+                quote! {
+                    #enum_name::#variant_name { ref request_id, ref resource, .. } => {
+                        // Only the XML body reports the resource, empty bodies still need it bound
+                        let _ = resource;
+                        String::clone(&request_id)
+                    }
+                }
+            })
+            .collect();
+        let headers_arms: Vec<_> = variants
+            .iter()
+            .map(|v| {
+                let enum_name = &ident;
+                let variant_name = &v.ident;
+
+                if v.has_headers() {
+                    quote! {
+                        #enum_name::#variant_name { ref headers, .. } => headers.as_slice(),
+                    }
+                } else {
+                    quote! {
+                        #enum_name::#variant_name { .. } => &[],
+                    }
+                }
+            })
+            .collect();
         let status_code_arms:Vec<_> = variants.iter().map(|v| {
             let enum_name = &ident;
             let variant_name = &v.ident;
@@ -132,6 +215,38 @@ impl ToTokens for ErrorOpts {
             }
         }).collect();
 
+        let request_id = quote! {
+            match *self {
+                #(#request_id_arms)*
+            }
+        };
+        let builder = quote! {
+            let mut __builder = ::actix_web::HttpResponse::build(self.status_code());
+            __builder.insert_header(("x-amz-request-id", #request_id));
+
+            let __headers: &[(&'static str, ::std::string::String)] = match *self {
+                #(#headers_arms)*
+            };
+            for (__name, __value) in __headers {
+                __builder.insert_header((*__name, __value.as_str()));
+            }
+        };
+        let error_response = if empty_body.is_present() {
+            quote! {
+                #builder
+                __builder.finish()
+            }
+        } else {
+            quote! {
+                #builder
+                __builder
+                    .insert_header(::actix_web::http::header::ContentType::xml())
+                    .body(match *self {
+                         #(#error_response_arms)*
+                    })
+            }
+        };
+
         tokens.extend(quote! {
             #[allow(single_use_lifetimes)]
             impl<#(#quoted_generics),*> ::core::fmt::Display for #ident<#(#generic_names),*>
@@ -172,11 +287,7 @@ impl ToTokens for ErrorOpts {
                 #(#where_clauses),*
             {
                 fn error_response(&self) -> ::actix_web::HttpResponse<::actix_web::body::BoxBody> {
-                    ::actix_web::HttpResponse::build(self.status_code())
-                        .insert_header(::actix_web::http::header::ContentType::xml())
-                        .body(match *self {
-                             #(#error_response_arms)*
-                        })
+                    #error_response
                 }
                         
                 fn status_code(&self) -> ::actix_web::http::StatusCode {
@@ -192,8 +303,8 @@ impl ToTokens for ErrorOpts {
 #[cfg(test)]
 mod tests {
     use darling::FromDeriveInput;
-    use syn::parse_str;
     use quote::quote;
+    use syn::parse_str;
 
     use crate::error::ErrorOpts;
 
@@ -209,8 +320,8 @@ mod tests {
         let receiver = ErrorOpts::from_derive_input(&parsed).unwrap();
         let tokens = quote!(#receiver);
 
-    println!(
-        r#"
+        println!(
+            r#"
 INPUT:
 
 {}
@@ -223,7 +334,57 @@ EMITS:
 
 {}
     "#,
-        input, receiver, tokens
-    );
+            input, receiver, tokens
+        );
+    }
+
+    #[test]
+    fn test_empty_body() {
+        let input = r#"#[derive(Error)]
+    #[error(empty_body)]
+    pub enum TestError {
+        #[error(message = "test", status_code = 404)]
+        Test { request_id: String, resource: String },
+    }"#;
+
+        let parsed = parse_str(input).unwrap();
+        let receiver = ErrorOpts::from_derive_input(&parsed).unwrap();
+        let tokens = quote!(#receiver).to_string();
+
+        assert!(receiver.empty_body.is_present());
+        assert!(!tokens.contains("XmlError"));
+    }
+
+    #[test]
+    fn test_headers() {
+        let input = r#"#[derive(Error)]
+    pub enum TestError {
+        #[error(message = "test", status_code = 404)]
+        Test { request_id: String, resource: String, headers: Vec<(&'static str, String)> },
+        #[error(message = "other", status_code = 500)]
+        Other { request_id: String, resource: String },
+    }"#;
+
+        let parsed = parse_str(input).unwrap();
+        let receiver = ErrorOpts::from_derive_input(&parsed).unwrap();
+        let tokens = quote!(#receiver).to_string();
+
+        assert!(tokens.contains("TestError :: Test { ref headers , .. } => headers . as_slice ()"));
+        assert!(tokens.contains("TestError :: Other { .. } => & []"));
+    }
+
+    #[test]
+    fn test_code_override() {
+        let input = r#"#[derive(Error)]
+    pub enum TestError {
+        #[error(code = "AccessDenied", message = "Request has expired", status_code = 403)]
+        RequestExpired { request_id: String, resource: String },
+    }"#;
+
+        let parsed = parse_str(input).unwrap();
+        let receiver = ErrorOpts::from_derive_input(&parsed).unwrap();
+        let tokens = quote!(#receiver).to_string();
+
+        assert!(tokens.contains(r#"code : "AccessDenied" . into ()"#));
     }
 }
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+
+/// The group of everyone, anonymous callers included
+pub const ALL_USERS: &str = "http://acs.amazonaws.com/groups/global/AllUsers";
+/// The group of every caller that signs its requests
+pub const AUTHENTICATED_USERS: &str = "http://acs.amazonaws.com/groups/global/AuthenticatedUsers";
+/// An ACL can't have more grants than this
+pub const MAX_GRANTS: usize = 100;
+
+/// Who a bucket or an object belongs to
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Owner {
+    pub id: String,
+    pub display_name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Permission {
+    FullControl,
+    Read,
+    Write,
+    /// Reading the ACL itself
+    ReadAcp,
+    /// Replacing the ACL itself
+    WriteAcp,
+}
+
+impl Permission {
+    pub fn parse(permission: &str) -> Option<Self> {
+        match permission {
+            "FULL_CONTROL" => Some(Permission::FullControl),
+            "READ" => Some(Permission::Read),
+            "WRITE" => Some(Permission::Write),
+            "READ_ACP" => Some(Permission::ReadAcp),
+            "WRITE_ACP" => Some(Permission::WriteAcp),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::FullControl => "FULL_CONTROL",
+            Permission::Read => "READ",
+            Permission::Write => "WRITE",
+            Permission::ReadAcp => "READ_ACP",
+            Permission::WriteAcp => "WRITE_ACP",
+        }
+    }
+
+    /// Whether granting this permission gives `permission`
+    pub fn includes(self, permission: Permission) -> bool {
+        self == Permission::FullControl || self == permission
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grantee {
+    CanonicalUser {
+        id: String,
+        display_name: Option<String>,
+    },
+    /// A predefined group such as [`ALL_USERS`], by its URI
+    Group { uri: String },
+}
+
+impl Grantee {
+    /// Whether the grantee covers a caller, `None` standing for anonymous callers
+    pub fn matches(&self, principal: Option<&str>) -> bool {
+        match self {
+            Grantee::CanonicalUser { id, .. } => principal == Some(id.as_str()),
+            Grantee::Group { uri } => {
+                uri == ALL_USERS || (uri == AUTHENTICATED_USERS && principal.is_some())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grant {
+    pub grantee: Grantee,
+    pub permission: Permission,
+}
+
+/// The access control list of a bucket or an object. Those that were never given one have an
+/// empty list, without an owner.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessControlList {
+    pub owner: Owner,
+    pub grants: Vec<Grant>,
+}
+
+impl AccessControlList {
+    /// The list giving its owner full control and nobody else any access
+    pub fn private(owner: Owner) -> Self {
+        AccessControlList {
+            grants: vec![Grant {
+                grantee: Grantee::CanonicalUser {
+                    id: owner.id.clone(),
+                    display_name: Some(owner.display_name.clone()),
+                },
+                permission: Permission::FullControl,
+            }],
+            owner,
+        }
+    }
+
+    /// Whether the list was never set
+    pub fn is_empty(&self) -> bool {
+        self.owner.id.is_empty() && self.grants.is_empty()
+    }
+
+    /// Whether a grant gives a caller a permission, `None` standing for anonymous callers
+    pub fn allows(&self, principal: Option<&str>, permission: Permission) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.permission.includes(permission) && grant.grantee.matches(principal))
+    }
+}
+
+/// The predefined ACLs a request can ask for with `x-amz-acl`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CannedAcl {
+    Private,
+    PublicRead,
+    PublicReadWrite,
+    AuthenticatedRead,
+    /// An object the bucket owner can read, which is the same as private on buckets
+    BucketOwnerRead,
+    /// An object the bucket owner has full control of, which is the same as private on buckets
+    BucketOwnerFullControl,
+}
+
+impl CannedAcl {
+    pub fn parse(acl: &str) -> Option<Self> {
+        match acl {
+            "private" => Some(CannedAcl::Private),
+            "public-read" => Some(CannedAcl::PublicRead),
+            "public-read-write" => Some(CannedAcl::PublicReadWrite),
+            "authenticated-read" => Some(CannedAcl::AuthenticatedRead),
+            "bucket-owner-read" => Some(CannedAcl::BucketOwnerRead),
+            "bucket-owner-full-control" => Some(CannedAcl::BucketOwnerFullControl),
+            _ => None,
+        }
+    }
+
+    /// The list it stands for on a bucket or an object owned by `owner`, in a bucket owned by
+    /// `bucket_owner`
+    pub fn acl(self, owner: Owner, bucket_owner: &Owner) -> AccessControlList {
+        let group = |uri: &str, permission| Grant {
+            grantee: Grantee::Group { uri: uri.into() },
+            permission,
+        };
+        let bucket_owner_grant = |permission| Grant {
+            grantee: Grantee::CanonicalUser {
+                id: bucket_owner.id.clone(),
+                display_name: Some(bucket_owner.display_name.clone()),
+            },
+            permission,
+        };
+        let is_bucket_owner = owner.id == bucket_owner.id;
+        let mut acl = AccessControlList::private(owner);
+
+        match self {
+            CannedAcl::Private => {}
+            CannedAcl::PublicRead => acl.grants.push(group(ALL_USERS, Permission::Read)),
+            CannedAcl::PublicReadWrite => acl.grants.extend([
+                group(ALL_USERS, Permission::Read),
+                group(ALL_USERS, Permission::Write),
+            ]),
+            CannedAcl::AuthenticatedRead => acl
+                .grants
+                .push(group(AUTHENTICATED_USERS, Permission::Read)),
+            CannedAcl::BucketOwnerRead if !is_bucket_owner => {
+                acl.grants.push(bucket_owner_grant(Permission::Read))
+            }
+            CannedAcl::BucketOwnerFullControl if !is_bucket_owner => {
+                acl.grants.push(bucket_owner_grant(Permission::FullControl))
+            }
+            CannedAcl::BucketOwnerRead | CannedAcl::BucketOwnerFullControl => {}
+        }
+
+        acl
+    }
+}
+
+/// Who owns the objects written to a bucket, and whether ACLs apply to them at all
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ObjectOwnership {
+    /// ACLs are disabled, and every object belongs to the bucket owner
+    BucketOwnerEnforced,
+    /// Objects written with the `bucket-owner-full-control` ACL belong to the bucket owner
+    BucketOwnerPreferred,
+    /// Objects belong to whoever wrote them, which is how buckets without ownership controls
+    /// behave
+    #[default]
+    ObjectWriter,
+}
+
+impl ObjectOwnership {
+    pub fn parse(ownership: &str) -> Option<Self> {
+        match ownership {
+            "BucketOwnerEnforced" => Some(ObjectOwnership::BucketOwnerEnforced),
+            "BucketOwnerPreferred" => Some(ObjectOwnership::BucketOwnerPreferred),
+            "ObjectWriter" => Some(ObjectOwnership::ObjectWriter),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ObjectOwnership::BucketOwnerEnforced => "BucketOwnerEnforced",
+            ObjectOwnership::BucketOwnerPreferred => "BucketOwnerPreferred",
+            ObjectOwnership::ObjectWriter => "ObjectWriter",
+        }
+    }
+
+    /// Whether ACLs are disabled, and only the bucket owner and the bucket policy decide on
+    /// access
+    pub fn acls_disabled(self) -> bool {
+        self == ObjectOwnership::BucketOwnerEnforced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(id: &str) -> Owner {
+        Owner {
+            id: id.into(),
+            display_name: id.into(),
+        }
+    }
+
+    #[test]
+    fn test_canned_acl() {
+        let acl = CannedAcl::PublicRead.acl(owner("alice"), &owner("alice"));
+
+        assert!(acl.allows(Some("alice"), Permission::WriteAcp));
+        assert!(acl.allows(None, Permission::Read));
+        assert!(acl.allows(Some("bob"), Permission::Read));
+        assert!(!acl.allows(None, Permission::Write));
+
+        let acl = CannedAcl::AuthenticatedRead.acl(owner("alice"), &owner("alice"));
+        assert!(acl.allows(Some("bob"), Permission::Read));
+        assert!(!acl.allows(None, Permission::Read));
+
+        let acl = CannedAcl::BucketOwnerFullControl.acl(owner("bob"), &owner("alice"));
+        assert!(acl.allows(Some("alice"), Permission::Write));
+        assert_eq!(acl.grants.len(), 2);
+        let acl = CannedAcl::BucketOwnerFullControl.acl(owner("alice"), &owner("alice"));
+        assert_eq!(acl, AccessControlList::private(owner("alice")));
+    }
+
+    #[test]
+    fn test_empty_acl() {
+        let acl = AccessControlList::default();
+
+        assert!(acl.is_empty());
+        assert!(!acl.allows(None, Permission::Read));
+        assert!(!AccessControlList::private(owner("alice")).is_empty());
+    }
+}
@@ -1,7 +1,52 @@
+use crate::acl::{AccessControlList, ObjectOwnership};
 use chrono::{DateTime, Utc};
 
 pub struct Bucket {
     pub name: String,
     pub region: String,
     pub creation_date: DateTime<Utc>,
+    pub versioning: VersioningStatus,
+}
+
+/// What a bucket is created with, all stored at once with the bucket
+#[derive(Clone, Debug, Default)]
+pub struct CreateBucketOptions {
+    /// The default region when not given
+    pub region: Option<String>,
+    /// The canonical id of the bucket's owner, by which buckets are listed. Buckets without one
+    /// are only listed to administrators.
+    pub owner_id: Option<String>,
+    pub acl: Option<AccessControlList>,
+    pub object_ownership: Option<ObjectOwnership>,
+}
+
+/// Whether a bucket keeps the versions of its objects. Buckets start out unversioned, and once
+/// versioning was enabled it can only be suspended again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersioningStatus {
+    #[default]
+    Unversioned,
+    Enabled,
+    /// New writes replace the `null` version, the versions kept so far stay
+    Suspended,
+}
+
+impl VersioningStatus {
+    /// Parse the status the way S3 writes it, an unversioned bucket having none
+    pub fn parse(status: Option<&str>) -> Option<Self> {
+        match status {
+            None => Some(VersioningStatus::Unversioned),
+            Some("Enabled") => Some(VersioningStatus::Enabled),
+            Some("Suspended") => Some(VersioningStatus::Suspended),
+            Some(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            VersioningStatus::Unversioned => None,
+            VersioningStatus::Enabled => Some("Enabled"),
+            VersioningStatus::Suspended => Some("Suspended"),
+        }
+    }
 }
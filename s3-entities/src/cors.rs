@@ -0,0 +1,145 @@
+/// A CORS configuration can't have more rules than this
+pub const MAX_CORS_RULES: usize = 100;
+pub const MAX_RULE_ID_LENGTH: usize = 255;
+/// The methods a CORS rule may allow
+pub const CORS_METHODS: [&str; 5] = ["GET", "PUT", "POST", "DELETE", "HEAD"];
+
+/// The rules by which browsers on other origins may reach a bucket, a bucket without any
+/// having no CORS configuration
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorsConfiguration {
+    pub rules: Vec<CorsRule>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorsRule {
+    pub id: Option<String>,
+    /// Origins the rule applies to, each of them allowing a single `*` wildcard
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    /// Headers a preflight request may ask for, each of them allowing a single `*` wildcard
+    pub allowed_headers: Vec<String>,
+    /// Response headers the browser lets scripts read
+    pub expose_headers: Vec<String>,
+    /// How long the browser may cache the answer to a preflight request
+    pub max_age_seconds: Option<u32>,
+}
+
+/// Whether a value matches a pattern holding at most one `*`, which stands for any run of
+/// characters
+pub fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            value.len() >= prefix.len() + suffix.len()
+                && value.starts_with(prefix)
+                && value.ends_with(suffix)
+        }
+        None => pattern == value,
+    }
+}
+
+impl CorsRule {
+    /// The allowed origin matching a request's `Origin`, if any
+    pub fn allowed_origin(&self, origin: &str) -> Option<&str> {
+        self.allowed_origins
+            .iter()
+            .find(|allowed| wildcard_matches(allowed, origin))
+            .map(String::as_str)
+    }
+
+    /// Whether the rule lets a request from `origin` use `method` and send `headers`, the
+    /// headers being compared regardless of case
+    pub fn matches(&self, origin: &str, method: &str, headers: &[String]) -> bool {
+        self.allowed_origin(origin).is_some()
+            && self.allowed_methods.iter().any(|allowed| allowed == method)
+            && headers.iter().all(|header| {
+                let header = header.to_ascii_lowercase();
+                self.allowed_headers
+                    .iter()
+                    .any(|allowed| wildcard_matches(&allowed.to_ascii_lowercase(), &header))
+            })
+    }
+}
+
+impl CorsConfiguration {
+    /// The first rule matching a request, which is the one S3 answers with
+    pub fn find_rule(&self, origin: &str, method: &str, headers: &[String]) -> Option<&CorsRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(origin, method, headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_matches() {
+        assert!(wildcard_matches("*", "https://example.com"));
+        assert!(wildcard_matches(
+            "https://*.example.com",
+            "https://app.example.com"
+        ));
+        assert!(!wildcard_matches(
+            "https://*.example.com",
+            "https://example.com"
+        ));
+        assert!(!wildcard_matches(
+            "https://*.example.com",
+            "http://app.example.com"
+        ));
+        assert!(wildcard_matches(
+            "https://example.com",
+            "https://example.com"
+        ));
+        assert!(!wildcard_matches(
+            "https://example.com",
+            "https://example.org"
+        ));
+    }
+
+    #[test]
+    fn test_find_rule() {
+        let configuration = CorsConfiguration {
+            rules: vec![
+                CorsRule {
+                    id: Some("uploads".into()),
+                    allowed_origins: vec!["https://*.example.com".into()],
+                    allowed_methods: vec!["PUT".into(), "POST".into()],
+                    allowed_headers: vec!["Content-*".into(), "x-amz-*".into()],
+                    ..Default::default()
+                },
+                CorsRule {
+                    id: Some("reads".into()),
+                    allowed_origins: vec!["*".into()],
+                    allowed_methods: vec!["GET".into()],
+                    ..Default::default()
+                },
+            ],
+        };
+        let find = |origin: &str, method: &str, headers: &[&str]| {
+            let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+            configuration
+                .find_rule(origin, method, &headers)
+                .and_then(|rule| rule.id.as_deref())
+        };
+
+        assert_eq!(
+            find(
+                "https://app.example.com",
+                "PUT",
+                &["content-type", "X-Amz-Date"]
+            ),
+            Some("uploads")
+        );
+        assert_eq!(
+            find("https://app.example.com", "PUT", &["authorization"]),
+            None
+        );
+        assert_eq!(find("https://app.example.org", "PUT", &[]), None);
+        assert_eq!(find("https://app.example.org", "GET", &[]), Some("reads"));
+        assert_eq!(find("https://app.example.org", "GET", &["range"]), None);
+        assert_eq!(find("https://app.example.com", "DELETE", &[]), None);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::acl::Owner;
+use chrono::{DateTime, Utc};
+
+/// A user can't have more access keys than this, which is enough to rotate one
+pub const MAX_ACCESS_KEYS: usize = 2;
+
+/// Someone who signs requests with the access keys they were given, and owns the buckets they
+/// create
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct User {
+    /// The canonical id buckets and objects are owned by, which unlike the name is never reused
+    pub id: String,
+    pub name: String,
+    pub creation_date: DateTime<Utc>,
+}
+
+impl User {
+    pub fn owner(&self) -> Owner {
+        Owner {
+            id: self.id.clone(),
+            display_name: self.name.clone(),
+        }
+    }
+}
+
+/// Whether an access key may sign requests. Keys are disabled rather than deleted while they're
+/// being rotated, so that they can be enabled again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccessKeyStatus {
+    #[default]
+    Active,
+    Inactive,
+}
+
+impl AccessKeyStatus {
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "Active" => Some(AccessKeyStatus::Active),
+            "Inactive" => Some(AccessKeyStatus::Inactive),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccessKeyStatus::Active => "Active",
+            AccessKeyStatus::Inactive => "Inactive",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessKey {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub user_name: String,
+    pub status: AccessKeyStatus,
+    pub creation_date: DateTime<Utc>,
+}
+
+/// A set of users the policies attached to it apply to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    pub creation_date: DateTime<Utc>,
+}
+
+/// Who an identity policy is attached to, by name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyHolder {
+    User(String),
+    Group(String),
+}
+
+/// A policy attached to a user or group, kept as the JSON document it was put as
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentityPolicy {
+    pub name: String,
+    pub document: String,
+}
+
+/// Temporary credentials issued to an access key, which act for it within the limits of their
+/// session policy until they expire
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// What requests signed with the temporary credentials have to carry along
+    pub session_token: String,
+    /// The long-term access key the session was issued to
+    pub parent_access_key_id: String,
+    /// The user of the parent access key, `None` for an administrator's
+    pub user_name: Option<String>,
+    pub session_name: String,
+    /// The JSON document of the session policy
+    pub policy: String,
+    pub expiration: DateTime<Utc>,
+}
@@ -1,3 +1,11 @@
+pub mod acl;
 pub mod bucket;
+pub mod cors;
+pub mod identity;
+pub mod lifecycle;
+pub mod list;
+pub mod multipart;
+pub mod object;
+pub mod policy;
 pub mod storage_provider;
 pub mod test;
@@ -0,0 +1,258 @@
+use crate::{
+    multipart::MultipartUpload,
+    object::{Object, Tags},
+};
+use chrono::{DateTime, Days, NaiveTime, Utc};
+
+/// A lifecycle configuration can't have more rules than this
+pub const MAX_LIFECYCLE_RULES: usize = 1000;
+pub const MAX_RULE_ID_LENGTH: usize = 255;
+
+/// The rules by which the objects of a bucket expire, a bucket without any having no lifecycle
+/// configuration
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LifecycleConfiguration {
+    pub rules: Vec<LifecycleRule>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LifecycleRule {
+    pub id: Option<String>,
+    /// Disabled rules are kept but never applied
+    pub enabled: bool,
+    pub filter: LifecycleFilter,
+    pub expiration: Option<Expiration>,
+    /// Days after a version stopped being the current one that it's deleted
+    pub noncurrent_days: Option<u32>,
+    /// Days after an upload was initiated that it's aborted if it wasn't completed
+    pub abort_incomplete_upload_days: Option<u32>,
+}
+
+/// Which objects a rule applies to, an empty filter matching all of them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LifecycleFilter {
+    pub prefix: String,
+    /// Tags an object needs to carry, all of them
+    pub tags: Tags,
+    pub object_size_greater_than: Option<u64>,
+    pub object_size_less_than: Option<u64>,
+}
+
+/// When the current versions of objects expire
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expiration {
+    /// Days after an object was written
+    Days(u32),
+    /// A date at midnight UTC
+    Date(DateTime<Utc>),
+    /// Remove delete markers that are all that's left of an object
+    ExpiredObjectDeleteMarker,
+}
+
+/// When something that happened at `since` is `days` old, which S3 rounds up to the next
+/// midnight UTC
+pub fn expiry_date(since: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    let due = since + Days::new(days.into());
+    let midnight = due.date_naive().and_time(NaiveTime::MIN).and_utc();
+
+    if midnight == due {
+        due
+    } else {
+        midnight + Days::new(1)
+    }
+}
+
+impl LifecycleFilter {
+    pub fn matches(&self, key: &str, size: u64, tags: &Tags) -> bool {
+        key.starts_with(&self.prefix)
+            && self
+                .tags
+                .iter()
+                .all(|(key, value)| tags.get(key) == Some(value))
+            && self
+                .object_size_greater_than
+                .is_none_or(|greater_than| size > greater_than)
+            && self
+                .object_size_less_than
+                .is_none_or(|less_than| size < less_than)
+    }
+}
+
+impl LifecycleRule {
+    /// Whether the current version of an object carrying `tags` has expired by `now`
+    pub fn expires_current(&self, object: &Object, tags: &Tags, now: DateTime<Utc>) -> bool {
+        let expired = match self.expiration {
+            Some(Expiration::Days(days)) => expiry_date(object.last_modified, days) <= now,
+            Some(Expiration::Date(date)) => date <= now,
+            Some(Expiration::ExpiredObjectDeleteMarker) | None => false,
+        };
+
+        self.enabled
+            && expired
+            && !object.delete_marker
+            && self.filter.matches(&object.key, object.size, tags)
+    }
+
+    /// Whether a version that stopped being the current one at `noncurrent_since` has expired
+    /// by `now`
+    pub fn expires_noncurrent(
+        &self,
+        object: &Object,
+        tags: &Tags,
+        noncurrent_since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let Some(days) = self.noncurrent_days else {
+            return false;
+        };
+
+        self.enabled
+            && expiry_date(noncurrent_since, days) <= now
+            && self.filter.matches(&object.key, object.size, tags)
+    }
+
+    /// Whether a delete marker is removed, which it only is once it's the only version left of
+    /// its object
+    pub fn removes_delete_marker(&self, marker: &Object) -> bool {
+        self.enabled
+            && self.expiration == Some(Expiration::ExpiredObjectDeleteMarker)
+            && marker.delete_marker
+            && self.filter.matches(&marker.key, 0, &Tags::new())
+    }
+
+    /// Whether an upload that wasn't completed is aborted by `now`. Uploads have no tags or
+    /// size yet, so only the prefix of the filter applies to them.
+    pub fn aborts_upload(&self, upload: &MultipartUpload, now: DateTime<Utc>) -> bool {
+        let Some(days) = self.abort_incomplete_upload_days else {
+            return false;
+        };
+
+        self.enabled
+            && expiry_date(upload.initiated, days) <= now
+            && upload.key.starts_with(&self.filter.prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectMetadata;
+
+    fn date(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date).unwrap().into()
+    }
+
+    fn object(key: &str, size: u64, last_modified: &str) -> Object {
+        Object {
+            key: key.into(),
+            size,
+            etag: String::new(),
+            metadata: ObjectMetadata::default(),
+            last_modified: date(last_modified),
+            version_id: None,
+            delete_marker: false,
+        }
+    }
+
+    #[test]
+    fn test_expiry_date() {
+        assert_eq!(
+            expiry_date(date("2026-01-01T10:30:00Z"), 1),
+            date("2026-01-03T00:00:00Z")
+        );
+        assert_eq!(
+            expiry_date(date("2026-01-01T00:00:00Z"), 30),
+            date("2026-01-31T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let tags: Tags = [("project".to_string(), "cat".to_string())].into();
+        let filter = LifecycleFilter {
+            prefix: "logs/".into(),
+            tags: tags.clone(),
+            object_size_greater_than: Some(10),
+            object_size_less_than: Some(100),
+        };
+
+        assert!(filter.matches("logs/a.log", 50, &tags));
+        assert!(!filter.matches("a.log", 50, &tags));
+        assert!(!filter.matches("logs/a.log", 50, &Tags::new()));
+        assert!(!filter.matches("logs/a.log", 10, &tags));
+        assert!(!filter.matches("logs/a.log", 100, &tags));
+        assert!(LifecycleFilter::default().matches("a.log", 0, &Tags::new()));
+    }
+
+    #[test]
+    fn test_expires_current() {
+        let rule = LifecycleRule {
+            enabled: true,
+            expiration: Some(Expiration::Days(1)),
+            ..Default::default()
+        };
+        let object = object("a.log", 1, "2026-01-01T10:30:00Z");
+
+        assert!(!rule.expires_current(&object, &Tags::new(), date("2026-01-02T23:59:59Z")));
+        assert!(rule.expires_current(&object, &Tags::new(), date("2026-01-03T00:00:00Z")));
+
+        let disabled = LifecycleRule {
+            enabled: false,
+            ..rule.clone()
+        };
+        assert!(!disabled.expires_current(&object, &Tags::new(), date("2027-01-01T00:00:00Z")));
+
+        let dated = LifecycleRule {
+            expiration: Some(Expiration::Date(date("2026-06-01T00:00:00Z"))),
+            ..rule
+        };
+        assert!(!dated.expires_current(&object, &Tags::new(), date("2026-05-31T00:00:00Z")));
+        assert!(dated.expires_current(&object, &Tags::new(), date("2026-06-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_expires_noncurrent() {
+        let rule = LifecycleRule {
+            enabled: true,
+            noncurrent_days: Some(2),
+            ..Default::default()
+        };
+        let object = object("a.log", 1, "2026-01-01T00:00:00Z");
+
+        // Counted from when the version was replaced rather than from when it was written
+        assert!(!rule.expires_noncurrent(
+            &object,
+            &Tags::new(),
+            date("2026-01-10T12:00:00Z"),
+            date("2026-01-12T12:00:00Z")
+        ));
+        assert!(rule.expires_noncurrent(
+            &object,
+            &Tags::new(),
+            date("2026-01-10T12:00:00Z"),
+            date("2026-01-13T00:00:00Z")
+        ));
+    }
+
+    #[test]
+    fn test_aborts_upload() {
+        let rule = LifecycleRule {
+            enabled: true,
+            filter: LifecycleFilter {
+                prefix: "logs/".into(),
+                ..Default::default()
+            },
+            abort_incomplete_upload_days: Some(7),
+            ..Default::default()
+        };
+        let upload = |key: &str| MultipartUpload {
+            upload_id: "upload".into(),
+            key: key.into(),
+            initiated: date("2026-01-01T00:00:00Z"),
+        };
+
+        assert!(rule.aborts_upload(&upload("logs/a.log"), date("2026-01-08T00:00:00Z")));
+        assert!(!rule.aborts_upload(&upload("logs/a.log"), date("2026-01-07T00:00:00Z")));
+        assert!(!rule.aborts_upload(&upload("a.log"), date("2026-01-08T00:00:00Z")));
+    }
+}
@@ -0,0 +1,151 @@
+/// The most entries a listing returns at once
+pub const MAX_KEYS: usize = 1000;
+
+/// One page of a listing, with the keys that share a prefix up to the delimiter rolled up into
+/// common prefixes
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub common_prefixes: Vec<String>,
+    pub is_truncated: bool,
+    /// The last key or common prefix on the page, to continue the listing from
+    pub next_marker: Option<String>,
+}
+
+/// Builds a [`Page`] out of entries pushed in key order
+pub struct PageBuilder<T> {
+    prefix: String,
+    delimiter: Option<String>,
+    max: usize,
+    last_prefix: Option<String>,
+    page: Page<T>,
+}
+
+impl<T> PageBuilder<T> {
+    /// A marker that's a common prefix was the end of a previous page, so the keys it rolls up
+    /// are skipped instead of being rolled up into it again
+    pub fn new(
+        prefix: Option<String>,
+        delimiter: Option<String>,
+        marker: Option<&str>,
+        max: usize,
+    ) -> Self {
+        let prefix = prefix.unwrap_or_default();
+        let delimiter = delimiter.filter(|delimiter| !delimiter.is_empty());
+        let last_prefix = match (marker, &delimiter) {
+            (Some(marker), Some(delimiter))
+                if marker.starts_with(&prefix) && marker.ends_with(delimiter.as_str()) =>
+            {
+                Some(marker.to_string())
+            }
+            _ => None,
+        };
+
+        PageBuilder {
+            prefix,
+            delimiter,
+            max: max.min(MAX_KEYS),
+            last_prefix,
+            page: Page {
+                items: Vec::new(),
+                common_prefixes: Vec::new(),
+                is_truncated: false,
+                next_marker: None,
+            },
+        }
+    }
+
+    /// The prefix up to and including the first delimiter after the listing's prefix
+    pub fn common_prefix(&self, key: &str) -> Option<String> {
+        let delimiter = self.delimiter.as_deref()?;
+        let rest = key.strip_prefix(&self.prefix)?;
+        let end = rest.find(delimiter)? + delimiter.len();
+
+        Some(format!("{}{}", self.prefix, &rest[..end]))
+    }
+
+    /// Add the next entry, returning `false` once the page is full and the listing should stop
+    pub fn push(&mut self, key: &str, item: T) -> bool {
+        let common_prefix = self.common_prefix(key);
+
+        if common_prefix.is_some() && common_prefix == self.last_prefix {
+            return true;
+        }
+        if self.page.items.len() + self.page.common_prefixes.len() >= self.max {
+            self.page.is_truncated = true;
+            return false;
+        }
+
+        match common_prefix {
+            Some(common_prefix) => {
+                self.page.next_marker = Some(common_prefix.clone());
+                self.page.common_prefixes.push(common_prefix.clone());
+                self.last_prefix = Some(common_prefix);
+            }
+            None => {
+                self.page.next_marker = Some(key.into());
+                self.page.items.push(item);
+            }
+        }
+
+        true
+    }
+
+    pub fn finish(self) -> Page<T> {
+        self.page
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(keys: &[&str], prefix: Option<&str>, marker: Option<&str>, max: usize) -> Page<String> {
+        let mut builder = PageBuilder::new(prefix.map(String::from), Some("/".into()), marker, max);
+
+        for key in keys {
+            if !builder.push(key, key.to_string()) {
+                break;
+            }
+        }
+
+        builder.finish()
+    }
+
+    #[test]
+    fn test_common_prefixes() {
+        let keys = [
+            "a.jpg",
+            "photos/2023/a.jpg",
+            "photos/2023/b.jpg",
+            "photos/c.jpg",
+            "z.jpg",
+        ];
+
+        let result = page(&keys, None, None, 1000);
+        assert_eq!(result.items, ["a.jpg", "z.jpg"]);
+        assert_eq!(result.common_prefixes, ["photos/"]);
+        assert!(!result.is_truncated);
+
+        let result = page(&keys[1..4], Some("photos/"), None, 1000);
+        assert_eq!(result.items, ["photos/c.jpg"]);
+        assert_eq!(result.common_prefixes, ["photos/2023/"]);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let keys = ["a.jpg", "photos/a.jpg", "photos/b.jpg", "z.jpg"];
+
+        let result = page(&keys, None, None, 2);
+        assert_eq!(result.items, ["a.jpg"]);
+        assert_eq!(result.common_prefixes, ["photos/"]);
+        assert_eq!(result.next_marker.as_deref(), Some("photos/"));
+        assert!(result.is_truncated);
+
+        // The keys under the common prefix the last page ended on aren't listed again
+        let result = page(&keys[1..], None, Some("photos/"), 2);
+        assert_eq!(result.items, ["z.jpg"]);
+        assert!(result.common_prefixes.is_empty());
+        assert!(!result.is_truncated);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::storage_provider::StorageErr;
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+
+/// Every part of a multipart upload but the last has to be at least this big
+pub const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+pub const MAX_PART_NUMBER: u16 = 10_000;
+
+#[derive(Clone, Debug)]
+pub struct MultipartUpload {
+    pub upload_id: String,
+    pub key: String,
+    pub initiated: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Part {
+    pub part_number: u16,
+    pub size: u64,
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Where a ListMultipartUploads request starts listing from and how it rolls up keys
+#[derive(Clone, Debug, Default)]
+pub struct ListUploadsOptions {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub key_marker: Option<String>,
+    /// Only used along with `key_marker`, to continue from within the uploads of a single key
+    pub upload_id_marker: Option<String>,
+    pub max_uploads: usize,
+}
+
+impl ListUploadsOptions {
+    /// Whether an upload comes after the markers, in order of key and then upload id
+    pub fn is_after_marker(&self, upload: &MultipartUpload) -> bool {
+        match (&self.key_marker, &self.upload_id_marker) {
+            (Some(key_marker), Some(upload_id_marker)) => {
+                (&upload.key, &upload.upload_id) > (key_marker, upload_id_marker)
+            }
+            (Some(key_marker), None) => &upload.key > key_marker,
+            (None, _) => true,
+        }
+    }
+}
+
+/// One page of the parts of an upload
+#[derive(Clone, Debug)]
+pub struct PartsPage {
+    pub parts: Vec<Part>,
+    pub is_truncated: bool,
+    pub next_part_number_marker: Option<u16>,
+}
+
+/// A part as it's listed in a CompleteMultipartUpload request
+#[derive(Clone, Debug)]
+pub struct CompletedPart {
+    pub part_number: u16,
+    pub etag: String,
+}
+
+/// Match the parts listed to complete an upload against the parts that were uploaded, checking
+/// them the way S3 does
+pub fn select_parts<'a>(
+    uploaded: &'a [Part],
+    completed: &[CompletedPart],
+) -> Result<Vec<&'a Part>, StorageErr> {
+    if completed
+        .windows(2)
+        .any(|pair| pair[0].part_number >= pair[1].part_number)
+    {
+        return Err(StorageErr::InvalidPartOrder);
+    }
+
+    let parts = completed
+        .iter()
+        .map(|completed| {
+            let etag = completed.etag.trim_matches('"');
+
+            uploaded
+                .iter()
+                .find(|part| {
+                    part.part_number == completed.part_number
+                        && part.etag.eq_ignore_ascii_case(etag)
+                })
+                .ok_or(StorageErr::InvalidPart)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some((_, rest)) = parts.split_last() {
+        if rest.iter().any(|part| part.size < MIN_PART_SIZE) {
+            return Err(StorageErr::EntityTooSmall);
+        }
+    }
+
+    Ok(parts)
+}
+
+/// The ETag of an object assembled from parts: the MD5 of the parts' binary MD5s, followed by
+/// the number of parts. Fails on a part whose ETag isn't an MD5.
+pub fn multipart_etag(parts: &[&Part]) -> Result<String, StorageErr> {
+    let mut hasher = Md5::new();
+
+    for part in parts {
+        let digest = hex::decode(&part.etag)
+            .ok()
+            .filter(|digest| digest.len() == Md5::output_size())
+            .ok_or_else(|| {
+                StorageErr::IOErr(format!("part ETag {} isn't an MD5", part.etag).into())
+            })?;
+        hasher.update(digest);
+    }
+
+    Ok(format!(
+        "{}-{}",
+        hex::encode(hasher.finalize()),
+        parts.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(part_number: u16, size: u64, data: &[u8]) -> Part {
+        Part {
+            part_number,
+            size,
+            etag: hex::encode(Md5::digest(data)),
+            last_modified: Utc::now(),
+        }
+    }
+
+    fn completed(part: &Part) -> CompletedPart {
+        CompletedPart {
+            part_number: part.part_number,
+            etag: format!("\"{}\"", part.etag),
+        }
+    }
+
+    #[test]
+    fn test_select_parts() {
+        let uploaded = vec![
+            part(1, MIN_PART_SIZE, b"a"),
+            part(2, MIN_PART_SIZE, b"b"),
+            part(3, 1, b"c"),
+        ];
+        let parts = select_parts(
+            &uploaded,
+            &[completed(&uploaded[0]), completed(&uploaded[2])],
+        )
+        .unwrap();
+
+        assert_eq!(
+            parts
+                .iter()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>(),
+            [1, 3]
+        );
+    }
+
+    #[test]
+    fn test_select_parts_errors() {
+        let uploaded = vec![part(1, 1, b"a"), part(2, 1, b"b")];
+
+        assert!(matches!(
+            select_parts(
+                &uploaded,
+                &[completed(&uploaded[1]), completed(&uploaded[0])]
+            ),
+            Err(StorageErr::InvalidPartOrder)
+        ));
+        assert!(matches!(
+            select_parts(
+                &uploaded,
+                &[CompletedPart {
+                    part_number: 1,
+                    etag: uploaded[1].etag.clone(),
+                }]
+            ),
+            Err(StorageErr::InvalidPart)
+        ));
+        assert!(matches!(
+            select_parts(
+                &uploaded,
+                &[completed(&uploaded[0]), completed(&uploaded[1])]
+            ),
+            Err(StorageErr::EntityTooSmall)
+        ));
+    }
+
+    #[test]
+    fn test_multipart_etag() {
+        let first = part(1, 1, b"a");
+        let second = part(2, 1, b"b");
+        let mut digests = Md5::digest(b"a").to_vec();
+        digests.extend(Md5::digest(b"b"));
+
+        assert_eq!(
+            multipart_etag(&[&first, &second]).unwrap(),
+            format!("{}-2", hex::encode(Md5::digest(&digests)))
+        );
+
+        let copied = Part {
+            etag: format!("{}-1", first.etag),
+            ..first.clone()
+        };
+        assert!(multipart_etag(&[&copied, &second]).is_err());
+    }
+}
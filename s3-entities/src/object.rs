@@ -0,0 +1,356 @@
+use crate::{acl::AccessControlList, storage_provider::StorageErr};
+use chrono::{DateTime, Utc};
+use std::{collections::BTreeMap, ops::Range};
+
+/// User-defined metadata can't be bigger than this, counting the bytes of its names and values
+pub const MAX_USER_METADATA_SIZE: usize = 2 * 1024;
+pub const MAX_TAGS: usize = 10;
+/// Tag keys and values are limited in characters rather than bytes
+pub const MAX_TAG_KEY_LENGTH: usize = 128;
+pub const MAX_TAG_VALUE_LENGTH: usize = 256;
+
+/// The version of objects written while versioning wasn't enabled, of which a key has at most
+/// one
+pub const NULL_VERSION_ID: &str = "null";
+
+/// The tags of an object, by their keys
+pub type Tags = BTreeMap<String, String>;
+
+/// Whether tags are within the limits S3 puts on them
+pub fn valid_tags(tags: &Tags) -> bool {
+    tags.len() <= MAX_TAGS
+        && tags.iter().all(|(key, value)| {
+            !key.is_empty()
+                && key.chars().count() <= MAX_TAG_KEY_LENGTH
+                && value.chars().count() <= MAX_TAG_VALUE_LENGTH
+        })
+}
+
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub key: String,
+    pub size: u64,
+    pub etag: String,
+    pub metadata: ObjectMetadata,
+    pub last_modified: DateTime<Utc>,
+    /// `None` in buckets that never had versioning enabled
+    pub version_id: Option<String>,
+    /// Whether this version marks the object deleted rather than holding any content
+    pub delete_marker: bool,
+}
+
+/// Pick the id of a new version of an object
+pub fn new_version_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// What deleting an object, or one of its versions, did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeletedObject {
+    /// The version that was deleted, or the delete marker that was created in its stead
+    pub version_id: Option<String>,
+    /// Whether a delete marker was created, or the deleted version was one
+    pub delete_marker: bool,
+}
+
+/// The headers stored along with an object and returned whenever it's read
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectMetadata {
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    pub expires: Option<String>,
+    /// `x-amz-meta-*` headers, by their lowercase name without the prefix
+    pub user: BTreeMap<String, String>,
+}
+
+impl ObjectMetadata {
+    /// The size of the user-defined metadata, as counted against [`MAX_USER_METADATA_SIZE`]
+    pub fn user_size(&self) -> usize {
+        self.user
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum()
+    }
+}
+
+/// What a write stores along with an object's body, all at once with the version it creates. A
+/// multipart upload keeps them until it's completed.
+#[derive(Clone, Debug, Default)]
+pub struct PutObjectOptions {
+    pub metadata: ObjectMetadata,
+    pub tags: Tags,
+    /// The ACL of the object, the default one granting its owner full control when not given
+    pub acl: Option<AccessControlList>,
+}
+
+impl From<ObjectMetadata> for PutObjectOptions {
+    fn from(metadata: ObjectMetadata) -> Self {
+        PutObjectOptions {
+            metadata,
+            ..Default::default()
+        }
+    }
+}
+
+/// What to list of a bucket's objects, starting after `marker`
+#[derive(Clone, Debug, Default)]
+pub struct ListObjectsOptions {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub marker: Option<String>,
+    pub max_keys: usize,
+}
+
+/// Where a ListObjectVersions request starts listing from and how it rolls up keys
+#[derive(Clone, Debug, Default)]
+pub struct ListVersionsOptions {
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub key_marker: Option<String>,
+    /// Only used along with `key_marker`, to continue from within the versions of a single key
+    pub version_id_marker: Option<String>,
+    pub max_keys: usize,
+}
+
+/// A version of an object as it's listed, which may be a delete marker
+#[derive(Clone, Debug)]
+pub struct ObjectVersion {
+    pub object: Object,
+    pub is_latest: bool,
+}
+
+/// The object a server-side copy reads from
+#[derive(Clone, Debug)]
+pub struct CopySource {
+    pub bucket: String,
+    pub key: String,
+    /// The version to copy, the current one when not given
+    pub version_id: Option<String>,
+}
+
+/// Where the metadata of a copied object comes from
+#[derive(Clone, Debug)]
+pub enum MetadataDirective {
+    /// Keep the source object's metadata
+    Copy,
+    /// Use the metadata sent along with the copy request instead
+    Replace(ObjectMetadata),
+}
+
+/// What a copy writes along with the object, and the conditions its source has to meet. They're
+/// all applied at once to the version the copy creates.
+#[derive(Clone, Debug)]
+pub struct CopyObjectOptions {
+    pub metadata: MetadataDirective,
+    /// The tags of the copy, those of its source when not given
+    pub tags: Option<Tags>,
+    /// The ACL of the copy, which never takes its source's along
+    pub acl: Option<AccessControlList>,
+    /// The copy fails with [`StorageErr::PreconditionFailed`] unless the source passes these
+    pub source_preconditions: Preconditions,
+}
+
+/// What a copy stored, along with the version of the source it read
+#[derive(Clone, Debug)]
+pub struct CopiedObject {
+    pub object: Object,
+    /// `None` when the source bucket never had versioning enabled
+    pub source_version_id: Option<String>,
+}
+
+/// Whether a conditional request should go ahead
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precondition {
+    Passed,
+    /// The object didn't change, which reads answer with 304 Not Modified
+    NotModified,
+    Failed,
+}
+
+/// The `If-*` headers of a conditional request, which only goes ahead when an object's ETag or
+/// modification date matches them
+#[derive(Clone, Debug, Default)]
+pub struct Preconditions {
+    pub if_match: Option<String>,
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<DateTime<Utc>>,
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+}
+
+impl Preconditions {
+    /// Evaluate the conditions against an object, in the order S3 does. A matching `If-Match`
+    /// overrides `If-Unmodified-Since`, and a present `If-None-Match` overrides
+    /// `If-Modified-Since`.
+    pub fn evaluate(&self, etag: &str, last_modified: &DateTime<Utc>) -> Precondition {
+        // Dates in headers only have second precision
+        let last_modified = last_modified.timestamp();
+
+        match (&self.if_match, &self.if_unmodified_since) {
+            (Some(if_match), _) if !etag_matches(if_match, etag) => return Precondition::Failed,
+            (None, Some(since)) if last_modified > since.timestamp() => {
+                return Precondition::Failed
+            }
+            _ => {}
+        }
+
+        match (&self.if_none_match, &self.if_modified_since) {
+            (Some(if_none_match), _) if etag_matches(if_none_match, etag) => {
+                Precondition::NotModified
+            }
+            (None, Some(since)) if last_modified <= since.timestamp() => Precondition::NotModified,
+            _ => Precondition::Passed,
+        }
+    }
+}
+
+/// Whether a list of ETags such as `"a", "b"` or `*` includes an object's ETag
+pub fn etag_matches(list: &str, etag: &str) -> bool {
+    list.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.trim_start_matches("W/").trim_matches('"') == etag
+    })
+}
+
+/// Whether a write may replace an object already stored under its key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteCondition {
+    #[default]
+    Overwrite,
+    /// `If-None-Match: *`, only write the object if there's none under the key yet
+    IfAbsent,
+}
+
+/// The bytes of an object a ranged read asks for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReadRange {
+    /// `bytes=first-last`, or `bytes=first-` when reading up to the end
+    Bytes { first: u64, last: Option<u64> },
+    /// `bytes=-length`, the last `length` bytes
+    Suffix(u64),
+    /// One part of an object completed from a multipart upload, counting from 1
+    Part(u16),
+}
+
+impl ReadRange {
+    /// Resolve the range against an object of `size` bytes uploaded as parts of `part_sizes`,
+    /// an object uploaded at once being a single part
+    pub fn resolve(&self, size: u64, part_sizes: &[u64]) -> Result<Range<u64>, StorageErr> {
+        match *self {
+            ReadRange::Bytes { first, .. } if first >= size => Err(StorageErr::InvalidRange),
+            ReadRange::Bytes { first, last } => {
+                Ok(first..last.map_or(size, |last| last.saturating_add(1).min(size)))
+            }
+            ReadRange::Suffix(length) if length == 0 || size == 0 => Err(StorageErr::InvalidRange),
+            ReadRange::Suffix(length) => Ok(size.saturating_sub(length)..size),
+            ReadRange::Part(part_number) => {
+                let single = [size];
+                let part_sizes = if part_sizes.is_empty() {
+                    &single
+                } else {
+                    part_sizes
+                };
+                let index = usize::from(part_number)
+                    .checked_sub(1)
+                    .filter(|&index| index < part_sizes.len())
+                    .ok_or(StorageErr::InvalidPartNumber)?;
+                let start: u64 = part_sizes[..index].iter().sum();
+
+                Ok(start..start + part_sizes[index])
+            }
+        }
+    }
+}
+
+/// The bytes of an object a copy or read covers, the whole object when no range was asked for
+pub fn resolve_range(range: Option<Range<u64>>, size: u64) -> Result<Range<u64>, StorageErr> {
+    match range {
+        Some(range) if range.start >= range.end || range.end > size => {
+            Err(StorageErr::InvalidRange)
+        }
+        Some(range) => Ok(range),
+        None => Ok(0..size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(resolve_range(None, 10).unwrap(), 0..10);
+        assert_eq!(resolve_range(Some(2..10), 10).unwrap(), 2..10);
+        assert!(matches!(
+            resolve_range(Some(2..11), 10),
+            Err(StorageErr::InvalidRange)
+        ));
+        assert!(matches!(
+            resolve_range(Some(0..1), 0),
+            Err(StorageErr::InvalidRange)
+        ));
+    }
+
+    #[test]
+    fn test_valid_tags() {
+        let tags = |tags: &[(&str, String)]| -> Tags {
+            tags.iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect()
+        };
+
+        assert!(valid_tags(&tags(&[("project", "cat".into())])));
+        assert!(valid_tags(&tags(&[("project", "".into())])));
+        assert!(valid_tags(&tags(&[(
+            "é".repeat(128).as_str(),
+            "é".repeat(256)
+        )])));
+        assert!(!valid_tags(&tags(&[("", "cat".into())])));
+        assert!(!valid_tags(&tags(&[(
+            "a".repeat(129).as_str(),
+            "cat".into()
+        )])));
+        assert!(!valid_tags(&tags(&[("project", "a".repeat(257))])));
+        assert!(!valid_tags(
+            &(0..11).map(|i| (i.to_string(), String::new())).collect()
+        ));
+    }
+
+    #[test]
+    fn test_read_range() {
+        let bytes = |first, last| ReadRange::Bytes { first, last };
+
+        assert_eq!(bytes(0, Some(3)).resolve(10, &[]).unwrap(), 0..4);
+        assert_eq!(bytes(5, Some(20)).resolve(10, &[]).unwrap(), 5..10);
+        assert_eq!(bytes(5, None).resolve(10, &[]).unwrap(), 5..10);
+        assert!(matches!(
+            bytes(10, None).resolve(10, &[]),
+            Err(StorageErr::InvalidRange)
+        ));
+        assert_eq!(ReadRange::Suffix(3).resolve(10, &[]).unwrap(), 7..10);
+        assert_eq!(ReadRange::Suffix(20).resolve(10, &[]).unwrap(), 0..10);
+        assert!(matches!(
+            ReadRange::Suffix(0).resolve(10, &[]),
+            Err(StorageErr::InvalidRange)
+        ));
+        assert!(matches!(
+            ReadRange::Suffix(3).resolve(0, &[]),
+            Err(StorageErr::InvalidRange)
+        ));
+    }
+
+    #[test]
+    fn test_read_part() {
+        assert_eq!(ReadRange::Part(1).resolve(10, &[]).unwrap(), 0..10);
+        assert_eq!(ReadRange::Part(2).resolve(10, &[6, 4]).unwrap(), 6..10);
+        assert!(matches!(
+            ReadRange::Part(2).resolve(10, &[]),
+            Err(StorageErr::InvalidPartNumber)
+        ));
+        assert!(matches!(
+            ReadRange::Part(0).resolve(10, &[6, 4]),
+            Err(StorageErr::InvalidPartNumber)
+        ));
+    }
+}
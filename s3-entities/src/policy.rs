@@ -0,0 +1,713 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, net::IpAddr};
+use thiserror::Error;
+
+/// A bucket policy can't be larger than this many bytes
+pub const MAX_POLICY_SIZE: usize = 20 * 1024;
+/// The versions of the policy language a document may declare
+const POLICY_VERSIONS: [&str; 2] = ["2012-10-17", "2008-10-17"];
+const ARN_PREFIX: &str = "arn:aws:s3:::";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("{0}")]
+pub struct PolicyError(String);
+
+/// An IAM-style policy document, as attached to a bucket or to a user or group
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    pub id: Option<String>,
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statement {
+    pub sid: Option<String>,
+    pub effect: Effect,
+    pub principal: Principal,
+    /// Actions such as `s3:GetObject`, which may hold `*` and `?` wildcards
+    pub actions: Vec<String>,
+    /// ARNs such as `arn:aws:s3:::bucket/logs/*`, which may hold `*` and `?` wildcards
+    pub resources: Vec<String>,
+    /// All of them have to hold for the statement to apply
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Principal {
+    /// Everyone, anonymous callers included. The statements of identity policies, which don't
+    /// name a principal, apply to whoever they're attached to.
+    Any,
+    /// The callers with these identifiers, `*` standing for everyone like [`Principal::Any`]
+    Aws(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    pub operator: ConditionOperator,
+    /// A key such as `aws:SourceIp`, compared regardless of case
+    pub key: String,
+    /// The condition holds when any of them matches
+    pub values: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConditionOperator {
+    StringEquals,
+    StringLike,
+    IpAddress,
+    Bool,
+    DateLessThan,
+}
+
+/// What a policy decided about a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// An explicit deny, which wins over any allow
+    Deny,
+    /// No statement applies to the request
+    NoMatch,
+}
+
+/// A request as a policy sees it
+#[derive(Clone, Debug, Default)]
+pub struct RequestContext {
+    /// Who signed the request, `None` for anonymous requests
+    pub principal: Option<String>,
+    pub action: String,
+    /// The ARN of the bucket or object the request is for
+    pub resource: String,
+    /// Values of the condition keys, such as `aws:SourceIp`, by their lowercased name
+    pub values: BTreeMap<String, String>,
+}
+
+/// A field holding either a single value or a list of them. Lists are tried first, since a
+/// single JSON value could be a list itself.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(one) => vec![one],
+            OneOrMany::Many(many) => many,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase", deny_unknown_fields)]
+struct PolicyDocument {
+    version: Option<String>,
+    id: Option<String>,
+    statement: OneOrMany<StatementDocument>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase", deny_unknown_fields)]
+struct StatementDocument {
+    sid: Option<String>,
+    effect: String,
+    principal: Option<PrincipalDocument>,
+    action: OneOrMany<String>,
+    resource: OneOrMany<String>,
+    #[serde(default)]
+    condition: BTreeMap<String, BTreeMap<String, OneOrMany<Value>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PrincipalDocument {
+    Any(String),
+    Aws {
+        #[serde(rename = "AWS")]
+        aws: OneOrMany<String>,
+    },
+}
+
+/// The ARN of a bucket
+pub fn bucket_arn(bucket: &str) -> String {
+    format!("{ARN_PREFIX}{bucket}")
+}
+
+/// The ARN of an object
+pub fn object_arn(bucket: &str, key: &str) -> String {
+    format!("{ARN_PREFIX}{bucket}/{key}")
+}
+
+/// Whether a value matches a pattern in which `*` stands for any run of characters and `?` for
+/// any single one
+pub fn glob_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // Where the last `*` was, and how much of the value it had taken then
+    let mut backtrack = None;
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match backtrack {
+                Some((star, taken)) => {
+                    backtrack = Some((star, taken + 1));
+                    p = star + 1;
+                    v = taken + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether an address falls within a CIDR block such as `192.0.2.0/24`, a bare address being a
+/// block of one
+fn ip_matches(cidr: &str, ip: IpAddr) -> bool {
+    let (network, prefix) = match cidr.split_once('/') {
+        Some((network, prefix)) => (network, prefix.parse().ok()),
+        None => (cidr, None),
+    };
+    let Ok(network) = network.parse::<IpAddr>() else {
+        return false;
+    };
+
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let prefix: u32 = prefix.unwrap_or(32).min(32);
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let prefix: u32 = prefix.unwrap_or(128).min(128);
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            date.parse()
+                .ok()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        })
+}
+
+impl ConditionOperator {
+    fn parse(operator: &str) -> Option<Self> {
+        match operator {
+            "StringEquals" => Some(ConditionOperator::StringEquals),
+            "StringLike" => Some(ConditionOperator::StringLike),
+            "IpAddress" => Some(ConditionOperator::IpAddress),
+            "Bool" => Some(ConditionOperator::Bool),
+            "DateLessThan" => Some(ConditionOperator::DateLessThan),
+            _ => None,
+        }
+    }
+
+    fn valid(self, value: &str) -> bool {
+        match self {
+            ConditionOperator::StringEquals | ConditionOperator::StringLike => true,
+            ConditionOperator::IpAddress => {
+                let (network, prefix) = match value.split_once('/') {
+                    Some((network, prefix)) => (network, Some(prefix)),
+                    None => (value, None),
+                };
+                network.parse::<IpAddr>().is_ok()
+                    && prefix.is_none_or(|prefix| prefix.parse::<u8>().is_ok())
+            }
+            ConditionOperator::Bool => matches!(value, "true" | "false"),
+            ConditionOperator::DateLessThan => parse_date(value).is_some(),
+        }
+    }
+
+    fn matches(self, expected: &str, actual: &str) -> bool {
+        match self {
+            ConditionOperator::StringEquals => expected == actual,
+            ConditionOperator::StringLike => glob_matches(expected, actual),
+            ConditionOperator::IpAddress => actual.parse().is_ok_and(|ip| ip_matches(expected, ip)),
+            ConditionOperator::Bool => expected.eq_ignore_ascii_case(actual),
+            ConditionOperator::DateLessThan => match (parse_date(actual), parse_date(expected)) {
+                (Some(actual), Some(expected)) => actual < expected,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Condition {
+    /// Whether the condition holds for a request, which it doesn't when the request has no
+    /// value for its key
+    pub fn matches(&self, request: &RequestContext) -> bool {
+        let Some(actual) = request.values.get(&self.key) else {
+            return false;
+        };
+
+        self.values
+            .iter()
+            .any(|expected| self.operator.matches(expected, actual))
+    }
+}
+
+impl Principal {
+    pub fn matches(&self, principal: Option<&str>) -> bool {
+        match (self, principal) {
+            (Principal::Any, _) => true,
+            (Principal::Aws(ids), Some(principal)) => {
+                ids.iter().any(|id| id == "*" || id == principal)
+            }
+            (Principal::Aws(ids), None) => ids.iter().any(|id| id == "*"),
+        }
+    }
+}
+
+impl Statement {
+    pub fn applies(&self, request: &RequestContext) -> bool {
+        self.principal.matches(request.principal.as_deref())
+            && self.actions.iter().any(|action| {
+                glob_matches(
+                    &action.to_ascii_lowercase(),
+                    &request.action.to_ascii_lowercase(),
+                )
+            })
+            && self
+                .resources
+                .iter()
+                .any(|resource| glob_matches(resource, &request.resource))
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(request))
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> PolicyError {
+    PolicyError(reason.into())
+}
+
+fn condition_value(value: Value) -> Result<String, PolicyError> {
+    match value {
+        Value::String(value) => Ok(value),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Number(value) => Ok(value.to_string()),
+        _ => Err(invalid("Condition values must be strings")),
+    }
+}
+
+/// Decide on a request subject to several policies, an explicit deny in any of them winning over
+/// the allows of the others
+pub fn evaluate<'a>(
+    policies: impl IntoIterator<Item = &'a Policy>,
+    request: &RequestContext,
+) -> Decision {
+    let mut decision = Decision::NoMatch;
+
+    for policy in policies {
+        match policy.evaluate(request) {
+            Decision::Deny => return Decision::Deny,
+            Decision::Allow => decision = Decision::Allow,
+            Decision::NoMatch => {}
+        }
+    }
+
+    decision
+}
+
+impl Policy {
+    /// Parse the policy of a bucket, checking that it only refers to the bucket and its objects
+    pub fn parse(document: &str, bucket: &str) -> Result<Policy, PolicyError> {
+        Policy::parse_document(document, Some(bucket))
+    }
+
+    /// Parse a policy attached to a user or group, which has no principal and may refer to any
+    /// bucket
+    pub fn parse_identity(document: &str) -> Result<Policy, PolicyError> {
+        Policy::parse_document(document, None)
+    }
+
+    fn parse_document(document: &str, bucket: Option<&str>) -> Result<Policy, PolicyError> {
+        if document.len() > MAX_POLICY_SIZE {
+            return Err(invalid("Policies must be less than 20 KB"));
+        }
+        let document: PolicyDocument =
+            serde_json::from_str(document).map_err(|err| invalid(err.to_string()))?;
+        if document
+            .version
+            .as_deref()
+            .is_some_and(|version| !POLICY_VERSIONS.contains(&version))
+        {
+            return Err(invalid("The policy must contain a valid version string"));
+        }
+
+        let statements = document.statement.into_vec();
+        if statements.is_empty() {
+            return Err(invalid("Missing required field Statement"));
+        }
+
+        let statements = statements
+            .into_iter()
+            .map(|statement| Statement::parse(statement, bucket))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Policy {
+            id: document.id,
+            statements,
+        })
+    }
+
+    /// Decide on a request, an explicit deny in any statement winning over the allows
+    pub fn evaluate(&self, request: &RequestContext) -> Decision {
+        let mut decision = Decision::NoMatch;
+
+        for statement in self
+            .statements
+            .iter()
+            .filter(|statement| statement.applies(request))
+        {
+            match statement.effect {
+                Effect::Deny => return Decision::Deny,
+                Effect::Allow => decision = Decision::Allow,
+            }
+        }
+
+        decision
+    }
+}
+
+impl Statement {
+    /// Parse a statement of the policy of a bucket, or of an identity policy when there's no
+    /// bucket
+    fn parse(statement: StatementDocument, bucket: Option<&str>) -> Result<Statement, PolicyError> {
+        let effect = match statement.effect.as_str() {
+            "Allow" => Effect::Allow,
+            "Deny" => Effect::Deny,
+            _ => return Err(invalid("Invalid effect")),
+        };
+        let principal = match (statement.principal, bucket) {
+            (Some(PrincipalDocument::Any(any)), Some(_)) if any == "*" => Principal::Any,
+            (Some(PrincipalDocument::Aws { aws }), Some(_)) => Principal::Aws(aws.into_vec()),
+            (None, None) => Principal::Any,
+            (None, Some(_)) => return Err(invalid("Missing required field Principal")),
+            (Some(_), _) => return Err(invalid("Invalid principal in policy")),
+        };
+
+        let actions = statement.action.into_vec();
+        if actions.is_empty()
+            || actions
+                .iter()
+                .any(|action| action != "*" && !action.to_ascii_lowercase().starts_with("s3:"))
+        {
+            return Err(invalid("Policy has invalid action"));
+        }
+
+        // Only the bucket itself and its objects can be governed by its policy, while identity
+        // policies can refer to any bucket
+        let resources = statement.resource.into_vec();
+        let valid_resource = |resource: &String| match bucket {
+            Some(bucket) => {
+                let bucket_arn = bucket_arn(bucket);
+                resource == &bucket_arn || resource.starts_with(&format!("{bucket_arn}/"))
+            }
+            None => resource == "*" || resource.starts_with(ARN_PREFIX),
+        };
+        if resources.is_empty() || !resources.iter().all(valid_resource) {
+            return Err(invalid("Policy has invalid resource"));
+        }
+
+        let mut conditions = Vec::new();
+        for (operator, keys) in statement.condition {
+            let operator = ConditionOperator::parse(&operator)
+                .ok_or_else(|| invalid(format!("Invalid Condition type : {operator}")))?;
+            for (key, values) in keys {
+                let values = values
+                    .into_vec()
+                    .into_iter()
+                    .map(condition_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if values.iter().any(|value| !operator.valid(value)) {
+                    return Err(invalid(format!("Invalid Condition value for {key}")));
+                }
+
+                conditions.push(Condition {
+                    operator,
+                    key: key.to_ascii_lowercase(),
+                    values,
+                });
+            }
+        }
+
+        Ok(Statement {
+            sid: statement.sid,
+            effect,
+            principal,
+            actions,
+            resources,
+            conditions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(principal: Option<&str>, action: &str, key: &str) -> RequestContext {
+        RequestContext {
+            principal: principal.map(String::from),
+            action: action.into(),
+            resource: object_arn("bucket", key),
+            values: [
+                ("aws:sourceip".to_string(), "192.0.2.10".to_string()),
+                ("aws:securetransport".to_string(), "false".to_string()),
+                (
+                    "aws:currenttime".to_string(),
+                    "2026-06-01T00:00:00Z".to_string(),
+                ),
+            ]
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("s3:Get*", "s3:GetObject"));
+        assert!(glob_matches("s3:*Object", "s3:PutObject"));
+        assert!(!glob_matches("s3:*Object", "s3:PutObjectTagging"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(glob_matches("ca?.jpg", "cat.jpg"));
+        assert!(!glob_matches("ca?.jpg", "ca.jpg"));
+    }
+
+    #[test]
+    fn test_ip_matches() {
+        assert!(ip_matches("192.0.2.0/24", "192.0.2.10".parse().unwrap()));
+        assert!(!ip_matches("192.0.2.0/24", "192.0.3.10".parse().unwrap()));
+        assert!(ip_matches("0.0.0.0/0", "203.0.113.1".parse().unwrap()));
+        assert!(ip_matches("192.0.2.10", "192.0.2.10".parse().unwrap()));
+        assert!(ip_matches("2001:db8::/32", "2001:db8::1".parse().unwrap()));
+        assert!(!ip_matches("2001:db8::/32", "192.0.2.10".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let policy = Policy::parse(
+            r#"{
+                "Version": "2012-10-17",
+                "Statement": [
+                    {
+                        "Sid": "PublicReads",
+                        "Effect": "Allow",
+                        "Principal": "*",
+                        "Action": ["s3:GetObject", "s3:ListBucket"],
+                        "Resource": "arn:aws:s3:::bucket/public/*"
+                    },
+                    {
+                        "Effect": "Deny",
+                        "Principal": {"AWS": "*"},
+                        "Action": "s3:Delete*",
+                        "Resource": "arn:aws:s3:::bucket/archive/*"
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Principal": {"AWS": ["AKIDEXAMPLE"]},
+                        "Action": "s3:*",
+                        "Resource": "arn:aws:s3:::bucket/*",
+                        "Condition": {
+                            "IpAddress": {"aws:SourceIp": "192.0.2.0/24"},
+                            "StringLike": {"s3:prefix": "home/*"}
+                        }
+                    }
+                ]
+            }"#,
+            "bucket",
+        )
+        .unwrap();
+
+        let evaluate = |request: &RequestContext| policy.evaluate(request);
+        assert_eq!(
+            evaluate(&request(None, "s3:GetObject", "public/cat.jpg")),
+            Decision::Allow
+        );
+        assert_eq!(
+            evaluate(&request(None, "s3:GetObject", "private/cat.jpg")),
+            Decision::NoMatch
+        );
+        assert_eq!(
+            evaluate(&request(
+                Some("AKIDEXAMPLE"),
+                "s3:DeleteObject",
+                "archive/cat.jpg"
+            )),
+            Decision::Deny
+        );
+        // `{"AWS": "*"}` covers anonymous callers too
+        assert_eq!(
+            evaluate(&request(None, "s3:DeleteObject", "archive/cat.jpg")),
+            Decision::Deny
+        );
+        assert_eq!(
+            evaluate(&request(None, "s3:PutObject", "home/cat.jpg")),
+            Decision::NoMatch
+        );
+
+        let mut listing = request(Some("AKIDEXAMPLE"), "s3:ListBucket", "x");
+        assert_eq!(evaluate(&listing), Decision::NoMatch);
+        listing
+            .values
+            .insert("s3:prefix".into(), "home/cat/".into());
+        assert_eq!(evaluate(&listing), Decision::Allow);
+        listing
+            .values
+            .insert("aws:sourceip".into(), "203.0.113.1".into());
+        assert_eq!(evaluate(&listing), Decision::NoMatch);
+    }
+
+    #[test]
+    fn test_conditions() {
+        let policy = |condition: &str| {
+            Policy::parse(
+                &format!(
+                    r#"{{"Statement": {{
+                        "Effect": "Deny",
+                        "Principal": "*",
+                        "Action": "s3:*",
+                        "Resource": "arn:aws:s3:::bucket/*",
+                        "Condition": {condition}
+                    }}}}"#
+                ),
+                "bucket",
+            )
+            .unwrap()
+        };
+        let request = request(None, "s3:GetObject", "cat.jpg");
+
+        for (condition, decision) in [
+            (
+                r#"{"Bool": {"aws:SecureTransport": false}}"#,
+                Decision::Deny,
+            ),
+            (
+                r#"{"Bool": {"aws:SecureTransport": "true"}}"#,
+                Decision::NoMatch,
+            ),
+            (
+                r#"{"DateLessThan": {"aws:CurrentTime": "2026-07-01T00:00:00Z"}}"#,
+                Decision::Deny,
+            ),
+            (
+                r#"{"DateLessThan": {"aws:CurrentTime": "2026-05-01T00:00:00Z"}}"#,
+                Decision::NoMatch,
+            ),
+            (
+                r#"{"StringEquals": {"aws:SourceIp": ["192.0.2.1", "192.0.2.10"]}}"#,
+                Decision::Deny,
+            ),
+            (
+                r#"{"StringEquals": {"aws:Referer": "https://example.com"}}"#,
+                Decision::NoMatch,
+            ),
+        ] {
+            assert_eq!(
+                policy(condition).evaluate(&request),
+                decision,
+                "{condition}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_identity_policy() {
+        let policy = Policy::parse_identity(
+            r#"{
+                "Version": "2012-10-17",
+                "Statement": [
+                    {
+                        "Effect": "Allow",
+                        "Action": "s3:*",
+                        "Resource": ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"]
+                    },
+                    {
+                        "Effect": "Deny",
+                        "Action": "s3:DeleteObject",
+                        "Resource": "*"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let bucket_policy = Policy::parse(
+            r#"{"Statement": {
+                "Effect": "Deny",
+                "Principal": {"AWS": "AIDAALICE"},
+                "Action": "s3:PutObject",
+                "Resource": "arn:aws:s3:::bucket/logs/*"
+            }}"#,
+            "bucket",
+        )
+        .unwrap();
+        let policies = [policy, bucket_policy];
+
+        for (action, key, decision) in [
+            ("s3:GetObject", "cat.jpg", Decision::Allow),
+            ("s3:DeleteObject", "cat.jpg", Decision::Deny),
+            ("s3:PutObject", "cat.jpg", Decision::Allow),
+            ("s3:PutObject", "logs/today", Decision::Deny),
+        ] {
+            let request = request(Some("AIDAALICE"), action, key);
+            assert_eq!(evaluate(&policies, &request), decision, "{action} {key}");
+        }
+        let mut elsewhere = request(Some("AIDAALICE"), "s3:GetObject", "cat.jpg");
+        elsewhere.resource = object_arn("other", "cat.jpg");
+        assert_eq!(evaluate(&policies, &elsewhere), Decision::NoMatch);
+
+        for document in [
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "*"}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Action": "s3:*", "Resource": "arn:aws:iam::user/*"}}"#,
+        ] {
+            assert!(Policy::parse_identity(document).is_err(), "{document}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_policy() {
+        for document in [
+            "not json",
+            r#"{"Statement": []}"#,
+            r#"{"Version": "2020-01-01", "Statement": {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket"}}"#,
+            r#"{"Statement": {"Effect": "Maybe", "Principal": "*", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket"}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "Action": "ec2:*", "Resource": "arn:aws:s3:::bucket"}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "arn:aws:s3:::other/*"}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket", "Condition": {"NumericLessThan": {"s3:max-keys": "10"}}}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket", "Condition": {"IpAddress": {"aws:SourceIp": "nowhere"}}}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Principal": "*", "NotAction": "s3:*", "Resource": "arn:aws:s3:::bucket"}}"#,
+            r#"{"Statement": {"Effect": "Allow", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket"}}"#,
+        ] {
+            assert!(Policy::parse(document, "bucket").is_err(), "{document}");
+        }
+    }
+}
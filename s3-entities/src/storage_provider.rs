@@ -1,20 +1,283 @@
-use super::bucket::Bucket;
+use super::{
+    acl::{AccessControlList, ObjectOwnership},
+    bucket::{Bucket, CreateBucketOptions, VersioningStatus},
+    cors::CorsConfiguration,
+    identity::{AccessKey, AccessKeyStatus, Group, IdentityPolicy, PolicyHolder, Session, User},
+    lifecycle::LifecycleConfiguration,
+    list::Page,
+    multipart::{CompletedPart, ListUploadsOptions, MultipartUpload, Part, PartsPage},
+    object::{
+        CopiedObject, CopyObjectOptions, CopySource, DeletedObject, ListObjectsOptions,
+        ListVersionsOptions, Object, ObjectVersion, PutObjectOptions, ReadRange, Tags,
+        WriteCondition,
+    },
+};
 use async_trait::async_trait;
-use std::error;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use std::{error, ops::Range, pin::Pin};
 use thiserror::Error;
 
+/// A body that's passed through chunk by chunk instead of being buffered in memory.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, StorageErr>> + Send>>;
+
 #[async_trait]
 pub trait StorageProvider: Send + Sync {
-    async fn list_buckets(&self) -> Result<Vec<Bucket>, StorageErr>;
-    async fn create_bucket(&self, name: &str, region: Option<String>) -> Result<(), StorageErr>;
-    async fn head_bucket(&self, name: &str) -> Result<(), StorageErr>;
+    /// List every bucket, or only those of the owner with the given canonical id
+    async fn list_buckets(&self, owner_id: Option<&str>) -> Result<Vec<Bucket>, StorageErr>;
+    async fn create_bucket(
+        &self,
+        name: &str,
+        options: CreateBucketOptions,
+    ) -> Result<(), StorageErr>;
+    async fn head_bucket(&self, name: &str) -> Result<Bucket, StorageErr>;
     async fn delete_bucket(&self, name: &str) -> Result<(), StorageErr>;
-    async fn delete_object(&self, bucket_name: &str, object: &str) -> Result<(), StorageErr>;
+    async fn put_bucket_versioning(
+        &self,
+        name: &str,
+        status: VersioningStatus,
+    ) -> Result<(), StorageErr>;
+    /// The lifecycle rules of a bucket, failing with
+    /// [`StorageErr::NoSuchLifecycleConfiguration`] if it has none
+    async fn get_bucket_lifecycle(&self, name: &str) -> Result<LifecycleConfiguration, StorageErr>;
+    /// Replace the lifecycle rules of a bucket, removing them when given none
+    async fn put_bucket_lifecycle(
+        &self,
+        name: &str,
+        configuration: LifecycleConfiguration,
+    ) -> Result<(), StorageErr>;
+    /// The CORS rules of a bucket, failing with [`StorageErr::NoSuchCORSConfiguration`] if it
+    /// has none
+    async fn get_bucket_cors(&self, name: &str) -> Result<CorsConfiguration, StorageErr>;
+    /// Replace the CORS rules of a bucket, removing them when given none
+    async fn put_bucket_cors(
+        &self,
+        name: &str,
+        configuration: CorsConfiguration,
+    ) -> Result<(), StorageErr>;
+    /// The policy document of a bucket, failing with [`StorageErr::NoSuchBucketPolicy`] if it
+    /// has none
+    async fn get_bucket_policy(&self, name: &str) -> Result<String, StorageErr>;
+    /// Replace the policy document of a bucket, removing it when given none
+    async fn put_bucket_policy(&self, name: &str, policy: Option<String>)
+        -> Result<(), StorageErr>;
+    /// The ACL of a bucket, which is empty if it was never given one
+    async fn get_bucket_acl(&self, name: &str) -> Result<AccessControlList, StorageErr>;
+    async fn put_bucket_acl(&self, name: &str, acl: AccessControlList) -> Result<(), StorageErr>;
+    /// The object ownership of a bucket, failing with [`StorageErr::NoSuchOwnershipControls`]
+    /// if it has none
+    async fn get_bucket_ownership_controls(
+        &self,
+        name: &str,
+    ) -> Result<ObjectOwnership, StorageErr>;
+    /// Replace the object ownership of a bucket, removing it when given none
+    async fn put_bucket_ownership_controls(
+        &self,
+        name: &str,
+        ownership: Option<ObjectOwnership>,
+    ) -> Result<(), StorageErr>;
+    async fn list_objects(
+        &self,
+        bucket_name: &str,
+        options: ListObjectsOptions,
+    ) -> Result<Page<Object>, StorageErr>;
+    /// List every version of the objects in a bucket, delete markers included, in order of key
+    /// and then newest first
+    async fn list_object_versions(
+        &self,
+        bucket_name: &str,
+        options: ListVersionsOptions,
+    ) -> Result<Page<ObjectVersion>, StorageErr>;
+    /// Store an object along with its metadata, tags and ACL, which are written in one go with
+    /// the version they belong to
+    async fn put_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        options: PutObjectOptions,
+        body: ByteStream,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr>;
+    /// Read an object, or one of its versions. Reading a delete marker fails with
+    /// [`StorageErr::DeleteMarker`].
+    async fn get_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<(Object, ByteStream), StorageErr>;
+    /// Read the bytes of an object within a range, returning the resolved range along with them
+    async fn get_object_range(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+        range: &ReadRange,
+    ) -> Result<(Object, Range<u64>, ByteStream), StorageErr>;
+    async fn head_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Object, StorageErr>;
+    async fn get_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Tags, StorageErr>;
+    /// Replace all the tags of an object, removing them when given none
+    async fn put_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        tags: Tags,
+    ) -> Result<(), StorageErr>;
+    /// The ACL of an object, which is empty if it was never given one
+    async fn get_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<AccessControlList, StorageErr>;
+    /// Replace the ACL of the current version of an object
+    async fn put_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        acl: AccessControlList,
+    ) -> Result<(), StorageErr>;
+    /// Copy an existing object to a key, without reading its body where the backend allows it.
+    /// The source's preconditions are checked against the very version that's copied, and the
+    /// copy is stored with its tags and ACL in one go.
+    async fn copy_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        source: &CopySource,
+        options: CopyObjectOptions,
+    ) -> Result<CopiedObject, StorageErr>;
+    /// Delete an object, which in a versioned bucket only hides it behind a delete marker, or
+    /// permanently delete one of its versions
+    async fn delete_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<DeletedObject, StorageErr>;
+    /// Delete objects given by their keys and optionally versions, as [`Self::delete_object`]
+    /// does each of them
     async fn delete_objects(
         &self,
         bucket_name: &str,
-        objects: Vec<String>,
-    ) -> Vec<Result<(), StorageErr>>;
+        objects: Vec<(String, Option<String>)>,
+    ) -> Vec<Result<DeletedObject, StorageErr>>;
+    /// Start an upload, which keeps the metadata, tags and ACL of the object until it's completed
+    async fn create_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        options: PutObjectOptions,
+    ) -> Result<MultipartUpload, StorageErr>;
+    async fn upload_part(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        body: ByteStream,
+    ) -> Result<Part, StorageErr>;
+    /// Copy an existing object, or a range of its bytes, into a part of an upload
+    async fn upload_part_copy(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        source: &CopySource,
+        range: Option<Range<u64>>,
+    ) -> Result<Part, StorageErr>;
+    async fn complete_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr>;
+    async fn abort_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<(), StorageErr>;
+    async fn list_multipart_uploads(
+        &self,
+        bucket_name: &str,
+        options: ListUploadsOptions,
+    ) -> Result<Page<MultipartUpload>, StorageErr>;
+    async fn list_parts(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number_marker: Option<u16>,
+        max_parts: usize,
+    ) -> Result<PartsPage, StorageErr>;
+    /// Add a user, failing with [`StorageErr::UserAlreadyExists`] if its name is taken
+    async fn create_user(&self, user: User) -> Result<(), StorageErr>;
+    async fn get_user(&self, name: &str) -> Result<User, StorageErr>;
+    async fn list_users(&self) -> Result<Vec<User>, StorageErr>;
+    /// Delete a user along with its access keys, policies and sessions, taking it out of its
+    /// groups. The buckets it owns are left as they are.
+    async fn delete_user(&self, name: &str) -> Result<(), StorageErr>;
+    /// Give a user an access key, failing with [`StorageErr::UserNotFound`] if it doesn't exist
+    async fn create_access_key(&self, access_key: AccessKey) -> Result<(), StorageErr>;
+    async fn get_access_key(&self, access_key_id: &str) -> Result<AccessKey, StorageErr>;
+    /// The access keys of a user, oldest first
+    async fn list_access_keys(&self, user_name: &str) -> Result<Vec<AccessKey>, StorageErr>;
+    async fn update_access_key(
+        &self,
+        access_key_id: &str,
+        status: AccessKeyStatus,
+    ) -> Result<(), StorageErr>;
+    async fn delete_access_key(&self, access_key_id: &str) -> Result<(), StorageErr>;
+    /// Add a group, failing with [`StorageErr::GroupAlreadyExists`] if its name is taken
+    async fn create_group(&self, group: Group) -> Result<(), StorageErr>;
+    async fn list_groups(&self) -> Result<Vec<Group>, StorageErr>;
+    /// Delete a group along with its policies. Its members are left without it.
+    async fn delete_group(&self, name: &str) -> Result<(), StorageErr>;
+    /// Add a user to a group, which it may already be in
+    async fn add_user_to_group(&self, group_name: &str, user_name: &str) -> Result<(), StorageErr>;
+    /// Take a user out of a group, which it may not be in
+    async fn remove_user_from_group(
+        &self,
+        group_name: &str,
+        user_name: &str,
+    ) -> Result<(), StorageErr>;
+    /// The groups a user is in, by name
+    async fn list_groups_for_user(&self, user_name: &str) -> Result<Vec<Group>, StorageErr>;
+    /// Attach a policy to a user or group, replacing the one of the same name, or delete it with
+    /// `None`, failing with [`StorageErr::NoSuchIdentityPolicy`] if there's none to delete
+    async fn put_identity_policy(
+        &self,
+        holder: &PolicyHolder,
+        name: &str,
+        document: Option<String>,
+    ) -> Result<(), StorageErr>;
+    /// The policies attached to a user or group, by name
+    async fn list_identity_policies(
+        &self,
+        holder: &PolicyHolder,
+    ) -> Result<Vec<IdentityPolicy>, StorageErr>;
+
+    async fn create_session(&self, session: Session) -> Result<(), StorageErr>;
+    /// A session by its access key id, failing with [`StorageErr::SessionNotFound`] if there's
+    /// none, expired or not
+    async fn get_session(&self, access_key_id: &str) -> Result<Session, StorageErr>;
+    /// Delete the sessions that expired before a date, returning how many there were
+    async fn delete_expired_sessions(&self, before: DateTime<Utc>) -> Result<u64, StorageErr>;
 }
 
 #[derive(Error, Debug)]
@@ -25,8 +288,56 @@ pub enum StorageErr {
     BucketNotEmpty,
     #[error("bucket already exists")]
     BucketAlreadyExists,
+    #[error("bucket has no lifecycle configuration")]
+    NoSuchLifecycleConfiguration,
+    #[error("bucket has no CORS configuration")]
+    NoSuchCORSConfiguration,
+    #[error("bucket has no policy")]
+    NoSuchBucketPolicy,
+    #[error("bucket has no ownership controls")]
+    NoSuchOwnershipControls,
+    #[error("user not found")]
+    UserNotFound,
+    #[error("user already exists")]
+    UserAlreadyExists,
+    #[error("access key not found")]
+    AccessKeyNotFound,
+    #[error("group not found")]
+    GroupNotFound,
+    #[error("group already exists")]
+    GroupAlreadyExists,
+    #[error("user or group has no such policy")]
+    NoSuchIdentityPolicy,
+    #[error("session not found")]
+    SessionNotFound,
     #[error("object not found")]
     ObjectNotFound,
+    #[error("object version not found")]
+    VersionNotFound,
+    #[error("object version is a delete marker")]
+    DeleteMarker { version_id: String },
+    #[error("multipart upload not found")]
+    UploadNotFound,
+    #[error("part not found or its etag doesn't match")]
+    InvalidPart,
+    #[error("parts aren't in ascending order")]
+    InvalidPartOrder,
+    #[error("part is smaller than the minimum allowed size")]
+    EntityTooSmall,
+    #[error("range isn't satisfiable")]
+    InvalidRange,
+    #[error("object has no such part")]
+    InvalidPartNumber,
+    #[error("body ended before its declared length")]
+    IncompleteBody,
+    #[error("body doesn't match its digest")]
+    BadDigest,
+    #[error("body doesn't match its signed SHA256")]
+    ContentSha256Mismatch,
+    #[error("body signature doesn't match")]
+    SignatureDoesNotMatch,
+    #[error("a condition of the write didn't hold")]
+    PreconditionFailed,
     #[error("failed due to IO error: {0}")]
     IOErr(#[from] Box<dyn error::Error + Send + Sync>),
 }
@@ -1,12 +1,26 @@
 use crate::{
-    bucket::Bucket,
-    storage_provider::{StorageErr, StorageProvider},
+    acl::{AccessControlList, ObjectOwnership},
+    bucket::{Bucket, CreateBucketOptions, VersioningStatus},
+    cors::CorsConfiguration,
+    identity::{AccessKey, AccessKeyStatus, Group, IdentityPolicy, PolicyHolder, Session, User},
+    lifecycle::LifecycleConfiguration,
+    list::{Page, PageBuilder},
+    multipart::{self, CompletedPart, ListUploadsOptions, MultipartUpload, Part, PartsPage},
+    object::{
+        self, CopiedObject, CopyObjectOptions, CopySource, DeletedObject, ListObjectsOptions,
+        ListVersionsOptions, MetadataDirective, Object, ObjectMetadata, ObjectVersion,
+        Precondition, PutObjectOptions, ReadRange, Tags, WriteCondition, NULL_VERSION_ID,
+    },
+    storage_provider::{ByteStream, StorageErr, StorageProvider},
 };
 use async_trait::async_trait;
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
-use futures::future;
+use futures::{future, stream, TryStreamExt};
+use md5::{Digest, Md5};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Range,
     sync::{Arc, Mutex},
 };
 
@@ -14,16 +28,36 @@ const DEFAULT_REGION: &str = "Europe";
 
 pub struct MockStorageProvider {
     buckets: Mutex<HashMap<String, MockBucket>>,
+    users: Mutex<BTreeMap<String, User>>,
+    /// The access keys of every user, oldest first
+    access_keys: Mutex<Vec<AccessKey>>,
+    groups: Mutex<BTreeMap<String, Group>>,
+    /// Group and user names of the members of the groups
+    group_members: Mutex<BTreeSet<(String, String)>>,
+    identity_policies: Mutex<Vec<(PolicyHolder, IdentityPolicy)>>,
+    sessions: Mutex<BTreeMap<String, Session>>,
 }
 
 impl MockStorageProvider {
     pub fn new() -> Self {
         MockStorageProvider {
             buckets: Mutex::new(HashMap::new()),
+            users: Mutex::new(BTreeMap::new()),
+            access_keys: Mutex::new(Vec::new()),
+            groups: Mutex::new(BTreeMap::new()),
+            group_members: Mutex::new(BTreeSet::new()),
+            identity_policies: Mutex::new(Vec::new()),
+            sessions: Mutex::new(BTreeMap::new()),
         }
     }
 }
 
+impl Default for MockStorageProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn get_mock_app_data() -> Arc<dyn StorageProvider> {
     let provider = MockStorageProvider::new();
 
@@ -32,13 +66,132 @@ pub fn get_mock_app_data() -> Arc<dyn StorageProvider> {
 
 pub struct MockBucket {
     region: String,
-    objects: Mutex<HashMap<String, Vec<u8>>>,
+    owner_id: Option<String>,
+    versioning: VersioningStatus,
+    lifecycle: LifecycleConfiguration,
+    cors: CorsConfiguration,
+    policy: Option<String>,
+    acl: AccessControlList,
+    ownership: Option<ObjectOwnership>,
+    /// The versions of each key, the current one last
+    objects: Mutex<HashMap<String, Vec<MockObject>>>,
+    uploads: Mutex<HashMap<String, MockUpload>>,
     creation_date: DateTime<Utc>,
 }
 
+impl MockBucket {
+    fn bucket(&self, name: &str) -> Bucket {
+        Bucket {
+            name: name.into(),
+            region: self.region.clone(),
+            creation_date: self.creation_date,
+            versioning: self.versioning,
+        }
+    }
+
+    /// An object as it's returned, whose version only shows once the bucket was versioned
+    fn object(&self, object: &MockObject) -> Object {
+        Object {
+            version_id: object
+                .object
+                .version_id
+                .clone()
+                .filter(|_| self.versioning != VersioningStatus::Unversioned),
+            ..object.object.clone()
+        }
+    }
+
+    /// Store a new version of an object, which replaces the `null` version unless versioning
+    /// is enabled
+    fn insert(
+        &self,
+        mut object: MockObject,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr> {
+        let mut objects = self
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let versions = objects.entry(object.object.key.clone()).or_default();
+        if condition == WriteCondition::IfAbsent
+            && matches!(versions.last(), Some(current) if !current.object.delete_marker)
+        {
+            return Err(StorageErr::PreconditionFailed);
+        }
+
+        let version_id = match self.versioning {
+            VersioningStatus::Enabled => object::new_version_id(),
+            VersioningStatus::Unversioned | VersioningStatus::Suspended => {
+                versions.retain(|version| {
+                    version.object.version_id.as_deref() != Some(NULL_VERSION_ID)
+                });
+                NULL_VERSION_ID.into()
+            }
+        };
+        object.object.version_id = Some(version_id);
+        let stored = self.object(&object);
+        versions.push(object);
+
+        Ok(stored)
+    }
+}
+
+/// The version of an object a read asks for, the current one when not given
+fn find_version<'a>(
+    versions: Option<&'a Vec<MockObject>>,
+    version_id: Option<&str>,
+) -> Result<&'a MockObject, StorageErr> {
+    let object = match version_id {
+        None => versions
+            .and_then(|versions| versions.last())
+            .ok_or(StorageErr::ObjectNotFound)?,
+        Some(version_id) => versions
+            .into_iter()
+            .flatten()
+            .find(|object| object.object.version_id.as_deref() == Some(version_id))
+            .ok_or(StorageErr::VersionNotFound)?,
+    };
+
+    if object.object.delete_marker {
+        return Err(StorageErr::DeleteMarker {
+            version_id: object.object.version_id.clone().unwrap_or_default(),
+        });
+    }
+
+    Ok(object)
+}
+
+/// The current version of an object, which doesn't exist while it's a delete marker
+fn current_mut(versions: Option<&mut Vec<MockObject>>) -> Result<&mut MockObject, StorageErr> {
+    versions
+        .and_then(|versions| versions.last_mut())
+        .filter(|object| !object.object.delete_marker)
+        .ok_or(StorageErr::ObjectNotFound)
+}
+
+pub struct MockObject {
+    object: Object,
+    data: Bytes,
+    /// The sizes of the parts an object was completed from, empty if it was uploaded at once
+    part_sizes: Vec<u64>,
+    tags: Tags,
+    acl: AccessControlList,
+}
+
+pub struct MockUpload {
+    upload: MultipartUpload,
+    options: PutObjectOptions,
+    parts: BTreeMap<u16, MockPart>,
+}
+
+pub struct MockPart {
+    part: Part,
+    data: Bytes,
+}
+
 #[async_trait]
 impl StorageProvider for MockStorageProvider {
-    async fn list_buckets(&self) -> Result<Vec<Bucket>, StorageErr> {
+    async fn list_buckets(&self, owner_id: Option<&str>) -> Result<Vec<Bucket>, StorageErr> {
         let buckets = self
             .buckets
             .lock()
@@ -46,19 +199,16 @@ impl StorageProvider for MockStorageProvider {
 
         Ok(buckets
             .iter()
-            .map(|bucket| {
-                let (bucket_name, data) = bucket;
-
-                return Bucket {
-                    name: bucket_name.into(),
-                    region: data.region.clone(),
-                    creation_date: data.creation_date,
-                };
-            })
+            .filter(|(_, bucket)| owner_id.is_none() || bucket.owner_id.as_deref() == owner_id)
+            .map(|(bucket_name, bucket)| bucket.bucket(bucket_name))
             .collect::<Vec<_>>())
     }
 
-    async fn create_bucket(&self, name: &str, region: Option<String>) -> Result<(), StorageErr> {
+    async fn create_bucket(
+        &self,
+        name: &str,
+        options: CreateBucketOptions,
+    ) -> Result<(), StorageErr> {
         let mut buckets = self
             .buckets
             .lock()
@@ -71,8 +221,16 @@ impl StorageProvider for MockStorageProvider {
         buckets.insert(
             name.into(),
             MockBucket {
-                region: region.unwrap_or(DEFAULT_REGION.into()),
+                region: options.region.unwrap_or(DEFAULT_REGION.into()),
+                owner_id: options.owner_id,
+                versioning: VersioningStatus::Unversioned,
+                lifecycle: LifecycleConfiguration::default(),
+                cors: CorsConfiguration::default(),
+                policy: None,
+                acl: options.acl.unwrap_or_default(),
+                ownership: options.object_ownership,
                 objects: Mutex::new(HashMap::new()),
+                uploads: Mutex::new(HashMap::new()),
                 creation_date: Utc::now(),
             },
         );
@@ -80,17 +238,14 @@ impl StorageProvider for MockStorageProvider {
         Ok(())
     }
 
-    async fn head_bucket(&self, name: &str) -> Result<(), StorageErr> {
+    async fn head_bucket(&self, name: &str) -> Result<Bucket, StorageErr> {
         let buckets = self
             .buckets
             .lock()
             .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
 
-        if !buckets.contains_key(name) {
-            return Err(StorageErr::BucketNotFound);
-        }
-
-        Ok(())
+        Ok(bucket.bucket(name))
     }
 
     async fn delete_bucket(&self, name: &str) -> Result<(), StorageErr> {
@@ -115,36 +270,1299 @@ impl StorageProvider for MockStorageProvider {
         Ok(())
     }
 
-    async fn delete_object(&self, bucket_name: &str, object: &str) -> Result<(), StorageErr> {
+    async fn put_bucket_versioning(
+        &self,
+        name: &str,
+        status: VersioningStatus,
+    ) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets
+            .get_mut(name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .versioning = status;
+
+        Ok(())
+    }
+
+    async fn get_bucket_lifecycle(&self, name: &str) -> Result<LifecycleConfiguration, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
+
+        if bucket.lifecycle.rules.is_empty() {
+            return Err(StorageErr::NoSuchLifecycleConfiguration);
+        }
+
+        Ok(bucket.lifecycle.clone())
+    }
+
+    async fn put_bucket_lifecycle(
+        &self,
+        name: &str,
+        configuration: LifecycleConfiguration,
+    ) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets
+            .get_mut(name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .lifecycle = configuration;
+
+        Ok(())
+    }
+
+    async fn get_bucket_cors(&self, name: &str) -> Result<CorsConfiguration, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
+
+        if bucket.cors.rules.is_empty() {
+            return Err(StorageErr::NoSuchCORSConfiguration);
+        }
+
+        Ok(bucket.cors.clone())
+    }
+
+    async fn put_bucket_cors(
+        &self,
+        name: &str,
+        configuration: CorsConfiguration,
+    ) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets
+            .get_mut(name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .cors = configuration;
+
+        Ok(())
+    }
+
+    async fn get_bucket_policy(&self, name: &str) -> Result<String, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
+
+        bucket.policy.clone().ok_or(StorageErr::NoSuchBucketPolicy)
+    }
+
+    async fn put_bucket_policy(
+        &self,
+        name: &str,
+        policy: Option<String>,
+    ) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets
+            .get_mut(name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .policy = policy;
+
+        Ok(())
+    }
+
+    async fn get_bucket_acl(&self, name: &str) -> Result<AccessControlList, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
+
+        Ok(bucket.acl.clone())
+    }
+
+    async fn put_bucket_acl(&self, name: &str, acl: AccessControlList) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets.get_mut(name).ok_or(StorageErr::BucketNotFound)?.acl = acl;
+
+        Ok(())
+    }
+
+    async fn get_bucket_ownership_controls(
+        &self,
+        name: &str,
+    ) -> Result<ObjectOwnership, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(name).ok_or(StorageErr::BucketNotFound)?;
+
+        bucket.ownership.ok_or(StorageErr::NoSuchOwnershipControls)
+    }
+
+    async fn put_bucket_ownership_controls(
+        &self,
+        name: &str,
+        ownership: Option<ObjectOwnership>,
+    ) -> Result<(), StorageErr> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        buckets
+            .get_mut(name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .ownership = ownership;
+
+        Ok(())
+    }
+
+    async fn list_objects(
+        &self,
+        bucket_name: &str,
+        options: ListObjectsOptions,
+    ) -> Result<Page<Object>, StorageErr> {
         let buckets = self
             .buckets
             .lock()
             .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
         let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
-        let mut bucket_objects = bucket
+        let objects = bucket
             .objects
             .lock()
             .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
 
-        if bucket_objects.contains_key(object) {
-            return Err(StorageErr::ObjectNotFound);
+        let prefix = options.prefix.clone().unwrap_or_default();
+        let mut objects: Vec<_> = objects
+            .values()
+            .filter_map(|versions| versions.last())
+            .filter(|object| !object.object.delete_marker)
+            .map(|object| bucket.object(object))
+            .filter(|object| object.key.starts_with(&prefix))
+            .filter(|object| match &options.marker {
+                Some(marker) => &object.key > marker,
+                None => true,
+            })
+            .collect();
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.marker.as_deref(),
+            options.max_keys,
+        );
+        for object in objects {
+            if !page.push(&object.key.clone(), object) {
+                break;
+            }
         }
 
-        bucket_objects.remove(object);
+        Ok(page.finish())
+    }
 
-        Ok(())
+    async fn list_object_versions(
+        &self,
+        bucket_name: &str,
+        options: ListVersionsOptions,
+    ) -> Result<Page<ObjectVersion>, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        let prefix = options.prefix.clone().unwrap_or_default();
+        let mut keys: Vec<_> = objects
+            .keys()
+            .filter(|key| key.starts_with(&prefix))
+            .filter(|key| match &options.key_marker {
+                Some(key_marker) => *key >= key_marker,
+                None => true,
+            })
+            .collect();
+        keys.sort();
+
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.key_marker.as_deref(),
+            options.max_keys,
+        );
+        for key in keys {
+            // Versions are kept oldest first
+            let mut versions = objects[key].iter().rev().enumerate();
+
+            // Within the marker's key, only the versions older than the marker are left
+            if options.key_marker.as_ref() == Some(key) {
+                let Some(version_id_marker) = &options.version_id_marker else {
+                    continue;
+                };
+                if versions
+                    .by_ref()
+                    .find(|(_, version)| {
+                        version.object.version_id.as_ref() == Some(version_id_marker)
+                    })
+                    .is_none()
+                {
+                    continue;
+                }
+            }
+
+            for (index, version) in versions {
+                let version = ObjectVersion {
+                    object: version.object.clone(),
+                    is_latest: index == 0,
+                };
+                if !page.push(key, version) {
+                    return Ok(page.finish());
+                }
+            }
+        }
+
+        Ok(page.finish())
     }
 
-    async fn delete_objects(
+    async fn put_object(
         &self,
         bucket_name: &str,
-        objects: Vec<String>,
-    ) -> Vec<Result<(), StorageErr>> {
-        let results = objects
-            .iter()
-            .map(|object| self.delete_object(bucket_name, object))
-            .collect::<Vec<_>>();
+        key: &str,
+        options: PutObjectOptions,
+        body: ByteStream,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr> {
+        self.head_bucket(bucket_name).await?;
 
-        future::join_all(results).await
+        let data: Vec<u8> = body
+            .try_fold(Vec::new(), |mut data, chunk| async move {
+                data.extend_from_slice(&chunk);
+                Ok(data)
+            })
+            .await?;
+        let object = Object {
+            key: key.into(),
+            size: data.len() as u64,
+            etag: hex::encode(Md5::digest(&data)),
+            metadata: options.metadata,
+            last_modified: Utc::now(),
+            version_id: None,
+            delete_marker: false,
+        };
+
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+
+        bucket.insert(
+            MockObject {
+                object,
+                data: data.into(),
+                part_sizes: Vec::new(),
+                tags: options.tags,
+                acl: options.acl.unwrap_or_default(),
+            },
+            condition,
+        )
+    }
+
+    async fn get_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<(Object, ByteStream), StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = find_version(bucket_objects.get(key), version_id)?;
+
+        Ok((
+            bucket.object(object),
+            Box::pin(stream::once(future::ready(Ok(object.data.clone())))),
+        ))
+    }
+
+    async fn get_object_range(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+        range: &ReadRange,
+    ) -> Result<(Object, Range<u64>, ByteStream), StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = find_version(bucket_objects.get(key), version_id)?;
+        let range = range.resolve(object.object.size, &object.part_sizes)?;
+        let data = object.data.slice(range.start as usize..range.end as usize);
+
+        Ok((
+            bucket.object(object),
+            range,
+            Box::pin(stream::once(future::ready(Ok(data)))),
+        ))
+    }
+
+    async fn head_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Object, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = find_version(bucket_objects.get(key), version_id)?;
+
+        Ok(bucket.object(object))
+    }
+
+    async fn get_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Tags, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = find_version(bucket_objects.get(key), version_id)?;
+
+        Ok(object.tags.clone())
+    }
+
+    async fn put_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        tags: Tags,
+    ) -> Result<(), StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = current_mut(bucket_objects.get_mut(key))?;
+
+        object.tags = tags;
+
+        Ok(())
+    }
+
+    async fn get_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<AccessControlList, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = find_version(bucket_objects.get(key), version_id)?;
+
+        Ok(object.acl.clone())
+    }
+
+    async fn put_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        acl: AccessControlList,
+    ) -> Result<(), StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let object = current_mut(bucket_objects.get_mut(key))?;
+
+        object.acl = acl;
+
+        Ok(())
+    }
+
+    async fn copy_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        source: &CopySource,
+        options: CopyObjectOptions,
+    ) -> Result<CopiedObject, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let (source_object, data, part_sizes, tags) = {
+            let source_bucket = buckets
+                .get(&source.bucket)
+                .ok_or(StorageErr::BucketNotFound)?;
+            let objects = source_bucket
+                .objects
+                .lock()
+                .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+            let source = find_version(objects.get(&source.key), source.version_id.as_deref())?;
+
+            (
+                source_bucket.object(source),
+                source.data.clone(),
+                source.part_sizes.clone(),
+                source.tags.clone(),
+            )
+        };
+        if options
+            .source_preconditions
+            .evaluate(&source_object.etag, &source_object.last_modified)
+            != Precondition::Passed
+        {
+            return Err(StorageErr::PreconditionFailed);
+        }
+
+        let source_version_id = source_object.version_id.clone();
+        let object = Object {
+            key: key.into(),
+            last_modified: Utc::now(),
+            metadata: match options.metadata {
+                MetadataDirective::Copy => source_object.metadata,
+                MetadataDirective::Replace(metadata) => metadata,
+            },
+            version_id: None,
+            ..source_object
+        };
+
+        let object = buckets
+            .get(bucket_name)
+            .ok_or(StorageErr::BucketNotFound)?
+            .insert(
+                MockObject {
+                    object,
+                    data,
+                    part_sizes,
+                    tags: options.tags.unwrap_or(tags),
+                    acl: options.acl.unwrap_or_default(),
+                },
+                WriteCondition::Overwrite,
+            )?;
+
+        Ok(CopiedObject {
+            object,
+            source_version_id,
+        })
+    }
+
+    async fn delete_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<DeletedObject, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut bucket_objects = bucket
+            .objects
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if let Some(version_id) = version_id {
+            let versions = bucket_objects
+                .get_mut(key)
+                .ok_or(StorageErr::VersionNotFound)?;
+            let index = versions
+                .iter()
+                .position(|object| object.object.version_id.as_deref() == Some(version_id))
+                .ok_or(StorageErr::VersionNotFound)?;
+            let deleted = versions.remove(index);
+            if versions.is_empty() {
+                bucket_objects.remove(key);
+            }
+
+            return Ok(DeletedObject {
+                version_id: Some(version_id.into()),
+                delete_marker: deleted.object.delete_marker,
+            });
+        }
+
+        if bucket.versioning == VersioningStatus::Unversioned {
+            return bucket_objects
+                .remove(key)
+                .map(|_| DeletedObject::default())
+                .ok_or(StorageErr::ObjectNotFound);
+        }
+
+        drop(bucket_objects);
+        let marker = bucket.insert(
+            MockObject {
+                object: Object {
+                    key: key.into(),
+                    size: 0,
+                    etag: String::new(),
+                    metadata: ObjectMetadata::default(),
+                    last_modified: Utc::now(),
+                    version_id: None,
+                    delete_marker: true,
+                },
+                data: Bytes::new(),
+                part_sizes: Vec::new(),
+                tags: Tags::new(),
+                acl: AccessControlList::default(),
+            },
+            WriteCondition::Overwrite,
+        )?;
+
+        Ok(DeletedObject {
+            version_id: marker.version_id,
+            delete_marker: true,
+        })
+    }
+
+    async fn delete_objects(
+        &self,
+        bucket_name: &str,
+        objects: Vec<(String, Option<String>)>,
+    ) -> Vec<Result<DeletedObject, StorageErr>> {
+        let results = objects
+            .iter()
+            .map(|(key, version_id)| self.delete_object(bucket_name, key, version_id.as_deref()))
+            .collect::<Vec<_>>();
+
+        future::join_all(results).await
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        options: PutObjectOptions,
+    ) -> Result<MultipartUpload, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let upload = MultipartUpload {
+            upload_id: uuid::Uuid::new_v4().simple().to_string(),
+            key: key.into(),
+            initiated: Utc::now(),
+        };
+
+        bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?
+            .insert(
+                upload.upload_id.clone(),
+                MockUpload {
+                    upload: upload.clone(),
+                    options,
+                    parts: BTreeMap::new(),
+                },
+            );
+
+        Ok(upload)
+    }
+
+    async fn upload_part(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        body: ByteStream,
+    ) -> Result<Part, StorageErr> {
+        self.head_bucket(bucket_name).await?;
+
+        let data: Vec<u8> = body
+            .try_fold(Vec::new(), |mut data, chunk| async move {
+                data.extend_from_slice(&chunk);
+                Ok(data)
+            })
+            .await?;
+        let part = Part {
+            part_number,
+            size: data.len() as u64,
+            etag: hex::encode(Md5::digest(&data)),
+            last_modified: Utc::now(),
+        };
+
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut uploads = bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let upload = uploads
+            .get_mut(upload_id)
+            .filter(|upload| upload.upload.key == key)
+            .ok_or(StorageErr::UploadNotFound)?;
+
+        upload.parts.insert(
+            part_number,
+            MockPart {
+                part: part.clone(),
+                data: data.into(),
+            },
+        );
+
+        Ok(part)
+    }
+
+    async fn upload_part_copy(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        source: &CopySource,
+        range: Option<Range<u64>>,
+    ) -> Result<Part, StorageErr> {
+        let data = {
+            let buckets = self
+                .buckets
+                .lock()
+                .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+            let objects = buckets
+                .get(&source.bucket)
+                .ok_or(StorageErr::BucketNotFound)?
+                .objects
+                .lock()
+                .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+            let source = find_version(objects.get(&source.key), source.version_id.as_deref())?;
+            let range = object::resolve_range(range, source.data.len() as u64)?;
+
+            source.data.slice(range.start as usize..range.end as usize)
+        };
+
+        self.upload_part(
+            bucket_name,
+            key,
+            upload_id,
+            part_number,
+            Box::pin(stream::once(future::ok(data))),
+        )
+        .await
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut uploads = bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let upload = uploads
+            .get(upload_id)
+            .filter(|upload| upload.upload.key == key)
+            .ok_or(StorageErr::UploadNotFound)?;
+
+        let uploaded: Vec<Part> = upload
+            .parts
+            .values()
+            .map(|part| part.part.clone())
+            .collect();
+        let selected = multipart::select_parts(&uploaded, &parts)?;
+        let data: Vec<u8> = selected
+            .iter()
+            .flat_map(|part| upload.parts[&part.part_number].data.iter().copied())
+            .collect();
+        let object = Object {
+            key: key.into(),
+            size: data.len() as u64,
+            etag: multipart::multipart_etag(&selected)?,
+            metadata: upload.options.metadata.clone(),
+            last_modified: Utc::now(),
+            version_id: None,
+            delete_marker: false,
+        };
+
+        let part_sizes = selected.iter().map(|part| part.size).collect();
+
+        let object = bucket.insert(
+            MockObject {
+                object,
+                data: data.into(),
+                part_sizes,
+                tags: upload.options.tags.clone(),
+                acl: upload.options.acl.clone().unwrap_or_default(),
+            },
+            condition,
+        )?;
+        uploads.remove(upload_id);
+
+        Ok(object)
+    }
+
+    async fn abort_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<(), StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut uploads = bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if uploads
+            .get(upload_id)
+            .filter(|upload| upload.upload.key == key)
+            .is_none()
+        {
+            return Err(StorageErr::UploadNotFound);
+        }
+
+        uploads.remove(upload_id);
+
+        Ok(())
+    }
+
+    async fn list_multipart_uploads(
+        &self,
+        bucket_name: &str,
+        options: ListUploadsOptions,
+    ) -> Result<Page<MultipartUpload>, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let mut uploads: Vec<MultipartUpload> = bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?
+            .values()
+            .map(|upload| upload.upload.clone())
+            .filter(|upload| {
+                upload
+                    .key
+                    .starts_with(options.prefix.as_deref().unwrap_or(""))
+            })
+            .filter(|upload| options.is_after_marker(upload))
+            .collect();
+        uploads.sort_by(|a, b| (&a.key, &a.upload_id).cmp(&(&b.key, &b.upload_id)));
+
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.key_marker.as_deref(),
+            options.max_uploads,
+        );
+        for upload in uploads {
+            if !page.push(&upload.key.clone(), upload) {
+                break;
+            }
+        }
+
+        Ok(page.finish())
+    }
+
+    async fn list_parts(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number_marker: Option<u16>,
+        max_parts: usize,
+    ) -> Result<PartsPage, StorageErr> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let bucket = buckets.get(bucket_name).ok_or(StorageErr::BucketNotFound)?;
+        let uploads = bucket
+            .uploads
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let upload = uploads
+            .get(upload_id)
+            .filter(|upload| upload.upload.key == key)
+            .ok_or(StorageErr::UploadNotFound)?;
+
+        let mut parts: Vec<Part> = upload
+            .parts
+            .range(part_number_marker.unwrap_or(0) + 1..)
+            .map(|(_, part)| part.part.clone())
+            .take(max_parts + 1)
+            .collect();
+        let is_truncated = parts.len() > max_parts;
+        parts.truncate(max_parts);
+
+        Ok(PartsPage {
+            next_part_number_marker: parts.last().map(|part| part.part_number),
+            parts,
+            is_truncated,
+        })
+    }
+
+    async fn create_user(&self, user: User) -> Result<(), StorageErr> {
+        let mut users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if users.contains_key(&user.name) {
+            return Err(StorageErr::UserAlreadyExists);
+        }
+        users.insert(user.name.clone(), user);
+
+        Ok(())
+    }
+
+    async fn get_user(&self, name: &str) -> Result<User, StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        users.get(name).cloned().ok_or(StorageErr::UserNotFound)
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        Ok(users.values().cloned().collect())
+    }
+
+    async fn delete_user(&self, name: &str) -> Result<(), StorageErr> {
+        let mut users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut group_members = self
+            .group_members
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut identity_policies = self
+            .identity_policies
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        users.remove(name).ok_or(StorageErr::UserNotFound)?;
+        access_keys.retain(|access_key| access_key.user_name != name);
+        group_members.retain(|(_, user_name)| user_name != name);
+        identity_policies.retain(|(holder, _)| holder != &PolicyHolder::User(name.into()));
+        sessions.retain(|_, session| session.user_name.as_deref() != Some(name));
+
+        Ok(())
+    }
+
+    async fn create_access_key(&self, access_key: AccessKey) -> Result<(), StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if !users.contains_key(&access_key.user_name) {
+            return Err(StorageErr::UserNotFound);
+        }
+        access_keys.push(access_key);
+
+        Ok(())
+    }
+
+    async fn get_access_key(&self, access_key_id: &str) -> Result<AccessKey, StorageErr> {
+        let access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        access_keys
+            .iter()
+            .find(|access_key| access_key.access_key_id == access_key_id)
+            .cloned()
+            .ok_or(StorageErr::AccessKeyNotFound)
+    }
+
+    async fn list_access_keys(&self, user_name: &str) -> Result<Vec<AccessKey>, StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if !users.contains_key(user_name) {
+            return Err(StorageErr::UserNotFound);
+        }
+
+        Ok(access_keys
+            .iter()
+            .filter(|access_key| access_key.user_name == user_name)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_access_key(
+        &self,
+        access_key_id: &str,
+        status: AccessKeyStatus,
+    ) -> Result<(), StorageErr> {
+        let mut access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let access_key = access_keys
+            .iter_mut()
+            .find(|access_key| access_key.access_key_id == access_key_id)
+            .ok_or(StorageErr::AccessKeyNotFound)?;
+
+        access_key.status = status;
+
+        Ok(())
+    }
+
+    async fn delete_access_key(&self, access_key_id: &str) -> Result<(), StorageErr> {
+        let mut access_keys = self
+            .access_keys
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let count = access_keys.len();
+
+        access_keys.retain(|access_key| access_key.access_key_id != access_key_id);
+        if access_keys.len() == count {
+            return Err(StorageErr::AccessKeyNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn create_group(&self, group: Group) -> Result<(), StorageErr> {
+        let mut groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if groups.contains_key(&group.name) {
+            return Err(StorageErr::GroupAlreadyExists);
+        }
+        groups.insert(group.name.clone(), group);
+
+        Ok(())
+    }
+
+    async fn list_groups(&self) -> Result<Vec<Group>, StorageErr> {
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        Ok(groups.values().cloned().collect())
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), StorageErr> {
+        let mut groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut group_members = self
+            .group_members
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut identity_policies = self
+            .identity_policies
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        groups.remove(name).ok_or(StorageErr::GroupNotFound)?;
+        group_members.retain(|(group_name, _)| group_name != name);
+        identity_policies.retain(|(holder, _)| holder != &PolicyHolder::Group(name.into()));
+
+        Ok(())
+    }
+
+    async fn add_user_to_group(&self, group_name: &str, user_name: &str) -> Result<(), StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut group_members = self
+            .group_members
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if !groups.contains_key(group_name) {
+            return Err(StorageErr::GroupNotFound);
+        }
+        if !users.contains_key(user_name) {
+            return Err(StorageErr::UserNotFound);
+        }
+        group_members.insert((group_name.into(), user_name.into()));
+
+        Ok(())
+    }
+
+    async fn remove_user_from_group(
+        &self,
+        group_name: &str,
+        user_name: &str,
+    ) -> Result<(), StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut group_members = self
+            .group_members
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if !groups.contains_key(group_name) {
+            return Err(StorageErr::GroupNotFound);
+        }
+        if !users.contains_key(user_name) {
+            return Err(StorageErr::UserNotFound);
+        }
+        group_members.remove(&(group_name.into(), user_name.into()));
+
+        Ok(())
+    }
+
+    async fn list_groups_for_user(&self, user_name: &str) -> Result<Vec<Group>, StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let group_members = self
+            .group_members
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        if !users.contains_key(user_name) {
+            return Err(StorageErr::UserNotFound);
+        }
+
+        Ok(group_members
+            .iter()
+            .filter(|(_, member)| member == user_name)
+            .filter_map(|(group_name, _)| groups.get(group_name).cloned())
+            .collect())
+    }
+
+    async fn put_identity_policy(
+        &self,
+        holder: &PolicyHolder,
+        name: &str,
+        document: Option<String>,
+    ) -> Result<(), StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let mut identity_policies = self
+            .identity_policies
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        match holder {
+            PolicyHolder::User(user_name) if !users.contains_key(user_name) => {
+                return Err(StorageErr::UserNotFound)
+            }
+            PolicyHolder::Group(group_name) if !groups.contains_key(group_name) => {
+                return Err(StorageErr::GroupNotFound)
+            }
+            _ => {}
+        }
+
+        let position = identity_policies
+            .iter()
+            .position(|(attached_to, policy)| attached_to == holder && policy.name == name);
+        match (position, document) {
+            (Some(position), Some(document)) => identity_policies[position].1.document = document,
+            (None, Some(document)) => identity_policies.push((
+                holder.clone(),
+                IdentityPolicy {
+                    name: name.into(),
+                    document,
+                },
+            )),
+            (Some(position), None) => {
+                identity_policies.remove(position);
+            }
+            (None, None) => return Err(StorageErr::NoSuchIdentityPolicy),
+        }
+
+        Ok(())
+    }
+
+    async fn list_identity_policies(
+        &self,
+        holder: &PolicyHolder,
+    ) -> Result<Vec<IdentityPolicy>, StorageErr> {
+        let users = self
+            .users
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let groups = self
+            .groups
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+        let identity_policies = self
+            .identity_policies
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        match holder {
+            PolicyHolder::User(user_name) if !users.contains_key(user_name) => {
+                return Err(StorageErr::UserNotFound)
+            }
+            PolicyHolder::Group(group_name) if !groups.contains_key(group_name) => {
+                return Err(StorageErr::GroupNotFound)
+            }
+            _ => {}
+        }
+
+        let mut policies: Vec<IdentityPolicy> = identity_policies
+            .iter()
+            .filter(|(attached_to, _)| attached_to == holder)
+            .map(|(_, policy)| policy.clone())
+            .collect();
+        policies.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(policies)
+    }
+
+    async fn create_session(&self, session: Session) -> Result<(), StorageErr> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        sessions.insert(session.access_key_id.clone(), session);
+
+        Ok(())
+    }
+
+    async fn get_session(&self, access_key_id: &str) -> Result<Session, StorageErr> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        sessions
+            .get(access_key_id)
+            .cloned()
+            .ok_or(StorageErr::SessionNotFound)
+    }
+
+    async fn delete_expired_sessions(&self, before: DateTime<Utc>) -> Result<u64, StorageErr> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|err| StorageErr::IOErr(err.to_string().into()))?;
+
+        let count = sessions.len();
+        sessions.retain(|_, session| session.expiration >= before);
+
+        Ok((count - sessions.len()) as u64)
     }
 }
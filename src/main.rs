@@ -1,4 +1,4 @@
 fn main() -> Result<(), std::io::Error> {
     let provider = s3_entities::test::storage_provider::MockStorageProvider::new();
-    s3_api::main(provider)
+    s3_api::main(provider, s3_api::auth::Credentials::from_env())
 }
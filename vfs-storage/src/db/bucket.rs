@@ -1,13 +1,21 @@
-use sea_orm::*;
+use sea_orm::{prelude::Json, *};
 
 use super::entity::bucket;
 
-pub async fn list_buckets(db: &DbConn) -> Result<Vec<bucket::Model>, DbErr> {
-    bucket::Entity::find().all(db).await
+pub async fn list_buckets(
+    db: &DbConn,
+    owner_id: Option<String>,
+) -> Result<Vec<bucket::Model>, DbErr> {
+    let mut query = bucket::Entity::find();
+    if let Some(owner_id) = owner_id {
+        query = query.filter(bucket::Column::OwnerId.eq(owner_id));
+    }
+
+    query.all(db).await
 }
 
-pub async fn head_bucket(db: &DbConn, name: String) -> Result<(), DbErr> {
-    let _: bucket::ActiveModel = bucket::Entity::find()
+pub async fn head_bucket(db: &DbConn, name: String) -> Result<bucket::Model, DbErr> {
+    bucket::Entity::find()
         .filter(bucket::Column::Name.eq(&name))
         .one(db)
         .await?
@@ -15,20 +23,70 @@ pub async fn head_bucket(db: &DbConn, name: String) -> Result<(), DbErr> {
             "bucket '{}' doesn't exist",
             &name
         )))
-        .map(Into::into)?;
-
-    Ok(())
 }
 
-pub async fn create_bucket(db: &DbConn, name: String) -> Result<bucket::ActiveModel, DbErr> {
+pub async fn create_bucket(
+    db: &DbConn,
+    name: String,
+    region: String,
+    owner_id: Option<String>,
+    acl: Option<Json>,
+    object_ownership: Option<String>,
+) -> Result<bucket::Model, DbErr> {
     bucket::ActiveModel {
         name: Set(name),
-        ..Default::default()
+        region: Set(region),
+        creation_date: Set(chrono::Utc::now()),
+        versioning: Set(None),
+        policy: Set(None),
+        acl: Set(acl),
+        object_ownership: Set(object_ownership),
+        owner_id: Set(owner_id),
     }
-    .save(db)
+    .insert(db)
     .await
 }
 
+pub async fn put_bucket_versioning(
+    db: &DbConn,
+    name: String,
+    versioning: Option<String>,
+) -> Result<bucket::Model, DbErr> {
+    let mut bucket: bucket::ActiveModel = head_bucket(db, name).await?.into();
+    bucket.versioning = Set(versioning);
+
+    bucket.update(db).await
+}
+
+pub async fn put_bucket_policy(
+    db: &DbConn,
+    name: String,
+    policy: Option<String>,
+) -> Result<bucket::Model, DbErr> {
+    let mut bucket: bucket::ActiveModel = head_bucket(db, name).await?.into();
+    bucket.policy = Set(policy);
+
+    bucket.update(db).await
+}
+
+pub async fn put_bucket_acl(db: &DbConn, name: String, acl: Json) -> Result<bucket::Model, DbErr> {
+    let mut bucket: bucket::ActiveModel = head_bucket(db, name).await?.into();
+    bucket.acl = Set(Some(acl));
+
+    bucket.update(db).await
+}
+
+pub async fn put_bucket_ownership(
+    db: &DbConn,
+    name: String,
+    object_ownership: Option<String>,
+) -> Result<bucket::Model, DbErr> {
+    let mut bucket: bucket::ActiveModel = head_bucket(db, name).await?.into();
+    bucket.object_ownership = Set(object_ownership);
+
+    bucket.update(db).await
+}
+
 pub async fn delete_bucket(db: &DbConn, name: String) -> Result<DeleteResult, DbErr> {
     let bucket: bucket::ActiveModel = bucket::Entity::find()
         .filter(bucket::Column::Name.eq(&name))
@@ -41,7 +41,7 @@ pub async fn run(db_url: String, db_name: String) -> Result<DatabaseConnection,
     let db = connect(db_url, db_name).await?;
     let schema_manager = SchemaManager::new(&db);
 
-    Migrator::refresh(&db).await?;
+    Migrator::up(&db, None).await?;
     assert!(schema_manager.has_table("buckets").await?);
     assert!(schema_manager.has_table("objects").await?);
 
@@ -0,0 +1,66 @@
+use s3_entities::cors::CorsRule;
+use sea_orm::{prelude::Json, *};
+
+use super::entity::cors_rule;
+
+fn strings_json(strings: Vec<String>) -> Json {
+    Json::Array(strings.into_iter().map(Json::String).collect())
+}
+
+/// Read back a list stored by [`put_cors_rules`]
+pub fn json_strings(json: &Json) -> Vec<String> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str().map(String::from))
+        .collect()
+}
+
+/// The CORS rules of a bucket, in the order they were configured
+pub async fn get_cors_rules(
+    db: &DbConn,
+    bucket_name: String,
+) -> Result<Vec<cors_rule::Model>, DbErr> {
+    cors_rule::Entity::find()
+        .filter(cors_rule::Column::BucketName.eq(bucket_name))
+        .order_by_asc(cors_rule::Column::RuleNumber)
+        .all(db)
+        .await
+}
+
+/// Replace all the CORS rules of a bucket
+pub async fn put_cors_rules(
+    db: &DbConn,
+    bucket_name: String,
+    rules: Vec<CorsRule>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    cors_rule::Entity::delete_many()
+        .filter(cors_rule::Column::BucketName.eq(&bucket_name))
+        .exec(&txn)
+        .await?;
+
+    let rules: Vec<_> = rules
+        .into_iter()
+        .enumerate()
+        .map(|(rule_number, rule)| cors_rule::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            bucket_name: Set(bucket_name.clone()),
+            rule_number: Set(rule_number as i32),
+            rule_id: Set(rule.id),
+            allowed_origins: Set(strings_json(rule.allowed_origins)),
+            allowed_methods: Set(strings_json(rule.allowed_methods)),
+            allowed_headers: Set(strings_json(rule.allowed_headers)),
+            expose_headers: Set(strings_json(rule.expose_headers)),
+            max_age_seconds: Set(rule.max_age_seconds.map(|seconds| seconds as i32)),
+        })
+        .collect();
+    if !rules.is_empty() {
+        cors_rule::Entity::insert_many(rules).exec(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
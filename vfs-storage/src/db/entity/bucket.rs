@@ -4,8 +4,20 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
 #[sea_orm(table_name = "buckets")]
 pub struct Model {
-    #[sea_orm(primary_key, unique, indexed)]
-    pub name: String
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    pub region: String,
+    pub creation_date: DateTimeUtc,
+    /// `Enabled` or `Suspended`, `None` until versioning is first configured
+    pub versioning: Option<String>,
+    /// The bucket policy, kept as the JSON document it was put as
+    pub policy: Option<String>,
+    /// The ACL of the bucket, `None` until it's first given one
+    pub acl: Option<Json>,
+    /// `None` for buckets without ownership controls
+    pub object_ownership: Option<String>,
+    /// The canonical id of the user who created the bucket, `None` for the administrators'
+    pub owner_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -20,4 +32,4 @@ impl Related<super::object::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
+impl ActiveModelBehavior for ActiveModel {}
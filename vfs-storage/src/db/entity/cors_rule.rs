@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+/// A rule of a bucket's CORS configuration, in order of `rule_number`
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cors_rules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub bucket_name: String,
+    pub rule_number: i32,
+    pub rule_id: Option<String>,
+    /// Each of the lists below is stored as a JSON array of strings
+    pub allowed_origins: Json,
+    pub allowed_methods: Json,
+    pub allowed_headers: Json,
+    pub expose_headers: Json,
+    pub max_age_seconds: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::bucket::Entity",
+        from = "Column::BucketName",
+        to = "super::bucket::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Bucket,
+}
+
+impl Related<super::bucket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Bucket.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "group_members")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub group_name: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group::Entity",
+        from = "Column::GroupName",
+        to = "super::group::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Group,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserName",
+        to = "super::user::Column::Name",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
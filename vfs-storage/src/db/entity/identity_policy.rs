@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+
+/// A policy attached to a user or a group; exactly one of `user_name` and
+/// `group_name` is set
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "identity_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_name: Option<String>,
+    pub group_name: Option<String>,
+    pub name: String,
+    #[sea_orm(column_type = "Text")]
+    pub document: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserName",
+        to = "super::user::Column::Name",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::group::Entity",
+        from = "Column::GroupName",
+        to = "super::group::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Group,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
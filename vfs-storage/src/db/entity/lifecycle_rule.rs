@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+
+/// A rule of a bucket's lifecycle configuration, in order of `rule_number`
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "lifecycle_rules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub bucket_name: String,
+    pub rule_number: i32,
+    pub rule_id: Option<String>,
+    pub enabled: bool,
+    pub prefix: String,
+    /// The tags the rule's filter asks for as a JSON object
+    pub tags: Option<Json>,
+    pub object_size_greater_than: Option<i64>,
+    pub object_size_less_than: Option<i64>,
+    pub expiration_days: Option<i32>,
+    pub expiration_date: Option<DateTimeUtc>,
+    pub expired_object_delete_marker: bool,
+    pub noncurrent_days: Option<i32>,
+    pub abort_incomplete_upload_days: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::bucket::Entity",
+        from = "Column::BucketName",
+        to = "super::bucket::Column::Name",
+        on_delete = "Cascade"
+    )]
+    Bucket,
+}
+
+impl Related<super::bucket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Bucket.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
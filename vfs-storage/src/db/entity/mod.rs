@@ -1,2 +1,16 @@
+pub mod access_key;
 pub mod bucket;
-pub mod object;
\ No newline at end of file
+pub mod cors_rule;
+pub mod group;
+pub mod group_member;
+pub mod identity_policy;
+pub mod lifecycle_rule;
+pub mod multipart_upload;
+pub mod object;
+pub mod object_acl;
+pub mod object_chunk;
+pub mod object_tag;
+pub mod part;
+pub mod part_chunk;
+pub mod session;
+pub mod user;
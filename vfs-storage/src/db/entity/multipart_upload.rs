@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "multipart_uploads")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub upload_id: String,
+    pub bucket_name: String,
+    pub key: String,
+    pub initiated: DateTimeUtc,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    pub expires: Option<String>,
+    /// `x-amz-meta-*` headers as a JSON object of their names without the prefix
+    pub user_metadata: Option<Json>,
+    /// The tags the completed object gets, as a JSON object of their values by key
+    pub tags: Option<Json>,
+    /// The ACL the completed object gets, the default one when not given
+    pub acl: Option<Json>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::bucket::Entity",
+        from = "Column::BucketName",
+        to = "super::bucket::Column::Name"
+    )]
+    Bucket,
+    #[sea_orm(has_many = "super::part::Entity")]
+    Part,
+}
+
+impl Related<super::bucket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Bucket.def()
+    }
+}
+
+impl Related<super::part::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Part.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -11,7 +11,21 @@ pub struct Model {
     pub etag: String,
     pub last_modified: DateTimeUtc,
     pub bucket_name: String,
-    pub file_id: String
+    /// Empty for objects that are read from several files, see [`super::object_chunk`]
+    pub file_id: String,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    pub expires: Option<String>,
+    /// `x-amz-meta-*` headers as a JSON object of their names without the prefix
+    pub user_metadata: Option<Json>,
+    /// `null` for the version written while the bucket wasn't versioned
+    pub version_id: String,
+    /// Whether this is the current version of its key, of which there's at most one
+    pub is_latest: bool,
+    pub is_delete_marker: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -22,6 +36,12 @@ pub enum Relation {
         to = "super::bucket::Column::Name"
     )]
     Bucket,
+    #[sea_orm(has_many = "super::object_chunk::Entity")]
+    ObjectChunk,
+    #[sea_orm(has_many = "super::object_tag::Entity")]
+    ObjectTag,
+    #[sea_orm(has_one = "super::object_acl::Entity")]
+    ObjectAcl,
 }
 
 impl Related<super::bucket::Entity> for Entity {
@@ -30,4 +50,22 @@ impl Related<super::bucket::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
+impl Related<super::object_chunk::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ObjectChunk.def()
+    }
+}
+
+impl Related<super::object_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ObjectTag.def()
+    }
+}
+
+impl Related<super::object_acl::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ObjectAcl.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+
+/// The ACL of an object, which objects that were never given one don't have
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "object_acls")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub object_id: Uuid,
+    pub acl: Json,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::object::Entity",
+        from = "Column::ObjectId",
+        to = "super::object::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Object,
+}
+
+impl Related<super::object::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Object.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// One of the backend files an object assembled from several files is read from, in order of
+/// `chunk_number`. Objects completed from a multipart upload also record which part each chunk
+/// belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "object_chunks")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub object_id: Uuid,
+    pub chunk_number: i32,
+    pub size: i64,
+    pub file_id: String,
+    pub part_number: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::object::Entity",
+        from = "Column::ObjectId",
+        to = "super::object::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Object,
+}
+
+impl Related<super::object::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Object.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "parts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub upload_id: String,
+    pub part_number: i32,
+    pub size: i64,
+    pub etag: String,
+    /// Empty for parts copied from several existing files, see [`super::part_chunk`]
+    pub file_id: String,
+    pub last_modified: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::multipart_upload::Entity",
+        from = "Column::UploadId",
+        to = "super::multipart_upload::Column::UploadId",
+        on_delete = "Cascade"
+    )]
+    MultipartUpload,
+    #[sea_orm(has_many = "super::part_chunk::Entity")]
+    PartChunk,
+}
+
+impl Related<super::multipart_upload::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MultipartUpload.def()
+    }
+}
+
+impl Related<super::part_chunk::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PartChunk.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+
+/// One of the backend files a part copied from existing files is read from, in order of
+/// `chunk_number`
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "part_chunks")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub part_id: Uuid,
+    pub chunk_number: i32,
+    pub size: i64,
+    pub file_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::part::Entity",
+        from = "Column::PartId",
+        to = "super::part::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Part,
+}
+
+impl Related<super::part::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Part.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+/// Temporary credentials, which act for their parent access key until they expire
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "sessions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: String,
+    pub parent_access_key_id: String,
+    /// `None` for the sessions of an administrator
+    pub user_name: Option<String>,
+    pub session_name: String,
+    #[sea_orm(column_type = "Text")]
+    pub policy: String,
+    pub expiration: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserName",
+        to = "super::user::Column::Name",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
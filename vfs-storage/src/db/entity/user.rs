@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "users")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    #[sea_orm(unique)]
+    pub id: String,
+    pub creation_date: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::access_key::Entity")]
+    AccessKey,
+}
+
+impl Related<super::access_key::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccessKey.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
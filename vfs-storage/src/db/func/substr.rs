@@ -17,7 +17,7 @@ impl Iden for Substr {
 ///
 /// # Examples
 ///
-/// ```
+/// ```ignore
 /// use sea_query::{tests_cfg::*, *};
 ///
 /// let query = Query::select()
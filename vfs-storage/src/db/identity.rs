@@ -0,0 +1,328 @@
+use s3_entities::identity::{AccessKey, Group, PolicyHolder, Session, User};
+use sea_orm::*;
+
+use super::entity::{access_key, group, group_member, identity_policy, session, user};
+
+pub async fn create_user(db: &DbConn, user: User) -> Result<user::Model, DbErr> {
+    user::ActiveModel {
+        name: Set(user.name),
+        id: Set(user.id),
+        creation_date: Set(user.creation_date),
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get_user(db: &DbConn, name: String) -> Result<user::Model, DbErr> {
+    user::Entity::find_by_id(&name)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "user '{}' doesn't exist",
+            &name
+        )))
+}
+
+pub async fn list_users(db: &DbConn) -> Result<Vec<user::Model>, DbErr> {
+    user::Entity::find()
+        .order_by_asc(user::Column::Name)
+        .all(db)
+        .await
+}
+
+/// Delete a user along with their access keys, policies, sessions and group memberships
+pub async fn delete_user(db: &DbConn, name: String) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    session::Entity::delete_many()
+        .filter(session::Column::UserName.eq(&name))
+        .exec(&txn)
+        .await?;
+    access_key::Entity::delete_many()
+        .filter(access_key::Column::UserName.eq(&name))
+        .exec(&txn)
+        .await?;
+    identity_policy::Entity::delete_many()
+        .filter(identity_policy::Column::UserName.eq(&name))
+        .exec(&txn)
+        .await?;
+    group_member::Entity::delete_many()
+        .filter(group_member::Column::UserName.eq(&name))
+        .exec(&txn)
+        .await?;
+    let result = user::Entity::delete_by_id(&name).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(format!(
+            "user '{}' doesn't exist",
+            &name
+        )));
+    }
+
+    txn.commit().await
+}
+
+pub async fn create_access_key(
+    db: &DbConn,
+    access_key: AccessKey,
+) -> Result<access_key::Model, DbErr> {
+    access_key::ActiveModel {
+        access_key_id: Set(access_key.access_key_id),
+        secret_access_key: Set(access_key.secret_access_key),
+        user_name: Set(access_key.user_name),
+        status: Set(access_key.status.as_str().into()),
+        creation_date: Set(access_key.creation_date),
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get_access_key(
+    db: &DbConn,
+    access_key_id: String,
+) -> Result<access_key::Model, DbErr> {
+    access_key::Entity::find_by_id(&access_key_id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "access key '{}' doesn't exist",
+            &access_key_id
+        )))
+}
+
+/// The access keys of a user, oldest first
+pub async fn list_access_keys(
+    db: &DbConn,
+    user_name: String,
+) -> Result<Vec<access_key::Model>, DbErr> {
+    access_key::Entity::find()
+        .filter(access_key::Column::UserName.eq(user_name))
+        .order_by_asc(access_key::Column::CreationDate)
+        .order_by_asc(access_key::Column::AccessKeyId)
+        .all(db)
+        .await
+}
+
+pub async fn update_access_key(
+    db: &DbConn,
+    access_key_id: String,
+    status: String,
+) -> Result<access_key::Model, DbErr> {
+    let mut access_key: access_key::ActiveModel = get_access_key(db, access_key_id).await?.into();
+    access_key.status = Set(status);
+
+    access_key.update(db).await
+}
+
+pub async fn delete_access_key(db: &DbConn, access_key_id: String) -> Result<(), DbErr> {
+    let result = access_key::Entity::delete_by_id(&access_key_id)
+        .exec(db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(format!(
+            "access key '{}' doesn't exist",
+            &access_key_id
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn create_group(db: &DbConn, group: Group) -> Result<group::Model, DbErr> {
+    group::ActiveModel {
+        name: Set(group.name),
+        id: Set(group.id),
+        creation_date: Set(group.creation_date),
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get_group(db: &DbConn, name: String) -> Result<group::Model, DbErr> {
+    group::Entity::find_by_id(&name)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "group '{}' doesn't exist",
+            &name
+        )))
+}
+
+pub async fn list_groups(db: &DbConn) -> Result<Vec<group::Model>, DbErr> {
+    group::Entity::find()
+        .order_by_asc(group::Column::Name)
+        .all(db)
+        .await
+}
+
+/// Delete a group along with its policies and memberships
+pub async fn delete_group(db: &DbConn, name: String) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    identity_policy::Entity::delete_many()
+        .filter(identity_policy::Column::GroupName.eq(&name))
+        .exec(&txn)
+        .await?;
+    group_member::Entity::delete_many()
+        .filter(group_member::Column::GroupName.eq(&name))
+        .exec(&txn)
+        .await?;
+    let result = group::Entity::delete_by_id(&name).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(format!(
+            "group '{}' doesn't exist",
+            &name
+        )));
+    }
+
+    txn.commit().await
+}
+
+pub async fn add_user_to_group(
+    db: &DbConn,
+    group_name: String,
+    user_name: String,
+) -> Result<(), DbErr> {
+    group_member::Entity::insert(group_member::ActiveModel {
+        group_name: Set(group_name),
+        user_name: Set(user_name),
+    })
+    .on_conflict(
+        sea_query::OnConflict::columns([
+            group_member::Column::GroupName,
+            group_member::Column::UserName,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_user_from_group(
+    db: &DbConn,
+    group_name: String,
+    user_name: String,
+) -> Result<(), DbErr> {
+    group_member::Entity::delete_many()
+        .filter(group_member::Column::GroupName.eq(group_name))
+        .filter(group_member::Column::UserName.eq(user_name))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn list_groups_for_user(
+    db: &DbConn,
+    user_name: String,
+) -> Result<Vec<group::Model>, DbErr> {
+    group::Entity::find()
+        .inner_join(group_member::Entity)
+        .filter(group_member::Column::UserName.eq(user_name))
+        .order_by_asc(group::Column::Name)
+        .all(db)
+        .await
+}
+
+fn holder_condition(holder: &PolicyHolder) -> Condition {
+    match holder {
+        PolicyHolder::User(user_name) => {
+            Condition::all().add(identity_policy::Column::UserName.eq(user_name))
+        }
+        PolicyHolder::Group(group_name) => {
+            Condition::all().add(identity_policy::Column::GroupName.eq(group_name))
+        }
+    }
+}
+
+/// Replace the policy of the given name, or delete it if `document` is `None`
+pub async fn put_identity_policy(
+    db: &DbConn,
+    holder: &PolicyHolder,
+    name: String,
+    document: Option<String>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    let result = identity_policy::Entity::delete_many()
+        .filter(holder_condition(holder))
+        .filter(identity_policy::Column::Name.eq(&name))
+        .exec(&txn)
+        .await?;
+    match document {
+        Some(document) => {
+            let (user_name, group_name) = match holder {
+                PolicyHolder::User(user_name) => (Some(user_name.clone()), None),
+                PolicyHolder::Group(group_name) => (None, Some(group_name.clone())),
+            };
+            identity_policy::ActiveModel {
+                id: Set(uuid::Uuid::new_v4()),
+                user_name: Set(user_name),
+                group_name: Set(group_name),
+                name: Set(name),
+                document: Set(document),
+            }
+            .insert(&txn)
+            .await?;
+        }
+        None if result.rows_affected == 0 => {
+            return Err(DbErr::RecordNotFound(format!(
+                "policy '{}' doesn't exist",
+                &name
+            )));
+        }
+        None => {}
+    }
+
+    txn.commit().await
+}
+
+pub async fn list_identity_policies(
+    db: &DbConn,
+    holder: &PolicyHolder,
+) -> Result<Vec<identity_policy::Model>, DbErr> {
+    identity_policy::Entity::find()
+        .filter(holder_condition(holder))
+        .order_by_asc(identity_policy::Column::Name)
+        .all(db)
+        .await
+}
+
+pub async fn create_session(db: &DbConn, session: Session) -> Result<session::Model, DbErr> {
+    session::ActiveModel {
+        access_key_id: Set(session.access_key_id),
+        secret_access_key: Set(session.secret_access_key),
+        session_token: Set(session.session_token),
+        parent_access_key_id: Set(session.parent_access_key_id),
+        user_name: Set(session.user_name),
+        session_name: Set(session.session_name),
+        policy: Set(session.policy),
+        expiration: Set(session.expiration),
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get_session(db: &DbConn, access_key_id: String) -> Result<session::Model, DbErr> {
+    session::Entity::find_by_id(&access_key_id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "session '{}' doesn't exist",
+            &access_key_id
+        )))
+}
+
+pub async fn delete_expired_sessions(
+    db: &DbConn,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<u64, DbErr> {
+    let result = session::Entity::delete_many()
+        .filter(session::Column::Expiration.lt(before))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,79 @@
+use s3_entities::lifecycle::{Expiration, LifecycleRule};
+use sea_orm::*;
+
+use super::{entity::lifecycle_rule, object::user_metadata_json};
+
+/// The lifecycle rules of a bucket, in the order they were configured
+pub async fn get_lifecycle_rules(
+    db: &DbConn,
+    bucket_name: String,
+) -> Result<Vec<lifecycle_rule::Model>, DbErr> {
+    lifecycle_rule::Entity::find()
+        .filter(lifecycle_rule::Column::BucketName.eq(bucket_name))
+        .order_by_asc(lifecycle_rule::Column::RuleNumber)
+        .all(db)
+        .await
+}
+
+/// Replace all the lifecycle rules of a bucket
+pub async fn put_lifecycle_rules(
+    db: &DbConn,
+    bucket_name: String,
+    rules: Vec<LifecycleRule>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    lifecycle_rule::Entity::delete_many()
+        .filter(lifecycle_rule::Column::BucketName.eq(&bucket_name))
+        .exec(&txn)
+        .await?;
+
+    let rules: Vec<_> = rules
+        .into_iter()
+        .enumerate()
+        .map(|(rule_number, rule)| {
+            let (expiration_days, expiration_date) = match rule.expiration {
+                Some(Expiration::Days(days)) => (Some(days as i32), None),
+                Some(Expiration::Date(date)) => (None, Some(date)),
+                Some(Expiration::ExpiredObjectDeleteMarker) | None => (None, None),
+            };
+
+            lifecycle_rule::ActiveModel {
+                id: Set(uuid::Uuid::new_v4()),
+                bucket_name: Set(bucket_name.clone()),
+                rule_number: Set(rule_number as i32),
+                rule_id: Set(rule.id),
+                enabled: Set(rule.enabled),
+                prefix: Set(rule.filter.prefix),
+                // Stored the same way as user-defined metadata
+                tags: Set(user_metadata_json(rule.filter.tags)),
+                object_size_greater_than: Set(rule
+                    .filter
+                    .object_size_greater_than
+                    .map(|size| size as i64)),
+                object_size_less_than: Set(rule
+                    .filter
+                    .object_size_less_than
+                    .map(|size| size as i64)),
+                expiration_days: Set(expiration_days),
+                expiration_date: Set(expiration_date),
+                expired_object_delete_marker: Set(
+                    rule.expiration == Some(Expiration::ExpiredObjectDeleteMarker)
+                ),
+                noncurrent_days: Set(rule.noncurrent_days.map(|days| days as i32)),
+                abort_incomplete_upload_days: Set(rule
+                    .abort_incomplete_upload_days
+                    .map(|days| days as i32)),
+            }
+        })
+        .collect();
+    if !rules.is_empty() {
+        lifecycle_rule::Entity::insert_many(rules)
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
@@ -18,17 +18,17 @@ impl MigrationTrait for Migration {
                 Table::create()
                     .table(Object::Table)
                     .col(ColumnDef::new(Object::Id).uuid().not_null().primary_key())
-                    .col(ColumnDef::new(Object::Key).string().not_null().unique_key())
+                    .col(ColumnDef::new(Object::Key).string().not_null())
                     .col(ColumnDef::new(Object::Size).big_integer().not_null())
                     .col(ColumnDef::new(Object::ETag).string().not_null())
                     .col(ColumnDef::new(Object::LastModified).timestamp().not_null())
-                    .col(ColumnDef::new(Object::BucketId).uuid().not_null())
+                    .col(ColumnDef::new(Object::BucketName).string().not_null())
                     .col(ColumnDef::new(Object::FileId).string().not_null())
                     .foreign_key(
                         ForeignKey::create()
-                            .name("fk-object-bucket_id")
-                            .from(Object::Table, Object::BucketId)
-                            .to(Bucket::Table, Bucket::Id),
+                            .name("fk-object-bucket_name")
+                            .from(Object::Table, Object::BucketName)
+                            .to(Bucket::Table, Bucket::Name),
                     )
                     .to_owned(),
             )
@@ -38,9 +38,11 @@ impl MigrationTrait for Migration {
             .create_index(
                 Index::create()
                     .if_not_exists()
-                    .name("idx-object-key")
+                    .name("idx-object-bucket_name-key")
                     .table(Object::Table)
+                    .col(Object::BucketName)
                     .col(Object::Key)
+                    .unique()
                     .to_owned(),
             )
             .await?;
@@ -57,6 +59,7 @@ impl MigrationTrait for Migration {
 
 #[derive(Iden)]
 pub enum Object {
+    #[iden = "objects"]
     Table,
     Id,
     Key,
@@ -64,6 +67,6 @@ pub enum Object {
     #[iden = "etag"]
     ETag,
     LastModified,
-    BucketId,
+    BucketName,
     FileId,
 }
@@ -15,13 +15,14 @@ impl MigrationTrait for Migration {
             .create_table(
                 Table::create()
                     .table(Bucket::Table)
-                    .col(ColumnDef::new(Bucket::Id).uuid().not_null().primary_key())
                     .col(
                         ColumnDef::new(Bucket::Name)
                             .string()
                             .not_null()
-                            .unique_key(),
+                            .primary_key(),
                     )
+                    .col(ColumnDef::new(Bucket::Region).string().not_null())
+                    .col(ColumnDef::new(Bucket::CreationDate).timestamp().not_null())
                     .to_owned(),
             )
             .await?;
@@ -55,7 +56,9 @@ impl MigrationTrait for Migration {
 
 #[derive(Iden)]
 pub enum Bucket {
+    #[iden = "buckets"]
     Table,
-    Id,
     Name,
+    Region,
+    CreationDate,
 }
@@ -0,0 +1,120 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000002_create_bucket_table::Bucket;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000003_create_multipart_upload_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MultipartUpload::Table)
+                    .col(
+                        ColumnDef::new(MultipartUpload::UploadId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MultipartUpload::BucketName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MultipartUpload::Key).string().not_null())
+                    .col(
+                        ColumnDef::new(MultipartUpload::Initiated)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-multipart_upload-bucket_name")
+                            .from(MultipartUpload::Table, MultipartUpload::BucketName)
+                            .to(Bucket::Table, Bucket::Name),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Part::Table)
+                    .col(ColumnDef::new(Part::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Part::UploadId).string().not_null())
+                    .col(ColumnDef::new(Part::PartNumber).integer().not_null())
+                    .col(ColumnDef::new(Part::Size).big_integer().not_null())
+                    .col(ColumnDef::new(Part::ETag).string().not_null())
+                    .col(ColumnDef::new(Part::FileId).string().not_null())
+                    .col(ColumnDef::new(Part::LastModified).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-part-upload_id")
+                            .from(Part::Table, Part::UploadId)
+                            .to(MultipartUpload::Table, MultipartUpload::UploadId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-part-upload_id-part_number")
+                    .table(Part::Table)
+                    .col(Part::UploadId)
+                    .col(Part::PartNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Part::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(MultipartUpload::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub enum MultipartUpload {
+    #[iden = "multipart_uploads"]
+    Table,
+    UploadId,
+    BucketName,
+    Key,
+    Initiated,
+}
+
+#[derive(Iden)]
+pub enum Part {
+    #[iden = "parts"]
+    Table,
+    Id,
+    UploadId,
+    PartNumber,
+    Size,
+    #[iden = "etag"]
+    ETag,
+    FileId,
+    LastModified,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000001_create_objects_table::Object;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000004_create_object_chunks_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ObjectChunk::Table)
+                    .col(
+                        ColumnDef::new(ObjectChunk::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ObjectChunk::ObjectId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ObjectChunk::ChunkNumber)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ObjectChunk::Size).big_integer().not_null())
+                    .col(ColumnDef::new(ObjectChunk::FileId).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-object_chunk-object_id")
+                            .from(ObjectChunk::Table, ObjectChunk::ObjectId)
+                            .to(Object::Table, Object::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-object_chunk-object_id-chunk_number")
+                    .table(ObjectChunk::Table)
+                    .col(ObjectChunk::ObjectId)
+                    .col(ObjectChunk::ChunkNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ObjectChunk::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ObjectChunk {
+    #[iden = "object_chunks"]
+    Table,
+    Id,
+    ObjectId,
+    ChunkNumber,
+    Size,
+    FileId,
+}
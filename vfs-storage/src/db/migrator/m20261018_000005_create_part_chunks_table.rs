@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20261018_000003_create_multipart_upload_tables::Part;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000005_create_part_chunks_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PartChunk::Table)
+                    .col(
+                        ColumnDef::new(PartChunk::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PartChunk::PartId).uuid().not_null())
+                    .col(ColumnDef::new(PartChunk::ChunkNumber).integer().not_null())
+                    .col(ColumnDef::new(PartChunk::Size).big_integer().not_null())
+                    .col(ColumnDef::new(PartChunk::FileId).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-part_chunk-part_id")
+                            .from(PartChunk::Table, PartChunk::PartId)
+                            .to(Part::Table, Part::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-part_chunk-part_id-chunk_number")
+                    .table(PartChunk::Table)
+                    .col(PartChunk::PartId)
+                    .col(PartChunk::ChunkNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PartChunk::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum PartChunk {
+    #[iden = "part_chunks"]
+    Table,
+    Id,
+    PartId,
+    ChunkNumber,
+    Size,
+    FileId,
+}
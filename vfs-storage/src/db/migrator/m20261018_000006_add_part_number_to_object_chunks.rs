@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20261018_000004_create_object_chunks_table::ObjectChunk;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000006_add_part_number_to_object_chunks"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ObjectChunk::Table)
+                    .add_column(ColumnDef::new(PartNumber::PartNumber).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ObjectChunk::Table)
+                    .drop_column(PartNumber::PartNumber)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PartNumber {
+    PartNumber,
+}
@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+use super::{
+    m20230730_000001_create_objects_table::Object,
+    m20261018_000003_create_multipart_upload_tables::MultipartUpload,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000007_add_metadata_columns"
+    }
+}
+
+/// Uploads keep the metadata they're created with until they're completed into an object
+fn tables() -> [DynIden; 2] {
+    [
+        SeaRc::new(Object::Table),
+        SeaRc::new(MultipartUpload::Table),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only alters one column at a time
+        for table in tables() {
+            for column in Metadata::headers() {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(table.clone())
+                            .add_column(ColumnDef::new(column).string().null())
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(ColumnDef::new(Metadata::UserMetadata).json().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in tables() {
+            for column in Metadata::headers()
+                .into_iter()
+                .chain([Metadata::UserMetadata])
+            {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(table.clone())
+                            .drop_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden, Clone, Copy)]
+enum Metadata {
+    ContentType,
+    ContentEncoding,
+    ContentDisposition,
+    ContentLanguage,
+    CacheControl,
+    Expires,
+    UserMetadata,
+}
+
+impl Metadata {
+    fn headers() -> [Metadata; 6] {
+        [
+            Metadata::ContentType,
+            Metadata::ContentEncoding,
+            Metadata::ContentDisposition,
+            Metadata::ContentLanguage,
+            Metadata::CacheControl,
+            Metadata::Expires,
+        ]
+    }
+}
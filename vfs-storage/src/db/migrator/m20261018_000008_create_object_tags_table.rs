@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000001_create_objects_table::Object;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000008_create_object_tags_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ObjectTag::Table)
+                    .col(
+                        ColumnDef::new(ObjectTag::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ObjectTag::ObjectId).uuid().not_null())
+                    .col(ColumnDef::new(ObjectTag::Key).string().not_null())
+                    .col(ColumnDef::new(ObjectTag::Value).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-object_tag-object_id")
+                            .from(ObjectTag::Table, ObjectTag::ObjectId)
+                            .to(Object::Table, Object::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-object_tag-object_id-key")
+                    .table(ObjectTag::Table)
+                    .col(ObjectTag::ObjectId)
+                    .col(ObjectTag::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Finds the objects carrying a tag, for filtering by tags
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-object_tag-key-value")
+                    .table(ObjectTag::Table)
+                    .col(ObjectTag::Key)
+                    .col(ObjectTag::Value)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ObjectTag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ObjectTag {
+    #[iden = "object_tags"]
+    Table,
+    Id,
+    ObjectId,
+    Key,
+    Value,
+}
@@ -0,0 +1,156 @@
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+use super::{
+    m20230730_000001_create_objects_table::Object, m20230730_000002_create_bucket_table::Bucket,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000009_add_object_versions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .add_column(ColumnDef::new(Versions::Versioning).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Objects stored so far become the `null` versions of their keys, SQLite only alters one
+        // column at a time
+        for mut column in [
+            ColumnDef::new(Versions::VersionId)
+                .string()
+                .not_null()
+                .default("null")
+                .to_owned(),
+            ColumnDef::new(Versions::IsLatest)
+                .boolean()
+                .not_null()
+                .default(true)
+                .to_owned(),
+            ColumnDef::new(Versions::IsDeleteMarker)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Object::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-object-bucket_name-key")
+                    .table(Object::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-object-bucket_name-key-version_id")
+                    .table(Object::Table)
+                    .col(Object::BucketName)
+                    .col(Object::Key)
+                    .col(Versions::VersionId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Only one version of a key can be the latest, which is what fails a conditional write
+        // racing another one. sea-query can't create partial indexes, and MySQL has none.
+        if manager.get_database_backend() != DbBackend::MySql {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    r#"CREATE UNIQUE INDEX IF NOT EXISTS "idx-object-bucket_name-key-latest" ON "objects" ("bucket_name", "key") WHERE "is_latest""#,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::MySql {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name("idx-object-bucket_name-key-latest")
+                        .table(Object::Table)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-object-bucket_name-key-version_id")
+                    .table(Object::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for column in [
+            Versions::VersionId,
+            Versions::IsLatest,
+            Versions::IsDeleteMarker,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Object::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-object-bucket_name-key")
+                    .table(Object::Table)
+                    .col(Object::BucketName)
+                    .col(Object::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .drop_column(Versions::Versioning)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Versions {
+    Versioning,
+    VersionId,
+    IsLatest,
+    IsDeleteMarker,
+}
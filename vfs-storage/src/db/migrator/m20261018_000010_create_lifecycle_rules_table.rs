@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000002_create_bucket_table::Bucket;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000010_create_lifecycle_rules_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LifecycleRule::Table)
+                    .col(
+                        ColumnDef::new(LifecycleRule::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::BucketName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::RuleNumber)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LifecycleRule::RuleId).string().null())
+                    .col(ColumnDef::new(LifecycleRule::Enabled).boolean().not_null())
+                    .col(ColumnDef::new(LifecycleRule::Prefix).string().not_null())
+                    .col(ColumnDef::new(LifecycleRule::Tags).json().null())
+                    .col(
+                        ColumnDef::new(LifecycleRule::ObjectSizeGreaterThan)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::ObjectSizeLessThan)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::ExpirationDays)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::ExpirationDate)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::ExpiredObjectDeleteMarker)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::NoncurrentDays)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LifecycleRule::AbortIncompleteUploadDays)
+                            .integer()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-lifecycle_rule-bucket_name")
+                            .from(LifecycleRule::Table, LifecycleRule::BucketName)
+                            .to(Bucket::Table, Bucket::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-lifecycle_rule-bucket_name-rule_number")
+                    .table(LifecycleRule::Table)
+                    .col(LifecycleRule::BucketName)
+                    .col(LifecycleRule::RuleNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LifecycleRule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum LifecycleRule {
+    #[iden = "lifecycle_rules"]
+    Table,
+    Id,
+    BucketName,
+    RuleNumber,
+    RuleId,
+    Enabled,
+    Prefix,
+    Tags,
+    ObjectSizeGreaterThan,
+    ObjectSizeLessThan,
+    ExpirationDays,
+    ExpirationDate,
+    ExpiredObjectDeleteMarker,
+    NoncurrentDays,
+    AbortIncompleteUploadDays,
+}
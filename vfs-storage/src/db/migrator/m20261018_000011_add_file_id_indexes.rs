@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+use super::{
+    m20230730_000001_create_objects_table::Object,
+    m20261018_000003_create_multipart_upload_tables::Part,
+    m20261018_000004_create_object_chunks_table::ObjectChunk,
+    m20261018_000005_create_part_chunks_table::PartChunk,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000011_add_file_id_indexes"
+    }
+}
+
+/// The tables that refer to backend files, which have to be checked before a file is deleted
+/// since copies share their files
+fn indexes() -> [(&'static str, DynIden, DynIden); 4] {
+    [
+        (
+            "idx-object-file_id",
+            SeaRc::new(Object::Table),
+            SeaRc::new(Object::FileId),
+        ),
+        (
+            "idx-object_chunk-file_id",
+            SeaRc::new(ObjectChunk::Table),
+            SeaRc::new(ObjectChunk::FileId),
+        ),
+        (
+            "idx-part-file_id",
+            SeaRc::new(Part::Table),
+            SeaRc::new(Part::FileId),
+        ),
+        (
+            "idx-part_chunk-file_id",
+            SeaRc::new(PartChunk::Table),
+            SeaRc::new(PartChunk::FileId),
+        ),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, column) in indexes() {
+            manager
+                .create_index(
+                    Index::create()
+                        .if_not_exists()
+                        .name(name)
+                        .table(table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, _) in indexes() {
+            manager
+                .drop_index(Index::drop().name(name).table(table).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000002_create_bucket_table::Bucket;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000012_create_cors_rules_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CorsRule::Table)
+                    .col(ColumnDef::new(CorsRule::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(CorsRule::BucketName).string().not_null())
+                    .col(ColumnDef::new(CorsRule::RuleNumber).integer().not_null())
+                    .col(ColumnDef::new(CorsRule::RuleId).string().null())
+                    .col(ColumnDef::new(CorsRule::AllowedOrigins).json().not_null())
+                    .col(ColumnDef::new(CorsRule::AllowedMethods).json().not_null())
+                    .col(ColumnDef::new(CorsRule::AllowedHeaders).json().not_null())
+                    .col(ColumnDef::new(CorsRule::ExposeHeaders).json().not_null())
+                    .col(ColumnDef::new(CorsRule::MaxAgeSeconds).integer().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-cors_rule-bucket_name")
+                            .from(CorsRule::Table, CorsRule::BucketName)
+                            .to(Bucket::Table, Bucket::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-cors_rule-bucket_name-rule_number")
+                    .table(CorsRule::Table)
+                    .col(CorsRule::BucketName)
+                    .col(CorsRule::RuleNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CorsRule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum CorsRule {
+    #[iden = "cors_rules"]
+    Table,
+    Id,
+    BucketName,
+    RuleNumber,
+    RuleId,
+    AllowedOrigins,
+    AllowedMethods,
+    AllowedHeaders,
+    ExposeHeaders,
+    MaxAgeSeconds,
+}
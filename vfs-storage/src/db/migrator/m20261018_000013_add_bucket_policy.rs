@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20230730_000002_create_bucket_table::Bucket;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000013_add_bucket_policy"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .add_column(ColumnDef::new(BucketPolicy::Policy).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .drop_column(BucketPolicy::Policy)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum BucketPolicy {
+    Policy,
+}
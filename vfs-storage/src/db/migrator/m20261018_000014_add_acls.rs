@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+use super::{
+    m20230730_000001_create_objects_table::Object, m20230730_000002_create_bucket_table::Bucket,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000014_add_acls"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only alters one column at a time
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .add_column(ColumnDef::new(BucketAcl::Acl).json().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .add_column(ColumnDef::new(BucketAcl::ObjectOwnership).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Kept apart from the objects, like their tags, since most objects never get one
+        manager
+            .create_table(
+                Table::create()
+                    .table(ObjectAcl::Table)
+                    .col(
+                        ColumnDef::new(ObjectAcl::ObjectId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ObjectAcl::Acl).json().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-object_acl-object_id")
+                            .from(ObjectAcl::Table, ObjectAcl::ObjectId)
+                            .to(Object::Table, Object::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ObjectAcl::Table).to_owned())
+            .await?;
+
+        for column in [BucketAcl::Acl, BucketAcl::ObjectOwnership] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Bucket::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub enum BucketAcl {
+    Acl,
+    ObjectOwnership,
+}
+
+#[derive(Iden)]
+pub enum ObjectAcl {
+    #[iden = "object_acls"]
+    Table,
+    ObjectId,
+    Acl,
+}
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000015_create_identity_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(User::Table)
+                    .col(ColumnDef::new(User::Name).string().not_null().primary_key())
+                    .col(ColumnDef::new(User::Id).string().not_null().unique_key())
+                    .col(ColumnDef::new(User::CreationDate).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccessKey::Table)
+                    .col(
+                        ColumnDef::new(AccessKey::AccessKeyId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AccessKey::SecretAccessKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccessKey::UserName).string().not_null())
+                    .col(ColumnDef::new(AccessKey::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(AccessKey::CreationDate)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-access_key-user_name")
+                            .from(AccessKey::Table, AccessKey::UserName)
+                            .to(User::Table, User::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-access_key-user_name")
+                    .table(AccessKey::Table)
+                    .col(AccessKey::UserName)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccessKey::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(User::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum User {
+    #[iden = "users"]
+    Table,
+    Name,
+    Id,
+    CreationDate,
+}
+
+#[derive(Iden)]
+pub enum AccessKey {
+    #[iden = "access_keys"]
+    Table,
+    AccessKeyId,
+    SecretAccessKey,
+    UserName,
+    Status,
+    CreationDate,
+}
@@ -0,0 +1,134 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20261018_000015_create_identity_tables::User;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000016_create_group_and_policy_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Group::Table)
+                    .col(
+                        ColumnDef::new(Group::Name)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Group::Id).string().not_null().unique_key())
+                    .col(ColumnDef::new(Group::CreationDate).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(GroupMember::Table)
+                    .col(ColumnDef::new(GroupMember::GroupName).string().not_null())
+                    .col(ColumnDef::new(GroupMember::UserName).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(GroupMember::GroupName)
+                            .col(GroupMember::UserName),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-group_member-group_name")
+                            .from(GroupMember::Table, GroupMember::GroupName)
+                            .to(Group::Table, Group::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-group_member-user_name")
+                            .from(GroupMember::Table, GroupMember::UserName)
+                            .to(User::Table, User::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A policy belongs to either a user or a group
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdentityPolicy::Table)
+                    .col(
+                        ColumnDef::new(IdentityPolicy::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdentityPolicy::UserName).string().null())
+                    .col(ColumnDef::new(IdentityPolicy::GroupName).string().null())
+                    .col(ColumnDef::new(IdentityPolicy::Name).string().not_null())
+                    .col(ColumnDef::new(IdentityPolicy::Document).text().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-identity_policy-user_name")
+                            .from(IdentityPolicy::Table, IdentityPolicy::UserName)
+                            .to(User::Table, User::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-identity_policy-group_name")
+                            .from(IdentityPolicy::Table, IdentityPolicy::GroupName)
+                            .to(Group::Table, Group::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdentityPolicy::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(GroupMember::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Group::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Group {
+    #[iden = "groups"]
+    Table,
+    Name,
+    Id,
+    CreationDate,
+}
+
+#[derive(Iden)]
+pub enum GroupMember {
+    #[iden = "group_members"]
+    Table,
+    GroupName,
+    UserName,
+}
+
+#[derive(Iden)]
+pub enum IdentityPolicy {
+    #[iden = "identity_policies"]
+    Table,
+    Id,
+    UserName,
+    GroupName,
+    Name,
+    Document,
+}
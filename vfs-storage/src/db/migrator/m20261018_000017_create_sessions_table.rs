@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20261018_000015_create_identity_tables::User;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000017_create_sessions_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Session::Table)
+                    .col(
+                        ColumnDef::new(Session::AccessKeyId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Session::SecretAccessKey).string().not_null())
+                    .col(ColumnDef::new(Session::SessionToken).string().not_null())
+                    .col(
+                        ColumnDef::new(Session::ParentAccessKeyId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Session::UserName).string().null())
+                    .col(ColumnDef::new(Session::SessionName).string().not_null())
+                    .col(ColumnDef::new(Session::Policy).text().not_null())
+                    .col(ColumnDef::new(Session::Expiration).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-session-user_name")
+                            .from(Session::Table, Session::UserName)
+                            .to(User::Table, User::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Expired sessions are purged by their expiration
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-session-expiration")
+                    .table(Session::Table)
+                    .col(Session::Expiration)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Session::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Session {
+    #[iden = "sessions"]
+    Table,
+    AccessKeyId,
+    SecretAccessKey,
+    SessionToken,
+    ParentAccessKeyId,
+    UserName,
+    SessionName,
+    Policy,
+    Expiration,
+}
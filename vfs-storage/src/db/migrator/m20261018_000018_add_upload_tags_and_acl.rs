@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20261018_000003_create_multipart_upload_tables::MultipartUpload;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000018_add_upload_tags_and_acl"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only alters one column at a time
+        for column in [UploadTagsAndAcl::Tags, UploadTagsAndAcl::Acl] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(MultipartUpload::Table)
+                        .add_column(ColumnDef::new(column).json().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [UploadTagsAndAcl::Tags, UploadTagsAndAcl::Acl] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(MultipartUpload::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub enum UploadTagsAndAcl {
+    Tags,
+    Acl,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+use super::m20230730_000002_create_bucket_table::Bucket;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_20261018_000019_add_bucket_owner"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .add_column(ColumnDef::new(BucketOwner::OwnerId).string().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx-bucket-owner_id")
+                    .table(Bucket::Table)
+                    .col(BucketOwner::OwnerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The owners of the buckets created so far are only in their ACLs
+        let owner_id = match manager.get_database_backend() {
+            DbBackend::Postgres => r#"NULLIF("acl"::jsonb -> 'owner' ->> 'id', '')"#,
+            DbBackend::MySql => "NULLIF(JSON_UNQUOTE(JSON_EXTRACT(`acl`, '$.owner.id')), '')",
+            DbBackend::Sqlite => r#"NULLIF(json_extract("acl", '$.owner.id'), '')"#,
+        };
+        let quote = match manager.get_database_backend() {
+            DbBackend::MySql => '`',
+            _ => '"',
+        };
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "UPDATE {quote}buckets{quote} SET {quote}owner_id{quote} = {owner_id} WHERE {quote}acl{quote} IS NOT NULL"
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-bucket-owner_id")
+                    .table(Bucket::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Bucket::Table)
+                    .drop_column(BucketOwner::OwnerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub enum BucketOwner {
+    OwnerId,
+}
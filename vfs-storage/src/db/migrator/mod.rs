@@ -2,6 +2,23 @@ use sea_orm_migration::prelude::*;
 
 mod m20230730_000001_create_objects_table;
 mod m20230730_000002_create_bucket_table;
+mod m20261018_000003_create_multipart_upload_tables;
+mod m20261018_000004_create_object_chunks_table;
+mod m20261018_000005_create_part_chunks_table;
+mod m20261018_000006_add_part_number_to_object_chunks;
+mod m20261018_000007_add_metadata_columns;
+mod m20261018_000008_create_object_tags_table;
+mod m20261018_000009_add_object_versions;
+mod m20261018_000010_create_lifecycle_rules_table;
+mod m20261018_000011_add_file_id_indexes;
+mod m20261018_000012_create_cors_rules_table;
+mod m20261018_000013_add_bucket_policy;
+mod m20261018_000014_add_acls;
+mod m20261018_000015_create_identity_tables;
+mod m20261018_000016_create_group_and_policy_tables;
+mod m20261018_000017_create_sessions_table;
+mod m20261018_000018_add_upload_tags_and_acl;
+mod m20261018_000019_add_bucket_owner;
 
 pub struct Migrator;
 
@@ -11,6 +28,23 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20230730_000001_create_objects_table::Migration),
             Box::new(m20230730_000002_create_bucket_table::Migration),
+            Box::new(m20261018_000003_create_multipart_upload_tables::Migration),
+            Box::new(m20261018_000004_create_object_chunks_table::Migration),
+            Box::new(m20261018_000005_create_part_chunks_table::Migration),
+            Box::new(m20261018_000006_add_part_number_to_object_chunks::Migration),
+            Box::new(m20261018_000007_add_metadata_columns::Migration),
+            Box::new(m20261018_000008_create_object_tags_table::Migration),
+            Box::new(m20261018_000009_add_object_versions::Migration),
+            Box::new(m20261018_000010_create_lifecycle_rules_table::Migration),
+            Box::new(m20261018_000011_add_file_id_indexes::Migration),
+            Box::new(m20261018_000012_create_cors_rules_table::Migration),
+            Box::new(m20261018_000013_add_bucket_policy::Migration),
+            Box::new(m20261018_000014_add_acls::Migration),
+            Box::new(m20261018_000015_create_identity_tables::Migration),
+            Box::new(m20261018_000016_create_group_and_policy_tables::Migration),
+            Box::new(m20261018_000017_create_sessions_table::Migration),
+            Box::new(m20261018_000018_add_upload_tags_and_acl::Migration),
+            Box::new(m20261018_000019_add_bucket_owner::Migration),
         ]
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,19 @@
 mod connection;
-mod entity;
+pub mod entity;
 mod func;
 mod migrator;
 
 mod bucket;
+mod cors;
+mod identity;
+mod lifecycle;
+mod multipart;
 mod object;
 
-pub use entity::*;
+pub use multipart::*;
 pub use object::*;
 pub use bucket::*;
+pub use cors::*;
+pub use identity::*;
+pub use lifecycle::*;
 pub use connection::*;
\ No newline at end of file
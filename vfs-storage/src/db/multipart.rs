@@ -0,0 +1,456 @@
+use s3_entities::{bucket::VersioningStatus, object::ObjectMetadata};
+use sea_orm::{prelude::Json, *};
+use std::collections::BTreeMap;
+
+use super::{
+    entity::{multipart_upload, object, object_chunk, part, part_chunk},
+    object::{insert_tags_and_acl, new_version, unreferenced_files},
+    user_metadata, user_metadata_json,
+};
+
+const MAX_UPLOADS: u64 = 1000;
+const MAX_PARTS: u64 = 10000;
+
+/// Start an upload, which keeps the metadata, tags and ACL of the object it's completed into
+/// until then
+pub async fn create_multipart_upload(
+    db: &DbConn,
+    bucket_name: String,
+    key: String,
+    metadata: ObjectMetadata,
+    tags: BTreeMap<String, String>,
+    acl: Option<Json>,
+) -> Result<multipart_upload::Model, DbErr> {
+    multipart_upload::ActiveModel {
+        upload_id: Set(uuid::Uuid::new_v4().simple().to_string()),
+        bucket_name: Set(bucket_name),
+        key: Set(key),
+        initiated: Set(chrono::Utc::now()),
+        content_type: Set(metadata.content_type),
+        content_encoding: Set(metadata.content_encoding),
+        content_disposition: Set(metadata.content_disposition),
+        content_language: Set(metadata.content_language),
+        cache_control: Set(metadata.cache_control),
+        expires: Set(metadata.expires),
+        user_metadata: Set(user_metadata_json(metadata.user)),
+        tags: Set(user_metadata_json(tags)),
+        acl: Set(acl),
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get_multipart_upload(
+    db: &DbConn,
+    bucket_name: String,
+    key: String,
+    upload_id: String,
+) -> Result<multipart_upload::Model, DbErr> {
+    multipart_upload::Entity::find()
+        .filter(multipart_upload::Column::UploadId.eq(&upload_id))
+        .filter(multipart_upload::Column::BucketName.eq(bucket_name))
+        .filter(multipart_upload::Column::Key.eq(key))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "upload '{}' doesn't exist",
+            &upload_id
+        )))
+}
+
+/// List the uploads in a bucket, in order of key and then upload id
+pub async fn list_multipart_uploads(
+    db: &DbConn,
+    bucket_name: String,
+    key_marker: Option<String>,
+    upload_id_marker: Option<String>,
+    max_uploads: Option<u64>,
+    prefix: Option<String>,
+) -> Result<Vec<multipart_upload::Model>, DbErr> {
+    let mut query = multipart_upload::Entity::find()
+        .filter(multipart_upload::Column::BucketName.eq(bucket_name))
+        .order_by_asc(multipart_upload::Column::Key)
+        .order_by_asc(multipart_upload::Column::UploadId);
+
+    match (key_marker, upload_id_marker) {
+        (Some(key_marker), Some(upload_id_marker)) => {
+            query = query.filter(
+                Condition::any()
+                    .add(multipart_upload::Column::Key.gt(key_marker.clone()))
+                    .add(
+                        Condition::all()
+                            .add(multipart_upload::Column::Key.eq(key_marker))
+                            .add(multipart_upload::Column::UploadId.gt(upload_id_marker)),
+                    ),
+            )
+        }
+        (Some(key_marker), None) => {
+            query = query.filter(multipart_upload::Column::Key.gt(key_marker))
+        }
+        (None, _) => {}
+    }
+
+    if let Some(limit) = max_uploads {
+        query = query.limit(std::cmp::min(limit, MAX_UPLOADS));
+    } else {
+        query = query.limit(MAX_UPLOADS);
+    }
+
+    if let Some(prefix) = prefix {
+        query = query.filter(multipart_upload::Column::Key.starts_with(prefix));
+    }
+
+    let uploads = query.all(db).await?;
+
+    Ok(uploads)
+}
+
+/// Store a part of an upload, replacing any part that was already uploaded under its number
+pub async fn put_part(
+    db: &DbConn,
+    upload_id: String,
+    part_number: i32,
+    size: i64,
+    etag: String,
+    file_id: String,
+) -> Result<part::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    part::Entity::delete_many()
+        .filter(part::Column::UploadId.eq(&upload_id))
+        .filter(part::Column::PartNumber.eq(part_number))
+        .exec(&txn)
+        .await?;
+
+    let part = part::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
+        upload_id: Set(upload_id),
+        part_number: Set(part_number),
+        size: Set(size),
+        etag: Set(etag),
+        file_id: Set(file_id),
+        last_modified: Set(chrono::Utc::now()),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(part)
+}
+
+/// Store a part that's read from several existing files, given as their sizes and file ids
+pub async fn put_part_chunks(
+    db: &DbConn,
+    upload_id: String,
+    part_number: i32,
+    etag: String,
+    files: Vec<(i64, String)>,
+) -> Result<part::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    part::Entity::delete_many()
+        .filter(part::Column::UploadId.eq(&upload_id))
+        .filter(part::Column::PartNumber.eq(part_number))
+        .exec(&txn)
+        .await?;
+
+    let part = part::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
+        upload_id: Set(upload_id),
+        part_number: Set(part_number),
+        size: Set(files.iter().map(|(size, _)| size).sum()),
+        etag: Set(etag),
+        file_id: Set(String::new()),
+        last_modified: Set(chrono::Utc::now()),
+    }
+    .insert(&txn)
+    .await?;
+
+    let chunks: Vec<_> = files
+        .into_iter()
+        .enumerate()
+        .map(|(chunk_number, (size, file_id))| part_chunk::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            part_id: Set(part.id),
+            chunk_number: Set(chunk_number as i32),
+            size: Set(size),
+            file_id: Set(file_id),
+        })
+        .collect();
+    if !chunks.is_empty() {
+        part_chunk::Entity::insert_many(chunks).exec(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(part)
+}
+
+pub async fn get_parts(db: &DbConn, upload_id: String) -> Result<Vec<part::Model>, DbErr> {
+    part::Entity::find()
+        .filter(part::Column::UploadId.eq(upload_id))
+        .order_by_asc(part::Column::PartNumber)
+        .all(db)
+        .await
+}
+
+/// List the parts of an upload, in order of part number
+pub async fn list_parts(
+    db: &DbConn,
+    upload_id: String,
+    part_number_marker: Option<i32>,
+    max_parts: Option<u64>,
+) -> Result<Vec<part::Model>, DbErr> {
+    let mut query = part::Entity::find()
+        .filter(part::Column::UploadId.eq(upload_id))
+        .order_by_asc(part::Column::PartNumber);
+
+    if let Some(marker) = part_number_marker {
+        query = query.filter(part::Column::PartNumber.gt(marker))
+    }
+
+    if let Some(limit) = max_parts {
+        query = query.limit(std::cmp::min(limit, MAX_PARTS));
+    } else {
+        query = query.limit(MAX_PARTS);
+    }
+
+    let parts = query.all(db).await?;
+
+    Ok(parts)
+}
+
+/// Assemble a new version of an object from the given parts of an upload, with the tags and ACL
+/// the upload was started with, and drop the upload, returning the object along with the files of the version it replaced that nothing else refers
+/// to. Unless `overwrite` is set, the insert fails on the unique index of latest versions if there's a
+/// current object already, and the upload is kept.
+pub async fn complete_multipart_upload(
+    db: &DbConn,
+    upload: multipart_upload::Model,
+    parts: Vec<part::Model>,
+    etag: String,
+    versioning: VersioningStatus,
+    overwrite: bool,
+) -> Result<(object::Model, Vec<String>), DbErr> {
+    let txn = db.begin().await?;
+
+    let (version_id, replaced_files) = new_version(
+        &txn,
+        &upload.bucket_name,
+        &upload.key,
+        versioning,
+        overwrite,
+    )
+    .await?;
+    let object = object::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
+        key: Set(upload.key),
+        size: Set(parts.iter().map(|part| part.size).sum()),
+        etag: Set(etag),
+        last_modified: Set(chrono::Utc::now()),
+        bucket_name: Set(upload.bucket_name),
+        file_id: Set(String::new()),
+        content_type: Set(upload.content_type),
+        content_encoding: Set(upload.content_encoding),
+        content_disposition: Set(upload.content_disposition),
+        content_language: Set(upload.content_language),
+        cache_control: Set(upload.cache_control),
+        expires: Set(upload.expires),
+        user_metadata: Set(upload.user_metadata),
+        version_id: Set(version_id),
+        is_latest: Set(true),
+        is_delete_marker: Set(false),
+    }
+    .insert(&txn)
+    .await?;
+    insert_tags_and_acl(
+        &txn,
+        object.id,
+        user_metadata(upload.tags.as_ref()),
+        upload.acl,
+    )
+    .await?;
+
+    // Parts copied from several files contribute each of their files in turn
+    let chunked_parts: Vec<_> = parts
+        .iter()
+        .filter(|part| part.file_id.is_empty())
+        .map(|part| part.id)
+        .collect();
+    let part_chunks = if chunked_parts.is_empty() {
+        Vec::new()
+    } else {
+        part_chunk::Entity::find()
+            .filter(part_chunk::Column::PartId.is_in(chunked_parts))
+            .order_by_asc(part_chunk::Column::ChunkNumber)
+            .all(&txn)
+            .await?
+    };
+
+    let chunks: Vec<_> = parts
+        .into_iter()
+        .flat_map(|part| {
+            if part.file_id.is_empty() {
+                part_chunks
+                    .iter()
+                    .filter(|chunk| chunk.part_id == part.id)
+                    .map(|chunk| (part.part_number, chunk.size, chunk.file_id.clone()))
+                    .collect()
+            } else {
+                vec![(part.part_number, part.size, part.file_id)]
+            }
+        })
+        .enumerate()
+        .map(
+            |(chunk_number, (part_number, size, file_id))| object_chunk::ActiveModel {
+                id: Set(uuid::Uuid::new_v4()),
+                object_id: Set(object.id),
+                chunk_number: Set(chunk_number as i32),
+                size: Set(size),
+                file_id: Set(file_id),
+                part_number: Set(Some(part_number)),
+            },
+        )
+        .collect();
+    if !chunks.is_empty() {
+        object_chunk::Entity::insert_many(chunks).exec(&txn).await?;
+    }
+
+    part::Entity::delete_many()
+        .filter(part::Column::UploadId.eq(&upload.upload_id))
+        .exec(&txn)
+        .await?;
+    multipart_upload::Entity::delete_by_id(upload.upload_id)
+        .exec(&txn)
+        .await?;
+    // Checked last, as the parts may have been copied from the version the object replaces
+    let files = unreferenced_files(&txn, replaced_files).await?;
+
+    txn.commit().await?;
+
+    Ok((object, files))
+}
+
+/// Delete an upload along with its parts, returning the files they leave behind that nothing
+/// else refers to
+pub async fn abort_multipart_upload(db: &DbConn, upload_id: String) -> Result<Vec<String>, DbErr> {
+    let txn = db.begin().await?;
+
+    let parts = part::Entity::find()
+        .filter(part::Column::UploadId.eq(&upload_id))
+        .all(&txn)
+        .await?;
+    let chunks = part_chunk::Entity::find()
+        .filter(part_chunk::Column::PartId.is_in(parts.iter().map(|part| part.id)))
+        .all(&txn)
+        .await?;
+
+    part::Entity::delete_many()
+        .filter(part::Column::UploadId.eq(&upload_id))
+        .exec(&txn)
+        .await?;
+    multipart_upload::Entity::delete_by_id(upload_id)
+        .exec(&txn)
+        .await?;
+    let files = unreferenced_files(
+        &txn,
+        parts
+            .into_iter()
+            .map(|part| part.file_id)
+            .chain(chunks.into_iter().map(|chunk| chunk.file_id)),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{entity::prelude::*, DatabaseBackend, MockDatabase, Transaction, Values};
+
+    use crate::db::{
+        entity::{multipart_upload, part},
+        multipart::{list_multipart_uploads, list_parts, MAX_UPLOADS},
+    };
+
+    #[tokio::test]
+    async fn test_list_multipart_uploads_markers() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DatabaseBackend::Sqlite)
+            .append_query_results([Vec::<multipart_upload::Model>::new()])
+            .into_connection();
+
+        list_multipart_uploads(
+            &db,
+            "test".to_owned(),
+            Some("sample.jpg".to_owned()),
+            Some("abc".to_owned()),
+            None,
+            None,
+        )
+        .await?;
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                [
+                    r#"SELECT"#,
+                    r#""multipart_uploads"."upload_id", "multipart_uploads"."bucket_name", "multipart_uploads"."key", "multipart_uploads"."initiated","#,
+                    r#""multipart_uploads"."content_type", "multipart_uploads"."content_encoding", "multipart_uploads"."content_disposition", "multipart_uploads"."content_language", "multipart_uploads"."cache_control", "multipart_uploads"."expires", "multipart_uploads"."user_metadata","#,
+                    r#""multipart_uploads"."tags", "multipart_uploads"."acl""#,
+                    r#"FROM "multipart_uploads""#,
+                    r#"WHERE "multipart_uploads"."bucket_name" = ?"#,
+                    r#"AND ("multipart_uploads"."key" > ? OR ("multipart_uploads"."key" = ? AND "multipart_uploads"."upload_id" > ?))"#,
+                    r#"ORDER BY "multipart_uploads"."key" ASC, "multipart_uploads"."upload_id" ASC"#,
+                    r#"LIMIT ?"#,
+                ]
+                .join(" "),
+                Values(vec![
+                    Value::String(Some(Box::new("test".to_owned()))),
+                    Value::String(Some(Box::new("sample.jpg".to_owned()))),
+                    Value::String(Some(Box::new("sample.jpg".to_owned()))),
+                    Value::String(Some(Box::new("abc".to_owned()))),
+                    Value::BigUnsigned(Some(MAX_UPLOADS)),
+                ])
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_parts_marker() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DatabaseBackend::Sqlite)
+            .append_query_results([Vec::<part::Model>::new()])
+            .into_connection();
+
+        list_parts(&db, "abc".to_owned(), Some(2), Some(10)).await?;
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                [
+                    r#"SELECT"#,
+                    r#""parts"."id", "parts"."upload_id", "parts"."part_number", "parts"."size", "parts"."etag", "parts"."file_id", "parts"."last_modified""#,
+                    r#"FROM "parts""#,
+                    r#"WHERE "parts"."upload_id" = ?"#,
+                    r#"AND "parts"."part_number" > ?"#,
+                    r#"ORDER BY "parts"."part_number" ASC"#,
+                    r#"LIMIT ?"#,
+                ]
+                .join(" "),
+                Values(vec![
+                    Value::String(Some(Box::new("abc".to_owned()))),
+                    Value::Int(Some(2)),
+                    Value::BigUnsigned(Some(10)),
+                ])
+            )]
+        );
+
+        Ok(())
+    }
+}
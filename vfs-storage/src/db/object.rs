@@ -1,17 +1,264 @@
-use sea_orm::{sea_query::Alias, *};
-use sea_query::Expr;
+use s3_entities::{
+    bucket::VersioningStatus,
+    object::{new_version_id, DeletedObject, ObjectMetadata, NULL_VERSION_ID},
+};
+use sea_orm::{
+    prelude::{Json, Uuid},
+    sea_query::Alias,
+    *,
+};
+use sea_query::{Expr, SimpleExpr};
+use std::collections::BTreeMap;
 
-use super::{entity::object, func};
+use super::{
+    entity::{object, object_acl, object_chunk, object_tag, part, part_chunk},
+    func,
+};
 
 const MAX_KEYS: u64 = 1000;
+const MAX_KEY_LENGTH: i32 = 1024;
 
-/// List objects in a bucket 
-/// 
+/// Store user-defined metadata as a JSON object, or not at all when there's none
+pub fn user_metadata_json(user: BTreeMap<String, String>) -> Option<Json> {
+    if user.is_empty() {
+        return None;
+    }
+
+    Some(Json::Object(
+        user.into_iter()
+            .map(|(name, value)| (name, Json::String(value)))
+            .collect(),
+    ))
+}
+
+/// Read back user-defined metadata stored by [`user_metadata_json`]
+pub fn user_metadata(json: Option<&Json>) -> BTreeMap<String, String> {
+    json.and_then(Json::as_object)
+        .map(|user| {
+            user.iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_owned())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// An object with nothing but its metadata columns set
+fn metadata_model(metadata: ObjectMetadata) -> object::ActiveModel {
+    object::ActiveModel {
+        content_type: Set(metadata.content_type),
+        content_encoding: Set(metadata.content_encoding),
+        content_disposition: Set(metadata.content_disposition),
+        content_language: Set(metadata.content_language),
+        cache_control: Set(metadata.cache_control),
+        expires: Set(metadata.expires),
+        user_metadata: Set(user_metadata_json(metadata.user)),
+        ..Default::default()
+    }
+}
+
+/// An object's version, or its latest version when not given
+fn find_version(
+    bucket_name: String,
+    key: &str,
+    version_id: Option<String>,
+) -> Select<object::Entity> {
+    let query = object::Entity::find()
+        .filter(object::Column::Key.eq(key))
+        .filter(object::Column::BucketName.eq(bucket_name));
+
+    match version_id {
+        Some(version_id) => query.filter(object::Column::VersionId.eq(version_id)),
+        None => query.filter(object::Column::IsLatest.eq(true)),
+    }
+}
+
+/// Make way for a new latest version of an object and pick its id. Unless versioning is enabled
+/// the new version replaces the `null` one, whose files are returned for the caller to pass to
+/// [`unreferenced_files`] once the new version refers to whatever it shares of them. A write that
+/// may not overwrite the current object leaves it in place, so that inserting the new version
+/// fails on the unique index of latest versions instead.
+pub(super) async fn new_version(
+    txn: &DatabaseTransaction,
+    bucket_name: &str,
+    key: &str,
+    versioning: VersioningStatus,
+    overwrite: bool,
+) -> Result<(String, Vec<String>), DbErr> {
+    if versioning == VersioningStatus::Enabled {
+        demote_latest(txn, bucket_name, key, overwrite).await?;
+
+        return Ok((new_version_id(), Vec::new()));
+    }
+
+    let mut replaced = object::Entity::find()
+        .filter(object::Column::Key.eq(key))
+        .filter(object::Column::BucketName.eq(bucket_name))
+        .filter(object::Column::VersionId.eq(NULL_VERSION_ID));
+    // Only a delete marker makes way for a write that may not overwrite
+    if !overwrite {
+        replaced = replaced.filter(
+            Condition::any()
+                .add(object::Column::IsLatest.eq(false))
+                .add(object::Column::IsDeleteMarker.eq(true)),
+        );
+    }
+
+    let mut files = Vec::new();
+    if let Some(replaced) = replaced.one(txn).await? {
+        let chunks = object_chunk::Entity::find()
+            .filter(object_chunk::Column::ObjectId.eq(replaced.id))
+            .all(txn)
+            .await?;
+        object::Entity::delete_by_id(replaced.id).exec(txn).await?;
+
+        files.push(replaced.file_id);
+        files.extend(chunks.into_iter().map(|chunk| chunk.file_id));
+    }
+    demote_latest(txn, bucket_name, key, overwrite).await?;
+
+    Ok((NULL_VERSION_ID.into(), files))
+}
+
+/// Stop the latest version of an object from being the latest, or only a delete marker when the
+/// write may not overwrite
+async fn demote_latest(
+    txn: &DatabaseTransaction,
+    bucket_name: &str,
+    key: &str,
+    overwrite: bool,
+) -> Result<(), DbErr> {
+    let demote = object::Entity::update_many()
+        .col_expr(object::Column::IsLatest, Expr::value(false))
+        .filter(object::Column::Key.eq(key))
+        .filter(object::Column::BucketName.eq(bucket_name))
+        .filter(object::Column::IsLatest.eq(true));
+    if overwrite {
+        demote.exec(txn).await?;
+    } else {
+        demote
+            .filter(object::Column::IsDeleteMarker.eq(true))
+            .exec(txn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// The files among `file_ids` that no object or part refers to anymore. Copies share the files
+/// of their source, so a file is only done with once the last of them is deleted.
+pub(super) async fn unreferenced_files<C: ConnectionTrait>(
+    conn: &C,
+    file_ids: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, DbErr> {
+    let mut unreferenced: Vec<String> = Vec::new();
+    for file_id in file_ids {
+        if file_id.is_empty() || unreferenced.contains(&file_id) {
+            continue;
+        }
+
+        let referenced = object::Entity::find()
+            .filter(object::Column::FileId.eq(&file_id))
+            .count(conn)
+            .await?
+            > 0
+            || object_chunk::Entity::find()
+                .filter(object_chunk::Column::FileId.eq(&file_id))
+                .count(conn)
+                .await?
+                > 0
+            || part::Entity::find()
+                .filter(part::Column::FileId.eq(&file_id))
+                .count(conn)
+                .await?
+                > 0
+            || part_chunk::Entity::find()
+                .filter(part_chunk::Column::FileId.eq(&file_id))
+                .count(conn)
+                .await?
+                > 0;
+        if !referenced {
+            unreferenced.push(file_id);
+        }
+    }
+
+    Ok(unreferenced)
+}
+
+/// Store the tags and ACL a new version of an object is written with
+pub(super) async fn insert_tags_and_acl(
+    txn: &DatabaseTransaction,
+    object_id: Uuid,
+    tags: BTreeMap<String, String>,
+    acl: Option<Json>,
+) -> Result<(), DbErr> {
+    let tags: Vec<_> = tags
+        .into_iter()
+        .map(|(key, value)| object_tag::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            object_id: Set(object_id),
+            key: Set(key),
+            value: Set(value),
+        })
+        .collect();
+    if !tags.is_empty() {
+        object_tag::Entity::insert_many(tags).exec(txn).await?;
+    }
+    if let Some(acl) = acl {
+        object_acl::ActiveModel {
+            object_id: Set(object_id),
+            acl: Set(acl),
+        }
+        .insert(txn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Where the first delimiter after the listing's prefix is in a key, counting from just after
+/// the prefix, or 0 if there's none
+fn delimiter_position(prefix: Option<&str>, delimiter: &str) -> SimpleExpr {
+    let prefix_length = prefix.unwrap_or_default().chars().count() as i32;
+
+    func::instr(
+        func::substr(
+            Expr::col(object::Column::Key),
+            Expr::expr(prefix_length + 1),
+            Expr::expr(MAX_KEY_LENGTH),
+        ),
+        Expr::expr(delimiter),
+    )
+    .into()
+}
+
+/// The common prefix of a key, which runs up to the first delimiter after the listing's prefix,
+/// or the key itself if it has none
+fn common_prefix(prefix: Option<&str>, delimiter: &str) -> SimpleExpr {
+    let prefix_length = prefix.unwrap_or_default().chars().count() as i32;
+    let delimiter_length = delimiter.chars().count() as i32;
+    let delimiter_position = delimiter_position(prefix, delimiter);
+
+    Expr::expr(
+        Expr::case(
+            Expr::expr(delimiter_position.clone()).gt(0),
+            func::substr(
+                Expr::col(object::Column::Key),
+                Expr::expr(1),
+                Expr::expr(delimiter_position).add(prefix_length + delimiter_length - 1),
+            ),
+        )
+        .finally(Expr::col(object::Column::Key)),
+    )
+    .into()
+}
+
+/// List objects in a bucket
+///
 /// # Example
-/// 
-/// ```
+///
+/// ```ignore
 /// use db;
-/// 
+///
 /// assert_eq!(
 ///     db::list_objects(
 ///         &db,
@@ -27,37 +274,114 @@ const MAX_KEYS: u64 = 1000;
 pub async fn list_objects(
     db: &DbConn,
     bucket_name: String,
-    delimiter: Option<char>,
+    delimiter: Option<String>,
     marker: Option<String>,
     max_keys: Option<u64>,
     prefix: Option<String>,
 ) -> Result<Vec<object::Model>, DbErr> {
     let mut query = object::Entity::find()
         .filter(object::Column::BucketName.eq(bucket_name))
-        .order_by_asc(object::Column::Key);
+        .filter(object::Column::IsLatest.eq(true))
+        .filter(object::Column::IsDeleteMarker.eq(false));
+
+    // Keys are grouped by their common prefix so that only one row is returned for each
+    if let Some(delimiter) = delimiter.filter(|delimiter| !delimiter.is_empty()) {
+        query = query
+            .column_as(common_prefix(prefix.as_deref(), &delimiter), "common_prefixes")
+            .group_by(Expr::col(Alias::new("common_prefixes")))
+            .order_by_asc(Expr::col(Alias::new("common_prefixes")))
+    } else {
+        query = query.order_by_asc(object::Column::Key)
+    }
 
-    if let Some(delimiter) = delimiter {
-        let delimiter_position = func::instr(Expr::col(object::Column::Key), Expr::expr(delimiter));
+    if let Some(marker) = marker {
+        query = query.filter(object::Column::Key.gt(marker))
+    }
+
+    if let Some(limit) = max_keys {
+        query = query.limit(std::cmp::min(limit, MAX_KEYS));
+    } else {
+        query = query.limit(MAX_KEYS);
+    }
+
+    if let Some(prefix) = prefix {
+        query = query.filter(object::Column::Key.starts_with(prefix));
+    }
+
+    let objects = query.all(db).await?;
 
-        let common_prefixes = Expr::expr(
+    Ok(objects)
+}
+
+/// List every version of the objects in a bucket, delete markers included, in order of key and
+/// then newest first. With a delimiter, the versions of all the keys that share a common prefix
+/// come back as a single row.
+pub async fn list_object_versions(
+    db: &DbConn,
+    bucket_name: String,
+    delimiter: Option<String>,
+    key_marker: Option<String>,
+    version_id_marker: Option<String>,
+    max_keys: Option<u64>,
+    prefix: Option<String>,
+) -> Result<Vec<object::Model>, DbErr> {
+    let mut query = object::Entity::find().filter(object::Column::BucketName.eq(&bucket_name));
+
+    // Like listing objects, but the versions of a key that isn't rolled up into a common prefix
+    // each keep a row of their own
+    if let Some(delimiter) = delimiter.filter(|delimiter| !delimiter.is_empty()) {
+        let version = Expr::expr(
             Expr::case(
-                Expr::expr(delimiter_position.clone()).gt(-1),
-                func::substr(
-                    Expr::col(object::Column::Key),
-                    Expr::expr(1),
-                    Expr::expr(delimiter_position.clone()),
-                ),
+                Expr::expr(delimiter_position(prefix.as_deref(), &delimiter)).gt(0),
+                Expr::val(""),
             )
-            .finally(Expr::col(object::Column::Key)),
+            .finally(Expr::col(object::Column::VersionId)),
         );
 
         query = query
-            .column_as(common_prefixes, "common_prefixes")
-            .group_by(Expr::col((object::Entity, Alias::new("common_prefixes"))))
+            .column_as(common_prefix(prefix.as_deref(), &delimiter), "common_prefixes")
+            .column_as(version, "common_prefix_version")
+            .group_by(Expr::col(Alias::new("common_prefixes")))
+            .group_by(Expr::col(Alias::new("common_prefix_version")))
+            .order_by_asc(Expr::col(Alias::new("common_prefixes")))
+    } else {
+        query = query.order_by_asc(object::Column::Key)
     }
+    query = query
+        .order_by_desc(object::Column::LastModified)
+        .order_by_desc(object::Column::VersionId);
 
-    if let Some(marker) = marker {
-        query = query.filter(object::Column::Key.gt(marker))
+    match (key_marker, version_id_marker) {
+        (Some(key_marker), Some(version_id_marker)) => {
+            let marker = object::Entity::find()
+                .filter(object::Column::Key.eq(&key_marker))
+                .filter(object::Column::BucketName.eq(bucket_name))
+                .filter(object::Column::VersionId.eq(&version_id_marker))
+                .one(db)
+                .await?;
+
+            // The versions of the marker's key that are older than the marker are left, unless
+            // it's gone in which case the listing goes on from the next key
+            let mut after_marker = Condition::any().add(object::Column::Key.gt(&key_marker));
+            if let Some(marker) = marker {
+                after_marker = after_marker.add(
+                    Condition::all()
+                        .add(object::Column::Key.eq(key_marker))
+                        .add(
+                            Condition::any()
+                                .add(object::Column::LastModified.lt(marker.last_modified))
+                                .add(
+                                    Condition::all()
+                                        .add(object::Column::LastModified.eq(marker.last_modified))
+                                        .add(object::Column::VersionId.lt(version_id_marker)),
+                                ),
+                        ),
+                );
+            }
+            query = query.filter(after_marker)
+        }
+        (Some(key_marker), None) => query = query.filter(object::Column::Key.gt(key_marker)),
+        (None, _) => {}
     }
 
     if let Some(limit) = max_keys {
@@ -70,69 +394,329 @@ pub async fn list_objects(
         query = query.filter(object::Column::Key.starts_with(prefix));
     }
 
-    let objects = query.all(db).await?;
+    let versions = query.all(db).await?;
 
-    Ok(objects)
+    Ok(versions)
 }
 
+/// Look up an object's version, or its latest version when not given, which may be a delete
+/// marker
+pub async fn get_object(
+    db: &DbConn,
+    bucket_name: String,
+    key: String,
+    version_id: Option<String>,
+) -> Result<object::Model, DbErr> {
+    find_version(bucket_name, &key, version_id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!(
+            "object '{}' doesn't exist",
+            &key
+        )))
+}
+
+/// The files an object assembled from several files is read from, in order
+pub async fn get_object_chunks(
+    db: &DbConn,
+    object_id: Uuid,
+) -> Result<Vec<object_chunk::Model>, DbErr> {
+    object_chunk::Entity::find()
+        .filter(object_chunk::Column::ObjectId.eq(object_id))
+        .order_by_asc(object_chunk::Column::ChunkNumber)
+        .all(db)
+        .await
+}
+
+/// The tags of an object, by their keys
+pub async fn get_object_tags(
+    db: &DbConn,
+    object_id: Uuid,
+) -> Result<BTreeMap<String, String>, DbErr> {
+    let tags = object_tag::Entity::find()
+        .filter(object_tag::Column::ObjectId.eq(object_id))
+        .all(db)
+        .await?;
+
+    Ok(tags.into_iter().map(|tag| (tag.key, tag.value)).collect())
+}
+
+/// Replace all the tags of an object
+pub async fn put_object_tags(
+    db: &DbConn,
+    object_id: Uuid,
+    tags: BTreeMap<String, String>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    object_tag::Entity::delete_many()
+        .filter(object_tag::Column::ObjectId.eq(object_id))
+        .exec(&txn)
+        .await?;
+
+    let tags: Vec<_> = tags
+        .into_iter()
+        .map(|(key, value)| object_tag::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            object_id: Set(object_id),
+            key: Set(key),
+            value: Set(value),
+        })
+        .collect();
+    if !tags.is_empty() {
+        object_tag::Entity::insert_many(tags).exec(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// The ACL of an object, `None` if it was never given one
+pub async fn get_object_acl(db: &DbConn, object_id: Uuid) -> Result<Option<Json>, DbErr> {
+    Ok(object_acl::Entity::find_by_id(object_id)
+        .one(db)
+        .await?
+        .map(|acl| acl.acl))
+}
+
+/// Replace the ACL of an object
+pub async fn put_object_acl(db: &DbConn, object_id: Uuid, acl: Json) -> Result<(), DbErr> {
+    object_acl::Entity::insert(object_acl::ActiveModel {
+        object_id: Set(object_id),
+        acl: Set(acl),
+    })
+    .on_conflict(
+        sea_query::OnConflict::column(object_acl::Column::ObjectId)
+            .update_column(object_acl::Column::Acl)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Store a new version of an object with its tags and ACL, returning it along with the files of
+/// the version it replaced that nothing else refers to. Unless `overwrite` is set, the insert fails on the unique index of
+/// latest versions if there's a current object already.
+pub async fn put_object(
+    db: &DbConn,
+    bucket_name: String,
+    key: String,
+    size: i64,
+    etag: String,
+    file_id: String,
+    metadata: ObjectMetadata,
+    tags: BTreeMap<String, String>,
+    acl: Option<Json>,
+    versioning: VersioningStatus,
+    overwrite: bool,
+) -> Result<(object::Model, Vec<String>), DbErr> {
+    let txn = db.begin().await?;
+
+    let (version_id, replaced_files) =
+        new_version(&txn, &bucket_name, &key, versioning, overwrite).await?;
+    let object = object::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
+        key: Set(key),
+        size: Set(size),
+        etag: Set(etag),
+        last_modified: Set(chrono::Utc::now()),
+        bucket_name: Set(bucket_name),
+        file_id: Set(file_id),
+        version_id: Set(version_id),
+        is_latest: Set(true),
+        is_delete_marker: Set(false),
+        ..metadata_model(metadata)
+    }
+    .insert(&txn)
+    .await?;
+    insert_tags_and_acl(&txn, object.id, tags, acl).await?;
+    let files = unreferenced_files(&txn, replaced_files).await?;
+
+    txn.commit().await?;
+
+    Ok((object, files))
+}
+
+/// Copy an object version to a new version of a key. The copy shares the source's files rather
+/// than duplicating them, and keeps its metadata and tags unless given new ones. The source is
+/// the version its id names, so that a copy never reads a version that replaced the one its
+/// caller checked. Returns the files of the version the copy replaced that nothing else refers to.
 pub async fn copy_object(
     db: &DbConn,
     dest_bucket_name: String,
     dest_key: String,
-    source_bucket_name: String,
-    source_key: String,
-) -> Result<object::Model, DbErr> {
-    let source_object: object::Model = object::Entity::find()
-        .filter(object::Column::Key.eq(source_key))
-        .filter(object::Column::BucketName.eq(source_bucket_name))
-        .one(db)
+    source_object_id: Uuid,
+    metadata: Option<ObjectMetadata>,
+    tags: Option<BTreeMap<String, String>>,
+    acl: Option<Json>,
+    versioning: VersioningStatus,
+) -> Result<(object::Model, Vec<String>), DbErr> {
+    let txn = db.begin().await?;
+
+    let source_object = object::Entity::find_by_id(source_object_id)
+        .one(&txn)
         .await?
-        .ok_or(DbErr::Custom("Cannot find source object.".to_owned()))
-        .map(Into::into)?;
+        .ok_or(DbErr::RecordNotFound(format!(
+            "object '{}' doesn't exist",
+            source_object_id
+        )))?;
+    // Read before making way for the destination, which replaces the source when an object is
+    // copied onto itself outside of a versioned bucket
+    let source_chunks = object_chunk::Entity::find()
+        .filter(object_chunk::Column::ObjectId.eq(source_object.id))
+        .order_by_asc(object_chunk::Column::ChunkNumber)
+        .all(&txn)
+        .await?;
+    let tags = match tags {
+        Some(tags) => tags,
+        None => object_tag::Entity::find()
+            .filter(object_tag::Column::ObjectId.eq(source_object.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|tag| (tag.key, tag.value))
+            .collect(),
+    };
 
+    let (version_id, replaced_files) =
+        new_version(&txn, &dest_bucket_name, &dest_key, versioning, true).await?;
+    let metadata = match metadata {
+        Some(metadata) => metadata_model(metadata),
+        None => object::ActiveModel {
+            content_type: Set(source_object.content_type),
+            content_encoding: Set(source_object.content_encoding),
+            content_disposition: Set(source_object.content_disposition),
+            content_language: Set(source_object.content_language),
+            cache_control: Set(source_object.cache_control),
+            expires: Set(source_object.expires),
+            user_metadata: Set(source_object.user_metadata),
+            ..Default::default()
+        },
+    };
     let dest_object: object::Model = object::ActiveModel {
         id: Set(uuid::Uuid::new_v4()),
         key: Set(dest_key),
         size: Set(source_object.size),
         etag: Set(source_object.etag),
-        last_modified: Set(source_object.last_modified),
+        last_modified: Set(chrono::Utc::now()),
         bucket_name: Set(dest_bucket_name),
         file_id: Set(source_object.file_id),
+        version_id: Set(version_id),
+        is_latest: Set(true),
+        is_delete_marker: Set(false),
+        ..metadata
     }
-    .insert(db)
+    .insert(&txn)
     .await?;
 
-    Ok(dest_object)
+    let chunks: Vec<_> = source_chunks
+        .into_iter()
+        .map(|chunk| object_chunk::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            object_id: Set(dest_object.id),
+            chunk_number: Set(chunk.chunk_number),
+            size: Set(chunk.size),
+            file_id: Set(chunk.file_id),
+            part_number: Set(chunk.part_number),
+        })
+        .collect();
+    if !chunks.is_empty() {
+        object_chunk::Entity::insert_many(chunks).exec(&txn).await?;
+    }
+
+    insert_tags_and_acl(&txn, dest_object.id, tags, acl).await?;
+    // Checked last, as a copy onto itself shares the files of the version it replaces
+    let files = unreferenced_files(&txn, replaced_files).await?;
+
+    txn.commit().await?;
+
+    Ok((dest_object, files))
 }
 
+/// Delete an object. Unless the bucket is unversioned that only adds a delete marker as its
+/// latest version, deleting a given version instead removes it for good. Returns the files the
+/// removed version, or the `null` version a delete marker replaced, leaves behind that nothing
+/// else refers to.
 pub async fn delete_object(
     db: &DbConn,
     bucket_name: String,
     key: String,
-) -> Result<DeleteResult, DbErr> {
-    let object: object::ActiveModel = object::Entity::find()
-        .filter(object::Column::Key.eq(key))
-        .filter(object::Column::BucketName.eq(bucket_name))
-        .one(db)
-        .await?
-        .ok_or(DbErr::Custom("Cannot find object.".to_owned()))
-        .map(Into::into)?;
+    version_id: Option<String>,
+    versioning: VersioningStatus,
+) -> Result<(DeletedObject, Vec<String>), DbErr> {
+    let txn = db.begin().await?;
 
-    Ok(object.delete(db).await?)
-}
+    let deleted = if version_id.is_none() && versioning != VersioningStatus::Unversioned {
+        let (version_id, replaced_files) =
+            new_version(&txn, &bucket_name, &key, versioning, true).await?;
+        object::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            key: Set(key),
+            size: Set(0),
+            etag: Set(String::new()),
+            last_modified: Set(chrono::Utc::now()),
+            bucket_name: Set(bucket_name),
+            file_id: Set(String::new()),
+            version_id: Set(version_id.clone()),
+            is_latest: Set(true),
+            is_delete_marker: Set(true),
+            ..metadata_model(ObjectMetadata::default())
+        }
+        .insert(&txn)
+        .await?;
 
-pub async fn delete_objects(
-    db: &DbConn,
-    bucket_name: String,
-    keys: Vec<String>,
-) -> Result<DeleteResult, DbErr> {
-    let result = object::Entity::delete_many()
-        .filter(object::Column::Key.is_in(keys))
-        .filter(object::Column::BucketName.eq(bucket_name))
-        .exec(db)
+        let deleted = DeletedObject {
+            version_id: Some(version_id),
+            delete_marker: true,
+        };
+        (deleted, unreferenced_files(&txn, replaced_files).await?)
+    } else {
+        let object = find_version(bucket_name.clone(), &key, version_id.clone())
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound(format!(
+                "object '{}' doesn't exist",
+                &key
+            )))?;
+        let chunks = object_chunk::Entity::find()
+            .filter(object_chunk::Column::ObjectId.eq(object.id))
+            .all(&txn)
+            .await?;
+        object::Entity::delete_by_id(object.id).exec(&txn).await?;
+
+        // The newest of the remaining versions takes the place of a deleted latest one
+        if object.is_latest {
+            let previous = object::Entity::find()
+                .filter(object::Column::Key.eq(&key))
+                .filter(object::Column::BucketName.eq(bucket_name))
+                .order_by_desc(object::Column::LastModified)
+                .one(&txn)
+                .await?;
+            if let Some(previous) = previous {
+                let mut previous: object::ActiveModel = previous.into();
+                previous.is_latest = Set(true);
+                previous.update(&txn).await?;
+            }
+        }
+
+        let files = unreferenced_files(
+            &txn,
+            std::iter::once(object.file_id).chain(chunks.into_iter().map(|chunk| chunk.file_id)),
+        )
         .await?;
+        let deleted = DeletedObject {
+            version_id,
+            delete_marker: object.is_delete_marker,
+        };
+        (deleted, files)
+    };
 
-    Ok(result)
+    txn.commit().await?;
+
+    Ok(deleted)
 }
 
 #[cfg(test)]
@@ -141,7 +725,7 @@ mod tests {
 
     use crate::db::{
         entity::object,
-        object::{list_objects, MAX_KEYS},
+        object::{get_object, list_object_versions, list_objects, MAX_KEYS, MAX_KEY_LENGTH},
     };
 
     fn setup_db() -> DatabaseConnection {
@@ -151,13 +735,21 @@ mod tests {
                 key: String::from("sample.jpg"),
                 size: 142863,
                 etag: String::from("bf1d737a4d46a19f3bced6905cc8b902"),
-                last_modified: DateTime::from_timestamp_millis(1662921288000)
-                    .unwrap()
-                    .and_utc(),
+                last_modified: DateTimeUtc::from_timestamp_millis(1662921288000).unwrap(),
                 bucket_name: String::from("test"),
                 file_id: String::from(
                     "BQACAgQAAxkDAAMEZMOIYdfPpjwYEl05ZAN9HiXE2HMAAt4NAAIm-CBSDo0lBzNVdIgvBA",
                 ),
+                content_type: Some(String::from("image/jpeg")),
+                content_encoding: None,
+                content_disposition: None,
+                content_language: None,
+                cache_control: None,
+                expires: None,
+                user_metadata: None,
+                version_id: String::from("null"),
+                is_latest: true,
+                is_delete_marker: false,
             }]])
             .into_connection()
     }
@@ -166,7 +758,15 @@ mod tests {
     async fn test_list_objects_delimiter() -> Result<(), DbErr> {
         let db = setup_db();
 
-        list_objects(&db, "test".to_owned(), Some('/'), None, None, None).await?;
+        list_objects(
+            &db,
+            "test".to_owned(),
+            Some("/".to_owned()),
+            None,
+            None,
+            Some("photos/".to_owned()),
+        )
+        .await?;
 
         assert_eq!(
             db.into_transaction_log(),
@@ -175,20 +775,94 @@ mod tests {
                 [
                     r#"SELECT"#,
                     r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
-                    r#"(CASE WHEN (INSTR("key", ?) > ?) THEN SUBSTR("key", ?, INSTR("key", ?)) ELSE "key" END) AS "common_prefixes""#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker","#,
+                    r#"(CASE WHEN (INSTR(SUBSTR("key", ?, ?), ?) > ?) THEN SUBSTR("key", ?, INSTR(SUBSTR("key", ?, ?), ?) + ?) ELSE "key" END) AS "common_prefixes""#,
                     r#"FROM "objects""#,
                     r#"WHERE "objects"."bucket_name" = ?"#,
-                    r#"GROUP BY "objects"."common_prefixes""#,
-                    r#"ORDER BY "objects"."key" ASC"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"AND "objects"."is_delete_marker" = ?"#,
+                    r#"AND "objects"."key" LIKE ?"#,
+                    r#"GROUP BY "common_prefixes""#,
+                    r#"ORDER BY "common_prefixes" ASC"#,
                     r#"LIMIT ?"#,
                 ]
                 .join(" "),
                 Values(vec![
-                    Value::Char(Some('/')),
-                    Value::Int(Some(-1)),
+                    Value::Int(Some(8)),
+                    Value::Int(Some(MAX_KEY_LENGTH)),
+                    Value::String(Some(Box::new("/".to_owned()))),
+                    Value::Int(Some(0)),
                     Value::Int(Some(1)),
-                    Value::Char(Some('/')),
+                    Value::Int(Some(8)),
+                    Value::Int(Some(MAX_KEY_LENGTH)),
+                    Value::String(Some(Box::new("/".to_owned()))),
+                    Value::Int(Some(7)),
                     Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::Bool(Some(false)),
+                    Value::String(Some(Box::new("photos/%".to_owned()))),
+                    Value::BigUnsigned(Some(MAX_KEYS)),
+                ])
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_object_versions_delimiter() -> Result<(), DbErr> {
+        let db = setup_db();
+
+        list_object_versions(
+            &db,
+            "test".to_owned(),
+            Some("/".to_owned()),
+            Some("photos/cat.jpg".to_owned()),
+            None,
+            None,
+            Some("photos/".to_owned()),
+        )
+        .await?;
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                [
+                    r#"SELECT"#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker","#,
+                    r#"(CASE WHEN (INSTR(SUBSTR("key", ?, ?), ?) > ?) THEN SUBSTR("key", ?, INSTR(SUBSTR("key", ?, ?), ?) + ?) ELSE "key" END) AS "common_prefixes","#,
+                    r#"(CASE WHEN (INSTR(SUBSTR("key", ?, ?), ?) > ?) THEN ? ELSE "version_id" END) AS "common_prefix_version""#,
+                    r#"FROM "objects""#,
+                    r#"WHERE "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."key" > ?"#,
+                    r#"AND "objects"."key" LIKE ?"#,
+                    r#"GROUP BY "common_prefixes", "common_prefix_version""#,
+                    r#"ORDER BY "common_prefixes" ASC, "objects"."last_modified" DESC, "objects"."version_id" DESC"#,
+                    r#"LIMIT ?"#,
+                ]
+                .join(" "),
+                Values(vec![
+                    Value::Int(Some(8)),
+                    Value::Int(Some(MAX_KEY_LENGTH)),
+                    Value::String(Some(Box::new("/".to_owned()))),
+                    Value::Int(Some(0)),
+                    Value::Int(Some(1)),
+                    Value::Int(Some(8)),
+                    Value::Int(Some(MAX_KEY_LENGTH)),
+                    Value::String(Some(Box::new("/".to_owned()))),
+                    Value::Int(Some(7)),
+                    Value::Int(Some(8)),
+                    Value::Int(Some(MAX_KEY_LENGTH)),
+                    Value::String(Some(Box::new("/".to_owned()))),
+                    Value::Int(Some(0)),
+                    Value::String(Some(Box::new("".to_owned()))),
+                    Value::String(Some(Box::new("test".to_owned()))),
+                    Value::String(Some(Box::new("photos/cat.jpg".to_owned()))),
+                    Value::String(Some(Box::new("photos/%".to_owned()))),
                     Value::BigUnsigned(Some(MAX_KEYS)),
                 ])
             )]
@@ -217,9 +891,13 @@ mod tests {
                 DatabaseBackend::Sqlite,
                 [
                     r#"SELECT"#,
-                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id""#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker""#,
                     r#"FROM "objects""#,
                     r#"WHERE "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"AND "objects"."is_delete_marker" = ?"#,
                     r#"AND "objects"."key" > ?"#,
                     r#"ORDER BY "objects"."key" ASC"#,
                     r#"LIMIT ?"#,
@@ -227,6 +905,8 @@ mod tests {
                 .join(" "),
                 Values(vec![
                     Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::Bool(Some(false)),
                     Value::String(Some(Box::new("sample.jpg".to_owned()))),
                     Value::BigUnsigned(Some(MAX_KEYS)),
                 ])
@@ -249,15 +929,21 @@ mod tests {
                 DatabaseBackend::Sqlite,
                 [
                     r#"SELECT"#,
-                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id""#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker""#,
                     r#"FROM "objects""#,
                     r#"WHERE "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"AND "objects"."is_delete_marker" = ?"#,
                     r#"ORDER BY "objects"."key" ASC"#,
                     r#"LIMIT ?"#,
                 ]
                 .join(" "),
                 Values(vec![
                     Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::Bool(Some(false)),
                     Value::BigUnsigned(Some(max)),
                 ])
             )]
@@ -279,15 +965,21 @@ mod tests {
                 DatabaseBackend::Sqlite,
                 [
                     r#"SELECT"#,
-                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id""#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker""#,
                     r#"FROM "objects""#,
                     r#"WHERE "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"AND "objects"."is_delete_marker" = ?"#,
                     r#"ORDER BY "objects"."key" ASC"#,
                     r#"LIMIT ?"#,
                 ]
                 .join(" "),
                 Values(vec![
                     Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::Bool(Some(false)),
                     Value::BigUnsigned(Some(MAX_KEYS)),
                 ])
             )]
@@ -317,9 +1009,13 @@ mod tests {
                 DatabaseBackend::Sqlite,
                 [
                     r#"SELECT"#,
-                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id""#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker""#,
                     r#"FROM "objects""#,
                     r#"WHERE "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"AND "objects"."is_delete_marker" = ?"#,
                     r#"AND "objects"."key" LIKE ?"#,
                     r#"ORDER BY "objects"."key" ASC"#,
                     r#"LIMIT ?"#,
@@ -327,6 +1023,8 @@ mod tests {
                 .join(" "),
                 Values(vec![
                     Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::Bool(Some(false)),
                     Value::String(Some(Box::new(prefix.clone() + "%"))),
                     Value::BigUnsigned(Some(MAX_KEYS)),
                 ])
@@ -335,4 +1033,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_object() -> Result<(), DbErr> {
+        let db = setup_db();
+
+        let object = get_object(&db, "test".to_owned(), "sample.jpg".to_owned(), None).await?;
+
+        assert_eq!(object.key, "sample.jpg");
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                [
+                    r#"SELECT"#,
+                    r#""objects"."id", "objects"."key", "objects"."size", "objects"."etag", "objects"."last_modified", "objects"."bucket_name", "objects"."file_id","#,
+                    r#""objects"."content_type", "objects"."content_encoding", "objects"."content_disposition", "objects"."content_language", "objects"."cache_control", "objects"."expires", "objects"."user_metadata","#,
+                    r#""objects"."version_id", "objects"."is_latest", "objects"."is_delete_marker""#,
+                    r#"FROM "objects""#,
+                    r#"WHERE "objects"."key" = ?"#,
+                    r#"AND "objects"."bucket_name" = ?"#,
+                    r#"AND "objects"."is_latest" = ?"#,
+                    r#"LIMIT ?"#,
+                ]
+                .join(" "),
+                Values(vec![
+                    Value::String(Some(Box::new("sample.jpg".to_owned()))),
+                    Value::String(Some(Box::new("test".to_owned()))),
+                    Value::Bool(Some(true)),
+                    Value::BigUnsigned(Some(1)),
+                ])
+            )]
+        );
+
+        Ok(())
+    }
 }
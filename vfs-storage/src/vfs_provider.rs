@@ -1,13 +1,58 @@
-use crate::db;
+use crate::db::{
+    self,
+    entity::{
+        access_key, bucket, cors_rule, group, identity_policy, lifecycle_rule, multipart_upload,
+        object, part, session, user,
+    },
+};
 use async_trait::async_trait;
-use s3::storage_provider::StorageErr;
-use sea_orm::{DatabaseConnection, DbErr};
-use std::env;
+use futures::{stream, StreamExt, TryStreamExt};
+use md5::{Digest, Md5};
+use s3_entities::{
+    acl::{AccessControlList, ObjectOwnership},
+    bucket::{Bucket, CreateBucketOptions, VersioningStatus},
+    cors::{CorsConfiguration, CorsRule},
+    identity::{AccessKey, AccessKeyStatus, Group, IdentityPolicy, PolicyHolder, Session, User},
+    lifecycle::{Expiration, LifecycleConfiguration, LifecycleFilter, LifecycleRule},
+    list::{Page, PageBuilder},
+    multipart::{self, CompletedPart, ListUploadsOptions, MultipartUpload, Part, PartsPage},
+    object::{
+        self as s3_object, CopiedObject, CopyObjectOptions, CopySource, DeletedObject,
+        ListObjectsOptions, ListVersionsOptions, MetadataDirective, Object, ObjectMetadata,
+        ObjectVersion, Precondition, PutObjectOptions, ReadRange, Tags, WriteCondition,
+    },
+    storage_provider::{ByteStream, StorageErr},
+};
+use sea_orm::{DatabaseConnection, DbErr, SqlErr};
+use std::{
+    env,
+    ops::{Deref, Range},
+    sync::{Arc, Mutex},
+};
+
+const DEFAULT_REGION: &str = "us-east-1";
+/// How many rows a listing reads from the database at a time
+const LIST_BATCH_SIZE: u64 = 1000;
 
 #[async_trait]
-pub trait VfsProvider: Sync {
-    async fn upload_file(&self) -> Result<(), StorageErr>;
-    async fn download_file(&self) -> Result<(), StorageErr>;
+pub trait VfsProvider: Send + Sync {
+    /// Upload a file to the backend and return the id it can later be downloaded by
+    async fn upload_file(&self, body: ByteStream) -> Result<String, StorageErr>;
+    async fn download_file(&self, file_id: &str) -> Result<ByteStream, StorageErr>;
+    /// Download the bytes of a file within a range. Backends that can seek should override
+    /// this instead of reading the file from its start.
+    async fn download_file_range(
+        &self,
+        file_id: &str,
+        range: Range<u64>,
+    ) -> Result<ByteStream, StorageErr> {
+        Ok(slice_body(self.download_file(file_id).await?, range))
+    }
+    /// Delete a file once no object or part refers to it anymore. Backends that can't delete
+    /// files keep them.
+    async fn delete_file(&self, _file_id: &str) -> Result<(), StorageErr> {
+        Ok(())
+    }
 
     async fn connect_to_db(&self) -> DatabaseConnection {
         dotenvy::dotenv().ok();
@@ -18,54 +63,3812 @@ pub trait VfsProvider: Sync {
     }
 }
 
+/// A [`VfsProvider`] serving as a storage provider. The bodies it returns keep a handle on it, to
+/// open the files of an object only as they're reached.
+#[derive(Clone)]
+pub struct VfsStorage(Arc<dyn VfsProvider>);
+
+impl VfsStorage {
+    pub fn new(provider: impl VfsProvider + 'static) -> Self {
+        VfsStorage(Arc::new(provider))
+    }
+}
+
+impl Deref for VfsStorage {
+    type Target = dyn VfsProvider;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+struct UploadedFile {
+    file_id: String,
+    size: u64,
+    etag: String,
+}
+
+/// Upload a body to the backend while computing its size and MD5 on the way through
+async fn upload_with_digest(
+    provider: &dyn VfsProvider,
+    body: ByteStream,
+) -> Result<UploadedFile, StorageErr> {
+    let digest = Arc::new(Mutex::new((Md5::new(), 0u64)));
+    let body = {
+        let digest = Arc::clone(&digest);
+
+        body.inspect_ok(move |chunk| {
+            let mut digest = digest.lock().unwrap();
+
+            digest.0.update(chunk);
+            digest.1 += chunk.len() as u64;
+        })
+    };
+
+    let file_id = provider.upload_file(Box::pin(body)).await?;
+    let (hasher, size) = digest
+        .lock()
+        .map_err(|err| StorageErr::IOErr(err.to_string().into()))?
+        .clone();
+
+    Ok(UploadedFile {
+        file_id,
+        size,
+        etag: hex::encode(hasher.finalize()),
+    })
+}
+
+/// The backend files an object is read from, in order, as their sizes and file ids
+async fn object_files(
+    conn: &DatabaseConnection,
+    object: &object::Model,
+) -> Result<Vec<(i64, String)>, StorageErr> {
+    let chunks = db::get_object_chunks(conn, object.id)
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+    if chunks.is_empty() {
+        return Ok(vec![(object.size, object.file_id.clone())]);
+    }
+
+    Ok(chunks
+        .into_iter()
+        .map(|chunk| (chunk.size, chunk.file_id))
+        .collect())
+}
+
+/// Open an object's body, chaining its files together when it was assembled from several
+async fn download_object(
+    provider: &Arc<dyn VfsProvider>,
+    conn: &DatabaseConnection,
+    object: &object::Model,
+) -> Result<ByteStream, StorageErr> {
+    let files = object_files(conn, object).await?;
+
+    Ok(download_files(
+        provider,
+        files.into_iter().map(|(_, file_id)| (file_id, None)),
+    ))
+}
+
+/// Chain the bodies of files, or of ranges of them, each file only being opened once the stream
+/// reaches it
+fn download_files(
+    provider: &Arc<dyn VfsProvider>,
+    files: impl IntoIterator<Item = (String, Option<Range<u64>>)>,
+) -> ByteStream {
+    let provider = Arc::clone(provider);
+    let files: Vec<_> = files.into_iter().collect();
+
+    Box::pin(
+        stream::iter(files)
+            .then(move |(file_id, range)| {
+                let provider = Arc::clone(&provider);
+
+                async move {
+                    match range {
+                        Some(range) => provider.download_file_range(&file_id, range).await,
+                        None => provider.download_file(&file_id).await,
+                    }
+                }
+            })
+            .try_flatten(),
+    )
+}
+
+/// The sizes of the parts an object was completed from, empty if it was uploaded at once
+async fn object_part_sizes(
+    conn: &DatabaseConnection,
+    object: &object::Model,
+) -> Result<Vec<u64>, StorageErr> {
+    let chunks = db::get_object_chunks(conn, object.id)
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+    let mut parts: Vec<(i32, u64)> = Vec::new();
+    for chunk in chunks {
+        let Some(part_number) = chunk.part_number else {
+            continue;
+        };
+
+        match parts.last_mut() {
+            Some((last, size)) if *last == part_number => *size += chunk.size as u64,
+            _ => parts.push((part_number, chunk.size as u64)),
+        }
+    }
+
+    Ok(parts.into_iter().map(|(_, size)| size).collect())
+}
+
+/// Open the bytes of an object within a range, only downloading the files that overlap it
+async fn download_object_range(
+    provider: &Arc<dyn VfsProvider>,
+    conn: &DatabaseConnection,
+    object: &object::Model,
+    range: Range<u64>,
+) -> Result<ByteStream, StorageErr> {
+    let files = object_files(conn, object).await?;
+
+    let mut reads = Vec::new();
+    let mut offset = 0;
+    for (size, file_id) in files {
+        let file_range = offset..offset + size as u64;
+        offset = file_range.end;
+
+        if file_range.end <= range.start || file_range.start >= range.end {
+            continue;
+        }
+        if range.start <= file_range.start && file_range.end <= range.end {
+            reads.push((file_id, None));
+            continue;
+        }
+
+        reads.push((
+            file_id,
+            Some(
+                range.start.saturating_sub(file_range.start)
+                    ..range.end.min(file_range.end) - file_range.start,
+            ),
+        ));
+    }
+
+    Ok(download_files(provider, reads))
+}
+
+/// Only pass through the bytes of a body within a range, and stop reading it past the end
+fn slice_body(body: ByteStream, range: Range<u64>) -> ByteStream {
+    Box::pin(stream::unfold(
+        (body, 0u64),
+        move |(mut body, mut offset)| {
+            let range = range.clone();
+
+            async move {
+                while offset < range.end {
+                    let chunk = match body.next().await? {
+                        Ok(chunk) => chunk,
+                        Err(err) => return Some((Err(err), (body, range.end))),
+                    };
+                    let chunk_start = offset;
+                    offset += chunk.len() as u64;
+
+                    let start = range
+                        .start
+                        .saturating_sub(chunk_start)
+                        .min(chunk.len() as u64);
+                    let end = range
+                        .end
+                        .saturating_sub(chunk_start)
+                        .min(chunk.len() as u64);
+                    if start < end {
+                        return Some((
+                            Ok(chunk.slice(start as usize..end as usize)),
+                            (body, offset),
+                        ));
+                    }
+                }
+
+                None
+            }
+        },
+    ))
+}
+
+/// Map the error of writing an object. A conditional write that runs into the unique key of an
+/// object stored in the meantime didn't have its condition hold.
+fn write_err(err: DbErr, condition: WriteCondition) -> StorageErr {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) if condition == WriteCondition::IfAbsent => {
+            StorageErr::PreconditionFailed
+        }
+        _ => StorageErr::IOErr(Box::new(err)),
+    }
+}
+
+/// Fail a write that's only allowed to create an object early, before uploading its body
+async fn check_write_condition(
+    conn: &DatabaseConnection,
+    bucket_name: &str,
+    key: &str,
+    condition: WriteCondition,
+) -> Result<(), StorageErr> {
+    if condition == WriteCondition::Overwrite {
+        return Ok(());
+    }
+
+    match find_object(conn, bucket_name, key, None).await {
+        Ok(_) => Err(StorageErr::PreconditionFailed),
+        Err(StorageErr::ObjectNotFound | StorageErr::DeleteMarker { .. }) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Look up an object's version, or its latest version, failing if that's a delete marker
+async fn find_object(
+    conn: &DatabaseConnection,
+    bucket_name: &str,
+    key: &str,
+    version_id: Option<&str>,
+) -> Result<object::Model, StorageErr> {
+    let object = db::get_object(
+        conn,
+        bucket_name.into(),
+        key.into(),
+        version_id.map(Into::into),
+    )
+    .await
+    .map_err(|err| match err {
+        DbErr::RecordNotFound(_) if version_id.is_some() => StorageErr::VersionNotFound,
+        DbErr::RecordNotFound(_) => StorageErr::ObjectNotFound,
+        other => StorageErr::IOErr(Box::new(other)),
+    })?;
+
+    if object.is_delete_marker {
+        return Err(StorageErr::DeleteMarker {
+            version_id: object.version_id,
+        });
+    }
+
+    Ok(object)
+}
+
+/// Store an ACL as JSON
+fn acl_json(acl: &AccessControlList) -> Result<sea_orm::prelude::Json, StorageErr> {
+    serde_json::to_value(acl).map_err(|err| StorageErr::IOErr(Box::new(err)))
+}
+
+/// Read back an ACL stored by [`acl_json`], which is empty if there's none
+fn parse_acl(json: Option<sea_orm::prelude::Json>) -> Result<AccessControlList, StorageErr> {
+    json.map(serde_json::from_value)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))
+}
+
+fn versioning(bucket: &bucket::Model) -> VersioningStatus {
+    VersioningStatus::parse(bucket.versioning.as_deref()).unwrap_or_default()
+}
+
+/// An object as it's returned, whose version only shows once its bucket was versioned
+fn versioned_object(bucket: &bucket::Model, object: object::Model) -> Object {
+    let mut object = Object::from(object);
+    if versioning(bucket) == VersioningStatus::Unversioned {
+        object.version_id = None;
+    }
+
+    object
+}
+
+/// Delete files that were let go of. Their rows are gone already, so a file that fails to be
+/// deleted is only left behind rather than failing the request.
+async fn delete_files(provider: &dyn VfsProvider, file_ids: Vec<String>) {
+    for file_id in file_ids {
+        let _ = provider.delete_file(&file_id).await;
+    }
+}
+
+/// Delete an object, or a version of it, in a bucket with the given versioning, along with the
+/// files only the removed version was read from
+async fn delete_version(
+    provider: &dyn VfsProvider,
+    conn: &DatabaseConnection,
+    bucket: &bucket::Model,
+    key: &str,
+    version_id: Option<&str>,
+) -> Result<DeletedObject, StorageErr> {
+    let (deleted, files) = db::delete_object(
+        conn,
+        bucket.name.clone(),
+        key.into(),
+        version_id.map(Into::into),
+        versioning(bucket),
+    )
+    .await
+    .map_err(|err| match err {
+        DbErr::RecordNotFound(_) if version_id.is_some() => StorageErr::VersionNotFound,
+        DbErr::RecordNotFound(_) => StorageErr::ObjectNotFound,
+        other => StorageErr::IOErr(Box::new(other)),
+    })?;
+    delete_files(provider, files).await;
+
+    Ok(deleted)
+}
+
+/// Feed a body into an MD5 on its way through
+fn digest_body(body: ByteStream, hasher: Arc<Mutex<Md5>>) -> ByteStream {
+    Box::pin(body.inspect_ok(move |chunk| hasher.lock().unwrap().update(chunk)))
+}
+
+impl From<bucket::Model> for Bucket {
+    fn from(model: bucket::Model) -> Self {
+        Bucket {
+            name: model.name,
+            region: model.region,
+            creation_date: model.creation_date,
+            versioning: VersioningStatus::parse(model.versioning.as_deref()).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<lifecycle_rule::Model> for LifecycleRule {
+    fn from(model: lifecycle_rule::Model) -> Self {
+        let expiration = match (model.expiration_days, model.expiration_date) {
+            (Some(days), _) => Some(Expiration::Days(days as u32)),
+            (None, Some(date)) => Some(Expiration::Date(date)),
+            (None, None) if model.expired_object_delete_marker => {
+                Some(Expiration::ExpiredObjectDeleteMarker)
+            }
+            (None, None) => None,
+        };
+
+        LifecycleRule {
+            id: model.rule_id,
+            enabled: model.enabled,
+            filter: LifecycleFilter {
+                prefix: model.prefix,
+                tags: db::user_metadata(model.tags.as_ref()),
+                object_size_greater_than: model.object_size_greater_than.map(|size| size as u64),
+                object_size_less_than: model.object_size_less_than.map(|size| size as u64),
+            },
+            expiration,
+            noncurrent_days: model.noncurrent_days.map(|days| days as u32),
+            abort_incomplete_upload_days: model
+                .abort_incomplete_upload_days
+                .map(|days| days as u32),
+        }
+    }
+}
+
+impl From<cors_rule::Model> for CorsRule {
+    fn from(model: cors_rule::Model) -> Self {
+        CorsRule {
+            id: model.rule_id,
+            allowed_origins: db::json_strings(&model.allowed_origins),
+            allowed_methods: db::json_strings(&model.allowed_methods),
+            allowed_headers: db::json_strings(&model.allowed_headers),
+            expose_headers: db::json_strings(&model.expose_headers),
+            max_age_seconds: model.max_age_seconds.map(|seconds| seconds as u32),
+        }
+    }
+}
+
+impl From<user::Model> for User {
+    fn from(model: user::Model) -> Self {
+        User {
+            id: model.id,
+            name: model.name,
+            creation_date: model.creation_date,
+        }
+    }
+}
+
+impl From<group::Model> for Group {
+    fn from(model: group::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            creation_date: model.creation_date,
+        }
+    }
+}
+
+impl From<identity_policy::Model> for IdentityPolicy {
+    fn from(model: identity_policy::Model) -> Self {
+        Self {
+            name: model.name,
+            document: model.document,
+        }
+    }
+}
+
+impl From<session::Model> for Session {
+    fn from(model: session::Model) -> Self {
+        Self {
+            access_key_id: model.access_key_id,
+            secret_access_key: model.secret_access_key,
+            session_token: model.session_token,
+            parent_access_key_id: model.parent_access_key_id,
+            user_name: model.user_name,
+            session_name: model.session_name,
+            policy: model.policy,
+            expiration: model.expiration,
+        }
+    }
+}
+
+impl From<access_key::Model> for AccessKey {
+    fn from(model: access_key::Model) -> Self {
+        AccessKey {
+            access_key_id: model.access_key_id,
+            secret_access_key: model.secret_access_key,
+            user_name: model.user_name,
+            status: AccessKeyStatus::parse(&model.status).unwrap_or(AccessKeyStatus::Inactive),
+            creation_date: model.creation_date,
+        }
+    }
+}
+
+impl From<object::Model> for Object {
+    fn from(model: object::Model) -> Self {
+        Object {
+            key: model.key,
+            size: model.size as u64,
+            etag: model.etag,
+            metadata: ObjectMetadata {
+                user: db::user_metadata(model.user_metadata.as_ref()),
+                content_type: model.content_type,
+                content_encoding: model.content_encoding,
+                content_disposition: model.content_disposition,
+                content_language: model.content_language,
+                cache_control: model.cache_control,
+                expires: model.expires,
+            },
+            last_modified: model.last_modified,
+            version_id: Some(model.version_id),
+            delete_marker: model.is_delete_marker,
+        }
+    }
+}
+
+impl From<object::Model> for ObjectVersion {
+    fn from(model: object::Model) -> Self {
+        ObjectVersion {
+            is_latest: model.is_latest,
+            object: model.into(),
+        }
+    }
+}
+
+impl From<multipart_upload::Model> for MultipartUpload {
+    fn from(model: multipart_upload::Model) -> Self {
+        MultipartUpload {
+            upload_id: model.upload_id,
+            key: model.key,
+            initiated: model.initiated,
+        }
+    }
+}
+
+impl From<part::Model> for Part {
+    fn from(model: part::Model) -> Self {
+        Part {
+            part_number: model.part_number as u16,
+            size: model.size as u64,
+            etag: model.etag,
+            last_modified: model.last_modified,
+        }
+    }
+}
+
 #[async_trait]
-impl s3::storage_provider::StorageProvider for dyn VfsProvider {
-    async fn create_bucket(&self, name: String) -> Result<(), StorageErr> {
+impl s3_entities::storage_provider::StorageProvider for VfsStorage {
+    async fn list_buckets(&self, owner_id: Option<&str>) -> Result<Vec<Bucket>, StorageErr> {
         let conn = self.connect_to_db().await;
-        let _ = db::create_bucket(&conn, name)
+        let buckets = db::list_buckets(&conn, owner_id.map(Into::into))
             .await
             .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
 
+        Ok(buckets.into_iter().map(Into::into).collect())
+    }
+
+    async fn create_bucket(
+        &self,
+        name: &str,
+        options: CreateBucketOptions,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        if db::head_bucket(&conn, name.into()).await.is_ok() {
+            return Err(StorageErr::BucketAlreadyExists);
+        }
+
+        let acl = options.acl.as_ref().map(acl_json).transpose()?;
+        let object_ownership = options
+            .object_ownership
+            .map(|ownership| ownership.as_str().to_string());
+        let _ = db::create_bucket(
+            &conn,
+            name.into(),
+            options.region.unwrap_or(DEFAULT_REGION.into()),
+            options.owner_id,
+            acl,
+            object_ownership,
+        )
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
         Ok(())
     }
 
-    async fn head_bucket(&self, name: String) -> Result<(), StorageErr> {
+    async fn head_bucket(&self, name: &str) -> Result<Bucket, StorageErr> {
         let conn = self.connect_to_db().await;
+        let bucket = db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
 
-        db::head_bucket(&conn, name).await.map_err(|err| match err {
-            DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
-            other => StorageErr::IOErr(Box::new(other)),
+        Ok(bucket.into())
+    }
+
+    async fn delete_bucket(&self, name: &str) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+        let _ = db::delete_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        Ok(())
+    }
+
+    async fn put_bucket_versioning(
+        &self,
+        name: &str,
+        status: VersioningStatus,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+        let _ = db::put_bucket_versioning(&conn, name.into(), status.as_str().map(Into::into))
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        Ok(())
+    }
+
+    async fn get_bucket_lifecycle(&self, name: &str) -> Result<LifecycleConfiguration, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let rules = db::get_lifecycle_rules(&conn, name.into())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+        if rules.is_empty() {
+            return Err(StorageErr::NoSuchLifecycleConfiguration);
+        }
+
+        Ok(LifecycleConfiguration {
+            rules: rules.into_iter().map(Into::into).collect(),
         })
     }
 
-    async fn delete_bucket(&self, name: String) -> Result<(), StorageErr> {
+    async fn put_bucket_lifecycle(
+        &self,
+        name: &str,
+        configuration: LifecycleConfiguration,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        db::put_lifecycle_rules(&conn, name.into(), configuration.rules)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn get_bucket_cors(&self, name: &str) -> Result<CorsConfiguration, StorageErr> {
         let conn = self.connect_to_db().await;
-        let _ = db::create_bucket(&conn, name)
+
+        db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let rules = db::get_cors_rules(&conn, name.into())
             .await
             .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+        if rules.is_empty() {
+            return Err(StorageErr::NoSuchCORSConfiguration);
+        }
+
+        Ok(CorsConfiguration {
+            rules: rules.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn put_bucket_cors(
+        &self,
+        name: &str,
+        configuration: CorsConfiguration,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        db::put_cors_rules(&conn, name.into(), configuration.rules)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn get_bucket_policy(&self, name: &str) -> Result<String, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let bucket = db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        bucket.policy.ok_or(StorageErr::NoSuchBucketPolicy)
+    }
+
+    async fn put_bucket_policy(
+        &self,
+        name: &str,
+        policy: Option<String>,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+        let _ = db::put_bucket_policy(&conn, name.into(), policy)
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
 
         Ok(())
     }
 
-    async fn delete_object(&self, bucket_name: String, key: String) -> Result<(), StorageErr> {
+    async fn get_bucket_acl(&self, name: &str) -> Result<AccessControlList, StorageErr> {
         let conn = self.connect_to_db().await;
-        let _ = db::delete_object(&conn, bucket_name, key)
+        let bucket = db::head_bucket(&conn, name.into())
             .await
-            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        parse_acl(bucket.acl)
+    }
+
+    async fn put_bucket_acl(&self, name: &str, acl: AccessControlList) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+        let _ = db::put_bucket_acl(&conn, name.into(), acl_json(&acl)?)
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
 
         Ok(())
     }
 
-    async fn delete_objects(
+    async fn get_bucket_ownership_controls(
+        &self,
+        name: &str,
+    ) -> Result<ObjectOwnership, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let bucket = db::head_bucket(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        bucket
+            .object_ownership
+            .as_deref()
+            .and_then(ObjectOwnership::parse)
+            .ok_or(StorageErr::NoSuchOwnershipControls)
+    }
+
+    async fn put_bucket_ownership_controls(
         &self,
-        bucket_name: String,
-        objects: Vec<String>,
+        name: &str,
+        ownership: Option<ObjectOwnership>,
     ) -> Result<(), StorageErr> {
         let conn = self.connect_to_db().await;
-        let _ = db::delete_objects(&conn, bucket_name, objects)
+        let object_ownership = ownership.map(|ownership| ownership.as_str().to_string());
+        let _ = db::put_bucket_ownership(&conn, name.into(), object_ownership)
             .await
-            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
 
         Ok(())
     }
+
+    async fn list_objects(
+        &self,
+        bucket_name: &str,
+        options: ListObjectsOptions,
+    ) -> Result<Page<Object>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let prefix = options.prefix.clone().unwrap_or_default();
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.marker.as_deref(),
+            options.max_keys,
+        );
+        let mut marker = options.marker;
+
+        // Each common prefix comes back as a single row, but the one a batch ends on can come
+        // back again at the start of the next
+        loop {
+            let objects = db::list_objects(
+                &conn,
+                bucket_name.into(),
+                options.delimiter.clone(),
+                marker.clone(),
+                Some(LIST_BATCH_SIZE),
+                options.prefix.clone(),
+            )
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+            let exhausted = (objects.len() as u64) < LIST_BATCH_SIZE;
+
+            for object in objects {
+                marker = Some(object.key.clone());
+
+                if object.key.starts_with(&prefix) && !page.push(&object.key.clone(), object.into())
+                {
+                    return Ok(page.finish());
+                }
+            }
+
+            if exhausted {
+                return Ok(page.finish());
+            }
+        }
+    }
+
+    async fn list_object_versions(
+        &self,
+        bucket_name: &str,
+        options: ListVersionsOptions,
+    ) -> Result<Page<ObjectVersion>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let prefix = options.prefix.clone().unwrap_or_default();
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.key_marker.as_deref(),
+            options.max_keys,
+        );
+        let mut key_marker = options.key_marker;
+        let mut version_id_marker = options.version_id_marker;
+
+        // As with listing objects, the common prefix a batch ends on can come back again at the
+        // start of the next
+        loop {
+            let versions = db::list_object_versions(
+                &conn,
+                bucket_name.into(),
+                options.delimiter.clone(),
+                key_marker.clone(),
+                version_id_marker.clone(),
+                Some(LIST_BATCH_SIZE),
+                options.prefix.clone(),
+            )
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+            let exhausted = (versions.len() as u64) < LIST_BATCH_SIZE;
+
+            for version in versions {
+                key_marker = Some(version.key.clone());
+                version_id_marker = Some(version.version_id.clone());
+
+                if version.key.starts_with(&prefix)
+                    && !page.push(&version.key.clone(), version.into())
+                {
+                    return Ok(page.finish());
+                }
+            }
+
+            if exhausted {
+                return Ok(page.finish());
+            }
+        }
+    }
+
+    async fn put_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        options: PutObjectOptions,
+        body: ByteStream,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+        check_write_condition(&conn, bucket_name, key, condition).await?;
+        let acl = options.acl.as_ref().map(acl_json).transpose()?;
+
+        let file = upload_with_digest(&**self, body).await?;
+        let (object, files) = db::put_object(
+            &conn,
+            bucket_name.into(),
+            key.into(),
+            file.size as i64,
+            file.etag,
+            file.file_id,
+            options.metadata,
+            options.tags,
+            acl,
+            versioning(&bucket),
+            condition == WriteCondition::Overwrite,
+        )
+        .await
+        .map_err(|err| write_err(err, condition))?;
+        delete_files(&**self, files).await;
+
+        Ok(versioned_object(&bucket, object))
+    }
+
+    async fn get_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<(Object, ByteStream), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, version_id).await?;
+        let body = download_object(&self.0, &conn, &object).await?;
+
+        Ok((versioned_object(&bucket, object), body))
+    }
+
+    async fn get_object_range(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+        range: &ReadRange,
+    ) -> Result<(Object, Range<u64>, ByteStream), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, version_id).await?;
+        let part_sizes = match range {
+            ReadRange::Part(_) => object_part_sizes(&conn, &object).await?,
+            _ => Vec::new(),
+        };
+        let range = range.resolve(object.size as u64, &part_sizes)?;
+        let body = download_object_range(&self.0, &conn, &object, range.clone()).await?;
+
+        Ok((versioned_object(&bucket, object), range, body))
+    }
+
+    async fn head_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Object, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, version_id).await?;
+
+        Ok(versioned_object(&bucket, object))
+    }
+
+    async fn get_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<Tags, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, version_id).await?;
+
+        db::get_object_tags(&conn, object.id)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn put_object_tagging(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        tags: Tags,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, None).await?;
+
+        db::put_object_tags(&conn, object.id, tags)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn get_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<AccessControlList, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, version_id).await?;
+        let acl = db::get_object_acl(&conn, object.id)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        parse_acl(acl)
+    }
+
+    async fn put_object_acl(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        acl: AccessControlList,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let object = find_object(&conn, bucket_name, key, None).await?;
+
+        db::put_object_acl(&conn, object.id, acl_json(&acl)?)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn copy_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        source: &CopySource,
+        options: CopyObjectOptions,
+    ) -> Result<CopiedObject, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let source_bucket = db::head_bucket(&conn, source.bucket.clone())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let source_object = find_object(
+            &conn,
+            &source.bucket,
+            &source.key,
+            source.version_id.as_deref(),
+        )
+        .await?;
+        let source_id = source_object.id;
+        let source_object = versioned_object(&source_bucket, source_object);
+        if options
+            .source_preconditions
+            .evaluate(&source_object.etag, &source_object.last_modified)
+            != Precondition::Passed
+        {
+            return Err(StorageErr::PreconditionFailed);
+        }
+
+        let acl = options.acl.as_ref().map(acl_json).transpose()?;
+        let (object, files) = db::copy_object(
+            &conn,
+            bucket_name.into(),
+            key.into(),
+            source_id,
+            match options.metadata {
+                MetadataDirective::Copy => None,
+                MetadataDirective::Replace(metadata) => Some(metadata),
+            },
+            options.tags,
+            acl,
+            versioning(&bucket),
+        )
+        .await
+        .map_err(|err| match err {
+            // The version that was checked was deleted or replaced in the meantime
+            DbErr::RecordNotFound(_) if source.version_id.is_some() => StorageErr::VersionNotFound,
+            DbErr::RecordNotFound(_) => StorageErr::ObjectNotFound,
+            other => StorageErr::IOErr(Box::new(other)),
+        })?;
+        delete_files(&**self, files).await;
+
+        Ok(CopiedObject {
+            object: versioned_object(&bucket, object),
+            source_version_id: source_object.version_id,
+        })
+    }
+
+    async fn delete_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Result<DeletedObject, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        delete_version(&**self, &conn, &bucket, key, version_id).await
+    }
+
+    async fn delete_objects(
+        &self,
+        bucket_name: &str,
+        objects: Vec<(String, Option<String>)>,
+    ) -> Vec<Result<DeletedObject, StorageErr>> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = match db::head_bucket(&conn, bucket_name.into()).await {
+            Ok(bucket) => bucket,
+            Err(err) => {
+                let error = |err: &DbErr| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                    other => StorageErr::IOErr(other.to_string().into()),
+                };
+
+                return objects.iter().map(|_| Err(error(&err))).collect();
+            }
+        };
+
+        let mut deleted = Vec::with_capacity(objects.len());
+        for (key, version_id) in objects {
+            deleted
+                .push(delete_version(&**self, &conn, &bucket, &key, version_id.as_deref()).await);
+        }
+
+        deleted
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        options: PutObjectOptions,
+    ) -> Result<MultipartUpload, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let acl = options.acl.as_ref().map(acl_json).transpose()?;
+        let upload = db::create_multipart_upload(
+            &conn,
+            bucket_name.into(),
+            key.into(),
+            options.metadata,
+            options.tags,
+            acl,
+        )
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(upload.into())
+    }
+
+    async fn upload_part(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        body: ByteStream,
+    ) -> Result<Part, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let upload =
+            db::get_multipart_upload(&conn, bucket_name.into(), key.into(), upload_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::UploadNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+
+        let file = upload_with_digest(&**self, body).await?;
+        let part = db::put_part(
+            &conn,
+            upload.upload_id,
+            part_number.into(),
+            file.size as i64,
+            file.etag,
+            file.file_id,
+        )
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(part.into())
+    }
+
+    async fn upload_part_copy(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        source: &CopySource,
+        range: Option<Range<u64>>,
+    ) -> Result<Part, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        for bucket_name in [bucket_name, &source.bucket] {
+            db::head_bucket(&conn, bucket_name.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+        }
+
+        let upload =
+            db::get_multipart_upload(&conn, bucket_name.into(), key.into(), upload_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::UploadNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+        let object = find_object(
+            &conn,
+            &source.bucket,
+            &source.key,
+            source.version_id.as_deref(),
+        )
+        .await?;
+        let range = s3_object::resolve_range(range, object.size as u64)?;
+        let source_files = object_files(&conn, &object).await?;
+        // The ETag of an object stored from a single file at once is the MD5 of its content
+        let whole_file = range == (0..object.size as u64)
+            && source_files.len() == 1
+            && !object.etag.contains('-');
+
+        // Files that lie entirely within the range are shared with the source, only the ones
+        // the range starts or ends inside of are copied. Either way the part's bytes are read to
+        // get its MD5, unless they're a whole file whose MD5 is known already.
+        let hasher = Arc::new(Mutex::new(Md5::new()));
+        let mut files = Vec::new();
+        let mut offset = 0;
+        for (size, file_id) in source_files {
+            let file_range = offset..offset + size as u64;
+            offset = file_range.end;
+
+            if file_range.end <= range.start || file_range.start >= range.end {
+                continue;
+            }
+            if range.start <= file_range.start && file_range.end <= range.end {
+                if !whole_file {
+                    digest_body(self.download_file(&file_id).await?, Arc::clone(&hasher))
+                        .try_for_each(|_| async { Ok(()) })
+                        .await?;
+                }
+                files.push((size, file_id));
+                continue;
+            }
+
+            let body = self
+                .download_file_range(
+                    &file_id,
+                    range.start.saturating_sub(file_range.start)
+                        ..range.end.min(file_range.end) - file_range.start,
+                )
+                .await?;
+            let file = upload_with_digest(&**self, digest_body(body, Arc::clone(&hasher))).await?;
+
+            files.push((file.size as i64, file.file_id));
+        }
+
+        let etag = match whole_file {
+            true => object.etag,
+            false => hex::encode(
+                hasher
+                    .lock()
+                    .map_err(|err| StorageErr::IOErr(err.to_string().into()))?
+                    .clone()
+                    .finalize(),
+            ),
+        };
+        let part = match files.as_slice() {
+            [(size, file_id)] => {
+                db::put_part(
+                    &conn,
+                    upload.upload_id,
+                    part_number.into(),
+                    *size,
+                    etag,
+                    file_id.clone(),
+                )
+                .await
+            }
+            _ => {
+                db::put_part_chunks(&conn, upload.upload_id, part_number.into(), etag, files).await
+            }
+        }
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(part.into())
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+        condition: WriteCondition,
+    ) -> Result<Object, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        let bucket = db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let upload =
+            db::get_multipart_upload(&conn, bucket_name.into(), key.into(), upload_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::UploadNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+        let uploaded_parts = db::get_parts(&conn, upload.upload_id.clone())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        let uploaded: Vec<Part> = uploaded_parts.iter().cloned().map(Into::into).collect();
+        let selected = multipart::select_parts(&uploaded, &parts)?;
+        let etag = multipart::multipart_etag(&selected)?;
+        let selected_parts = selected
+            .iter()
+            .filter_map(|selected| {
+                uploaded_parts
+                    .iter()
+                    .find(|part| part.part_number == i32::from(selected.part_number))
+                    .cloned()
+            })
+            .collect();
+
+        let (object, files) = db::complete_multipart_upload(
+            &conn,
+            upload,
+            selected_parts,
+            etag,
+            versioning(&bucket),
+            condition == WriteCondition::Overwrite,
+        )
+        .await
+        .map_err(|err| write_err(err, condition))?;
+        delete_files(&**self, files).await;
+
+        Ok(versioned_object(&bucket, object))
+    }
+
+    async fn abort_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let upload =
+            db::get_multipart_upload(&conn, bucket_name.into(), key.into(), upload_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::UploadNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+
+        let files = db::abort_multipart_upload(&conn, upload.upload_id)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+        delete_files(&**self, files).await;
+
+        Ok(())
+    }
+
+    async fn list_multipart_uploads(
+        &self,
+        bucket_name: &str,
+        options: ListUploadsOptions,
+    ) -> Result<Page<MultipartUpload>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let prefix = options.prefix.clone().unwrap_or_default();
+        let mut page = PageBuilder::new(
+            options.prefix.clone(),
+            options.delimiter.clone(),
+            options.key_marker.as_deref(),
+            options.max_uploads,
+        );
+        let mut key_marker = options.key_marker;
+        let mut upload_id_marker = options.upload_id_marker;
+
+        // Keys rolled up into common prefixes don't count towards the page, so it can take
+        // more than one batch to fill it
+        loop {
+            let uploads = db::list_multipart_uploads(
+                &conn,
+                bucket_name.into(),
+                key_marker.clone(),
+                upload_id_marker.clone(),
+                Some(LIST_BATCH_SIZE),
+                options.prefix.clone(),
+            )
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+            let exhausted = (uploads.len() as u64) < LIST_BATCH_SIZE;
+
+            for upload in uploads {
+                key_marker = Some(upload.key.clone());
+                upload_id_marker = Some(upload.upload_id.clone());
+
+                if upload.key.starts_with(&prefix) && !page.push(&upload.key.clone(), upload.into())
+                {
+                    return Ok(page.finish());
+                }
+            }
+
+            if exhausted {
+                return Ok(page.finish());
+            }
+        }
+    }
+
+    async fn list_parts(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number_marker: Option<u16>,
+        max_parts: usize,
+    ) -> Result<PartsPage, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::head_bucket(&conn, bucket_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::BucketNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let upload =
+            db::get_multipart_upload(&conn, bucket_name.into(), key.into(), upload_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::UploadNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+        let mut parts: Vec<Part> = db::list_parts(
+            &conn,
+            upload.upload_id,
+            part_number_marker.map(i32::from),
+            Some(max_parts as u64 + 1),
+        )
+        .await
+        .map_err(|err| StorageErr::IOErr(Box::new(err)))?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        let is_truncated = parts.len() > max_parts;
+        parts.truncate(max_parts);
+
+        Ok(PartsPage {
+            next_part_number_marker: parts.last().map(|part| part.part_number),
+            parts,
+            is_truncated,
+        })
+    }
+
+    async fn create_user(&self, user: User) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        if db::get_user(&conn, user.name.clone()).await.is_ok() {
+            return Err(StorageErr::UserAlreadyExists);
+        }
+
+        db::create_user(&conn, user)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    async fn get_user(&self, name: &str) -> Result<User, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let user = db::get_user(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::UserNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        Ok(user.into())
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let users = db::list_users(&conn)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(users.into_iter().map(Into::into).collect())
+    }
+
+    async fn delete_user(&self, name: &str) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::delete_user(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::UserNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })
+    }
+
+    async fn create_access_key(&self, access_key: AccessKey) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::get_user(&conn, access_key.user_name.clone())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::UserNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        db::create_access_key(&conn, access_key)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    async fn get_access_key(&self, access_key_id: &str) -> Result<AccessKey, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let access_key = db::get_access_key(&conn, access_key_id.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::AccessKeyNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        Ok(access_key.into())
+    }
+
+    async fn list_access_keys(&self, user_name: &str) -> Result<Vec<AccessKey>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::get_user(&conn, user_name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::UserNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        let access_keys = db::list_access_keys(&conn, user_name.into())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(access_keys.into_iter().map(Into::into).collect())
+    }
+
+    async fn update_access_key(
+        &self,
+        access_key_id: &str,
+        status: AccessKeyStatus,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+        let _ = db::update_access_key(&conn, access_key_id.into(), status.as_str().into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::AccessKeyNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })?;
+
+        Ok(())
+    }
+
+    async fn delete_access_key(&self, access_key_id: &str) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::delete_access_key(&conn, access_key_id.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::AccessKeyNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })
+    }
+
+    async fn create_group(&self, group: Group) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        if db::get_group(&conn, group.name.clone()).await.is_ok() {
+            return Err(StorageErr::GroupAlreadyExists);
+        }
+
+        db::create_group(&conn, group)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    async fn list_groups(&self) -> Result<Vec<Group>, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let groups = db::list_groups(&conn)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(groups.into_iter().map(Into::into).collect())
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::delete_group(&conn, name.into())
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::GroupNotFound,
+                other => StorageErr::IOErr(Box::new(other)),
+            })
+    }
+
+    async fn add_user_to_group(&self, group_name: &str, user_name: &str) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        policy_holder_exists(&conn, &PolicyHolder::Group(group_name.into())).await?;
+        policy_holder_exists(&conn, &PolicyHolder::User(user_name.into())).await?;
+
+        db::add_user_to_group(&conn, group_name.into(), user_name.into())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn remove_user_from_group(
+        &self,
+        group_name: &str,
+        user_name: &str,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        policy_holder_exists(&conn, &PolicyHolder::Group(group_name.into())).await?;
+        policy_holder_exists(&conn, &PolicyHolder::User(user_name.into())).await?;
+
+        db::remove_user_from_group(&conn, group_name.into(), user_name.into())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+
+    async fn list_groups_for_user(&self, user_name: &str) -> Result<Vec<Group>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        policy_holder_exists(&conn, &PolicyHolder::User(user_name.into())).await?;
+
+        let groups = db::list_groups_for_user(&conn, user_name.into())
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(groups.into_iter().map(Into::into).collect())
+    }
+
+    async fn put_identity_policy(
+        &self,
+        holder: &PolicyHolder,
+        name: &str,
+        document: Option<String>,
+    ) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        policy_holder_exists(&conn, holder).await?;
+
+        db::put_identity_policy(&conn, holder, name.into(), document)
+            .await
+            .map_err(|err| match err {
+                DbErr::RecordNotFound(_) => StorageErr::NoSuchIdentityPolicy,
+                other => StorageErr::IOErr(Box::new(other)),
+            })
+    }
+
+    async fn list_identity_policies(
+        &self,
+        holder: &PolicyHolder,
+    ) -> Result<Vec<IdentityPolicy>, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        policy_holder_exists(&conn, holder).await?;
+
+        let policies = db::list_identity_policies(&conn, holder)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(policies.into_iter().map(Into::into).collect())
+    }
+
+    async fn create_session(&self, session: Session) -> Result<(), StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::create_session(&conn, session)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    async fn get_session(&self, access_key_id: &str) -> Result<Session, StorageErr> {
+        let conn = self.connect_to_db().await;
+        let session =
+            db::get_session(&conn, access_key_id.into())
+                .await
+                .map_err(|err| match err {
+                    DbErr::RecordNotFound(_) => StorageErr::SessionNotFound,
+                    other => StorageErr::IOErr(Box::new(other)),
+                })?;
+
+        Ok(session.into())
+    }
+
+    async fn delete_expired_sessions(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, StorageErr> {
+        let conn = self.connect_to_db().await;
+
+        db::delete_expired_sessions(&conn, before)
+            .await
+            .map_err(|err| StorageErr::IOErr(Box::new(err)))
+    }
+}
+
+/// Fail with [`StorageErr::UserNotFound`] or [`StorageErr::GroupNotFound`] unless the user or
+/// group exists
+async fn policy_holder_exists(
+    conn: &DatabaseConnection,
+    holder: &PolicyHolder,
+) -> Result<(), StorageErr> {
+    let result = match holder {
+        PolicyHolder::User(user_name) => db::get_user(conn, user_name.clone()).await.map(|_| ()),
+        PolicyHolder::Group(group_name) => {
+            db::get_group(conn, group_name.clone()).await.map(|_| ())
+        }
+    };
+
+    result.map_err(|err| match (err, holder) {
+        (DbErr::RecordNotFound(_), PolicyHolder::User(_)) => StorageErr::UserNotFound,
+        (DbErr::RecordNotFound(_), PolicyHolder::Group(_)) => StorageErr::GroupNotFound,
+        (other, _) => StorageErr::IOErr(Box::new(other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s3_entities::{
+        acl::{CannedAcl, Owner},
+        multipart::MIN_PART_SIZE,
+        object::Preconditions,
+        storage_provider::StorageProvider,
+    };
+    use std::collections::HashMap;
+
+    /// Keeps files in memory and the database in a shared in-memory SQLite, which lives as
+    /// long as its first connection is kept open
+    struct MemoryVfs {
+        files: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+        db_url: String,
+        _db: DatabaseConnection,
+    }
+
+    impl MemoryVfs {
+        async fn new() -> VfsStorage {
+            MemoryVfs::with_files().await.0
+        }
+
+        /// A provider along with the files it keeps, to check which of them are deleted
+        async fn with_files() -> (VfsStorage, Arc<Mutex<HashMap<String, Vec<u8>>>>) {
+            let db_url = format!(
+                "sqlite:file:{}?mode=memory&cache=shared",
+                uuid::Uuid::new_v4().simple()
+            );
+            let db = db::run(db_url.clone(), "test".into()).await.unwrap();
+            let files = Arc::new(Mutex::new(HashMap::new()));
+
+            let provider = VfsStorage::new(MemoryVfs {
+                files: Arc::clone(&files),
+                db_url,
+                _db: db,
+            });
+
+            (provider, files)
+        }
+    }
+
+    #[async_trait]
+    impl VfsProvider for MemoryVfs {
+        async fn upload_file(&self, body: ByteStream) -> Result<String, StorageErr> {
+            let chunks: Vec<_> = body.try_collect().await?;
+            let file_id = uuid::Uuid::new_v4().to_string();
+
+            self.files
+                .lock()
+                .unwrap()
+                .insert(file_id.clone(), chunks.concat());
+
+            Ok(file_id)
+        }
+
+        async fn download_file(&self, file_id: &str) -> Result<ByteStream, StorageErr> {
+            let data = self
+                .files
+                .lock()
+                .unwrap()
+                .get(file_id)
+                .cloned()
+                .ok_or(StorageErr::ObjectNotFound)?;
+
+            Ok(Box::pin(stream::once(async move { Ok(data.into()) })))
+        }
+
+        async fn delete_file(&self, file_id: &str) -> Result<(), StorageErr> {
+            self.files.lock().unwrap().remove(file_id);
+
+            Ok(())
+        }
+
+        async fn connect_to_db(&self) -> DatabaseConnection {
+            db::run(self.db_url.clone(), "test".into()).await.unwrap()
+        }
+    }
+
+    fn body(data: Vec<u8>) -> ByteStream {
+        Box::pin(stream::once(async move { Ok(data.into()) }))
+    }
+
+    fn copy_options(metadata: MetadataDirective) -> CopyObjectOptions {
+        CopyObjectOptions {
+            metadata,
+            tags: None,
+            acl: None,
+            source_preconditions: Default::default(),
+        }
+    }
+
+    async fn read(provider: &VfsStorage, key: &str) -> (Object, Vec<u8>) {
+        let (object, body) = StorageProvider::get_object(provider, "bucket", key, None)
+            .await
+            .unwrap();
+        let chunks: Vec<_> = body.try_collect().await.unwrap();
+
+        (object, chunks.concat())
+    }
+
+    #[tokio::test]
+    async fn test_put_object() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let object = provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let (_, data) = read(&provider, "key").await;
+
+        assert_eq!(object.etag, "4a4be40c96ac6314e91d93f38043a634");
+        assert_eq!(data, b"meow");
+    }
+
+    #[tokio::test]
+    async fn test_content_type() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let put = |content_type: Option<&str>| {
+            provider.put_object(
+                "bucket",
+                "key",
+                ObjectMetadata {
+                    content_type: content_type.map(String::from),
+                    ..Default::default()
+                }
+                .into(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+        };
+        put(Some("text/plain")).await.unwrap();
+        let (object, _) = read(&provider, "key").await;
+        assert_eq!(object.metadata.content_type.as_deref(), Some("text/plain"));
+
+        // Overwriting an object replaces its Content-Type rather than keeping the old one
+        put(None).await.unwrap();
+        let (object, _) = read(&provider, "key").await;
+        assert_eq!(object.metadata.content_type, None);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let first = vec![b'a'; MIN_PART_SIZE as usize];
+        let mut parts = Vec::new();
+        for (part_number, data) in [(1, first.clone()), (2, b"meow".to_vec())] {
+            let part = provider
+                .upload_part("bucket", "key", &upload.upload_id, part_number, body(data))
+                .await
+                .unwrap();
+
+            parts.push(CompletedPart {
+                part_number,
+                etag: part.etag,
+            });
+        }
+
+        let object = provider
+            .complete_multipart_upload(
+                "bucket",
+                "key",
+                &upload.upload_id,
+                parts,
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let (stored, data) = read(&provider, "key").await;
+
+        assert!(object.etag.ends_with("-2"));
+        assert_eq!(stored.size, MIN_PART_SIZE + 4);
+        assert_eq!(data, [first, b"meow".to_vec()].concat());
+        assert!(matches!(
+            provider
+                .abort_multipart_upload("bucket", "key", &upload.upload_id)
+                .await,
+            Err(StorageErr::UploadNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_files_lazily() {
+        let (provider, files) = MemoryVfs::with_files().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let mut parts = Vec::new();
+        for (part_number, data) in [
+            (1, vec![b'a'; MIN_PART_SIZE as usize]),
+            (2, b"meow".to_vec()),
+        ] {
+            let part = provider
+                .upload_part("bucket", "key", &upload.upload_id, part_number, body(data))
+                .await
+                .unwrap();
+
+            parts.push(CompletedPart {
+                part_number,
+                etag: part.etag,
+            });
+        }
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "key",
+                &upload.upload_id,
+                parts,
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let (_, mut body) = StorageProvider::get_object(&provider, "bucket", "key", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            body.next().await.unwrap().unwrap().len(),
+            MIN_PART_SIZE as usize
+        );
+
+        // The second part is only opened once the first one has been read
+        files.lock().unwrap().clear();
+        assert!(matches!(
+            body.next().await,
+            Some(Err(StorageErr::ObjectNotFound))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_object_metadata() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let metadata = ObjectMetadata {
+            content_type: Some("text/plain".into()),
+            cache_control: Some("no-cache".into()),
+            user: [("color".to_owned(), "orange".to_owned())].into(),
+            ..Default::default()
+        };
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                metadata.clone().into(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let (object, _) = read(&provider, "key").await;
+
+        assert_eq!(object.metadata, metadata);
+
+        let upload = provider
+            .create_multipart_upload("bucket", "uploaded", metadata.clone().into())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part(
+                "bucket",
+                "uploaded",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "uploaded",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let (object, _) = read(&provider, "uploaded").await;
+
+        assert_eq!(object.metadata, metadata);
+
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "key".into(),
+            version_id: None,
+        };
+        let copied = provider
+            .copy_object(
+                "bucket",
+                "copied",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+        let replaced = provider
+            .copy_object(
+                "bucket",
+                "replaced",
+                &source,
+                copy_options(MetadataDirective::Replace(ObjectMetadata::default())),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(copied.object.metadata, metadata);
+        assert_eq!(replaced.object.metadata, ObjectMetadata::default());
+    }
+
+    #[tokio::test]
+    async fn test_write_if_absent() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part(
+                "bucket",
+                "key",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                body(b"purr".to_vec()),
+                WriteCondition::IfAbsent,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            provider
+                .put_object(
+                    "bucket",
+                    "key",
+                    PutObjectOptions::default(),
+                    body(b"hiss".to_vec()),
+                    WriteCondition::IfAbsent,
+                )
+                .await,
+            Err(StorageErr::PreconditionFailed)
+        ));
+        // Completing doesn't check ahead, so this runs into the object's unique key instead
+        assert!(matches!(
+            provider
+                .complete_multipart_upload(
+                    "bucket",
+                    "key",
+                    &upload.upload_id,
+                    vec![CompletedPart {
+                        part_number: 1,
+                        etag: part.etag,
+                    }],
+                    WriteCondition::IfAbsent,
+                )
+                .await,
+            Err(StorageErr::PreconditionFailed)
+        ));
+
+        let (_, data) = read(&provider, "key").await;
+        assert_eq!(data, b"purr");
+    }
+
+    #[tokio::test]
+    async fn test_get_object_range() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let first = vec![b'a'; MIN_PART_SIZE as usize];
+        let mut parts = Vec::new();
+        for (part_number, data) in [(1, first), (2, b"meow".to_vec())] {
+            let part = provider
+                .upload_part("bucket", "key", &upload.upload_id, part_number, body(data))
+                .await
+                .unwrap();
+
+            parts.push(CompletedPart {
+                part_number,
+                etag: part.etag,
+            });
+        }
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "key",
+                &upload.upload_id,
+                parts,
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let read_range = |range: ReadRange| {
+            let provider = &provider;
+
+            async move {
+                let (_, range, body) = provider
+                    .get_object_range("bucket", "key", None, &range)
+                    .await
+                    .unwrap();
+                let chunks: Vec<_> = body.try_collect().await.unwrap();
+
+                (range, chunks.concat())
+            }
+        };
+
+        let (range, data) = read_range(ReadRange::Suffix(6)).await;
+        assert_eq!(range, MIN_PART_SIZE - 2..MIN_PART_SIZE + 4);
+        assert_eq!(data, b"aameow");
+
+        let (range, data) = read_range(ReadRange::Bytes {
+            first: MIN_PART_SIZE + 1,
+            last: Some(MIN_PART_SIZE + 2),
+        })
+        .await;
+        assert_eq!(range, MIN_PART_SIZE + 1..MIN_PART_SIZE + 3);
+        assert_eq!(data, b"eo");
+
+        let (range, data) = read_range(ReadRange::Part(2)).await;
+        assert_eq!(range, MIN_PART_SIZE..MIN_PART_SIZE + 4);
+        assert_eq!(data, b"meow");
+
+        assert!(matches!(
+            provider
+                .get_object_range("bucket", "key", None, &ReadRange::Part(3))
+                .await,
+            Err(StorageErr::InvalidPartNumber)
+        ));
+        assert!(matches!(
+            provider
+                .get_object_range(
+                    "bucket",
+                    "key",
+                    None,
+                    &ReadRange::Bytes {
+                        first: MIN_PART_SIZE + 4,
+                        last: None
+                    }
+                )
+                .await,
+            Err(StorageErr::InvalidRange)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_multipart_uploads() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        for key in ["a.jpg", "photos/a.jpg", "photos/b.jpg", "z.jpg"] {
+            provider
+                .create_multipart_upload("bucket", key, PutObjectOptions::default())
+                .await
+                .unwrap();
+        }
+
+        let options = ListUploadsOptions {
+            delimiter: Some("/".into()),
+            max_uploads: 2,
+            ..Default::default()
+        };
+        let page = provider
+            .list_multipart_uploads("bucket", options.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(page.items[0].key, "a.jpg");
+        assert_eq!(page.common_prefixes, ["photos/"]);
+        assert!(page.is_truncated);
+
+        let page = provider
+            .list_multipart_uploads(
+                "bucket",
+                ListUploadsOptions {
+                    key_marker: page.next_marker,
+                    ..options
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].key, "z.jpg");
+        assert!(!page.is_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_list_parts() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        for part_number in 1..=3 {
+            provider
+                .upload_part(
+                    "bucket",
+                    "key",
+                    &upload.upload_id,
+                    part_number,
+                    body(b"meow".to_vec()),
+                )
+                .await
+                .unwrap();
+        }
+
+        let page = provider
+            .list_parts("bucket", "key", &upload.upload_id, Some(1), 1)
+            .await
+            .unwrap();
+
+        assert_eq!(page.parts.len(), 1);
+        assert_eq!(page.parts[0].part_number, 2);
+        assert_eq!(page.next_part_number_marker, Some(2));
+        assert!(page.is_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_upload_part_copy() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "source", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let first = provider
+            .upload_part(
+                "bucket",
+                "source",
+                &upload.upload_id,
+                1,
+                body(vec![b'a'; MIN_PART_SIZE as usize]),
+            )
+            .await
+            .unwrap();
+        let second = provider
+            .upload_part(
+                "bucket",
+                "source",
+                &upload.upload_id,
+                2,
+                body(b"bbb".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "source",
+                &upload.upload_id,
+                [first, second]
+                    .into_iter()
+                    .map(|part| CompletedPart {
+                        part_number: part.part_number,
+                        etag: part.etag,
+                    })
+                    .collect(),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "source".into(),
+            version_id: None,
+        };
+        let upload = provider
+            .create_multipart_upload("bucket", "copy", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let start = MIN_PART_SIZE - 2;
+        let part = provider
+            .upload_part_copy(
+                "bucket",
+                "copy",
+                &upload.upload_id,
+                1,
+                &source,
+                Some(start..start + 5),
+            )
+            .await
+            .unwrap();
+
+        // The ETag of a part is the MD5 of its bytes, however many files they're stored in
+        assert_eq!(part.size, 5);
+        assert_eq!(part.etag, hex::encode(Md5::digest(b"aabbb")));
+        assert!(matches!(
+            provider
+                .upload_part_copy(
+                    "bucket",
+                    "copy",
+                    &upload.upload_id,
+                    2,
+                    &source,
+                    Some(start..start + 6),
+                )
+                .await,
+            Err(StorageErr::InvalidRange)
+        ));
+
+        // The second part of the source is reused rather than copied
+        let conn = provider.connect_to_db().await;
+        let parts = db::get_parts(&conn, upload.upload_id.clone())
+            .await
+            .unwrap();
+        assert!(parts[0].file_id.is_empty());
+
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "copy",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let (object, data) = read(&provider, "copy").await;
+        assert_eq!(object.size, 5);
+        assert_eq!(data, b"aabbb");
+
+        // Nor is the ETag of a whole object completed from a single part, stored in a single
+        // file, that of the part
+        let upload = provider
+            .create_multipart_upload("bucket", "single", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part(
+                "bucket",
+                "single",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        let single = provider
+            .complete_multipart_upload(
+                "bucket",
+                "single",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "single".into(),
+            version_id: None,
+        };
+        let upload = provider
+            .create_multipart_upload("bucket", "again", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part_copy("bucket", "again", &upload.upload_id, 1, &source, None)
+            .await
+            .unwrap();
+        assert_eq!(part.etag, hex::encode(Md5::digest(b"meow")));
+
+        let again = provider
+            .complete_multipart_upload(
+                "bucket",
+                "again",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        assert_eq!(again.etag, single.etag);
+    }
+
+    #[tokio::test]
+    async fn test_list_objects() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        for key in [
+            "a.jpg",
+            "photos/2023/a.jpg",
+            "photos/2023/b.jpg",
+            "photos/2024/a.jpg",
+            "photos/c.jpg",
+            "photos0",
+        ] {
+            provider
+                .put_object(
+                    "bucket",
+                    key,
+                    PutObjectOptions::default(),
+                    body(b"meow".to_vec()),
+                    WriteCondition::Overwrite,
+                )
+                .await
+                .unwrap();
+        }
+
+        let options = ListObjectsOptions {
+            prefix: Some("photos/".into()),
+            delimiter: Some("/".into()),
+            max_keys: 2,
+            ..Default::default()
+        };
+        let page = provider
+            .list_objects("bucket", options.clone())
+            .await
+            .unwrap();
+
+        assert!(page.items.is_empty());
+        assert_eq!(page.common_prefixes, ["photos/2023/", "photos/2024/"]);
+        assert!(page.is_truncated);
+
+        let page = provider
+            .list_objects(
+                "bucket",
+                ListObjectsOptions {
+                    marker: page.next_marker,
+                    ..options
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].key, "photos/c.jpg");
+        assert!(page.common_prefixes.is_empty());
+        assert!(!page.is_truncated);
+
+        let page = provider
+            .list_objects(
+                "bucket",
+                ListObjectsOptions {
+                    delimiter: Some("/".into()),
+                    max_keys: 1000,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let keys: Vec<_> = page
+            .items
+            .iter()
+            .map(|object| object.key.as_str())
+            .collect();
+
+        assert_eq!(keys, ["a.jpg", "photos0"]);
+        assert_eq!(page.common_prefixes, ["photos/"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_object_versions() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let put = |key: &'static str| {
+            provider.put_object(
+                "bucket",
+                key,
+                PutObjectOptions::default(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+        };
+
+        put("a.jpg").await.unwrap();
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        put("a.jpg").await.unwrap();
+        put("photos/a.jpg").await.unwrap();
+        put("photos/b.jpg").await.unwrap();
+        put("z.jpg").await.unwrap();
+        provider
+            .delete_object("bucket", "z.jpg", None)
+            .await
+            .unwrap();
+
+        let options = ListVersionsOptions {
+            delimiter: Some("/".into()),
+            max_keys: 1000,
+            ..Default::default()
+        };
+        let page = provider
+            .list_object_versions("bucket", options.clone())
+            .await
+            .unwrap();
+        let versions: Vec<_> = page
+            .items
+            .iter()
+            .map(|version| {
+                (
+                    version.object.key.as_str(),
+                    version.object.delete_marker,
+                    version.is_latest,
+                )
+            })
+            .collect();
+
+        // Keys in order, and each key's versions newest first
+        assert_eq!(
+            versions,
+            [
+                ("a.jpg", false, true),
+                ("a.jpg", false, false),
+                ("z.jpg", true, true),
+                ("z.jpg", false, false),
+            ]
+        );
+        assert_eq!(
+            page.items[1].object.version_id.as_deref(),
+            Some(s3_object::NULL_VERSION_ID)
+        );
+        assert_eq!(page.common_prefixes, ["photos/"]);
+
+        // A page can end in between the versions of a key
+        let page = provider
+            .list_object_versions(
+                "bucket",
+                ListVersionsOptions {
+                    max_keys: 1,
+                    ..options.clone()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(page.is_truncated);
+
+        let page = provider
+            .list_object_versions(
+                "bucket",
+                ListVersionsOptions {
+                    key_marker: page.next_marker,
+                    version_id_marker: page.items[0].object.version_id.clone(),
+                    max_keys: 2,
+                    ..options
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].object.key, "a.jpg");
+        assert!(!page.items[0].is_latest);
+        assert_eq!(page.common_prefixes, ["photos/"]);
+        assert!(page.is_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_copy_object() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "source", PutObjectOptions::default())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part(
+                "bucket",
+                "source",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "source",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "source".into(),
+            version_id: None,
+        };
+        provider
+            .copy_object(
+                "bucket",
+                "copy",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+        // Copying an object onto itself keeps its files
+        provider
+            .copy_object(
+                "bucket",
+                "source",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(read(&provider, "copy").await.1, b"meow");
+        assert_eq!(read(&provider, "source").await.1, b"meow");
+        assert!(matches!(
+            provider
+                .copy_object(
+                    "bucket",
+                    "copy",
+                    &CopySource {
+                        bucket: "bucket".into(),
+                        key: "missing".into(),
+                        version_id: None,
+                    },
+                    copy_options(MetadataDirective::Copy),
+                )
+                .await,
+            Err(StorageErr::ObjectNotFound)
+        ));
+
+        // The preconditions, tags and ACL apply to the version the copy reads and creates
+        let etag = provider
+            .head_object("bucket", "source", None)
+            .await
+            .unwrap()
+            .etag;
+        let acl = AccessControlList {
+            owner: Owner {
+                id: "owner".into(),
+                display_name: "owner".into(),
+            },
+            grants: vec![],
+        };
+        let copied = provider
+            .copy_object(
+                "bucket",
+                "copy",
+                &source,
+                CopyObjectOptions {
+                    tags: Some(Tags::from([("project".to_owned(), "cat".to_owned())])),
+                    acl: Some(acl.clone()),
+                    source_preconditions: Preconditions {
+                        if_match: Some(format!("\"{etag}\"")),
+                        ..Default::default()
+                    },
+                    ..copy_options(MetadataDirective::Copy)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(copied.object.etag, etag);
+        assert_eq!(
+            provider
+                .get_object_tagging("bucket", "copy", None)
+                .await
+                .unwrap()["project"],
+            "cat"
+        );
+        assert_eq!(
+            provider
+                .get_object_acl("bucket", "copy", None)
+                .await
+                .unwrap(),
+            acl
+        );
+        assert!(matches!(
+            provider
+                .copy_object(
+                    "bucket",
+                    "copy",
+                    &source,
+                    CopyObjectOptions {
+                        source_preconditions: Preconditions {
+                            if_none_match: Some(format!("\"{etag}\"")),
+                            ..Default::default()
+                        },
+                        ..copy_options(MetadataDirective::Copy)
+                    },
+                )
+                .await,
+            Err(StorageErr::PreconditionFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_object_tagging() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "source",
+                PutObjectOptions::default(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        let tags = Tags::from([
+            ("project".to_owned(), "cat".to_owned()),
+            ("color".to_owned(), "orange".to_owned()),
+        ]);
+        provider
+            .put_object_tagging("bucket", "source", tags.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            provider
+                .get_object_tagging("bucket", "source", None)
+                .await
+                .unwrap(),
+            tags
+        );
+
+        // Tags travel with copies
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "source".into(),
+            version_id: None,
+        };
+        provider
+            .copy_object(
+                "bucket",
+                "copy",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            provider
+                .get_object_tagging("bucket", "copy", None)
+                .await
+                .unwrap(),
+            tags
+        );
+
+        // Putting tags replaces all of them
+        let tags = Tags::from([("project".to_owned(), "dog".to_owned())]);
+        provider
+            .put_object_tagging("bucket", "source", tags.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            provider
+                .get_object_tagging("bucket", "source", None)
+                .await
+                .unwrap(),
+            tags
+        );
+
+        // Overwriting an object drops its tags
+        provider
+            .put_object(
+                "bucket",
+                "source",
+                PutObjectOptions::default(),
+                body(b"purr".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        assert!(provider
+            .get_object_tagging("bucket", "source", None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            provider.get_object_tagging("bucket", "missing", None).await,
+            Err(StorageErr::ObjectNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bucket_versioning() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let put = |data: &'static [u8], condition| {
+            provider.put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                body(data.to_vec()),
+                condition,
+            )
+        };
+
+        // Objects of a bucket that was never versioned show no version
+        let object = put(b"meow", WriteCondition::Overwrite).await.unwrap();
+        assert_eq!(object.version_id, None);
+
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Enabled)
+            .await
+            .unwrap();
+        let object = put(b"purr", WriteCondition::Overwrite).await.unwrap();
+        let version_id = object.version_id.unwrap();
+        assert_ne!(version_id, s3_object::NULL_VERSION_ID);
+        assert_eq!(read(&provider, "key").await.1, b"purr");
+
+        // Deleting hides the object behind a delete marker, its versions stay readable
+        let deleted = provider.delete_object("bucket", "key", None).await.unwrap();
+        assert!(deleted.delete_marker);
+        let marker = deleted.version_id.unwrap();
+        assert!(matches!(
+            provider.head_object("bucket", "key", None).await,
+            Err(StorageErr::DeleteMarker { version_id }) if version_id == marker
+        ));
+        let page = provider
+            .list_objects("bucket", ListObjectsOptions::default())
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+        let (_, body) = provider
+            .get_object("bucket", "key", Some(s3_object::NULL_VERSION_ID))
+            .await
+            .unwrap();
+        let chunks: Vec<_> = body.try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), b"meow");
+
+        // Writes that may not overwrite an object can replace a delete marker
+        assert!(matches!(
+            provider.head_object("bucket", "key", Some("missing")).await,
+            Err(StorageErr::VersionNotFound)
+        ));
+        assert_eq!(
+            provider
+                .delete_object("bucket", "key", Some(&marker))
+                .await
+                .unwrap(),
+            DeletedObject {
+                version_id: Some(marker),
+                delete_marker: true,
+            }
+        );
+        assert_eq!(read(&provider, "key").await.1, b"purr");
+        assert!(matches!(
+            put(b"hiss", WriteCondition::IfAbsent).await,
+            Err(StorageErr::PreconditionFailed)
+        ));
+        provider.delete_object("bucket", "key", None).await.unwrap();
+        put(b"hiss", WriteCondition::IfAbsent).await.unwrap();
+
+        // Suspended versioning writes replace the null version and keep the others
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Suspended)
+            .await
+            .unwrap();
+        let object = put(b"mrrp", WriteCondition::Overwrite).await.unwrap();
+        assert_eq!(
+            object.version_id.as_deref(),
+            Some(s3_object::NULL_VERSION_ID)
+        );
+        let object = put(b"mew", WriteCondition::Overwrite).await.unwrap();
+        assert_eq!(
+            object.version_id.as_deref(),
+            Some(s3_object::NULL_VERSION_ID)
+        );
+        assert_eq!(
+            provider
+                .head_object("bucket", "key", Some(&version_id))
+                .await
+                .unwrap()
+                .size,
+            4
+        );
+
+        // Deleting the latest version brings back the one before it
+        provider
+            .delete_object("bucket", "key", Some(s3_object::NULL_VERSION_ID))
+            .await
+            .unwrap();
+        assert_eq!(read(&provider, "key").await.1, b"hiss");
+    }
+
+    #[tokio::test]
+    async fn test_abort_multipart_upload() {
+        let (provider, files) = MemoryVfs::with_files().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+
+        let upload = provider
+            .create_multipart_upload("bucket", "key", PutObjectOptions::default())
+            .await
+            .unwrap();
+        provider
+            .upload_part(
+                "bucket",
+                "key",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .abort_multipart_upload("bucket", "key", &upload.upload_id)
+            .await
+            .unwrap();
+
+        assert!(files.lock().unwrap().is_empty());
+        assert!(matches!(
+            provider
+                .upload_part(
+                    "bucket",
+                    "key",
+                    &upload.upload_id,
+                    2,
+                    body(b"meow".to_vec())
+                )
+                .await,
+            Err(StorageErr::UploadNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_objects_missing_bucket() {
+        let provider = MemoryVfs::new().await;
+        let results = provider
+            .delete_objects("missing", vec![("a".into(), None), ("b".into(), None)])
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(StorageErr::BucketNotFound))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_object_files() {
+        let (provider, files) = MemoryVfs::with_files().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        provider
+            .put_object(
+                "bucket",
+                "source",
+                PutObjectOptions::default(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "source".into(),
+            version_id: None,
+        };
+        provider
+            .copy_object(
+                "bucket",
+                "copy",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+
+        // The copy shares the source's file, which stays until neither is left
+        provider
+            .delete_object("bucket", "source", None)
+            .await
+            .unwrap();
+        assert_eq!(files.lock().unwrap().len(), 1);
+        assert_eq!(read(&provider, "copy").await.1, b"meow");
+
+        provider
+            .delete_object("bucket", "copy", None)
+            .await
+            .unwrap();
+        assert!(files.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_object_files() {
+        let (provider, files) = MemoryVfs::with_files().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let put = |body_bytes: &'static [u8]| {
+            provider.put_object(
+                "bucket",
+                "key",
+                PutObjectOptions::default(),
+                body(body_bytes.to_vec()),
+                WriteCondition::Overwrite,
+            )
+        };
+
+        // The version an overwrite replaces takes its file with it
+        put(b"meow").await.unwrap();
+        put(b"purr").await.unwrap();
+        assert_eq!(files.lock().unwrap().len(), 1);
+        assert_eq!(read(&provider, "key").await.1, b"purr");
+
+        // Unless a copy still refers to it, including a copy onto itself
+        let source = CopySource {
+            bucket: "bucket".into(),
+            key: "key".into(),
+            version_id: None,
+        };
+        provider
+            .copy_object(
+                "bucket",
+                "key",
+                &source,
+                copy_options(MetadataDirective::Replace(ObjectMetadata::default())),
+            )
+            .await
+            .unwrap();
+        provider
+            .copy_object(
+                "bucket",
+                "copy",
+                &source,
+                copy_options(MetadataDirective::Copy),
+            )
+            .await
+            .unwrap();
+        put(b"hiss").await.unwrap();
+        assert_eq!(files.lock().unwrap().len(), 2);
+        assert_eq!(read(&provider, "copy").await.1, b"purr");
+
+        // A delete marker replaces the null version of a suspended bucket
+        provider
+            .put_bucket_versioning("bucket", VersioningStatus::Suspended)
+            .await
+            .unwrap();
+        provider
+            .delete_object("bucket", "copy", None)
+            .await
+            .unwrap();
+        assert_eq!(files.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_lifecycle() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_lifecycle("bucket").await,
+            Err(StorageErr::NoSuchLifecycleConfiguration)
+        ));
+
+        let configuration = LifecycleConfiguration {
+            rules: vec![
+                LifecycleRule {
+                    id: Some("logs".into()),
+                    enabled: true,
+                    filter: LifecycleFilter {
+                        prefix: "logs/".into(),
+                        tags: [("project".to_string(), "cat".to_string())].into(),
+                        object_size_greater_than: Some(10),
+                        object_size_less_than: None,
+                    },
+                    expiration: Some(Expiration::Days(30)),
+                    noncurrent_days: Some(7),
+                    abort_incomplete_upload_days: None,
+                },
+                LifecycleRule {
+                    id: None,
+                    enabled: false,
+                    filter: LifecycleFilter::default(),
+                    expiration: Some(Expiration::ExpiredObjectDeleteMarker),
+                    noncurrent_days: None,
+                    abort_incomplete_upload_days: Some(1),
+                },
+            ],
+        };
+        provider
+            .put_bucket_lifecycle("bucket", configuration.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            provider.get_bucket_lifecycle("bucket").await.unwrap(),
+            configuration
+        );
+
+        provider
+            .put_bucket_lifecycle("bucket", LifecycleConfiguration::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_lifecycle("bucket").await,
+            Err(StorageErr::NoSuchLifecycleConfiguration)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bucket_cors() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_cors("bucket").await,
+            Err(StorageErr::NoSuchCORSConfiguration)
+        ));
+
+        let configuration = CorsConfiguration {
+            rules: vec![
+                CorsRule {
+                    id: Some("uploads".into()),
+                    allowed_origins: vec!["https://*.example.com".into()],
+                    allowed_methods: vec!["PUT".into(), "POST".into()],
+                    allowed_headers: vec!["*".into()],
+                    expose_headers: vec!["ETag".into()],
+                    max_age_seconds: Some(3000),
+                },
+                CorsRule {
+                    id: None,
+                    allowed_origins: vec!["*".into()],
+                    allowed_methods: vec!["GET".into()],
+                    ..Default::default()
+                },
+            ],
+        };
+        provider
+            .put_bucket_cors("bucket", configuration.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            provider.get_bucket_cors("bucket").await.unwrap(),
+            configuration
+        );
+
+        provider
+            .put_bucket_cors("bucket", CorsConfiguration::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_cors("bucket").await,
+            Err(StorageErr::NoSuchCORSConfiguration)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bucket_policy() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_policy("bucket").await,
+            Err(StorageErr::NoSuchBucketPolicy)
+        ));
+
+        let policy = r#"{"Statement": []}"#.to_string();
+        provider
+            .put_bucket_policy("bucket", Some(policy.clone()))
+            .await
+            .unwrap();
+        assert_eq!(provider.get_bucket_policy("bucket").await.unwrap(), policy);
+
+        provider.put_bucket_policy("bucket", None).await.unwrap();
+        assert!(matches!(
+            provider.get_bucket_policy("bucket").await,
+            Err(StorageErr::NoSuchBucketPolicy)
+        ));
+        assert!(matches!(
+            provider.put_bucket_policy("nonexisting", None).await,
+            Err(StorageErr::BucketNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bucket_owners() {
+        let provider = MemoryVfs::new().await;
+        let owner = Owner {
+            id: "AIDAALICE".into(),
+            display_name: "alice".into(),
+        };
+        let acl = CannedAcl::PublicRead.acl(owner.clone(), &owner);
+        let options = CreateBucketOptions {
+            owner_id: Some(owner.id.clone()),
+            acl: Some(acl.clone()),
+            object_ownership: Some(ObjectOwnership::ObjectWriter),
+            ..Default::default()
+        };
+        provider.create_bucket("mine", options).await.unwrap();
+        provider
+            .create_bucket("admins", Default::default())
+            .await
+            .unwrap();
+
+        assert_eq!(provider.get_bucket_acl("mine").await.unwrap(), acl);
+        assert_eq!(
+            provider
+                .get_bucket_ownership_controls("mine")
+                .await
+                .unwrap(),
+            ObjectOwnership::ObjectWriter
+        );
+
+        let names = |buckets: Vec<Bucket>| {
+            let mut names: Vec<_> = buckets.into_iter().map(|bucket| bucket.name).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(provider.list_buckets(None).await.unwrap()),
+            ["admins", "mine"]
+        );
+        assert_eq!(
+            names(provider.list_buckets(Some("AIDAALICE")).await.unwrap()),
+            ["mine"]
+        );
+        assert!(provider
+            .list_buckets(Some("AIDABOB"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_acls() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        assert!(provider.get_bucket_acl("bucket").await.unwrap().is_empty());
+        assert!(matches!(
+            provider.get_bucket_ownership_controls("bucket").await,
+            Err(StorageErr::NoSuchOwnershipControls)
+        ));
+
+        let owner = Owner {
+            id: "AKIDEXAMPLE".into(),
+            display_name: "AKIDEXAMPLE".into(),
+        };
+        let acl = CannedAcl::PublicRead.acl(owner.clone(), &owner);
+        provider
+            .put_bucket_acl("bucket", acl.clone())
+            .await
+            .unwrap();
+        assert_eq!(provider.get_bucket_acl("bucket").await.unwrap(), acl);
+
+        provider
+            .put_bucket_ownership_controls("bucket", Some(ObjectOwnership::BucketOwnerEnforced))
+            .await
+            .unwrap();
+        assert_eq!(
+            provider
+                .get_bucket_ownership_controls("bucket")
+                .await
+                .unwrap(),
+            ObjectOwnership::BucketOwnerEnforced
+        );
+        provider
+            .put_bucket_ownership_controls("bucket", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.get_bucket_ownership_controls("bucket").await,
+            Err(StorageErr::NoSuchOwnershipControls)
+        ));
+
+        provider
+            .put_object(
+                "bucket",
+                "cat.jpg",
+                PutObjectOptions::default(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+        assert!(provider
+            .get_object_acl("bucket", "cat.jpg", None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let private = AccessControlList::private(owner);
+        for acl in [acl, private] {
+            provider
+                .put_object_acl("bucket", "cat.jpg", acl.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                provider
+                    .get_object_acl("bucket", "cat.jpg", None)
+                    .await
+                    .unwrap(),
+                acl
+            );
+        }
+
+        assert!(matches!(
+            provider
+                .put_object_acl("bucket", "missing", AccessControlList::default())
+                .await,
+            Err(StorageErr::ObjectNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_tags_and_acl() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_bucket("bucket", Default::default())
+            .await
+            .unwrap();
+        let options = PutObjectOptions {
+            metadata: ObjectMetadata::default(),
+            tags: [("project".to_owned(), "cat".to_owned())].into(),
+            acl: Some(AccessControlList::private(Owner {
+                id: "AKIDEXAMPLE".into(),
+                display_name: "AKIDEXAMPLE".into(),
+            })),
+        };
+
+        // The tags and ACL are stored along with the version that's written
+        provider
+            .put_object(
+                "bucket",
+                "put",
+                options.clone(),
+                body(b"meow".to_vec()),
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        // Or kept by an upload until it's completed
+        let upload = provider
+            .create_multipart_upload("bucket", "uploaded", options.clone())
+            .await
+            .unwrap();
+        let part = provider
+            .upload_part(
+                "bucket",
+                "uploaded",
+                &upload.upload_id,
+                1,
+                body(b"meow".to_vec()),
+            )
+            .await
+            .unwrap();
+        provider
+            .complete_multipart_upload(
+                "bucket",
+                "uploaded",
+                &upload.upload_id,
+                vec![CompletedPart {
+                    part_number: 1,
+                    etag: part.etag,
+                }],
+                WriteCondition::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        for key in ["put", "uploaded"] {
+            assert_eq!(
+                provider
+                    .get_object_tagging("bucket", key, None)
+                    .await
+                    .unwrap(),
+                options.tags
+            );
+            assert_eq!(
+                provider.get_object_acl("bucket", key, None).await.unwrap(),
+                options.acl.clone().unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_users() {
+        let provider = MemoryVfs::new().await;
+        let user = User {
+            id: "AIDAEXAMPLEUSER".into(),
+            name: "alice".into(),
+            creation_date: chrono::Utc::now(),
+        };
+        provider.create_user(user.clone()).await.unwrap();
+        assert!(matches!(
+            provider.create_user(user.clone()).await,
+            Err(StorageErr::UserAlreadyExists)
+        ));
+        assert_eq!(provider.get_user("alice").await.unwrap().id, user.id);
+        assert_eq!(provider.list_users().await.unwrap().len(), 1);
+
+        let access_key = |access_key_id: &str, user_name: &str| AccessKey {
+            access_key_id: access_key_id.into(),
+            secret_access_key: "secret".into(),
+            user_name: user_name.into(),
+            status: AccessKeyStatus::Active,
+            creation_date: chrono::Utc::now(),
+        };
+        provider
+            .create_access_key(access_key("AKIAFIRST", "alice"))
+            .await
+            .unwrap();
+        provider
+            .create_access_key(access_key("AKIASECOND", "alice"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider
+                .create_access_key(access_key("AKIABOB", "bob"))
+                .await,
+            Err(StorageErr::UserNotFound)
+        ));
+
+        provider
+            .update_access_key("AKIAFIRST", AccessKeyStatus::Inactive)
+            .await
+            .unwrap();
+        assert_eq!(
+            provider.get_access_key("AKIAFIRST").await.unwrap().status,
+            AccessKeyStatus::Inactive
+        );
+        provider.delete_access_key("AKIAFIRST").await.unwrap();
+        assert!(matches!(
+            provider.delete_access_key("AKIAFIRST").await,
+            Err(StorageErr::AccessKeyNotFound)
+        ));
+        let keys = provider.list_access_keys("alice").await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].access_key_id, "AKIASECOND");
+
+        provider.delete_user("alice").await.unwrap();
+        assert!(matches!(
+            provider.get_access_key("AKIASECOND").await,
+            Err(StorageErr::AccessKeyNotFound)
+        ));
+        assert!(matches!(
+            provider.delete_user("alice").await,
+            Err(StorageErr::UserNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_groups_and_policies() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_user(User {
+                id: "AIDAEXAMPLEUSER".into(),
+                name: "alice".into(),
+                creation_date: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let group = Group {
+            id: "AGPAEXAMPLEGROUP".into(),
+            name: "readers".into(),
+            creation_date: chrono::Utc::now(),
+        };
+        provider.create_group(group.clone()).await.unwrap();
+        assert!(matches!(
+            provider.create_group(group).await,
+            Err(StorageErr::GroupAlreadyExists)
+        ));
+
+        provider
+            .add_user_to_group("readers", "alice")
+            .await
+            .unwrap();
+        provider
+            .add_user_to_group("readers", "alice")
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.add_user_to_group("writers", "alice").await,
+            Err(StorageErr::GroupNotFound)
+        ));
+        let groups = provider.list_groups_for_user("alice").await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "readers");
+
+        let user = PolicyHolder::User("alice".into());
+        let readers = PolicyHolder::Group("readers".into());
+        provider
+            .put_identity_policy(&user, "write", Some("first".into()))
+            .await
+            .unwrap();
+        provider
+            .put_identity_policy(&user, "write", Some("second".into()))
+            .await
+            .unwrap();
+        provider
+            .put_identity_policy(&readers, "read", Some("document".into()))
+            .await
+            .unwrap();
+        let policies = provider.list_identity_policies(&user).await.unwrap();
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].document, "second");
+
+        provider
+            .put_identity_policy(&user, "write", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            provider.put_identity_policy(&user, "write", None).await,
+            Err(StorageErr::NoSuchIdentityPolicy)
+        ));
+        assert!(matches!(
+            provider
+                .list_identity_policies(&PolicyHolder::User("bob".into()))
+                .await,
+            Err(StorageErr::UserNotFound)
+        ));
+
+        provider
+            .remove_user_from_group("readers", "alice")
+            .await
+            .unwrap();
+        assert!(provider
+            .list_groups_for_user("alice")
+            .await
+            .unwrap()
+            .is_empty());
+
+        provider
+            .add_user_to_group("readers", "alice")
+            .await
+            .unwrap();
+        provider.delete_group("readers").await.unwrap();
+        assert!(provider
+            .list_groups_for_user("alice")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            provider.list_identity_policies(&readers).await,
+            Err(StorageErr::GroupNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sessions() {
+        let provider = MemoryVfs::new().await;
+        provider
+            .create_user(User {
+                id: "AIDAEXAMPLEUSER".into(),
+                name: "alice".into(),
+                creation_date: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let now = chrono::Utc::now();
+        let session = |access_key_id: &str, expiration| Session {
+            access_key_id: access_key_id.into(),
+            secret_access_key: "secret".into(),
+            session_token: "token".into(),
+            parent_access_key_id: "AKIAALICE".into(),
+            user_name: Some("alice".into()),
+            session_name: "ci".into(),
+            policy: "{}".into(),
+            expiration,
+        };
+        provider
+            .create_session(session(
+                "ASIACURRENT",
+                now + std::time::Duration::from_secs(60 * 60),
+            ))
+            .await
+            .unwrap();
+        provider
+            .create_session(session(
+                "ASIAEXPIRED",
+                now - std::time::Duration::from_secs(60 * 60),
+            ))
+            .await
+            .unwrap();
+
+        let current = provider.get_session("ASIACURRENT").await.unwrap();
+        assert_eq!(current.session_token, "token");
+        assert_eq!(current.user_name.as_deref(), Some("alice"));
+
+        assert_eq!(provider.delete_expired_sessions(now).await.unwrap(), 1);
+        assert!(matches!(
+            provider.get_session("ASIAEXPIRED").await,
+            Err(StorageErr::SessionNotFound)
+        ));
+
+        provider.delete_user("alice").await.unwrap();
+        assert!(matches!(
+            provider.get_session("ASIACURRENT").await,
+            Err(StorageErr::SessionNotFound)
+        ));
+    }
 }